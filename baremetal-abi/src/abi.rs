@@ -2,8 +2,11 @@
 //!
 //! Defines calling conventions, core affinity, and system calls
 
+use crate::cpu::{self, msr, read_msr, write_msr};
+use crate::memory::layout::MMIO_LAYOUT;
+use crate::mmio::MMIOCoherency;
 use crate::{CoreAffinity, CoreType};
-use core::arch::asm;
+use core::arch::{asm, naked_asm};
 
 /// Function calling convention
 ///
@@ -40,6 +43,120 @@ impl CallingConvention {
 #[repr(transparent)]
 pub struct CoreAffinityAttr(pub CoreAffinity);
 
+/// Check whether `affinity` permits running on a core of type `core`
+///
+/// `Any` and `ThreadDirector` accept every core; the others require the
+/// matching core type.
+pub const fn affinity_allows(affinity: CoreAffinity, core: CoreType) -> bool {
+    match affinity {
+        CoreAffinity::Any | CoreAffinity::ThreadDirector => true,
+        CoreAffinity::PerformanceRequired | CoreAffinity::PerformanceWithHT => {
+            matches!(core, CoreType::Performance)
+        }
+        CoreAffinity::EfficiencyPreferred => matches!(core, CoreType::Efficiency),
+    }
+}
+
+/// Run `f` if the current core satisfies `affinity`
+///
+/// There is no scheduler to migrate to another core, so a mismatch is
+/// reported instead.
+pub fn run_with_affinity<F: FnOnce() -> R, R>(
+    affinity: CoreAffinity,
+    f: F,
+) -> Result<R, &'static str> {
+    // Skip CPUID when any core is acceptable
+    let allowed = match affinity {
+        CoreAffinity::Any | CoreAffinity::ThreadDirector => true,
+        _ => affinity_allows(affinity, cpu::get_core_type()),
+    };
+
+    if allowed {
+        Ok(f())
+    } else {
+        Err("wrong core type")
+    }
+}
+
+/// Fixed-size FIFO of background work for E-cores
+///
+/// P-cores stay free for latency-critical work: `run_pending` only drains
+/// the queue when called on an efficiency core.
+pub struct EcoreTaskQueue<const N: usize> {
+    tasks: [Option<fn()>; N],
+    head: usize,
+    len: usize,
+}
+
+impl<const N: usize> EcoreTaskQueue<N> {
+    /// Create an empty queue
+    pub const fn new() -> Self {
+        Self {
+            tasks: [None; N],
+            head: 0,
+            len: 0,
+        }
+    }
+
+    /// Queue `task` to run on the next E-core drain
+    pub fn enqueue(&mut self, task: fn()) -> Result<(), &'static str> {
+        if self.len == N {
+            return Err("E-core task queue full");
+        }
+
+        self.tasks[(self.head + self.len) % N] = Some(task);
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Run every queued task if this is an E-core; returns how many ran
+    pub fn run_pending(&mut self) -> usize {
+        self.run_pending_on(cpu::get_core_type())
+    }
+
+    /// Run every queued task if `core` is an E-core; returns how many ran
+    pub fn run_pending_on(&mut self, core: CoreType) -> usize {
+        if !affinity_allows(CoreAffinity::EfficiencyPreferred, core) {
+            return 0;
+        }
+
+        let mut ran = 0;
+        while let Some(task) = self.pop() {
+            task();
+            ran += 1;
+        }
+        ran
+    }
+
+    /// Number of queued tasks
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Check if no tasks are queued
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Take the oldest queued task
+    fn pop(&mut self) -> Option<fn()> {
+        if self.len == 0 {
+            return None;
+        }
+
+        let task = self.tasks[self.head].take();
+        self.head = (self.head + 1) % N;
+        self.len -= 1;
+        task
+    }
+}
+
+impl<const N: usize> Default for EcoreTaskQueue<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// System call interface
 #[derive(Debug, Clone, Copy)]
 #[repr(u64)]
@@ -56,6 +173,33 @@ pub enum Syscall {
     PerfCounterStop = 0x12,
 }
 
+impl Syscall {
+    /// Decode a raw syscall number
+    pub const fn from_u64(number: u64) -> Option<Self> {
+        match number {
+            0x00 => Some(Syscall::Exit),
+            0x01 => Some(Syscall::ReadMsr),
+            0x02 => Some(Syscall::WriteMsr),
+            0x03 => Some(Syscall::GetCoreType),
+            0x04 => Some(Syscall::SetCoreAffinity),
+            0x05 => Some(Syscall::CacheFlush),
+            0x06 => Some(Syscall::CacheInvalidate),
+            0x10 => Some(Syscall::PerfCounterRead),
+            0x11 => Some(Syscall::PerfCounterStart),
+            0x12 => Some(Syscall::PerfCounterStop),
+            _ => None,
+        }
+    }
+}
+
+/// Syscall error codes (returned negated in RAX)
+pub mod errno {
+    /// Invalid argument
+    pub const EINVAL: i64 = -22;
+    /// Syscall not implemented
+    pub const ENOSYS: i64 = -38;
+}
+
 /// Execute a system call
 ///
 /// # Safety
@@ -69,6 +213,7 @@ pub unsafe fn syscall0(number: Syscall) -> i64 {
         lateout("rax") result,
         lateout("rcx") _,  // Clobbered by syscall
         lateout("r11") _,  // Clobbered by syscall
+        clobber_abi("C"),  // Handler runs ordinary Rust code
         options(nostack, preserves_flags)
     );
     result
@@ -85,11 +230,37 @@ pub unsafe fn syscall1(number: Syscall, arg1: u64) -> i64 {
         lateout("rax") result,
         lateout("rcx") _,
         lateout("r11") _,
+        clobber_abi("C"),
         options(nostack, preserves_flags)
     );
     result
 }
 
+/// System call with 1 argument whose 64-bit result comes back in RDX
+///
+/// Returns the status from RAX and the value from RDX. Used where any u64
+/// is a valid result, so the value can't share RAX with the error codes.
+///
+/// # Safety
+/// Same as `syscall0`
+#[inline]
+pub unsafe fn syscall1_value(number: Syscall, arg1: u64) -> (i64, u64) {
+    let status: i64;
+    let value: u64;
+    asm!(
+        "syscall",
+        in("rax") number as u64,
+        in("rdi") arg1,
+        lateout("rax") status,
+        lateout("rdx") value,
+        lateout("rcx") _,
+        lateout("r11") _,
+        clobber_abi("C"),
+        options(nostack, preserves_flags)
+    );
+    (status, value)
+}
+
 /// System call with 2 arguments
 #[inline]
 pub unsafe fn syscall2(number: Syscall, arg1: u64, arg2: u64) -> i64 {
@@ -102,6 +273,7 @@ pub unsafe fn syscall2(number: Syscall, arg1: u64, arg2: u64) -> i64 {
         lateout("rax") result,
         lateout("rcx") _,
         lateout("r11") _,
+        clobber_abi("C"),
         options(nostack, preserves_flags)
     );
     result
@@ -120,6 +292,7 @@ pub unsafe fn syscall3(number: Syscall, arg1: u64, arg2: u64, arg3: u64) -> i64
         lateout("rax") result,
         lateout("rcx") _,
         lateout("r11") _,
+        clobber_abi("C"),
         options(nostack, preserves_flags)
     );
     result
@@ -137,11 +310,11 @@ pub mod syscalls {
 
     /// Read Model Specific Register
     pub unsafe fn read_msr(msr: u32) -> Result<u64, i64> {
-        let result = syscall1(Syscall::ReadMsr, msr as u64);
-        if result >= 0 {
-            Ok(result as u64)
+        let (status, value) = syscall1_value(Syscall::ReadMsr, msr as u64);
+        if status >= 0 {
+            Ok(value)
         } else {
-            Err(result)
+            Err(status)
         }
     }
 
@@ -180,6 +353,10 @@ pub mod syscalls {
     }
 
     /// Flush cache line
+    ///
+    /// # Safety
+    /// `address` must be mapped: the kernel flushes it as given, and an
+    /// unmapped address faults inside the syscall
     pub unsafe fn cache_flush(address: u64) -> Result<(), i64> {
         let result = syscall1(Syscall::CacheFlush, address);
         if result >= 0 {
@@ -200,6 +377,183 @@ pub mod syscalls {
     }
 }
 
+/// Kernel-side services backing the system calls
+pub trait SyscallHandler {
+    /// Read an MSR
+    fn read_msr(&mut self, msr: u32) -> Result<u64, &'static str>;
+    /// Write an MSR
+    fn write_msr(&mut self, msr: u32, value: u64) -> Result<(), &'static str>;
+    /// Get the type of the current core
+    fn core_type(&mut self) -> CoreType;
+    /// Write back and invalidate the cache line holding `address`
+    fn cache_flush(&mut self, address: u64);
+    /// Invalidate the cache line holding `address`
+    fn cache_invalidate(&mut self, address: u64);
+}
+
+/// Values a system call hands back to its caller
+///
+/// Returned in RAX:RDX by `kernel_syscall_dispatch` (a 16-byte `repr(C)`
+/// struct under the System V ABI).
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SyscallReturn {
+    /// RAX: a non-negative result on success or a negative `errno` value
+    pub status: i64,
+    /// RDX: the full 64-bit result of calls where any u64 is valid (`ReadMsr`)
+    pub value: u64,
+}
+
+impl SyscallReturn {
+    /// Status only, RDX zeroed
+    const fn status(status: i64) -> Self {
+        Self { status, value: 0 }
+    }
+}
+
+/// Dispatch a system call by number
+///
+/// `ReadMsr` returns status 0 and the MSR in `value`, since an MSR with
+/// bit 63 set would otherwise look like an error code.
+pub fn dispatch<H: SyscallHandler>(
+    handler: &mut H,
+    number: u64,
+    arg1: u64,
+    arg2: u64,
+    _arg3: u64,
+) -> SyscallReturn {
+    let status = match Syscall::from_u64(number) {
+        Some(Syscall::ReadMsr) => match u32::try_from(arg1) {
+            Ok(index) => match handler.read_msr(index) {
+                Ok(value) => return SyscallReturn { status: 0, value },
+                Err(_) => errno::EINVAL,
+            },
+            Err(_) => errno::EINVAL,
+        },
+        Some(Syscall::WriteMsr) => match u32::try_from(arg1) {
+            Ok(index) => handler.write_msr(index, arg2).map_or(errno::EINVAL, |()| 0),
+            Err(_) => errno::EINVAL,
+        },
+        Some(Syscall::GetCoreType) => handler.core_type() as u8 as i64,
+        Some(Syscall::CacheFlush) => {
+            handler.cache_flush(arg1);
+            0
+        }
+        Some(Syscall::CacheInvalidate) => {
+            handler.cache_invalidate(arg1);
+            0
+        }
+        _ => errno::ENOSYS,
+    };
+
+    SyscallReturn::status(status)
+}
+
+/// Syscall handler backed by the real CPU and coherency MMIO
+pub struct KernelSyscalls;
+
+impl KernelSyscalls {
+    /// MMIO coherency interface of the current core
+    fn coherency(&self) -> (u8, MMIOCoherency) {
+        let core_id = cpu::get_core_id();
+        let base = MMIO_LAYOUT.core_coherency_base(core_id);
+        (core_id, unsafe { MMIOCoherency::new(base) })
+    }
+}
+
+impl SyscallHandler for KernelSyscalls {
+    fn read_msr(&mut self, msr: u32) -> Result<u64, &'static str> {
+        unsafe { cpu::try_read_msr(msr) }
+    }
+
+    fn write_msr(&mut self, msr: u32, value: u64) -> Result<(), &'static str> {
+        unsafe { cpu::try_write_msr(msr, value) }
+    }
+
+    fn core_type(&mut self) -> CoreType {
+        cpu::get_core_type()
+    }
+
+    fn cache_flush(&mut self, address: u64) {
+        // SAFETY: `syscall::cache_flush` requires callers to pass a mapped
+        // address; CLFLUSHOPT is only used when CPUID reports it
+        unsafe {
+            if cpu::cached_features().clflushopt {
+                cpu::clflushopt(address as *const u8);
+            } else {
+                cpu::clflush(address as *const u8);
+            }
+        }
+        cpu::mfence();
+    }
+
+    fn cache_invalidate(&mut self, address: u64) {
+        let (core_id, mut mmio) = self.coherency();
+        let _ = unsafe { mmio.mmio_invalidate(core_id, address) };
+    }
+}
+
+/// RFLAGS bits cleared on syscall entry (IF, DF)
+const SYSCALL_RFLAGS_MASK: u64 = (1 << 9) | (1 << 10);
+
+/// Program the SYSCALL MSRs to enter `syscall_entry`
+///
+/// # Safety
+/// Must be called from ring 0 after the GDT is loaded
+pub unsafe fn install_syscall_handler() {
+    use x86_64::instructions::segmentation::{Segment, CS};
+
+    // Everything runs in ring 0, so SYSCALL and SYSRET share the kernel CS
+    let cs = CS::get_reg().0 as u64;
+    write_msr(msr::IA32_STAR, (cs << 48) | (cs << 32));
+    write_msr(msr::IA32_LSTAR, syscall_entry as usize as u64);
+    write_msr(msr::IA32_FMASK, SYSCALL_RFLAGS_MASK);
+
+    // Enable SYSCALL/SYSRET (EFER.SCE)
+    let efer = read_msr(msr::IA32_EFER);
+    write_msr(msr::IA32_EFER, efer | 1);
+}
+
+/// SYSCALL entry point
+///
+/// RAX holds the syscall number and RDI/RSI/RDX the arguments. The result
+/// is returned in RAX, with a 64-bit value in RDX (see `SyscallReturn`).
+/// Callers run in ring 0, so this returns by restoring
+/// RFLAGS and jumping to RCX rather than SYSRET (which always enters ring 3).
+#[unsafe(naked)]
+extern "C" fn syscall_entry() {
+    naked_asm!(
+        // SYSCALL saved RIP in RCX and RFLAGS in R11
+        "push rcx",
+        "push r11",
+        "push rbp",
+        "mov rbp, rsp",
+        "and rsp, -16",
+        // kernel_syscall_dispatch(number, arg1, arg2, arg3)
+        "mov rcx, rdx",
+        "mov rdx, rsi",
+        "mov rsi, rdi",
+        "mov rdi, rax",
+        "call {dispatch}",
+        "mov rsp, rbp",
+        "pop rbp",
+        "popfq",
+        "pop rcx",
+        "jmp rcx",
+        dispatch = sym kernel_syscall_dispatch,
+    );
+}
+
+/// Rust side of `syscall_entry`
+extern "C" fn kernel_syscall_dispatch(
+    number: u64,
+    arg1: u64,
+    arg2: u64,
+    arg3: u64,
+) -> SyscallReturn {
+    dispatch(&mut KernelSyscalls, number, arg1, arg2, arg3)
+}
+
 /// Function prologue macro for custom ABI
 #[macro_export]
 macro_rules! abi_function_prologue {
@@ -280,4 +634,191 @@ mod tests {
         assert_eq!(Syscall::Exit as u64, 0x00);
         assert_eq!(Syscall::GetCoreType as u64, 0x03);
     }
+
+    #[test]
+    fn test_syscall_from_u64_round_trip() {
+        for number in 0..0x20u64 {
+            if let Some(syscall) = Syscall::from_u64(number) {
+                assert_eq!(syscall as u64, number);
+            }
+        }
+        assert!(Syscall::from_u64(0x07).is_none());
+        assert!(Syscall::from_u64(u64::MAX).is_none());
+    }
+
+    #[test]
+    fn test_affinity_allows_each_combination() {
+        use CoreAffinity::*;
+        use CoreType::{Efficiency, Performance, Unknown};
+
+        let cases = [
+            (Any, [true, true, true]),
+            (ThreadDirector, [true, true, true]),
+            (PerformanceRequired, [true, false, false]),
+            (PerformanceWithHT, [true, false, false]),
+            (EfficiencyPreferred, [false, true, false]),
+        ];
+
+        for (affinity, expected) in cases {
+            for (core, allowed) in [Performance, Efficiency, Unknown].into_iter().zip(expected) {
+                assert_eq!(affinity_allows(affinity, core), allowed, "{:?} on {:?}", affinity, core);
+            }
+        }
+    }
+
+    #[test]
+    fn test_run_with_affinity_any_core() {
+        assert_eq!(run_with_affinity(CoreAffinity::Any, || 42), Ok(42));
+        assert_eq!(run_with_affinity(CoreAffinity::ThreadDirector, || "ran"), Ok("ran"));
+
+        // Exactly one of the strict affinities can hold on a given core
+        let p = run_with_affinity(CoreAffinity::PerformanceRequired, || ()).is_ok();
+        let e = run_with_affinity(CoreAffinity::EfficiencyPreferred, || ()).is_ok();
+        assert!(!(p && e));
+    }
+
+    /// Syscall handler that records calls instead of touching hardware
+    #[derive(Default)]
+    struct MockHandler {
+        msr_value: u64,
+        written: Option<(u32, u64)>,
+        flushed: Option<u64>,
+        invalidated: Option<u64>,
+    }
+
+    impl SyscallHandler for MockHandler {
+        fn read_msr(&mut self, msr: u32) -> Result<u64, &'static str> {
+            if cpu::is_supported_msr(msr) {
+                Ok(self.msr_value)
+            } else {
+                Err("unsupported MSR")
+            }
+        }
+
+        fn write_msr(&mut self, msr: u32, value: u64) -> Result<(), &'static str> {
+            if cpu::is_supported_msr(msr) {
+                self.written = Some((msr, value));
+                Ok(())
+            } else {
+                Err("unsupported MSR")
+            }
+        }
+
+        fn core_type(&mut self) -> CoreType {
+            CoreType::Efficiency
+        }
+
+        fn cache_flush(&mut self, address: u64) {
+            self.flushed = Some(address);
+        }
+
+        fn cache_invalidate(&mut self, address: u64) {
+            self.invalidated = Some(address);
+        }
+    }
+
+    #[test]
+    fn test_dispatch_msr_syscalls() {
+        let mut handler = MockHandler { msr_value: 0x1234, ..Default::default() };
+        let platform_info = msr::MSR_PLATFORM_INFO as u64;
+
+        assert_eq!(
+            dispatch(&mut handler, Syscall::ReadMsr as u64, platform_info, 0, 0),
+            SyscallReturn { status: 0, value: 0x1234 }
+        );
+        let read_msr = Syscall::ReadMsr as u64;
+        assert_eq!(dispatch(&mut handler, read_msr, 0xDEAD, 0, 0).status, errno::EINVAL);
+        assert_eq!(dispatch(&mut handler, read_msr, u64::MAX, 0, 0).status, errno::EINVAL);
+
+        assert_eq!(dispatch(&mut handler, Syscall::WriteMsr as u64, platform_info, 7, 0).status, 0);
+        assert_eq!(handler.written, Some((msr::MSR_PLATFORM_INFO, 7)));
+    }
+
+    #[test]
+    fn test_dispatch_read_msr_high_bit() {
+        let mut handler = MockHandler { msr_value: (1 << 63) | 0x16, ..Default::default() };
+        let platform_info = msr::MSR_PLATFORM_INFO as u64;
+
+        // Bit 63 set must not read as a negative errno
+        let result = dispatch(&mut handler, Syscall::ReadMsr as u64, platform_info, 0, 0);
+        assert_eq!(result, SyscallReturn { status: 0, value: (1 << 63) | 0x16 });
+
+        handler.msr_value = errno::EINVAL as u64;
+        let result = dispatch(&mut handler, Syscall::ReadMsr as u64, platform_info, 0, 0);
+        assert_eq!(result, SyscallReturn { status: 0, value: errno::EINVAL as u64 });
+    }
+
+    #[test]
+    fn test_dispatch_core_and_cache_syscalls() {
+        let mut handler = MockHandler::default();
+
+        assert_eq!(dispatch(&mut handler, Syscall::GetCoreType as u64, 0, 0, 0).status, 0x20);
+
+        assert_eq!(dispatch(&mut handler, Syscall::CacheFlush as u64, 0x1000, 0, 0).status, 0);
+        assert_eq!(handler.flushed, Some(0x1000));
+
+        assert_eq!(dispatch(&mut handler, Syscall::CacheInvalidate as u64, 0x2000, 0, 0).status, 0);
+        assert_eq!(handler.invalidated, Some(0x2000));
+    }
+
+    #[test]
+    fn test_dispatch_unimplemented() {
+        let mut handler = MockHandler::default();
+        assert_eq!(
+            dispatch(&mut handler, Syscall::PerfCounterRead as u64, 0, 0, 0),
+            SyscallReturn::status(errno::ENOSYS)
+        );
+        assert_eq!(dispatch(&mut handler, 0xFFFF, 0, 0, 0).status, errno::ENOSYS);
+    }
+
+    #[test]
+    fn test_ecore_queue_runs_only_on_ecores() {
+        use core::sync::atomic::{AtomicUsize, Ordering};
+        static RUNS: AtomicUsize = AtomicUsize::new(0);
+        fn work() {
+            RUNS.fetch_add(1, Ordering::SeqCst);
+        }
+
+        let mut queue = EcoreTaskQueue::<4>::new();
+        queue.enqueue(work).unwrap();
+        queue.enqueue(work).unwrap();
+
+        // P-cores and unidentified cores leave the queue alone
+        assert_eq!(queue.run_pending_on(CoreType::Performance), 0);
+        assert_eq!(queue.run_pending_on(CoreType::Unknown), 0);
+        assert_eq!(queue.len(), 2);
+        assert_eq!(RUNS.load(Ordering::SeqCst), 0);
+
+        assert_eq!(queue.run_pending_on(CoreType::Efficiency), 2);
+        assert!(queue.is_empty());
+        assert_eq!(RUNS.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_ecore_queue_full_and_fifo_order() {
+        use core::sync::atomic::{AtomicUsize, Ordering};
+        static ORDER: AtomicUsize = AtomicUsize::new(0);
+        fn first() {
+            ORDER.store(ORDER.load(Ordering::SeqCst) * 10 + 1, Ordering::SeqCst);
+        }
+        fn second() {
+            ORDER.store(ORDER.load(Ordering::SeqCst) * 10 + 2, Ordering::SeqCst);
+        }
+        fn third() {
+            ORDER.store(ORDER.load(Ordering::SeqCst) * 10 + 3, Ordering::SeqCst);
+        }
+
+        let mut queue = EcoreTaskQueue::<2>::new();
+        queue.enqueue(first).unwrap();
+        queue.enqueue(second).unwrap();
+        assert_eq!(queue.enqueue(third), Err("E-core task queue full"));
+
+        // Drain, then refill across the wrap point
+        assert_eq!(queue.run_pending_on(CoreType::Efficiency), 2);
+        queue.enqueue(third).unwrap();
+        queue.enqueue(first).unwrap();
+        assert_eq!(queue.run_pending_on(CoreType::Efficiency), 2);
+
+        assert_eq!(ORDER.load(Ordering::SeqCst), 1231);
+    }
 }
@@ -0,0 +1,380 @@
+//! Kernel heap allocator for i9-12900K bare-metal
+//!
+//! Address-ordered free-list allocator with block coalescing
+
+use crate::cpu::prefetch_t0;
+use crate::memory::layout::{KERNEL_HEAP_SIZE, KERNEL_HEAP_START};
+use core::alloc::{GlobalAlloc, Layout};
+use core::cell::UnsafeCell;
+use core::mem;
+use core::ptr;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+/// Free block header stored in-place at the start of each free region
+struct ListNode {
+    size: usize,
+    next: *mut ListNode,
+}
+
+impl ListNode {
+    const fn new(size: usize) -> Self {
+        Self {
+            size,
+            next: ptr::null_mut(),
+        }
+    }
+
+    fn start_addr(&self) -> usize {
+        self as *const Self as usize
+    }
+
+    fn end_addr(&self) -> usize {
+        self.start_addr() + self.size
+    }
+}
+
+/// Smallest block the allocator can track
+const MIN_BLOCK_SIZE: usize = mem::size_of::<ListNode>();
+
+/// Align value up (align must be a power of two)
+const fn align_up(addr: usize, align: usize) -> usize {
+    (addr + align - 1) & !(align - 1)
+}
+
+/// Linked-list heap allocator
+///
+/// Free regions are kept sorted by address so that adjacent blocks are
+/// merged on deallocation, keeping fragmentation bounded.
+pub struct LinkedListAllocator {
+    head: ListNode,
+}
+
+impl LinkedListAllocator {
+    /// Create an empty allocator (all allocations fail until `init`)
+    pub const fn new() -> Self {
+        Self {
+            head: ListNode::new(0),
+        }
+    }
+
+    /// Hand a memory region to the allocator
+    ///
+    /// # Safety
+    /// The region must be valid, writable, unused memory and may only be
+    /// added once.
+    pub unsafe fn init(&mut self, start: usize, size: usize) {
+        let aligned = align_up(start, mem::align_of::<ListNode>());
+        let size = size.saturating_sub(aligned - start) & !(mem::align_of::<ListNode>() - 1);
+        self.add_free_region(aligned, size);
+    }
+
+    /// Insert a free region, coalescing with its neighbours
+    unsafe fn add_free_region(&mut self, addr: usize, size: usize) {
+        if size < MIN_BLOCK_SIZE {
+            return;
+        }
+
+        // Find the last node before `addr`
+        let mut prev: *mut ListNode = &mut self.head;
+        while !(*prev).next.is_null() && ((*prev).next as usize) < addr {
+            prev = (*prev).next;
+        }
+        let next = (*prev).next;
+
+        // Merge into the previous block if adjacent
+        let node = if prev != &mut self.head as *mut ListNode && (*prev).end_addr() == addr {
+            (*prev).size += size;
+            prev
+        } else {
+            let node = addr as *mut ListNode;
+            node.write(ListNode::new(size));
+            (*node).next = next;
+            (*prev).next = node;
+            node
+        };
+
+        // Absorb the following block if adjacent
+        if !next.is_null() && (*node).end_addr() == next as usize {
+            (*node).size += (*next).size;
+            (*node).next = (*next).next;
+        }
+    }
+
+    /// Adjust a layout so the block can later hold a `ListNode`
+    fn size_align(layout: Layout) -> (usize, usize) {
+        let align = layout.align().max(mem::align_of::<ListNode>());
+        let size = align_up(layout.size().max(MIN_BLOCK_SIZE), mem::align_of::<ListNode>());
+        (size, align)
+    }
+
+    /// Find a fitting block, split it, and return the allocation start
+    unsafe fn allocate(&mut self, layout: Layout) -> *mut u8 {
+        let (size, align) = Self::size_align(layout);
+
+        let mut prev: *mut ListNode = &mut self.head;
+        while !(*prev).next.is_null() {
+            let region = (*prev).next;
+            let region_start = (*region).start_addr();
+            let region_end = (*region).end_addr();
+
+            // Warm the next header while this region is checked
+            prefetch_t0((*region).next as *const u8);
+
+            // Leading padding must be zero or large enough to stay a free block
+            let mut alloc_start = align_up(region_start, align);
+            while alloc_start != region_start && alloc_start - region_start < MIN_BLOCK_SIZE {
+                alloc_start += align;
+            }
+
+            let fits = alloc_start
+                .checked_add(size)
+                .filter(|&alloc_end| alloc_end <= region_end)
+                .filter(|&alloc_end| {
+                    let excess = region_end - alloc_end;
+                    excess == 0 || excess >= MIN_BLOCK_SIZE
+                });
+
+            if let Some(alloc_end) = fits {
+                // Unlink the region, then give back the unused head and tail
+                (*prev).next = (*region).next;
+                self.add_free_region(region_start, alloc_start - region_start);
+                self.add_free_region(alloc_end, region_end - alloc_end);
+                return alloc_start as *mut u8;
+            }
+
+            prev = region;
+        }
+
+        ptr::null_mut()
+    }
+
+    /// Return a block to the free list
+    unsafe fn deallocate(&mut self, ptr: *mut u8, layout: Layout) {
+        let (size, _) = Self::size_align(layout);
+        self.add_free_region(ptr as usize, size);
+    }
+}
+
+/// Spinlock-protected allocator usable as `#[global_allocator]`
+///
+/// The lock is taken with interrupts disabled, so an interrupt handler that
+/// allocates can't spin on a lock held by the code it interrupted.
+pub struct LockedHeap {
+    locked: AtomicBool,
+    heap: UnsafeCell<LinkedListAllocator>,
+}
+
+unsafe impl Sync for LockedHeap {}
+
+impl LockedHeap {
+    /// Create an empty heap
+    pub const fn empty() -> Self {
+        Self {
+            locked: AtomicBool::new(false),
+            heap: UnsafeCell::new(LinkedListAllocator::new()),
+        }
+    }
+
+    /// Initialize the heap with a memory region
+    ///
+    /// # Safety
+    /// See `LinkedListAllocator::init`
+    pub unsafe fn init(&self, start: usize, size: usize) {
+        self.with_heap(|heap| heap.init(start, size));
+    }
+
+    fn with_heap<R>(&self, f: impl FnOnce(&mut LinkedListAllocator) -> R) -> R {
+        without_interrupts(|| {
+            while self
+                .locked
+                .compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed)
+                .is_err()
+            {
+                core::hint::spin_loop();
+            }
+
+            let result = f(unsafe { &mut *self.heap.get() });
+            self.locked.store(false, Ordering::Release);
+            result
+        })
+    }
+}
+
+/// Run `f` with maskable interrupts off, then restore the saved RFLAGS.IF
+#[cfg(not(test))]
+fn without_interrupts<R>(f: impl FnOnce() -> R) -> R {
+    x86_64::instructions::interrupts::without_interrupts(f)
+}
+
+/// Hosted tests run in ring 3, where `cli` faults: run `f` unmasked
+#[cfg(test)]
+fn without_interrupts<R>(f: impl FnOnce() -> R) -> R {
+    f()
+}
+
+unsafe impl GlobalAlloc for LockedHeap {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        self.with_heap(|heap| heap.allocate(layout))
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        self.with_heap(|heap| heap.deallocate(ptr, layout));
+    }
+}
+
+/// Kernel heap, filled in by `init_heap` or `init_kernel_heap`
+pub static KERNEL_HEAP: LockedHeap = LockedHeap::empty();
+
+/// Zero-sized handle that allocates from `KERNEL_HEAP`
+///
+/// The library never registers an allocator itself, so it can still be
+/// linked into hosted tests and other kernels. A kernel opts in with
+/// `#[global_allocator] static HEAP: KernelHeap = KernelHeap;`.
+pub struct KernelHeap;
+
+unsafe impl GlobalAlloc for KernelHeap {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        KERNEL_HEAP.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        KERNEL_HEAP.dealloc(ptr, layout);
+    }
+}
+
+/// Initialize the kernel heap with a memory region
+///
+/// # Safety
+/// The region must be mapped, writable and not used for anything else
+pub unsafe fn init_heap(start: u64, size: u64) {
+    KERNEL_HEAP.init(start as usize, size as usize);
+}
+
+/// Initialize the kernel heap over `memory::layout`'s heap region
+///
+/// # Safety
+/// `KERNEL_HEAP_START..KERNEL_HEAP_START + KERNEL_HEAP_SIZE` must be mapped
+pub unsafe fn init_kernel_heap() {
+    init_heap(KERNEL_HEAP_START, KERNEL_HEAP_SIZE);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    extern crate alloc;
+    use alloc::boxed::Box;
+    use alloc::vec::Vec;
+
+    #[repr(C, align(4096))]
+    struct Backing([u8; 4096]);
+
+    fn heap_with_backing() -> (LinkedListAllocator, Box<Backing>) {
+        let mut backing = Box::new(Backing([0; 4096]));
+        let mut heap = LinkedListAllocator::new();
+        unsafe {
+            heap.init(backing.0.as_mut_ptr() as usize, 4096);
+        }
+        (heap, backing)
+    }
+
+    /// Collect (offset, size) of every free region relative to `base`
+    fn free_regions(heap: &LinkedListAllocator, base: usize) -> Vec<(usize, usize)> {
+        let mut regions = Vec::new();
+        let mut node = heap.head.next;
+        while !node.is_null() {
+            unsafe {
+                regions.push(((*node).start_addr() - base, (*node).size));
+                node = (*node).next;
+            }
+        }
+        regions
+    }
+
+    #[test]
+    fn test_allocate_splits_region() {
+        let (mut heap, backing) = heap_with_backing();
+        let base = backing.0.as_ptr() as usize;
+
+        let layout = Layout::from_size_align(64, 8).unwrap();
+        let ptr = unsafe { heap.allocate(layout) };
+
+        assert_eq!(ptr as usize, base);
+        assert_eq!(free_regions(&heap, base), [(64, 4096 - 64)]);
+    }
+
+    #[test]
+    fn test_dealloc_coalesces_neighbours() {
+        let (mut heap, backing) = heap_with_backing();
+        let base = backing.0.as_ptr() as usize;
+        let layout = Layout::from_size_align(128, 8).unwrap();
+
+        unsafe {
+            let a = heap.allocate(layout);
+            let b = heap.allocate(layout);
+            let c = heap.allocate(layout);
+            assert_eq!(free_regions(&heap, base), [(384, 4096 - 384)]);
+
+            // Freeing the middle block leaves a hole
+            heap.deallocate(b, layout);
+            assert_eq!(free_regions(&heap, base), [(128, 128), (384, 4096 - 384)]);
+
+            // Freeing the first merges with the hole
+            heap.deallocate(a, layout);
+            assert_eq!(free_regions(&heap, base), [(0, 256), (384, 4096 - 384)]);
+
+            // Freeing the last merges everything back together
+            heap.deallocate(c, layout);
+            assert_eq!(free_regions(&heap, base), [(0, 4096)]);
+        }
+    }
+
+    #[test]
+    fn test_allocate_respects_alignment() {
+        let (mut heap, backing) = heap_with_backing();
+        let base = backing.0.as_ptr() as usize;
+
+        unsafe {
+            let small = heap.allocate(Layout::from_size_align(16, 8).unwrap());
+            let aligned = heap.allocate(Layout::from_size_align(64, 256).unwrap());
+
+            assert_eq!(small as usize, base);
+            assert_eq!(aligned as usize % 256, 0);
+            assert_eq!(aligned as usize, base + 256);
+
+            // Padding between the two allocations stays allocatable
+            assert_eq!(free_regions(&heap, base)[0], (16, 240));
+        }
+    }
+
+    #[test]
+    fn test_allocate_exhausted() {
+        let (mut heap, _backing) = heap_with_backing();
+
+        unsafe {
+            assert!(!heap.allocate(Layout::from_size_align(4096, 8).unwrap()).is_null());
+            assert!(heap.allocate(Layout::from_size_align(8, 8).unwrap()).is_null());
+        }
+    }
+
+    #[test]
+    fn test_empty_heap_fails() {
+        let heap = LockedHeap::empty();
+        let ptr = unsafe { heap.alloc(Layout::from_size_align(8, 8).unwrap()) };
+        assert!(ptr.is_null());
+    }
+
+    #[test]
+    fn test_init_heap_backs_kernel_heap() {
+        let layout = Layout::from_size_align(64, 8).unwrap();
+        let backing = Box::leak(Box::new(Backing([0; 4096])));
+        let start = backing.0.as_mut_ptr() as u64;
+
+        unsafe {
+            init_heap(start, 4096);
+            let ptr = KernelHeap.alloc(layout);
+            assert!(!ptr.is_null());
+            assert!((start..start + 4096).contains(&(ptr as u64)));
+            KernelHeap.dealloc(ptr, layout);
+        }
+    }
+}
@@ -12,73 +12,71 @@
 
 extern crate alloc;
 
-use core::alloc::{GlobalAlloc, Layout};
 use core::fmt::Write;
 use bootloader_api::{entry_point, BootInfo};
+use silent_breath_mmio::{FuseMode, RegisterId};
 use i9_12900k_baremetal_abi::{
-    cpu, performance, CoreType,
+    allocator::{self, KernelHeap}, boot, cpu, memory, performance, selftest, CoreType,
+    boot::{SerialOverflow, SerialRing},
     coherency_runtime::CoherencyRuntime,
+    interrupts,
+    ShadowRegisterRuntime,
 };
 
-/// Dummy allocator for bare-metal (fails all allocations)
-/// This satisfies the linker requirement for a global allocator
-struct DummyAllocator;
-
-unsafe impl GlobalAlloc for DummyAllocator {
-    unsafe fn alloc(&self, _layout: Layout) -> *mut u8 {
-        core::ptr::null_mut()
-    }
-
-    unsafe fn dealloc(&self, _ptr: *mut u8, _layout: Layout) {
-        // No-op
-    }
-}
-
+/// Global allocator, backed by `allocator::KERNEL_HEAP`
 #[global_allocator]
-static ALLOCATOR: DummyAllocator = DummyAllocator;
+static GLOBAL_HEAP: KernelHeap = KernelHeap;
 
-/// Simple serial port driver (COM1)
-struct SerialPort;
+/// Bootstrap heap size (1 MiB)
+const BOOT_HEAP_SIZE: usize = 1024 * 1024;
 
-impl SerialPort {
-    const PORT: u16 = 0x3F8; // COM1
+/// Bootstrap heap in .bss, used until the layout heap region is mapped
+static mut BOOT_HEAP: [u8; BOOT_HEAP_SIZE] = [0; BOOT_HEAP_SIZE];
 
-    /// Initialize serial port
-    #[allow(dead_code)]
-    unsafe fn init() {
-        use core::arch::asm;
+/// Shadow register runtime, reported by the panic handler
+static mut SHADOW_RUNTIME: ShadowRegisterRuntime = ShadowRegisterRuntime::new();
 
-        // Disable interrupts
-        asm!("out dx, al", in("dx") Self::PORT + 1, in("al") 0x00u8, options(nomem, nostack));
+/// Fuse-backed shadow registers set up at boot (one 8-byte fuse each)
+const SHADOW_REGISTER_COUNT: u32 = 8;
 
-        // Enable DLAB
-        asm!("out dx, al", in("dx") Self::PORT + 3, in("al") 0x80u8, options(nomem, nostack));
+/// Cache coherency runtime, reported by the panic handler
+static mut COHERENCY: CoherencyRuntime = CoherencyRuntime::new();
 
-        // Set divisor to 3 (38400 baud)
-        asm!("out dx, al", in("dx") Self::PORT, in("al") 0x03u8, options(nomem, nostack));
-        asm!("out dx, al", in("dx") Self::PORT + 1, in("al") 0x00u8, options(nomem, nostack));
+/// I/O port base of the active serial port (set by `SerialPort::init_port`)
+static mut SERIAL_PORT_BASE: u16 = boot::COM1_PORT;
 
-        // 8N1
-        asm!("out dx, al", in("dx") Self::PORT + 3, in("al") 0x03u8, options(nomem, nostack));
+/// Simple serial port driver (COM1 by default)
+struct SerialPort;
 
-        // Enable FIFO
-        asm!("out dx, al", in("dx") Self::PORT + 2, in("al") 0xC7u8, options(nomem, nostack));
+impl SerialPort {
+    /// Initialize COM1 at 38400 baud
+    unsafe fn init() {
+        // 38400 divides the UART clock exactly, so this cannot fail
+        let _ = Self::init_port(boot::COM1_PORT, 38_400);
+    }
 
-        // Enable IRQs, RTS/DSR
-        asm!("out dx, al", in("dx") Self::PORT + 4, in("al") 0x0Bu8, options(nomem, nostack));
+    /// Initialize the UART at `base` for `baud` and route output to it
+    ///
+    /// Fails without touching the port (or the output base) if
+    /// `boot::uart_divisor` has no divisor for the rate.
+    unsafe fn init_port(base: u16, baud: u32) -> Result<(), &'static str> {
+        boot::init_uart(base, baud)?;
+        SERIAL_PORT_BASE = base;
+        Ok(())
     }
 
     /// Write a byte to serial port
     fn write_byte(byte: u8) {
         unsafe {
             use core::arch::asm;
+            let base = SERIAL_PORT_BASE;
             // Wait for transmit ready (bit 5 of line status)
             let mut ready = 0u8;
             while (ready & 0x20) == 0 {
-                asm!("in al, dx", out("al") ready, in("dx") Self::PORT + 5, options(nomem, nostack));
+                asm!("in al, dx", out("al") ready, in("dx") base + 5, options(nomem, nostack));
             }
             // Write byte
-            asm!("out dx, al", in("dx") Self::PORT, in("al") byte, options(nomem, nostack));
+            asm!("out dx, al", in("dx") base, in("al") byte, options(nomem, nostack));
         }
     }
 }
@@ -92,13 +90,40 @@ impl Write for SerialPort {
     }
 }
 
-/// Serial output macro
+/// Size of the buffered serial output ring
+const SERIAL_RING_SIZE: usize = 4096;
+
+/// Pending serial output, drained to the UART by `serial_flush`
+///
+/// Dropping the oldest bytes keeps interrupt handlers from ever waiting
+/// on the UART.
+static SERIAL_RING: SerialRing<SERIAL_RING_SIZE> = SerialRing::new(SerialOverflow::DropOldest);
+
+/// Writer that queues bytes in `SERIAL_RING` instead of hitting the UART
+struct BufferedSerial;
+
+impl Write for BufferedSerial {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        for byte in s.bytes() {
+            // DropOldest never refuses a byte
+            SERIAL_RING.push(byte);
+        }
+        Ok(())
+    }
+}
+
+/// Drain buffered serial output to the UART
+fn serial_flush() {
+    SERIAL_RING.drain(SerialPort::write_byte);
+}
+
+/// Serial output macro (buffered, see `serial_flush`)
 macro_rules! serial_print {
     ($($arg:tt)*) => {{
         #[cfg(not(test))]
         {
             use core::fmt::Write;
-            let _ = write!(SerialPort, $($arg)*);
+            let _ = write!(BufferedSerial, $($arg)*);
         }
     }};
 }
@@ -116,29 +141,60 @@ entry_point!(kernel_main);
 
 /// Main kernel entry point
 fn kernel_main(boot_info: &'static mut BootInfo) -> ! {
-    // Suppress unused warning
+    // The memory map arrives in `boot_info` from bootloader_api; there is
+    // no Multiboot2 entry, so `boot::multiboot2` is not used here
     let _ = boot_info;
 
+    // Program the UART before the first flush
+    unsafe {
+        SerialPort::init();
+    }
+
     serial_println!("========================================");
     serial_println!("i9-12900K Minimal Bare-Metal Kernel");
     serial_println!("ABI Version: 0.1.0");
     serial_println!("========================================\n");
+    serial_flush();
 
-    // Step 1: Initialize CPU (enable SSE/AVX)
-    serial_println!("[1/7] Initializing CPU...");
+    // Give the global allocator its bootstrap heap
+    unsafe {
+        let heap_start = core::ptr::addr_of_mut!(BOOT_HEAP) as u64;
+        allocator::init_heap(heap_start, BOOT_HEAP_SIZE as u64);
+    }
+
+    // Step 0: Initialize CPU (enable SSE/AVX) before any code that may use it
+    serial_println!("[0/8] Initializing CPU...");
     unsafe {
         cpu::init_cpu();
     }
     serial_println!("      ✓ CPU initialized (SSE/AVX enabled)\n");
+    serial_flush();
+
+    // Step 1: Self-test the subsystems before relying on them
+    serial_println!("[1/8] Running self-tests...");
+    let report = selftest::run();
+    for test in selftest::SelfTest::ALL {
+        match report.result(test) {
+            Ok(()) => serial_println!("      ✓ {}", test.name()),
+            Err(reason) => serial_println!("      ✗ {}: {}", test.name(), reason),
+        }
+    }
+    if !report.all_passed() {
+        serial_println!("      ⚠ {} self-test(s) failed", report.failed_count());
+    }
+    serial_println!("");
+    serial_flush();
 
     // Step 2: Initialize interrupts
-    serial_println!("[2/7] Setting up interrupt handlers...");
-    i9_12900k_baremetal_abi::interrupts::init();
+    serial_println!("[2/8] Setting up interrupt handlers...");
+    interrupts::init();
+    interrupts::set_panic_dump(interrupts::subsystem_panic_dump);
     serial_println!("      ✓ IDT loaded with 10 exception handlers\n");
+    serial_flush();
 
     // Step 3: Detect CPU features
-    serial_println!("[3/7] Detecting CPU features...");
-    let features = cpu::CpuFeatures::detect();
+    serial_println!("[3/8] Detecting CPU features...");
+    let features = cpu::cached_features();
     serial_println!("      CPU Feature Support:");
     serial_println!("      - SSE4.2:    {}", if features.sse4_2 { "✓" } else { "✗" });
     serial_println!("      - AVX:       {}", if features.avx { "✓" } else { "✗" });
@@ -148,9 +204,10 @@ fn kernel_main(boot_info: &'static mut BootInfo) -> ! {
     serial_println!("      - RDRAND:    {}", if features.rdrand { "✓" } else { "✗" });
     serial_println!("      - BMI2:      {}", if features.bmi2 { "✓" } else { "✗" });
     serial_println!("");
+    serial_flush();
 
     // Step 4: Detect current core type
-    serial_println!("[4/7] Detecting core type...");
+    serial_println!("[4/8] Detecting core type...");
     let core_type = cpu::get_core_type();
     let core_id = cpu::get_core_id();
     let apic_id = cpu::get_apic_id();
@@ -175,11 +232,12 @@ fn kernel_main(boot_info: &'static mut BootInfo) -> ! {
         }
     }
     serial_println!("");
+    serial_flush();
 
     // Step 5: Initialize cache coherency
-    serial_println!("[5/7] Initializing cache coherency (MESI protocol)...");
-    let mut coherency = CoherencyRuntime::new();
+    serial_println!("[5/8] Initializing cache coherency (MESI protocol)...");
     unsafe {
+        let coherency = &mut *core::ptr::addr_of_mut!(COHERENCY);
         // Initialize P-cores (0-7)
         for p_core in 0..8 {
             coherency.init_core(p_core);
@@ -188,30 +246,61 @@ fn kernel_main(boot_info: &'static mut BootInfo) -> ! {
         for e_core in 8..16 {
             coherency.init_core(e_core);
         }
+        interrupts::set_panic_coherency_runtime(core::ptr::addr_of!(COHERENCY));
     }
     serial_println!("      ✓ Cache coherency initialized for 16 cores");
     serial_println!("        - P-cores: 0-7 (Golden Cove)");
     serial_println!("        - E-cores: 8-15 (Gracemont)");
     serial_println!("");
+    serial_flush();
+
+    // Step 6: Initialize shadow registers
+    serial_println!("[6/8] Initializing shadow registers...");
+    let registered = unsafe {
+        let shadow = &mut *core::ptr::addr_of_mut!(SHADOW_RUNTIME);
+        shadow.init();
+        // Values stay unloaded: the fuse window isn't mapped at this point
+        let mut registered = 0;
+        for index in 0..SHADOW_REGISTER_COUNT {
+            let fuse_addr = memory::layout::FUSE_BASE + index as u64 * 8;
+            if shadow.register_fuse(RegisterId(index), fuse_addr, FuseMode::MTP).is_ok() {
+                registered += 1;
+            }
+        }
+        interrupts::set_panic_shadow_runtime(core::ptr::addr_of!(SHADOW_RUNTIME));
+        registered
+    };
+    serial_println!("      ✓ {} fuse-backed shadow registers registered", registered);
+    serial_println!("");
+    serial_flush();
 
-    // Step 6: Initialize performance monitoring
-    serial_println!("[6/7] Initializing performance counters...");
+    // Step 7: Initialize performance monitoring
+    serial_println!("[7/8] Initializing performance counters...");
     performance::init();
     serial_println!("      ✓ Performance monitoring enabled");
     serial_println!("        - Fixed counters: Instructions, Cycles, Ref Cycles");
     serial_println!("        - Programmable counters: 4 available");
     serial_println!("");
+    serial_flush();
 
-    // Step 7: Read MSRs for platform info
-    serial_println!("[7/7] Reading platform MSRs...");
+    // Step 8: Read MSRs for platform info
+    serial_println!("[8/8] Reading platform MSRs...");
     unsafe {
         let platform_info = cpu::read_msr(cpu::msr::MSR_PLATFORM_INFO);
-        let max_non_turbo = (platform_info >> 8) & 0xFF;
+        let max_non_turbo = cpu::platform_base_ratio(platform_info);
+        let tsc_hz = cpu::calibrate_tsc();
         serial_println!("      Platform Info:");
         serial_println!("      - Max Non-Turbo Ratio: {}x (base frequency)", max_non_turbo);
+        serial_println!("      - TSC Frequency: {} MHz", tsc_hz / 1_000_000);
+        serial_println!(
+            "      - Max Turbo: {} MHz (1 core), {} MHz (8 cores)",
+            cpu::max_turbo_mhz(1),
+            cpu::max_turbo_mhz(8)
+        );
         serial_println!("      - MSR_PLATFORM_INFO: {:#018x}", platform_info);
     }
     serial_println!("");
+    serial_flush();
 
     serial_println!("========================================");
     serial_println!("Kernel Initialization Complete!");
@@ -226,10 +315,14 @@ fn kernel_main(boot_info: &'static mut BootInfo) -> ! {
 /// Main demonstration loop
 fn demonstration_loop() -> ! {
     let mut iteration = 0u64;
+    let start_tsc = cpu::read_tsc();
 
     loop {
         iteration += 1;
 
+        // Push out anything logged since the last pass
+        serial_flush();
+
         // Every 100 million iterations, print status
         if iteration % 100_000_000 == 0 {
             serial_println!("--- Iteration {} ---", iteration / 100_000_000);
@@ -237,6 +330,8 @@ fn demonstration_loop() -> ! {
             // Read TSC
             let tsc = cpu::read_tsc();
             serial_println!("TSC: {} cycles", tsc);
+            let elapsed_ns = cpu::tsc_to_nanos(tsc.wrapping_sub(start_tsc));
+            serial_println!("Elapsed: {} ms", elapsed_ns / 1_000_000);
 
             // Measure IPC
             let ipc = unsafe {
@@ -266,10 +361,10 @@ fn demonstration_loop() -> ! {
             core::arch::asm!("pause", options(nomem, nostack));
         }
 
-        // Every billion iterations, halt briefly
+        // Every billion iterations, idle in C1 until the next interrupt
         if iteration % 1_000_000_000 == 0 {
             unsafe {
-                core::arch::asm!("hlt", options(nomem, nostack));
+                cpu::idle();
             }
         }
     }
@@ -303,8 +398,17 @@ fn panic(info: &core::panic::PanicInfo) -> ! {
     let tsc = cpu::read_tsc();
     serial_println!("  TSC: {}", tsc);
 
+    serial_println!("\nSubsystem State:");
+    // The dump writes to the UART directly; keep it after the buffered lines
+    serial_flush();
+    #[cfg(not(test))]
+    if !interrupts::run_panic_dump(&mut SerialPort) {
+        serial_println!("  (no panic dump registered)");
+    }
+
     serial_println!("\nHalting CPU...");
     serial_println!("========================================\n");
+    serial_flush();
 
     // Halt forever
     loop {
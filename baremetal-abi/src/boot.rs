@@ -4,6 +4,7 @@
 
 use crate::cpu;
 use bootloader_api::{entry_point, BootInfo};
+use core::sync::atomic::{AtomicU8, AtomicUsize, Ordering};
 
 /// Main kernel initialization function
 ///
@@ -31,20 +32,27 @@ pub fn kernel_main(boot_info: &'static mut BootInfo) -> ! {
 
     log("CPU initialized");
 
-    // Detect CPU features
-    let features = cpu::CpuFeatures::detect();
-    log("CPU Features detected:");
-    log_fmt(format_args!("  AVX2: {}", features.avx2));
-    log_fmt(format_args!("  AES-NI: {}", features.aes));
-    log_fmt(format_args!("  AVX-512: {}", features.avx512f));
+    // Detect CPU features (cached for later hot-path checks)
+    cpu::cached_features();
 
-    // Detect core type
-    let core_type = cpu::get_core_type();
-    let core_id = cpu::get_core_id();
+    // Inventory the boot core in one CPUID pass for the banner
+    let info = cpu::SystemInfo::probe();
+    log_fmt(format_args!("CPU Features detected (max CPUID leaf {:#x}):", info.max_leaf));
+    log_fmt(format_args!("  AVX2: {}", info.features.avx2));
+    log_fmt(format_args!("  AES-NI: {}", info.features.aes));
+    log_fmt(format_args!("  AVX-512: {}", info.features.avx512f));
     log_fmt(format_args!(
-        "Running on Core {} - Type: {:?}",
-        core_id, core_type
+        "Running on APIC {} - Type: {:?}",
+        info.apic_id, info.core_info.core_type
     ));
+    for cache in info.caches() {
+        log_fmt(format_args!(
+            "  L{} {:?}: {} KiB",
+            cache.level,
+            cache.cache_type,
+            cache.total_bytes / 1024
+        ));
+    }
 
     // Initialize cache coherency system
     log("Initializing cache coherency...");
@@ -102,6 +110,11 @@ fn kernel_loop() -> ! {
         // Every billion cycles, print a message
         if counter % 1_000_000_000 == 0 {
             log_fmt(format_args!("TSC: {}", tsc));
+
+            // Nothing pending until the next interrupt
+            unsafe {
+                cpu::idle();
+            }
         }
 
         counter += 1;
@@ -138,41 +151,505 @@ fn log_fmt(args: core::fmt::Arguments) {
     let _ = args;
 }
 
-/// Optional serial port module for debugging
-#[cfg(feature = "serial")]
-mod serial {
-    use core::fmt;
+/// Maximum number of memory map entries retained from the boot loader
+pub const MAX_MEMORY_REGIONS: usize = 64;
+
+/// Physical memory region classification
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemoryRegionType {
+    /// Free RAM available to the kernel
+    Usable,
+    /// Reserved by firmware or hardware
+    Reserved,
+    /// ACPI tables, reclaimable after parsing
+    AcpiReclaimable,
+    /// ACPI non-volatile storage
+    AcpiNvs,
+    /// Defective RAM
+    BadMemory,
+}
+
+/// Physical memory region from the boot loader memory map
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryRegion {
+    /// Physical start address
+    pub start: u64,
+    /// Size in bytes
+    pub size: u64,
+    /// Region type
+    pub region_type: MemoryRegionType,
+}
+
+impl MemoryRegion {
+    /// Exclusive end address
+    pub const fn end(&self) -> u64 {
+        self.start.saturating_add(self.size)
+    }
+
+    /// Check if an address falls inside this region
+    pub const fn contains(&self, addr: u64) -> bool {
+        addr >= self.start && addr < self.end()
+    }
+
+    /// Check if two regions share at least one byte
+    pub const fn overlaps(&self, other: &MemoryRegion) -> bool {
+        self.size != 0
+            && other.size != 0
+            && self.start < other.end()
+            && other.start < self.end()
+    }
+}
+
+/// Linear framebuffer description
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FramebufferInfo {
+    /// Physical address of the framebuffer
+    pub address: u64,
+    /// Bytes per scanline
+    pub pitch: u32,
+    /// Width in pixels
+    pub width: u32,
+    /// Height in pixels
+    pub height: u32,
+    /// Bits per pixel
+    pub bpp: u8,
+}
+
+/// Boot information decoded from a Multiboot2 info structure
+#[derive(Debug, Clone, Copy)]
+pub struct Multiboot2Info {
+    regions: [MemoryRegion; MAX_MEMORY_REGIONS],
+    region_count: usize,
+    /// Framebuffer, if the boot loader provided one
+    pub framebuffer: Option<FramebufferInfo>,
+}
+
+impl Multiboot2Info {
+    /// Create an empty boot info
+    pub const fn empty() -> Self {
+        const EMPTY: MemoryRegion = MemoryRegion {
+            start: 0,
+            size: 0,
+            region_type: MemoryRegionType::Reserved,
+        };
+        Self {
+            regions: [EMPTY; MAX_MEMORY_REGIONS],
+            region_count: 0,
+            framebuffer: None,
+        }
+    }
+
+    /// Memory regions from the memory map tag
+    pub fn memory_regions(&self) -> &[MemoryRegion] {
+        &self.regions[..self.region_count]
+    }
+
+    fn push_region(&mut self, region: MemoryRegion) {
+        if self.region_count < MAX_MEMORY_REGIONS {
+            self.regions[self.region_count] = region;
+            self.region_count += 1;
+        }
+    }
+}
+
+/// Multiboot2 boot information parsing
+///
+/// Not used by the kernels in this crate: `bin/minimal.rs` and
+/// `kernel_main` boot through `bootloader_api::entry_point!` and get their
+/// memory map from `BootInfo::memory_regions`. The parser is for a kernel
+/// with its own Multiboot2 entry (e.g. loaded by GRUB), which would call
+/// `parse_at` with the info pointer from EBX after checking
+/// `BOOTLOADER_MAGIC` in EAX.
+pub mod multiboot2 {
+    use super::{FramebufferInfo, MemoryRegion, MemoryRegionType, Multiboot2Info};
+
+    /// Magic value passed in EAX by a Multiboot2-compliant boot loader
+    pub const BOOTLOADER_MAGIC: u32 = 0x36D7_6289;
+
+    /// Terminating tag
+    pub const TAG_END: u32 = 0;
+    /// Memory map tag
+    pub const TAG_MEMORY_MAP: u32 = 6;
+    /// Framebuffer info tag
+    pub const TAG_FRAMEBUFFER: u32 = 8;
+
+    /// Map a Multiboot2 memory map entry type to `MemoryRegionType`
+    pub const fn region_type(mb_type: u32) -> MemoryRegionType {
+        match mb_type {
+            1 => MemoryRegionType::Usable,
+            3 => MemoryRegionType::AcpiReclaimable,
+            4 => MemoryRegionType::AcpiNvs,
+            5 => MemoryRegionType::BadMemory,
+            _ => MemoryRegionType::Reserved,
+        }
+    }
+
+    fn read_u8(bytes: &[u8], offset: usize) -> Option<u8> {
+        bytes.get(offset).copied()
+    }
+
+    fn read_u32(bytes: &[u8], offset: usize) -> Option<u32> {
+        let b = bytes.get(offset..offset + 4)?;
+        Some(u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    fn read_u64(bytes: &[u8], offset: usize) -> Option<u64> {
+        let low = read_u32(bytes, offset)? as u64;
+        let high = read_u32(bytes, offset + 4)? as u64;
+        Some((high << 32) | low)
+    }
+
+    /// Parse a Multiboot2 info structure from a byte buffer
+    ///
+    /// Walks the 8-byte aligned tag list until the end tag, collecting the
+    /// memory map and framebuffer. Unknown tags are skipped.
+    pub fn parse(bytes: &[u8]) -> Result<Multiboot2Info, &'static str> {
+        let total_size = read_u32(bytes, 0).ok_or("truncated header")? as usize;
+        if total_size < 8 || total_size > bytes.len() {
+            return Err("invalid total size");
+        }
+        let bytes = &bytes[..total_size];
+
+        let mut info = Multiboot2Info::empty();
+        let mut offset = 8;
+
+        loop {
+            let tag_type = read_u32(bytes, offset).ok_or("missing end tag")?;
+            let tag_size = read_u32(bytes, offset + 4).ok_or("missing end tag")? as usize;
+
+            if tag_type == TAG_END {
+                return Ok(info);
+            }
+            if tag_size < 8 || offset + tag_size > bytes.len() {
+                return Err("invalid tag size");
+            }
+
+            match tag_type {
+                TAG_MEMORY_MAP => parse_memory_map(&bytes[offset..offset + tag_size], &mut info)?,
+                TAG_FRAMEBUFFER => {
+                    let tag = &bytes[offset..offset + tag_size];
+                    info.framebuffer = Some(FramebufferInfo {
+                        address: read_u64(tag, 8).ok_or("truncated framebuffer tag")?,
+                        pitch: read_u32(tag, 16).ok_or("truncated framebuffer tag")?,
+                        width: read_u32(tag, 20).ok_or("truncated framebuffer tag")?,
+                        height: read_u32(tag, 24).ok_or("truncated framebuffer tag")?,
+                        bpp: read_u8(tag, 28).ok_or("truncated framebuffer tag")?,
+                    });
+                }
+                _ => {}
+            }
+
+            // Tags are padded to 8-byte alignment
+            offset += (tag_size + 7) & !7;
+        }
+    }
+
+    fn parse_memory_map(tag: &[u8], info: &mut Multiboot2Info) -> Result<(), &'static str> {
+        let entry_size = read_u32(tag, 8).ok_or("truncated memory map tag")? as usize;
+        if entry_size < 24 {
+            return Err("invalid memory map entry size");
+        }
+
+        let mut offset = 16;
+        while offset + entry_size <= tag.len() {
+            info.push_region(MemoryRegion {
+                start: read_u64(tag, offset).ok_or("truncated memory map entry")?,
+                size: read_u64(tag, offset + 8).ok_or("truncated memory map entry")?,
+                region_type: region_type(
+                    read_u32(tag, offset + 16).ok_or("truncated memory map entry")?,
+                ),
+            });
+            offset += entry_size;
+        }
+
+        Ok(())
+    }
+
+    /// Parse the Multiboot2 info structure at a physical address
+    ///
+    /// # Safety
+    /// `addr` must point to a valid, identity-mapped Multiboot2 info structure
+    pub unsafe fn parse_at(addr: u64) -> Result<Multiboot2Info, &'static str> {
+        let total_size = core::ptr::read_unaligned(addr as *const u32) as usize;
+        let bytes = core::slice::from_raw_parts(addr as *const u8, total_size);
+        parse(bytes)
+    }
+}
+
+/// COM1 16550 UART I/O port base
+pub const COM1_PORT: u16 = 0x3F8;
+
+/// COM2 16550 UART I/O port base
+pub const COM2_PORT: u16 = 0x2F8;
+
+/// 16550 UART input clock divided by 16 (baud at divisor 1)
+pub const UART_BASE_BAUD: u32 = 115_200;
+
+/// Divisor latch value for a baud rate
+///
+/// Returns `None` for rates the UART clock cannot divide down to exactly,
+/// or whose divisor does not fit the 16-bit divisor latch.
+pub const fn uart_divisor(baud: u32) -> Option<u16> {
+    if baud == 0 || baud > UART_BASE_BAUD || !UART_BASE_BAUD.is_multiple_of(baud) {
+        return None;
+    }
+
+    let divisor = UART_BASE_BAUD / baud;
+    if divisor > u16::MAX as u32 {
+        return None;
+    }
+    Some(divisor as u16)
+}
+
+/// Program the 16550 UART at `base` for `baud`, 8N1 with FIFOs enabled
+///
+/// Fails without touching the port if `uart_divisor` has no divisor for
+/// the rate.
+///
+/// # Safety
+/// `base` must be the I/O port base of a 16550-compatible UART.
+pub unsafe fn init_uart(base: u16, baud: u32) -> Result<(), &'static str> {
     use x86_64::instructions::port::Port;
 
-    const SERIAL_IO_PORT: u16 = 0x3F8; // COM1
+    let divisor = match uart_divisor(baud) {
+        Some(divisor) => divisor,
+        None => return Err("Unsupported baud rate"),
+    };
+    let [divisor_lo, divisor_hi] = divisor.to_le_bytes();
+
+    Port::<u8>::new(base + 1).write(0x00); // Disable interrupts
+    Port::<u8>::new(base + 3).write(0x80); // Enable DLAB
+    Port::<u8>::new(base).write(divisor_lo); // Divisor low byte
+    Port::<u8>::new(base + 1).write(divisor_hi); // Divisor high byte
+    Port::<u8>::new(base + 3).write(0x03); // 8N1
+    Port::<u8>::new(base + 2).write(0xC7); // Enable FIFO
+    Port::<u8>::new(base + 4).write(0x0B); // IRQs enabled, RTS/DSR set
+    Ok(())
+}
 
-    pub struct SerialPort;
+/// Baud rate produced by a divisor latch value (0 for divisor 0)
+pub const fn uart_baud(divisor: u16) -> u32 {
+    if divisor == 0 {
+        return 0;
+    }
+    UART_BASE_BAUD / divisor as u32
+}
 
-    pub fn init() {
-        unsafe {
-            let mut port = Port::<u8>::new(SERIAL_IO_PORT + 1);
-            port.write(0x00); // Disable interrupts
+/// What `SerialRing::push` does when the ring is full
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SerialOverflow {
+    /// Discard the oldest queued byte to make room
+    DropOldest,
+    /// Refuse the byte; the caller drains and retries
+    Block,
+}
 
-            let mut port = Port::<u8>::new(SERIAL_IO_PORT + 3);
-            port.write(0x80); // Enable DLAB
+/// Lock-free byte ring decoupling serial output from the UART
+///
+/// Producers (`serial_print!`, interrupt handlers) push bytes without
+/// touching the UART; the main loop drains them with the line-status
+/// busy-wait. Any number of producers and consumers may race: each claims
+/// a position by CAS and a per-slot sequence number marks when the byte in
+/// it is ready, so an interrupt pushing while the main loop is mid-push
+/// gets its own slot instead of overwriting one.
+pub struct SerialRing<const N: usize> {
+    buf: [AtomicU8; N],
+    /// Per slot: position + 1 once its byte is written, position + N once
+    /// consumed (free for the next lap)
+    seq: [AtomicUsize; N],
+    /// Total positions claimed by producers (index = head % N)
+    head: AtomicUsize,
+    /// Total positions claimed by consumers (index = tail % N)
+    tail: AtomicUsize,
+    dropped: AtomicUsize,
+    policy: SerialOverflow,
+}
 
-            let mut port = Port::<u8>::new(SERIAL_IO_PORT);
-            port.write(0x03); // Divisor low byte (38400 baud)
+/// Outcome of trying to take the oldest byte
+enum Take {
+    Byte(u8),
+    Empty,
+    /// The oldest slot is claimed but its producer hasn't written it yet
+    Pending,
+}
 
-            let mut port = Port::<u8>::new(SERIAL_IO_PORT + 1);
-            port.write(0x00); // Divisor high byte
+impl<const N: usize> SerialRing<N> {
+    /// Create an empty ring
+    pub const fn new(policy: SerialOverflow) -> Self {
+        let mut seq = [const { AtomicUsize::new(0) }; N];
+        let mut i = 0;
+        while i < N {
+            seq[i] = AtomicUsize::new(i);
+            i += 1;
+        }
 
-            let mut port = Port::<u8>::new(SERIAL_IO_PORT + 3);
-            port.write(0x03); // 8N1
+        Self {
+            buf: [const { AtomicU8::new(0) }; N],
+            seq,
+            head: AtomicUsize::new(0),
+            tail: AtomicUsize::new(0),
+            dropped: AtomicUsize::new(0),
+            policy,
+        }
+    }
 
-            let mut port = Port::<u8>::new(SERIAL_IO_PORT + 2);
-            port.write(0xC7); // Enable FIFO
+    /// Queue a byte; `false` only under `Block` with the ring full
+    ///
+    /// Under `DropOldest` a full ring gives up its oldest byte. If that byte
+    /// belongs to a producer this one interrupted, the new byte is dropped
+    /// instead, since waiting for it would never finish.
+    pub fn push(&self, byte: u8) -> bool {
+        loop {
+            if let Some(pos) = self.reserve() {
+                self.publish(pos, byte);
+                return true;
+            }
+
+            match self.policy {
+                SerialOverflow::Block => return false,
+                SerialOverflow::DropOldest => match self.take() {
+                    Take::Byte(_) => {
+                        self.dropped.fetch_add(1, Ordering::Relaxed);
+                    }
+                    Take::Pending => {
+                        self.dropped.fetch_add(1, Ordering::Relaxed);
+                        return true;
+                    }
+                    // A consumer made room meanwhile
+                    Take::Empty => {}
+                },
+            }
+        }
+    }
+
+    /// Claim the next free position, or `None` if the ring is full
+    fn reserve(&self) -> Option<usize> {
+        loop {
+            let pos = self.head.load(Ordering::Relaxed);
+            let seq = self.seq[pos % N].load(Ordering::Acquire);
+
+            match seq.wrapping_sub(pos) as isize {
+                0 => {
+                    if self
+                        .head
+                        .compare_exchange_weak(
+                            pos,
+                            pos.wrapping_add(1),
+                            Ordering::Relaxed,
+                            Ordering::Relaxed,
+                        )
+                        .is_ok()
+                    {
+                        return Some(pos);
+                    }
+                }
+                // Slot still holds the byte from the previous lap
+                lag if lag < 0 => return None,
+                // Another producer claimed `pos`; reload
+                _ => {}
+            }
+            core::hint::spin_loop();
+        }
+    }
+
+    /// Store `byte` at a claimed position and mark it ready
+    fn publish(&self, pos: usize, byte: u8) {
+        self.buf[pos % N].store(byte, Ordering::Relaxed);
+        self.seq[pos % N].store(pos.wrapping_add(1), Ordering::Release);
+    }
 
-            let mut port = Port::<u8>::new(SERIAL_IO_PORT + 4);
-            port.write(0x0B); // IRQs enabled, RTS/DSR set
+    /// Take the oldest queued byte
+    ///
+    /// Returns `None` both when the ring is empty and when the oldest byte
+    /// is still being written by an interrupted producer.
+    pub fn pop(&self) -> Option<u8> {
+        match self.take() {
+            Take::Byte(byte) => Some(byte),
+            Take::Empty | Take::Pending => None,
         }
     }
 
+    /// Claim the oldest position if its byte is ready
+    fn take(&self) -> Take {
+        loop {
+            let pos = self.tail.load(Ordering::Relaxed);
+            let seq = self.seq[pos % N].load(Ordering::Acquire);
+
+            match seq.wrapping_sub(pos.wrapping_add(1)) as isize {
+                0 => {
+                    if self
+                        .tail
+                        .compare_exchange_weak(
+                            pos,
+                            pos.wrapping_add(1),
+                            Ordering::Relaxed,
+                            Ordering::Relaxed,
+                        )
+                        .is_ok()
+                    {
+                        let byte = self.buf[pos % N].load(Ordering::Relaxed);
+                        // Hand the slot to the producer one lap ahead
+                        self.seq[pos % N].store(pos.wrapping_add(N), Ordering::Release);
+                        return Take::Byte(byte);
+                    }
+                }
+                lag if lag < 0 => {
+                    return if self.head.load(Ordering::Relaxed) == pos {
+                        Take::Empty
+                    } else {
+                        Take::Pending
+                    };
+                }
+                // Another consumer took `pos`; reload
+                _ => {}
+            }
+            core::hint::spin_loop();
+        }
+    }
+
+    /// Pop every queued byte into `sink`; returns how many were drained
+    pub fn drain(&self, mut sink: impl FnMut(u8)) -> usize {
+        let mut drained = 0;
+        while let Some(byte) = self.pop() {
+            sink(byte);
+            drained += 1;
+        }
+        drained
+    }
+
+    /// Number of queued bytes (including any still being written)
+    pub fn len(&self) -> usize {
+        let tail = self.tail.load(Ordering::Acquire);
+        self.head.load(Ordering::Acquire).wrapping_sub(tail)
+    }
+
+    /// Check if no bytes are queued
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Bytes discarded by `DropOldest` so far
+    pub fn dropped(&self) -> usize {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+/// Optional serial port module for debugging
+#[cfg(feature = "serial")]
+mod serial {
+    use core::fmt;
+    use x86_64::instructions::port::Port;
+
+    const SERIAL_IO_PORT: u16 = super::COM1_PORT;
+    const SERIAL_BAUD: u32 = 38_400;
+
+    pub struct SerialPort;
+
+    pub fn init() {
+        // 38400 divides the UART clock exactly, so this cannot fail
+        let _ = unsafe { super::init_uart(SERIAL_IO_PORT, SERIAL_BAUD) };
+    }
+
     pub fn write_byte(byte: u8) {
         unsafe {
             let mut port = Port::<u8>::new(SERIAL_IO_PORT);
@@ -196,3 +673,255 @@ mod serial {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    extern crate alloc;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_uart_divisor() {
+        assert_eq!(uart_divisor(115_200), Some(1));
+        assert_eq!(uart_divisor(38_400), Some(3));
+        assert_eq!(uart_divisor(9_600), Some(12));
+        assert_eq!(uart_divisor(0), None);
+        assert_eq!(uart_divisor(230_400), None);
+        assert_eq!(uart_divisor(100_000), None);
+
+        // Divisors past the 16-bit latch would silently truncate
+        assert_eq!(uart_divisor(2), Some(57_600));
+        assert_eq!(uart_divisor(1), None);
+
+        for baud in [115_200, 57_600, 38_400, 19_200, 9_600] {
+            assert_eq!(uart_baud(uart_divisor(baud).unwrap()), baud);
+        }
+        assert_eq!(uart_baud(0), 0);
+    }
+
+    #[test]
+    fn test_serial_ring_fifo_and_wrap() {
+        let ring = SerialRing::<4>::new(SerialOverflow::Block);
+        assert!(ring.is_empty());
+        assert_eq!(ring.pop(), None);
+
+        // Many passes around the ring keep byte order intact
+        let mut out = Vec::new();
+        for chunk in b"hello, serial ring".chunks(3) {
+            for &byte in chunk {
+                assert!(ring.push(byte));
+            }
+            assert_eq!(ring.len(), chunk.len());
+            assert_eq!(ring.drain(|byte| out.push(byte)), chunk.len());
+        }
+        assert_eq!(out, b"hello, serial ring");
+        assert!(ring.is_empty());
+    }
+
+    #[test]
+    fn test_serial_ring_block_when_full() {
+        let ring = SerialRing::<3>::new(SerialOverflow::Block);
+        assert!(ring.push(b'a'));
+        assert!(ring.push(b'b'));
+        assert!(ring.push(b'c'));
+        assert!(!ring.push(b'd'));
+        assert_eq!(ring.len(), 3);
+        assert_eq!(ring.dropped(), 0);
+
+        // Draining one byte makes room again
+        assert_eq!(ring.pop(), Some(b'a'));
+        assert!(ring.push(b'd'));
+
+        let mut out = Vec::new();
+        ring.drain(|byte| out.push(byte));
+        assert_eq!(out, b"bcd");
+    }
+
+    #[test]
+    fn test_serial_ring_drop_oldest() {
+        let ring = SerialRing::<3>::new(SerialOverflow::DropOldest);
+        for &byte in b"abcde" {
+            assert!(ring.push(byte));
+        }
+        assert_eq!(ring.len(), 3);
+        assert_eq!(ring.dropped(), 2);
+
+        let mut out = Vec::new();
+        assert_eq!(ring.drain(|byte| out.push(byte)), 3);
+        assert_eq!(out, b"cde");
+    }
+
+    #[test]
+    fn test_serial_ring_interrupted_producer() {
+        let ring = SerialRing::<3>::new(SerialOverflow::DropOldest);
+
+        // Main loop claims a slot, then an interrupt pushes before it writes
+        let pos = ring.reserve().unwrap();
+        assert!(ring.push(b'i'));
+        assert_eq!(ring.len(), 2);
+
+        // Bytes stay in claim order: nothing drains past the pending slot
+        assert_eq!(ring.pop(), None);
+
+        // Once full, the oldest byte is the pending one: the new byte goes
+        assert!(ring.push(b'j'));
+        assert!(ring.push(b'k'));
+        assert_eq!(ring.dropped(), 1);
+
+        ring.publish(pos, b'm');
+        let mut out = Vec::new();
+        assert_eq!(ring.drain(|byte| out.push(byte)), 3);
+        assert_eq!(out, b"mij");
+        assert!(ring.is_empty());
+    }
+
+    fn push_u32(buf: &mut Vec<u8>, value: u32) {
+        buf.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn push_u64(buf: &mut Vec<u8>, value: u64) {
+        buf.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn pad_to_8(buf: &mut Vec<u8>) {
+        while buf.len() % 8 != 0 {
+            buf.push(0);
+        }
+    }
+
+    /// Build a Multiboot2 info buffer with a memory map, framebuffer and end tag
+    fn build_multiboot2_info() -> Vec<u8> {
+        let mut buf = Vec::new();
+        push_u32(&mut buf, 0); // total_size (patched below)
+        push_u32(&mut buf, 0); // reserved
+
+        // Unknown tag (boot loader name) with unaligned size
+        push_u32(&mut buf, 2);
+        push_u32(&mut buf, 13);
+        buf.extend_from_slice(b"GRUB\0");
+        pad_to_8(&mut buf);
+
+        // Memory map tag: 3 entries of 24 bytes
+        push_u32(&mut buf, multiboot2::TAG_MEMORY_MAP);
+        push_u32(&mut buf, 16 + 3 * 24);
+        push_u32(&mut buf, 24); // entry_size
+        push_u32(&mut buf, 0); // entry_version
+        for &(base, len, ty) in &[
+            (0x0u64, 0x9_FC00u64, 1u32),
+            (0xF_0000, 0x1_0000, 2),
+            (0x10_0000, 0x7FF0_0000, 1),
+        ] {
+            push_u64(&mut buf, base);
+            push_u64(&mut buf, len);
+            push_u32(&mut buf, ty);
+            push_u32(&mut buf, 0);
+        }
+        pad_to_8(&mut buf);
+
+        // Framebuffer tag
+        push_u32(&mut buf, multiboot2::TAG_FRAMEBUFFER);
+        push_u32(&mut buf, 31);
+        push_u64(&mut buf, 0xFD00_0000);
+        push_u32(&mut buf, 4096); // pitch
+        push_u32(&mut buf, 1024); // width
+        push_u32(&mut buf, 768); // height
+        buf.push(32); // bpp
+        buf.push(1); // type
+        buf.push(0); // reserved
+        pad_to_8(&mut buf);
+
+        // End tag
+        push_u32(&mut buf, multiboot2::TAG_END);
+        push_u32(&mut buf, 8);
+
+        let total = buf.len() as u32;
+        buf[0..4].copy_from_slice(&total.to_le_bytes());
+        buf
+    }
+
+    #[test]
+    fn test_multiboot2_parse_memory_map() {
+        let buf = build_multiboot2_info();
+        let info = multiboot2::parse(&buf).unwrap();
+
+        let regions = info.memory_regions();
+        assert_eq!(regions.len(), 3);
+        assert_eq!(regions[0].start, 0x0);
+        assert_eq!(regions[0].size, 0x9_FC00);
+        assert_eq!(regions[0].region_type, MemoryRegionType::Usable);
+        assert_eq!(regions[1].region_type, MemoryRegionType::Reserved);
+        assert_eq!(regions[2].start, 0x10_0000);
+        assert_eq!(regions[2].region_type, MemoryRegionType::Usable);
+    }
+
+    #[test]
+    fn test_multiboot2_parse_framebuffer() {
+        let buf = build_multiboot2_info();
+        let info = multiboot2::parse(&buf).unwrap();
+
+        let fb = info.framebuffer.unwrap();
+        assert_eq!(fb.address, 0xFD00_0000);
+        assert_eq!(fb.pitch, 4096);
+        assert_eq!(fb.width, 1024);
+        assert_eq!(fb.height, 768);
+        assert_eq!(fb.bpp, 32);
+    }
+
+    #[test]
+    fn test_multiboot2_missing_end_tag() {
+        let mut buf = build_multiboot2_info();
+        let len = buf.len() - 8;
+        buf.truncate(len);
+        buf[0..4].copy_from_slice(&(len as u32).to_le_bytes());
+
+        assert_eq!(multiboot2::parse(&buf).unwrap_err(), "missing end tag");
+    }
+
+    fn region(start: u64, size: u64) -> MemoryRegion {
+        MemoryRegion {
+            start,
+            size,
+            region_type: MemoryRegionType::Usable,
+        }
+    }
+
+    #[test]
+    fn test_memory_region_contains() {
+        let r = region(0x1000, 0x1000);
+        assert_eq!(r.end(), 0x2000);
+        assert!(r.contains(0x1000));
+        assert!(r.contains(0x1FFF));
+        assert!(!r.contains(0x0FFF));
+        assert!(!r.contains(0x2000));
+
+        // Zero-size region contains nothing
+        let empty = region(0x1000, 0);
+        assert!(!empty.contains(0x1000));
+    }
+
+    #[test]
+    fn test_memory_region_overlaps() {
+        let a = region(0x1000, 0x1000);
+
+        assert!(a.overlaps(&region(0x1800, 0x1000)));
+        assert!(a.overlaps(&region(0x0, 0x4000)));
+        assert!(a.overlaps(&a));
+
+        // Adjacent regions do not overlap
+        assert!(!a.overlaps(&region(0x2000, 0x1000)));
+        assert!(!a.overlaps(&region(0x0, 0x1000)));
+
+        // Zero-size regions never overlap
+        assert!(!a.overlaps(&region(0x1800, 0)));
+        assert!(!region(0x1800, 0).overlaps(&a));
+    }
+
+    #[test]
+    fn test_multiboot2_region_type_mapping() {
+        assert_eq!(multiboot2::region_type(1), MemoryRegionType::Usable);
+        assert_eq!(multiboot2::region_type(2), MemoryRegionType::Reserved);
+        assert_eq!(multiboot2::region_type(3), MemoryRegionType::AcpiReclaimable);
+        assert_eq!(multiboot2::region_type(4), MemoryRegionType::AcpiNvs);
+        assert_eq!(multiboot2::region_type(5), MemoryRegionType::BadMemory);
+    }
+}
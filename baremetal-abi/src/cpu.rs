@@ -2,8 +2,11 @@
 //!
 //! Provides MSR access, CPUID queries, and core type detection
 
+use crate::performance::msr as perf_msr;
 use crate::{CoreAffinity, CoreType};
 use core::arch::asm;
+use core::cell::UnsafeCell;
+use core::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use x86_64::registers::model_specific::Msr;
 
 /// Model Specific Registers for i9-12900K
@@ -24,6 +27,14 @@ pub mod msr {
     pub const MSR_APIC_BASE: u32 = 0x1B;
     /// Time Stamp Counter
     pub const MSR_TSC: u32 = 0x10;
+    /// Extended Feature Enable Register
+    pub const IA32_EFER: u32 = 0xC000_0080;
+    /// SYSCALL/SYSRET segment selectors
+    pub const IA32_STAR: u32 = 0xC000_0081;
+    /// SYSCALL target RIP
+    pub const IA32_LSTAR: u32 = 0xC000_0082;
+    /// SYSCALL RFLAGS mask
+    pub const IA32_FMASK: u32 = 0xC000_0084;
 }
 
 /// Read a Model Specific Register
@@ -61,6 +72,70 @@ pub unsafe fn write_msr(msr: u32, value: u64) {
     );
 }
 
+/// MSRs accessible through the checked `try_read_msr`/`try_write_msr` API
+///
+/// The SYSCALL MSRs are deliberately excluded; they are only programmed by
+/// `abi::install_syscall_handler`.
+pub const SUPPORTED_MSRS: &[u32] = &[
+    msr::MSR_HWP_REQUEST,
+    msr::MSR_TURBO_RATIO_LIMIT,
+    msr::MSR_PLATFORM_INFO,
+    msr::MSR_ENERGY_PERF_BIAS,
+    msr::MSR_HW_FEEDBACK_PTR,
+    msr::MSR_PKG_CST_CONFIG_CONTROL,
+    msr::MSR_APIC_BASE,
+    msr::MSR_TSC,
+    perf_msr::IA32_PMC0,
+    perf_msr::IA32_PMC1,
+    perf_msr::IA32_PERFEVTSEL0,
+    perf_msr::IA32_PERFEVTSEL1,
+    perf_msr::IA32_FIXED_CTR0,
+    perf_msr::IA32_FIXED_CTR1,
+    perf_msr::IA32_FIXED_CTR2,
+    perf_msr::IA32_FIXED_CTR_CTRL,
+    perf_msr::IA32_PERF_GLOBAL_CTRL,
+];
+
+/// Check if an MSR index is in the supported allowlist
+pub const fn is_supported_msr(msr: u32) -> bool {
+    let mut i = 0;
+    while i < SUPPORTED_MSRS.len() {
+        if SUPPORTED_MSRS[i] == msr {
+            return true;
+        }
+        i += 1;
+    }
+    false
+}
+
+/// Read a Model Specific Register, rejecting unsupported indices
+///
+/// Unlike `read_msr`, an MSR outside `SUPPORTED_MSRS` returns an error
+/// instead of raising #GP.
+///
+/// # Safety
+/// Must be called from ring 0
+#[inline]
+pub unsafe fn try_read_msr(msr: u32) -> Result<u64, &'static str> {
+    if !is_supported_msr(msr) {
+        return Err("unsupported MSR");
+    }
+    Ok(read_msr(msr))
+}
+
+/// Write a Model Specific Register, rejecting unsupported indices
+///
+/// # Safety
+/// Must be called from ring 0 with a value valid for the target MSR
+#[inline]
+pub unsafe fn try_write_msr(msr: u32, value: u64) -> Result<(), &'static str> {
+    if !is_supported_msr(msr) {
+        return Err("unsupported MSR");
+    }
+    write_msr(msr, value);
+    Ok(())
+}
+
 /// CPUID result
 #[derive(Debug, Clone, Copy)]
 #[repr(C)]
@@ -96,26 +171,51 @@ pub fn cpuid(leaf: u32, subleaf: u32) -> CpuidResult {
     CpuidResult { eax, ebx, ecx, edx }
 }
 
-/// Get current core type (P-core or E-core)
-pub fn get_core_type() -> CoreType {
-    // CPUID leaf 0x1A provides native model ID and core type
-    let result = cpuid(0x1A, 0);
+/// Hybrid core information from CPUID leaf 0x1A
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CoreInfo {
+    /// Core type (P-core or E-core)
+    pub core_type: CoreType,
+    /// Native model ID (EAX[23:0]), distinguishes core generations
+    pub native_model_id: u32,
+}
 
+/// Decode the EAX value of CPUID leaf 0x1A
+pub const fn decode_core_info(eax: u32) -> CoreInfo {
     // EAX[31:24] contains core type
-    let core_type_id = (result.eax >> 24) as u8;
-
-    match core_type_id {
+    let core_type = match (eax >> 24) as u8 {
         0x40 => CoreType::Performance,  // Intel Core (P-core)
         0x20 => CoreType::Efficiency,   // Intel Atom (E-core)
         _ => CoreType::Unknown,
+    };
+
+    CoreInfo {
+        core_type,
+        // EAX[23:0] contains native model ID
+        native_model_id: eax & 0x00FF_FFFF,
     }
 }
 
+/// Get current core type and native model ID
+pub fn get_core_info() -> CoreInfo {
+    // CPUID leaf 0x1A provides native model ID and core type
+    let result = cpuid(0x1A, 0);
+    decode_core_info(result.eax)
+}
+
+/// Get current core type (P-core or E-core)
+pub fn get_core_type() -> CoreType {
+    get_core_info().core_type
+}
+
+/// Decode the initial APIC ID from CPUID leaf 0x1 (EBX[31:24])
+pub const fn apic_id_from_leaf_1(leaf_1: CpuidResult) -> u32 {
+    leaf_1.ebx >> 24
+}
+
 /// Get current logical processor ID (APIC ID)
 pub fn get_apic_id() -> u32 {
-    // CPUID leaf 0x1: EDX[31:24] contains initial APIC ID
-    let result = cpuid(0x1, 0);
-    (result.edx >> 24) & 0xFF
+    apic_id_from_leaf_1(cpuid(0x1, 0))
 }
 
 /// Get current core ID (0-15 for i9-12900K)
@@ -127,6 +227,74 @@ pub fn get_core_id() -> u8 {
     (apic_id & 0xFF) as u8
 }
 
+/// First APIC ID of the E-core clusters on i9-12900K
+pub const E_CORE_APIC_BASE: u32 = 32;
+
+/// Number of per-core slots (8 P-cores + 8 E-cores)
+pub const CORE_SLOTS: usize = 16;
+
+/// Map an APIC ID to a physical core slot in `0..CORE_SLOTS`
+///
+/// i9-12900K APIC IDs:
+/// - P-cores 0-7: 0-15, two per core (HT siblings share a slot)
+/// - E-cores 0-7: 32, 34, ..., 46 (no HT, IDs step by 2)
+///
+/// P-cores land in slots 0-7 and E-cores in 8-15. IDs outside these
+/// ranges wrap into the same 0-15 range rather than panicking.
+pub const fn core_slot_from_apic(apic_id: u32) -> usize {
+    if apic_id < E_CORE_APIC_BASE {
+        (apic_id >> 1) as usize & 7
+    } else {
+        8 + (((apic_id - E_CORE_APIC_BASE) >> 1) as usize & 7)
+    }
+}
+
+/// Physical core slot (0-15) of the executing core
+#[inline]
+pub fn current_core_slot() -> usize {
+    core_slot_from_apic(get_apic_id())
+}
+
+/// Per-core storage indexed by the executing core's slot
+///
+/// Holds one `T` per core (perf counters, cache controllers) so hot paths
+/// need no lock. `N` must cover every slot in use, normally `CORE_SLOTS`.
+pub struct CoreLocal<T, const N: usize> {
+    slots: UnsafeCell<[T; N]>,
+}
+
+unsafe impl<T: Send + Sync, const N: usize> Sync for CoreLocal<T, N> {}
+
+impl<T, const N: usize> CoreLocal<T, N> {
+    /// Create storage with one initial value per slot
+    pub const fn new(values: [T; N]) -> Self {
+        Self {
+            slots: UnsafeCell::new(values),
+        }
+    }
+
+    /// Current core's value
+    ///
+    /// Panics if the current slot is `N` or above.
+    pub fn get(&self) -> &T {
+        // SAFETY: shared access; `get_mut` callers guarantee exclusivity
+        unsafe { &(*self.slots.get())[current_core_slot()] }
+    }
+
+    /// Current core's value, mutably
+    ///
+    /// Panics if the current slot is `N` or above.
+    ///
+    /// # Safety
+    /// No other reference to this core's slot may be live: disable
+    /// interrupts that touch it, and keep the HT sibling (which shares
+    /// the slot) away from it.
+    #[allow(clippy::mut_from_ref)]
+    pub unsafe fn get_mut(&self) -> &mut T {
+        &mut (*self.slots.get())[current_core_slot()]
+    }
+}
+
 /// Check if current core is a P-core
 #[inline]
 pub fn is_performance_core() -> bool {
@@ -139,8 +307,156 @@ pub fn is_efficiency_core() -> bool {
     get_core_type() == CoreType::Efficiency
 }
 
+/// Cache type from CPUID leaf 0x4 EAX[4:0]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum CacheType {
+    Data = 1,
+    Instruction = 2,
+    Unified = 3,
+}
+
+/// One cache level described by a CPUID leaf 0x4 subleaf
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CacheInfo {
+    /// Cache level (1 = L1, ...)
+    pub level: u8,
+    /// Data, instruction or unified cache
+    pub cache_type: CacheType,
+    /// Coherency line size in bytes
+    pub line_size: u32,
+    /// Number of sets
+    pub sets: u32,
+    /// Ways of associativity
+    pub ways: u32,
+    /// Total size (line_size * ways * sets, times physical line partitions)
+    pub total_bytes: u64,
+}
+
+/// Decode one CPUID leaf 0x4 subleaf (`None` once the type field is 0)
+pub const fn decode_cache_info(leaf_4: CpuidResult) -> Option<CacheInfo> {
+    // EAX[4:0] = cache type, EAX[7:5] = level
+    let cache_type = match leaf_4.eax & 0x1F {
+        1 => CacheType::Data,
+        2 => CacheType::Instruction,
+        3 => CacheType::Unified,
+        _ => return None,
+    };
+
+    // EBX[31:22] = ways - 1, EBX[21:12] = partitions - 1, EBX[11:0] = line size - 1
+    let ways = (leaf_4.ebx >> 22) + 1;
+    let partitions = ((leaf_4.ebx >> 12) & 0x3FF) + 1;
+    let line_size = (leaf_4.ebx & 0xFFF) + 1;
+    // ECX = sets - 1
+    let sets = leaf_4.ecx.wrapping_add(1);
+
+    Some(CacheInfo {
+        level: ((leaf_4.eax >> 5) & 0x7) as u8,
+        cache_type,
+        line_size,
+        sets,
+        ways,
+        total_bytes: line_size as u64 * ways as u64 * partitions as u64 * sets as u64,
+    })
+}
+
+/// Upper bound on leaf 0x4 subleaves walked, in case the type never reads 0
+const MAX_CACHE_SUBLEAVES: u32 = 16;
+
+/// Iterator over the cache levels reported by CPUID leaf 0x4
+///
+/// `leaf_4` returns the CPUID result for a subleaf; walking stops at the
+/// first subleaf whose type field is 0.
+pub struct CacheIter<F: FnMut(u32) -> CpuidResult> {
+    leaf_4: F,
+    subleaf: u32,
+}
+
+impl<F: FnMut(u32) -> CpuidResult> CacheIter<F> {
+    /// Walk caches using a custom leaf 0x4 source
+    pub const fn new(leaf_4: F) -> Self {
+        Self { leaf_4, subleaf: 0 }
+    }
+}
+
+impl<F: FnMut(u32) -> CpuidResult> Iterator for CacheIter<F> {
+    type Item = CacheInfo;
+
+    fn next(&mut self) -> Option<CacheInfo> {
+        if self.subleaf >= MAX_CACHE_SUBLEAVES {
+            return None;
+        }
+
+        let info = decode_cache_info((self.leaf_4)(self.subleaf));
+        if info.is_some() {
+            self.subleaf += 1;
+        } else {
+            // Stay exhausted once the terminator is seen
+            self.subleaf = MAX_CACHE_SUBLEAVES;
+        }
+        info
+    }
+}
+
+/// Enumerate this CPU's caches from CPUID leaf 0x4
+pub fn enumerate_caches() -> CacheIter<fn(u32) -> CpuidResult> {
+    fn leaf_4(subleaf: u32) -> CpuidResult {
+        cpuid(0x4, subleaf)
+    }
+
+    CacheIter::new(leaf_4)
+}
+
+/// Bit positions of `CpuFeatures::to_bits`
+///
+/// These are a stable ABI: bits are only ever appended, never reassigned.
+/// Bits 19-31 are reserved and read back as zero.
+pub mod feature_bits {
+    /// `CpuFeatures::sse`
+    pub const SSE: u32 = 1 << 0;
+    /// `CpuFeatures::sse2`
+    pub const SSE2: u32 = 1 << 1;
+    /// `CpuFeatures::sse3`
+    pub const SSE3: u32 = 1 << 2;
+    /// `CpuFeatures::ssse3`
+    pub const SSSE3: u32 = 1 << 3;
+    /// `CpuFeatures::sse4_1`
+    pub const SSE4_1: u32 = 1 << 4;
+    /// `CpuFeatures::sse4_2`
+    pub const SSE4_2: u32 = 1 << 5;
+    /// `CpuFeatures::avx`
+    pub const AVX: u32 = 1 << 6;
+    /// `CpuFeatures::avx2`
+    pub const AVX2: u32 = 1 << 7;
+    /// `CpuFeatures::avx512f`
+    pub const AVX512F: u32 = 1 << 8;
+    /// `CpuFeatures::aes`
+    pub const AES: u32 = 1 << 9;
+    /// `CpuFeatures::rdrand`
+    pub const RDRAND: u32 = 1 << 10;
+    /// `CpuFeatures::rdseed`
+    pub const RDSEED: u32 = 1 << 11;
+    /// `CpuFeatures::bmi1`
+    pub const BMI1: u32 = 1 << 12;
+    /// `CpuFeatures::bmi2`
+    pub const BMI2: u32 = 1 << 13;
+    /// `CpuFeatures::fma`
+    pub const FMA: u32 = 1 << 14;
+    /// `CpuFeatures::movbe`
+    pub const MOVBE: u32 = 1 << 15;
+    /// `CpuFeatures::xsave`
+    pub const XSAVE: u32 = 1 << 16;
+    /// `CpuFeatures::hypervisor`
+    pub const HYPERVISOR: u32 = 1 << 17;
+    /// `CpuFeatures::clflushopt`
+    pub const CLFLUSHOPT: u32 = 1 << 18;
+
+    /// Every assigned bit
+    pub const ALL: u32 = (1 << 19) - 1;
+}
+
 /// CPU Features detected via CPUID
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct CpuFeatures {
     pub sse: bool,
     pub sse2: bool,
@@ -160,14 +476,17 @@ pub struct CpuFeatures {
     pub movbe: bool,
     pub xsave: bool,
     pub hypervisor: bool,
+    pub clflushopt: bool,
 }
 
 impl CpuFeatures {
     /// Detect CPU features using CPUID
     pub fn detect() -> Self {
-        let leaf_1 = cpuid(0x1, 0);
-        let leaf_7 = cpuid(0x7, 0);
+        Self::from_leaves(cpuid(0x1, 0), cpuid(0x7, 0))
+    }
 
+    /// Decode features from raw CPUID leaf 1 and leaf 7 results
+    pub const fn from_leaves(leaf_1: CpuidResult, leaf_7: CpuidResult) -> Self {
         Self {
             // Leaf 1, ECX
             sse3: (leaf_1.ecx & (1 << 0)) != 0,
@@ -192,10 +511,177 @@ impl CpuFeatures {
             bmi2: (leaf_7.ebx & (1 << 8)) != 0,
             rdseed: (leaf_7.ebx & (1 << 18)) != 0,
             avx512f: (leaf_7.ebx & (1 << 16)) != 0,
+            clflushopt: (leaf_7.ebx & (1 << 23)) != 0,
+        }
+    }
+
+    /// Pack the flags into a `feature_bits` bitmask
+    pub const fn to_bits(&self) -> u32 {
+        let mut bits = 0;
+        if self.sse {
+            bits |= feature_bits::SSE;
+        }
+        if self.sse2 {
+            bits |= feature_bits::SSE2;
+        }
+        if self.sse3 {
+            bits |= feature_bits::SSE3;
+        }
+        if self.ssse3 {
+            bits |= feature_bits::SSSE3;
+        }
+        if self.sse4_1 {
+            bits |= feature_bits::SSE4_1;
+        }
+        if self.sse4_2 {
+            bits |= feature_bits::SSE4_2;
+        }
+        if self.avx {
+            bits |= feature_bits::AVX;
+        }
+        if self.avx2 {
+            bits |= feature_bits::AVX2;
+        }
+        if self.avx512f {
+            bits |= feature_bits::AVX512F;
+        }
+        if self.aes {
+            bits |= feature_bits::AES;
+        }
+        if self.rdrand {
+            bits |= feature_bits::RDRAND;
+        }
+        if self.rdseed {
+            bits |= feature_bits::RDSEED;
+        }
+        if self.bmi1 {
+            bits |= feature_bits::BMI1;
+        }
+        if self.bmi2 {
+            bits |= feature_bits::BMI2;
         }
+        if self.fma {
+            bits |= feature_bits::FMA;
+        }
+        if self.movbe {
+            bits |= feature_bits::MOVBE;
+        }
+        if self.xsave {
+            bits |= feature_bits::XSAVE;
+        }
+        if self.hypervisor {
+            bits |= feature_bits::HYPERVISOR;
+        }
+        if self.clflushopt {
+            bits |= feature_bits::CLFLUSHOPT;
+        }
+        bits
+    }
+
+    /// Rebuild the flags from a `feature_bits` bitmask (reserved bits ignored)
+    pub const fn from_bits(bits: u32) -> Self {
+        Self {
+            sse: bits & feature_bits::SSE != 0,
+            sse2: bits & feature_bits::SSE2 != 0,
+            sse3: bits & feature_bits::SSE3 != 0,
+            ssse3: bits & feature_bits::SSSE3 != 0,
+            sse4_1: bits & feature_bits::SSE4_1 != 0,
+            sse4_2: bits & feature_bits::SSE4_2 != 0,
+            avx: bits & feature_bits::AVX != 0,
+            avx2: bits & feature_bits::AVX2 != 0,
+            avx512f: bits & feature_bits::AVX512F != 0,
+            aes: bits & feature_bits::AES != 0,
+            rdrand: bits & feature_bits::RDRAND != 0,
+            rdseed: bits & feature_bits::RDSEED != 0,
+            bmi1: bits & feature_bits::BMI1 != 0,
+            bmi2: bits & feature_bits::BMI2 != 0,
+            fma: bits & feature_bits::FMA != 0,
+            movbe: bits & feature_bits::MOVBE != 0,
+            xsave: bits & feature_bits::XSAVE != 0,
+            hypervisor: bits & feature_bits::HYPERVISOR != 0,
+            clflushopt: bits & feature_bits::CLFLUSHOPT != 0,
+        }
+    }
+
+    /// Get the feature set that is safe to use on the given core type
+    ///
+    /// P-cores and E-cores on the i9-12900K have asymmetric ISA support.
+    /// AVX-512 is only present on Golden Cove P-cores, so code that may be
+    /// migrated to a Gracemont E-core must not rely on it. `Unknown` core
+    /// types are treated like E-cores to stay on the safe side.
+    pub fn effective_isa(&self, core_type: CoreType) -> CpuFeatures {
+        let mut features = *self;
+
+        if core_type != CoreType::Performance {
+            // P-core-only extensions
+            features.avx512f = false;
+        }
+
+        features
+    }
+}
+
+/// Run-once cache for a `CpuFeatures` value
+///
+/// `no_std` has no `OnceLock`, so `claimed` picks the single initializer
+/// and `ready` publishes the value; other callers spin until it is set.
+pub struct FeatureCache {
+    claimed: AtomicBool,
+    ready: AtomicBool,
+    value: UnsafeCell<CpuFeatures>,
+}
+
+unsafe impl Sync for FeatureCache {}
+
+impl FeatureCache {
+    /// Create an empty cache
+    pub const fn new() -> Self {
+        Self {
+            claimed: AtomicBool::new(false),
+            ready: AtomicBool::new(false),
+            value: UnsafeCell::new(CpuFeatures::from_bits(0)),
+        }
+    }
+
+    /// Return the cached features, running `detect` on the first call only
+    pub fn get_or_init(&self, detect: impl FnOnce() -> CpuFeatures) -> &CpuFeatures {
+        if !self.ready.load(Ordering::Acquire) {
+            if self.claimed.swap(true, Ordering::AcqRel) {
+                while !self.ready.load(Ordering::Acquire) {
+                    core::hint::spin_loop();
+                }
+            } else {
+                // SAFETY: `claimed` admits one writer, and no reader touches
+                // `value` before `ready` is published
+                unsafe { *self.value.get() = detect() };
+                self.ready.store(true, Ordering::Release);
+            }
+        }
+
+        // SAFETY: `ready` is set, so `value` is never written again
+        unsafe { &*self.value.get() }
     }
 }
 
+impl Default for FeatureCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Features of the boot CPU, detected once
+static CACHED_FEATURES: FeatureCache = FeatureCache::new();
+
+/// CPU features detected once and cached for hot paths
+///
+/// Prime this on the BSP right after `init_cpu` so later callers (the
+/// syscall path, the panic handler) never race to run CPUID. Leaf 1 and
+/// leaf 7 report the same ISA on P- and E-cores; use `effective_isa` for
+/// per-core-type limits.
+pub fn cached_features() -> &'static CpuFeatures {
+    CACHED_FEATURES.get_or_init(CpuFeatures::detect)
+}
+
 /// Initialize CPU for bare-metal operation
 ///
 /// # Safety
@@ -228,6 +714,48 @@ pub unsafe fn init_cpu() {
     );
 }
 
+/// Flush the cache line containing `addr` (CLFLUSH)
+///
+/// Writes back and invalidates the line in every cache level. CLFLUSH is
+/// ordered against other CLFLUSHes and stores.
+///
+/// # Safety
+/// `addr` must lie in a mapped page; CLFLUSH faults like a load otherwise
+#[inline]
+pub unsafe fn clflush(addr: *const u8) {
+    asm!("clflush [{}]", in(reg) addr, options(nostack, preserves_flags));
+}
+
+/// Flush the cache line containing `addr` (CLFLUSHOPT)
+///
+/// Weakly ordered; follow with `mfence` before relying on completion.
+/// Only valid when `CpuFeatures::clflushopt` is set.
+///
+/// # Safety
+/// The CPU must support CLFLUSHOPT, and `addr` must lie in a mapped page
+#[inline]
+pub unsafe fn clflushopt(addr: *const u8) {
+    asm!("clflushopt [{}]", in(reg) addr, options(nostack, preserves_flags));
+}
+
+/// Prefetch the cache line containing `addr` into all cache levels (PREFETCHT0)
+///
+/// A hint only: it never faults, even for unmapped addresses.
+#[inline]
+pub fn prefetch_t0(addr: *const u8) {
+    unsafe {
+        asm!("prefetcht0 [{}]", in(reg) addr, options(nostack, preserves_flags, readonly));
+    }
+}
+
+/// Full memory fence (MFENCE)
+#[inline]
+pub fn mfence() {
+    unsafe {
+        asm!("mfence", options(nostack, preserves_flags));
+    }
+}
+
 /// Read Time Stamp Counter (TSC)
 #[inline]
 pub fn read_tsc() -> u64 {
@@ -251,6 +779,470 @@ pub fn read_tsc_serialized() -> u64 {
     ((edx as u64) << 32) | (eax as u64)
 }
 
+/// RDRAND attempts before giving up (Intel recommends 10)
+const RDRAND_RETRIES: u32 = 10;
+
+/// Hardware random number from RDRAND
+///
+/// `None` if the CPU lacks RDRAND or the DRNG stayed exhausted for every
+/// retry. Suitable for seeding test PRNGs, not as a sole key source.
+pub fn rdrand_u64() -> Option<u64> {
+    if !cached_features().rdrand {
+        return None;
+    }
+
+    for _ in 0..RDRAND_RETRIES {
+        let value: u64;
+        let ok: u8;
+        unsafe {
+            asm!(
+                "rdrand {value}",
+                "setc {ok}",
+                value = out(reg) value,
+                ok = out(reg_byte) ok,
+                options(nomem, nostack)
+            );
+        }
+        if ok != 0 {
+            return Some(value);
+        }
+    }
+    None
+}
+
+/// Bus (BCLK) frequency the platform ratios multiply
+pub const BUS_CLOCK_HZ: u64 = 100_000_000;
+
+/// Maximum non-turbo ratio, MSR_PLATFORM_INFO[15:8]
+pub const fn platform_base_ratio(platform_info: u64) -> u8 {
+    ((platform_info >> 8) & 0xFF) as u8
+}
+
+/// TSC frequency for a base ratio (the invariant TSC runs at base clock)
+pub const fn tsc_hz_from_ratio(ratio: u8) -> u64 {
+    ratio as u64 * BUS_CLOCK_HZ
+}
+
+/// Convert TSC ticks at `hz` to nanoseconds (0 if `hz` is 0)
+pub const fn ticks_to_nanos(ticks: u64, hz: u64) -> u64 {
+    if hz == 0 {
+        return 0;
+    }
+    (ticks as u128 * 1_000_000_000 / hz as u128) as u64
+}
+
+/// Max turbo ratio per active-core count, MSR_TURBO_RATIO_LIMIT bytes 0..8
+///
+/// Index 0 is the limit with one core active, index 7 with eight.
+pub const fn decode_turbo_ratios(turbo_ratio_limit: u64) -> [u8; 8] {
+    turbo_ratio_limit.to_le_bytes()
+}
+
+/// Turbo frequency in MHz for `active_cores` from decoded ratios
+///
+/// `active_cores` is clamped to 1..=8, the range the MSR describes.
+pub const fn turbo_mhz_from_ratios(ratios: &[u8; 8], active_cores: u8) -> u32 {
+    let index = match active_cores {
+        0 => 0,
+        1..=8 => active_cores as usize - 1,
+        _ => 7,
+    };
+    (ratios[index] as u64 * BUS_CLOCK_HZ / 1_000_000) as u32
+}
+
+/// This core's max turbo ratios, one per active-core count (1 through 8)
+///
+/// # Safety
+/// Must run in ring 0 (reads an MSR)
+pub unsafe fn turbo_ratios() -> [u8; 8] {
+    decode_turbo_ratios(read_msr(msr::MSR_TURBO_RATIO_LIMIT))
+}
+
+/// Max turbo frequency in MHz with `active_cores` cores busy
+///
+/// # Safety
+/// Must run in ring 0 (reads an MSR)
+pub unsafe fn max_turbo_mhz(active_cores: u8) -> u32 {
+    turbo_mhz_from_ratios(&turbo_ratios(), active_cores)
+}
+
+/// Calibrated TSC frequency (0 until `calibrate_tsc`)
+static TSC_HZ: AtomicU64 = AtomicU64::new(0);
+
+/// Derive the TSC frequency from MSR_PLATFORM_INFO and cache it
+///
+/// # Safety
+/// Must run in ring 0 (reads an MSR)
+pub unsafe fn calibrate_tsc() -> u64 {
+    let ratio = platform_base_ratio(read_msr(msr::MSR_PLATFORM_INFO));
+    let hz = tsc_hz_from_ratio(ratio);
+    TSC_HZ.store(hz, Ordering::Release);
+    hz
+}
+
+/// TSC frequency in Hz (0 until `calibrate_tsc` has run)
+pub fn tsc_hz() -> u64 {
+    TSC_HZ.load(Ordering::Acquire)
+}
+
+/// Convert TSC ticks to nanoseconds using the calibrated frequency
+///
+/// Returns 0 until `calibrate_tsc` has run.
+pub fn tsc_to_nanos(ticks: u64) -> u64 {
+    ticks_to_nanos(ticks, tsc_hz())
+}
+
+/// Hardware P-state request (MSR_HWP_REQUEST)
+///
+/// Performance values are in the HWP scale reported by
+/// IA32_HWP_CAPABILITIES (on the 12900K one unit is one 100 MHz ratio).
+/// `desired_perf` 0 lets hardware choose within `min_perf..=max_perf`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HwpRequest {
+    /// Minimum performance, bits [7:0]
+    pub min_perf: u8,
+    /// Maximum performance, bits [15:8]
+    pub max_perf: u8,
+    /// Desired performance, bits [23:16] (0 = autonomous)
+    pub desired_perf: u8,
+    /// Energy-performance preference, bits [31:24] (0 = perf, 255 = power save)
+    pub energy_pref: u8,
+}
+
+impl HwpRequest {
+    /// Decode the low 32 bits of an MSR_HWP_REQUEST value
+    pub const fn from_msr(value: u64) -> Self {
+        Self {
+            min_perf: value as u8,
+            max_perf: (value >> 8) as u8,
+            desired_perf: (value >> 16) as u8,
+            energy_pref: (value >> 24) as u8,
+        }
+    }
+
+    /// Pack into an MSR_HWP_REQUEST value, keeping bits [63:32] of `current`
+    ///
+    /// The upper half holds the activity window and package control.
+    pub const fn to_msr(&self, current: u64) -> u64 {
+        (current & !0xFFFF_FFFF)
+            | (self.energy_pref as u64) << 24
+            | (self.desired_perf as u64) << 16
+            | (self.max_perf as u64) << 8
+            | self.min_perf as u64
+    }
+
+    /// Read this core's HWP request
+    ///
+    /// # Safety
+    /// Must run in ring 0 with HWP enabled (IA32_PM_ENABLE), else #GP
+    pub unsafe fn read() -> Self {
+        Self::from_msr(read_msr(msr::MSR_HWP_REQUEST))
+    }
+
+    /// Write this request to the current core
+    ///
+    /// # Safety
+    /// Must run in ring 0 with HWP enabled (IA32_PM_ENABLE), else #GP
+    pub unsafe fn write(&self) {
+        let current = read_msr(msr::MSR_HWP_REQUEST);
+        write_msr(msr::MSR_HWP_REQUEST, self.to_msr(current));
+    }
+}
+
+/// Energy-performance bias favouring performance (MSR_ENERGY_PERF_BIAS)
+pub const EPB_MAX_PERFORMANCE: u8 = 0;
+/// Energy-performance bias the firmware usually boots with
+pub const EPB_NORMAL: u8 = 6;
+/// Energy-performance bias favouring power savings
+pub const EPB_MAX_POWER_SAVE: u8 = 15;
+
+/// Clamp a bias to the 4-bit MSR field (values above 15 become 15)
+pub const fn clamp_energy_perf_bias(bias: u8) -> u8 {
+    if bias > EPB_MAX_POWER_SAVE {
+        EPB_MAX_POWER_SAVE
+    } else {
+        bias
+    }
+}
+
+/// MSR_ENERGY_PERF_BIAS value with the low nibble replaced by `bias`
+///
+/// Bits above [3:0] are reserved and kept as read.
+pub const fn energy_perf_bias_value(current: u64, bias: u8) -> u64 {
+    (current & !0xF) | clamp_energy_perf_bias(bias) as u64
+}
+
+/// Bias for a core type: performance on P-cores, power savings on E-cores
+pub const fn core_type_bias(core_type: CoreType) -> u8 {
+    match core_type {
+        CoreType::Performance => EPB_MAX_PERFORMANCE,
+        CoreType::Efficiency => EPB_MAX_POWER_SAVE,
+        CoreType::Unknown => EPB_NORMAL,
+    }
+}
+
+/// Set this core's energy-performance bias (0 = max perf, 15 = max power save)
+///
+/// Values above 15 are clamped.
+///
+/// # Safety
+/// Must run in ring 0 (writes an MSR)
+pub unsafe fn set_energy_perf_bias(bias: u8) {
+    let current = read_msr(msr::MSR_ENERGY_PERF_BIAS);
+    write_msr(msr::MSR_ENERGY_PERF_BIAS, energy_perf_bias_value(current, bias));
+}
+
+/// This core's energy-performance bias, MSR_ENERGY_PERF_BIAS[3:0]
+///
+/// # Safety
+/// Must run in ring 0 (reads an MSR)
+pub unsafe fn get_energy_perf_bias() -> u8 {
+    (read_msr(msr::MSR_ENERGY_PERF_BIAS) & 0xF) as u8
+}
+
+/// Set this core's bias from `get_core_type` and return the value written
+///
+/// The MSR is per logical processor, so call this on every core.
+///
+/// # Safety
+/// Must run in ring 0 (writes an MSR)
+pub unsafe fn apply_core_type_bias() -> u8 {
+    let bias = core_type_bias(get_core_type());
+    set_energy_perf_bias(bias);
+    bias
+}
+
+/// Deepest MWAIT C-state index encodable in a hint (CPUID leaf 5 EDX has 8 nibbles)
+pub const MAX_MWAIT_CSTATE: u8 = 7;
+
+/// MONITOR/MWAIT capabilities from CPUID leaves 1 and 5
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MwaitSupport {
+    /// Sub-state count per MWAIT C-state, 4 bits each (leaf 5 EDX)
+    pub substates: u32,
+    /// Interrupts break MWAIT even with IF clear (leaf 5 ECX bit 1)
+    pub interrupt_break: bool,
+}
+
+impl MwaitSupport {
+    /// Detect MONITOR/MWAIT support using CPUID
+    pub fn detect() -> Self {
+        let leaf_1 = cpuid(0x1, 0);
+        let leaf_5 = if leaf_1.ecx & (1 << 3) != 0 {
+            cpuid(0x5, 0)
+        } else {
+            CpuidResult {
+                eax: 0,
+                ebx: 0,
+                ecx: 0,
+                edx: 0,
+            }
+        };
+        Self::from_leaves(leaf_1, leaf_5)
+    }
+
+    /// Decode from raw CPUID leaf 1 and leaf 5 results
+    ///
+    /// Without MONITOR (leaf 1 ECX bit 3) no C-state is usable. If leaf 5
+    /// does not enumerate extensions (ECX bit 0) only C1 is assumed.
+    pub const fn from_leaves(leaf_1: CpuidResult, leaf_5: CpuidResult) -> Self {
+        if leaf_1.ecx & (1 << 3) == 0 {
+            return Self {
+                substates: 0,
+                interrupt_break: false,
+            };
+        }
+        if leaf_5.ecx & (1 << 0) == 0 {
+            return Self {
+                substates: 1 << 4,
+                interrupt_break: false,
+            };
+        }
+        Self {
+            substates: leaf_5.edx,
+            interrupt_break: leaf_5.ecx & (1 << 1) != 0,
+        }
+    }
+
+    /// Check if MONITOR/MWAIT is usable at all
+    pub const fn available(&self) -> bool {
+        self.substates != 0
+    }
+
+    /// Check if MWAIT C-state `level` (1 = C1) has at least one sub-state
+    pub const fn supports(&self, level: u8) -> bool {
+        level >= 1 && level <= MAX_MWAIT_CSTATE && (self.substates >> (level as u32 * 4)) & 0xF != 0
+    }
+
+    /// Pack to the cached form (bit 63 marks a detected value)
+    const fn to_bits(self) -> u64 {
+        (1 << 63) | ((self.interrupt_break as u64) << 32) | self.substates as u64
+    }
+
+    const fn from_bits(bits: u64) -> Self {
+        Self {
+            substates: bits as u32,
+            interrupt_break: bits & (1 << 32) != 0,
+        }
+    }
+}
+
+/// MWAIT EAX hint for a C-state: bits [7:4] hold `level - 1`, sub-state 0
+///
+/// `level` uses the MWAIT numbering of CPUID leaf 5 (1 = C1); it is not the
+/// ACPI/package name (on Alder Lake MWAIT C2 enters core C6).
+pub const fn mwait_hint(level: u8) -> Result<u32, &'static str> {
+    if level == 0 || level > MAX_MWAIT_CSTATE {
+        return Err("C-state out of range");
+    }
+    Ok(((level - 1) as u32) << 4)
+}
+
+/// Cached `MwaitSupport::to_bits` (0 until first detected)
+static MWAIT_SUPPORT: AtomicU64 = AtomicU64::new(0);
+
+/// MONITOR/MWAIT support, detected once
+pub fn mwait_support() -> MwaitSupport {
+    let bits = MWAIT_SUPPORT.load(Ordering::Relaxed);
+    if bits != 0 {
+        return MwaitSupport::from_bits(bits);
+    }
+
+    let support = MwaitSupport::detect();
+    MWAIT_SUPPORT.store(support.to_bits(), Ordering::Relaxed);
+    support
+}
+
+/// Cache line armed by MONITOR in `request_cstate`; a store here wakes idlers
+static IDLE_WAKE: AtomicU64 = AtomicU64::new(0);
+
+/// Wake cores parked in `request_cstate`
+pub fn wake_idle() {
+    IDLE_WAKE.fetch_add(1, Ordering::Release);
+}
+
+/// Park the core in MWAIT C-state `level` until an interrupt or `wake_idle`
+///
+/// Fails without entering MWAIT if the CPU lacks MONITOR/MWAIT or does not
+/// enumerate `level`. When the CPU allows it, interrupts break the wait even
+/// if they are masked, so a `cli` caller cannot sleep forever.
+///
+/// # Safety
+/// Must be called from ring 0
+pub unsafe fn request_cstate(level: u8) -> Result<(), &'static str> {
+    let hint = mwait_hint(level)?;
+    let support = mwait_support();
+    if !support.available() {
+        return Err("MONITOR/MWAIT not supported");
+    }
+    if !support.supports(level) {
+        return Err("C-state not supported");
+    }
+
+    let extensions = support.interrupt_break as u32;
+    asm!(
+        "monitor",
+        in("rax") IDLE_WAKE.as_ptr(),
+        in("ecx") 0u32,
+        in("edx") 0u32,
+        options(nostack, preserves_flags)
+    );
+    asm!(
+        "mwait",
+        in("eax") hint,
+        in("ecx") extensions,
+        options(nostack, preserves_flags)
+    );
+    Ok(())
+}
+
+/// Idle the core until the next interrupt: MWAIT C1, falling back to HLT
+///
+/// # Safety
+/// Must be called from ring 0
+pub unsafe fn idle() {
+    if request_cstate(1).is_err() {
+        asm!("hlt", options(nomem, nostack));
+    }
+}
+
+/// Leaf 0x4 subleaves kept by `SystemInfo`
+pub const MAX_CACHE_LEVELS: usize = MAX_CACHE_SUBLEAVES as usize;
+
+/// Stand-in for a leaf above the CPU's maximum
+const EMPTY_LEAF: CpuidResult = CpuidResult {
+    eax: 0,
+    ebx: 0,
+    ecx: 0,
+    edx: 0,
+};
+
+/// One-shot system inventory gathered from CPUID
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SystemInfo {
+    /// Highest basic CPUID leaf (leaf 0 EAX)
+    pub max_leaf: u32,
+    /// ISA extensions (leaves 1 and 7)
+    pub features: CpuFeatures,
+    /// Hybrid core type and native model (leaf 0x1A)
+    pub core_info: CoreInfo,
+    /// Initial APIC ID of the probing core (leaf 1)
+    pub apic_id: u32,
+    /// MONITOR/MWAIT C-states (leaves 1 and 5)
+    pub mwait: MwaitSupport,
+    caches: [Option<CacheInfo>; MAX_CACHE_LEVELS],
+}
+
+impl SystemInfo {
+    /// Inventory the executing core
+    pub fn probe() -> Self {
+        Self::from_cpuid(cpuid)
+    }
+
+    /// Inventory from a custom CPUID source
+    ///
+    /// Each leaf is queried once, and leaves above the reported maximum are
+    /// not queried at all (they decode as zero).
+    pub fn from_cpuid(mut cpuid: impl FnMut(u32, u32) -> CpuidResult) -> Self {
+        let max_leaf = cpuid(0x0, 0).eax;
+        let mut leaf = |leaf: u32, subleaf: u32| {
+            if leaf <= max_leaf {
+                cpuid(leaf, subleaf)
+            } else {
+                EMPTY_LEAF
+            }
+        };
+
+        let leaf_1 = leaf(0x1, 0);
+        let leaf_5 = if leaf_1.ecx & (1 << 3) != 0 {
+            leaf(0x5, 0)
+        } else {
+            EMPTY_LEAF
+        };
+        let leaf_7 = leaf(0x7, 0);
+        let leaf_1a = leaf(0x1A, 0);
+
+        let mut caches = [None; MAX_CACHE_LEVELS];
+        for (slot, info) in caches.iter_mut().zip(CacheIter::new(|subleaf| leaf(0x4, subleaf))) {
+            *slot = Some(info);
+        }
+
+        Self {
+            max_leaf,
+            features: CpuFeatures::from_leaves(leaf_1, leaf_7),
+            core_info: decode_core_info(leaf_1a.eax),
+            apic_id: apic_id_from_leaf_1(leaf_1),
+            mwait: MwaitSupport::from_leaves(leaf_1, leaf_5),
+            caches,
+        }
+    }
+
+    /// Caches reported by leaf 0x4, in enumeration order
+    pub fn caches(&self) -> impl Iterator<Item = &CacheInfo> {
+        self.caches.iter().flatten()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -261,6 +1253,427 @@ mod tests {
         assert_eq!(CoreType::Efficiency as u8, 0x20);
     }
 
+    fn all_features() -> CpuFeatures {
+        CpuFeatures {
+            sse: true,
+            sse2: true,
+            sse3: true,
+            ssse3: true,
+            sse4_1: true,
+            sse4_2: true,
+            avx: true,
+            avx2: true,
+            avx512f: true,
+            aes: true,
+            rdrand: true,
+            rdseed: true,
+            bmi1: true,
+            bmi2: true,
+            fma: true,
+            movbe: true,
+            xsave: true,
+            hypervisor: true,
+            clflushopt: true,
+        }
+    }
+
+    #[test]
+    fn test_decode_cache_info() {
+        // 48 KiB L1D: 12 ways, 64-byte lines, 64 sets
+        let l1d = CpuidResult {
+            eax: 0x0000_0121, // type 1 (data), level 1, self-initializing
+            ebx: (11 << 22) | 63,
+            ecx: 63,
+            edx: 0,
+        };
+        let info = decode_cache_info(l1d).unwrap();
+        assert_eq!(info.level, 1);
+        assert_eq!(info.cache_type, CacheType::Data);
+        assert_eq!(info.line_size, 64);
+        assert_eq!(info.ways, 12);
+        assert_eq!(info.sets, 64);
+        assert_eq!(info.total_bytes, 48 * 1024);
+
+        // Type 0 terminates the list
+        let end = CpuidResult { eax: 0x0000_0060, ebx: 0, ecx: 0, edx: 0 };
+        assert_eq!(decode_cache_info(end), None);
+    }
+
+    #[test]
+    fn test_cache_iter_stops_at_null_type() {
+        // i9-12900K P-core: L1D 48K, L1I 32K, L2 1.25M, L3 30M
+        let leaves = [
+            CpuidResult { eax: 0x121, ebx: (11 << 22) | 63, ecx: 63, edx: 0 },
+            CpuidResult { eax: 0x122, ebx: (7 << 22) | 63, ecx: 63, edx: 0 },
+            CpuidResult { eax: 0x143, ebx: (9 << 22) | 63, ecx: 2047, edx: 0 },
+            CpuidResult { eax: 0x163, ebx: (11 << 22) | 63, ecx: 40959, edx: 0 },
+            CpuidResult { eax: 0, ebx: 0, ecx: 0, edx: 0 },
+            // Never reached
+            CpuidResult { eax: 0x121, ebx: 0, ecx: 0, edx: 0 },
+        ];
+
+        let mut iter = CacheIter::new(|subleaf| leaves[subleaf as usize]);
+        let caches: [CacheInfo; 4] = core::array::from_fn(|_| iter.next().unwrap());
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next(), None);
+
+        assert_eq!(caches.map(|c| c.level), [1, 1, 2, 3]);
+        assert_eq!(
+            caches.map(|c| c.cache_type),
+            [CacheType::Data, CacheType::Instruction, CacheType::Unified, CacheType::Unified]
+        );
+        assert_eq!(
+            caches.map(|c| c.total_bytes),
+            [48 * 1024, 32 * 1024, 1280 * 1024, 30 * 1024 * 1024]
+        );
+    }
+
+    #[test]
+    fn test_effective_isa_efficiency_clears_avx512() {
+        let features = all_features().effective_isa(CoreType::Efficiency);
+        assert!(!features.avx512f);
+
+        // Shared ISA is preserved
+        assert!(features.avx2);
+        assert!(features.aes);
+        assert!(features.bmi2);
+    }
+
+    #[test]
+    fn test_effective_isa_performance_preserves_avx512() {
+        let features = all_features().effective_isa(CoreType::Performance);
+        assert!(features.avx512f);
+        assert!(features.avx2);
+    }
+
+    #[test]
+    fn test_detect_clflushopt_from_leaf_7() {
+        let leaf_1 = CpuidResult { eax: 0, ebx: 0, ecx: 0, edx: 0 };
+
+        let leaf_7 = CpuidResult { eax: 0, ebx: 1 << 23, ecx: 0, edx: 0 };
+        let with = CpuFeatures::from_leaves(leaf_1, leaf_7);
+        assert!(with.clflushopt);
+        assert!(!with.avx2);
+
+        let leaf_7 = CpuidResult { eax: 0, ebx: 1 << 5, ecx: 0, edx: 0 };
+        let without = CpuFeatures::from_leaves(leaf_1, leaf_7);
+        assert!(!without.clflushopt);
+        assert!(without.avx2);
+    }
+
+    #[test]
+    fn test_feature_bits_round_trip() {
+        let detected = CpuFeatures::detect();
+        assert_eq!(CpuFeatures::from_bits(detected.to_bits()), detected);
+
+        for bits in [
+            0,
+            feature_bits::ALL,
+            feature_bits::SSE | feature_bits::SSE2,
+            feature_bits::AVX512F | feature_bits::CLFLUSHOPT,
+            feature_bits::ALL & !feature_bits::AVX512F,
+        ] {
+            assert_eq!(CpuFeatures::from_bits(bits).to_bits(), bits);
+        }
+
+        assert_eq!(all_features().to_bits(), feature_bits::ALL);
+    }
+
+    #[test]
+    fn test_feature_bits_layout() {
+        let only_avx2 = CpuFeatures::from_bits(1 << 7);
+        assert!(only_avx2.avx2);
+        assert!(!only_avx2.avx);
+        assert_eq!(feature_bits::CLFLUSHOPT, 1 << 18);
+
+        // Reserved bits are dropped
+        assert_eq!(CpuFeatures::from_bits(u32::MAX).to_bits(), feature_bits::ALL);
+    }
+
+    #[test]
+    fn test_feature_cache_detects_once() {
+        use core::sync::atomic::AtomicUsize;
+
+        let cache = FeatureCache::new();
+        let calls = AtomicUsize::new(0);
+        let counting_detect = || {
+            calls.fetch_add(1, Ordering::SeqCst);
+            CpuFeatures::detect()
+        };
+
+        let first = *cache.get_or_init(counting_detect);
+        let second = *cache.get_or_init(counting_detect);
+
+        assert_eq!(first, CpuFeatures::detect());
+        assert_eq!(second, first);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_cached_features_matches_detect() {
+        assert_eq!(*cached_features(), CpuFeatures::detect());
+        assert!(core::ptr::eq(cached_features(), cached_features()));
+    }
+
+    #[test]
+    fn test_tsc_ratio_arithmetic() {
+        // 12900K: base ratio 36 in MSR_PLATFORM_INFO[15:8]
+        let platform_info = 0x0804_0000_8000_2400;
+        assert_eq!(platform_base_ratio(platform_info), 36);
+
+        let hz = tsc_hz_from_ratio(36);
+        assert_eq!(hz, 3_600_000_000);
+
+        assert_eq!(ticks_to_nanos(hz, hz), 1_000_000_000);
+        assert_eq!(ticks_to_nanos(36, hz), 10);
+        assert_eq!(ticks_to_nanos(3_600, hz), 1_000);
+        assert_eq!(ticks_to_nanos(35, hz), 9); // truncates
+
+        // Large tick counts don't overflow the intermediate product
+        assert_eq!(ticks_to_nanos(u64::MAX / 2, hz), 2_562_047_788_015_215_501);
+
+        // Uncalibrated frequency yields zero rather than dividing by it
+        assert_eq!(ticks_to_nanos(1_000, 0), 0);
+    }
+
+    #[test]
+    fn test_turbo_ratio_decoding() {
+        // 12900K P-cores: 52x for 1-2 active, 49x for 3-4, 47x for 5-8
+        let msr = 0x2F2F_2F2F_3131_3434;
+        let ratios = decode_turbo_ratios(msr);
+        assert_eq!(ratios, [52, 52, 49, 49, 47, 47, 47, 47]);
+
+        assert_eq!(turbo_mhz_from_ratios(&ratios, 1), 5200);
+        assert_eq!(turbo_mhz_from_ratios(&ratios, 3), 4900);
+        assert_eq!(turbo_mhz_from_ratios(&ratios, 8), 4700);
+
+        // Out-of-range counts clamp to the nearest described entry
+        assert_eq!(turbo_mhz_from_ratios(&ratios, 0), 5200);
+        assert_eq!(turbo_mhz_from_ratios(&ratios, 16), 4700);
+    }
+
+    #[test]
+    fn test_rdrand_matches_feature_bit() {
+        let value = rdrand_u64();
+        assert_eq!(value.is_some(), cached_features().rdrand);
+    }
+
+    #[test]
+    fn test_clflush_and_mfence() {
+        let data = [0u8; 64];
+        unsafe { clflush(data.as_ptr()) };
+        mfence();
+        assert_eq!(data[0], 0);
+    }
+
+    #[test]
+    fn test_prefetch_t0() {
+        let data = [7u8; 64];
+        prefetch_t0(data.as_ptr());
+
+        // A hint never faults, even on a null address
+        prefetch_t0(core::ptr::null());
+        assert_eq!(data[0], 7);
+    }
+
+    #[test]
+    fn test_decode_core_info() {
+        let p_core = decode_core_info(0x4000_0001);
+        assert_eq!(p_core.core_type, CoreType::Performance);
+        assert_eq!(p_core.native_model_id, 0x000001);
+
+        let e_core = decode_core_info(0x2012_3456);
+        assert_eq!(e_core.core_type, CoreType::Efficiency);
+        assert_eq!(e_core.native_model_id, 0x12_3456);
+
+        let unknown = decode_core_info(0x00FF_FFFF);
+        assert_eq!(unknown.core_type, CoreType::Unknown);
+        assert_eq!(unknown.native_model_id, 0xFF_FFFF);
+    }
+
+    #[test]
+    fn test_supported_msr_allowlist() {
+        assert!(is_supported_msr(msr::MSR_PLATFORM_INFO));
+        assert!(is_supported_msr(msr::MSR_TSC));
+        assert!(is_supported_msr(perf_msr::IA32_FIXED_CTR0));
+        assert!(is_supported_msr(perf_msr::IA32_PERF_GLOBAL_CTRL));
+
+        assert!(!is_supported_msr(0x0));
+        assert!(!is_supported_msr(0xDEAD_BEEF));
+    }
+
+    #[test]
+    fn test_try_msr_rejects_unsupported() {
+        unsafe {
+            assert_eq!(try_read_msr(0xDEAD_BEEF), Err("unsupported MSR"));
+            assert_eq!(try_write_msr(0xDEAD_BEEF, 0), Err("unsupported MSR"));
+        }
+    }
+
+    #[test]
+    fn test_core_slot_from_apic() {
+        // P-core HT siblings share a slot
+        assert_eq!(core_slot_from_apic(0), 0);
+        assert_eq!(core_slot_from_apic(1), 0);
+        assert_eq!(core_slot_from_apic(6), 3);
+        assert_eq!(core_slot_from_apic(15), 7);
+
+        // E-cores follow the P-cores
+        assert_eq!(core_slot_from_apic(E_CORE_APIC_BASE), 8);
+        assert_eq!(core_slot_from_apic(38), 11);
+        assert_eq!(core_slot_from_apic(46), 15);
+
+        // Unexpected IDs still stay in range
+        for apic_id in 0..=0xFF {
+            assert!(core_slot_from_apic(apic_id) < CORE_SLOTS);
+        }
+    }
+
+    #[test]
+    fn test_hwp_request_round_trip() {
+        // EPP 0x80, desired auto, max ratio 52, min ratio 8
+        let raw = 0x8000_3408;
+        let request = HwpRequest::from_msr(raw);
+        assert_eq!(
+            request,
+            HwpRequest {
+                min_perf: 8,
+                max_perf: 52,
+                desired_perf: 0,
+                energy_pref: 0x80,
+            }
+        );
+        assert_eq!(request.to_msr(0), raw);
+
+        // Activity window and package control bits are preserved
+        let upper = (1u64 << 42) | (0x3FF << 32);
+        assert_eq!(request.to_msr(upper | 0xFFFF_FFFF), upper | raw);
+
+        let pinned = HwpRequest {
+            min_perf: 24,
+            max_perf: 24,
+            desired_perf: 24,
+            energy_pref: 0xFF,
+        };
+        assert_eq!(pinned.to_msr(0), 0xFF18_1818);
+        assert_eq!(HwpRequest::from_msr(pinned.to_msr(upper)), pinned);
+    }
+
+    #[test]
+    fn test_energy_perf_bias_clamping() {
+        assert_eq!(clamp_energy_perf_bias(0), 0);
+        assert_eq!(clamp_energy_perf_bias(7), 7);
+        assert_eq!(clamp_energy_perf_bias(15), 15);
+        assert_eq!(clamp_energy_perf_bias(16), 15);
+        assert_eq!(clamp_energy_perf_bias(u8::MAX), 15);
+
+        // Only the low nibble changes; reserved bits survive
+        assert_eq!(energy_perf_bias_value(0xABC0_0006, 0), 0xABC0_0000);
+        assert_eq!(energy_perf_bias_value(0xABC0_0006, 200), 0xABC0_000F);
+        assert_eq!(energy_perf_bias_value(0xF, 3), 0x3);
+    }
+
+    #[test]
+    fn test_core_type_bias() {
+        assert_eq!(core_type_bias(CoreType::Performance), EPB_MAX_PERFORMANCE);
+        assert_eq!(core_type_bias(CoreType::Efficiency), EPB_MAX_POWER_SAVE);
+        assert_eq!(core_type_bias(CoreType::Unknown), EPB_NORMAL);
+    }
+
+    #[test]
+    fn test_mwait_hint_encoding() {
+        assert_eq!(mwait_hint(1), Ok(0x00));
+        assert_eq!(mwait_hint(2), Ok(0x10));
+        assert_eq!(mwait_hint(4), Ok(0x30));
+        assert_eq!(mwait_hint(MAX_MWAIT_CSTATE), Ok(0x60));
+        assert_eq!(mwait_hint(0), Err("C-state out of range"));
+        assert_eq!(mwait_hint(MAX_MWAIT_CSTATE + 1), Err("C-state out of range"));
+    }
+
+    #[test]
+    fn test_mwait_support_gating() {
+        let leaf = |ecx, edx| CpuidResult { eax: 0, ebx: 0, ecx, edx };
+
+        // No MONITOR bit: nothing usable, whatever leaf 5 says
+        let none = MwaitSupport::from_leaves(leaf(0, 0), leaf(0b11, 0x0011_2220));
+        assert!(!none.available());
+        assert!(!none.supports(1));
+
+        // MONITOR without leaf 5 extensions: C1 only
+        let c1_only = MwaitSupport::from_leaves(leaf(1 << 3, 0), leaf(0, 0));
+        assert!(c1_only.supports(1));
+        assert!(!c1_only.supports(2));
+        assert!(!c1_only.interrupt_break);
+
+        // Sparse leaf 5: C1, C2 and C4 enumerated
+        let adl = MwaitSupport::from_leaves(leaf(1 << 3, 0), leaf(0b11, 0x0001_0220));
+        assert!(adl.interrupt_break);
+        assert!(!adl.supports(0));
+        assert!(adl.supports(1));
+        assert!(adl.supports(2));
+        assert!(!adl.supports(3));
+        assert!(adl.supports(4));
+        assert!(!adl.supports(5));
+        assert!(!adl.supports(MAX_MWAIT_CSTATE + 1));
+
+        assert_eq!(MwaitSupport::from_bits(adl.to_bits()), adl);
+        assert_ne!(none.to_bits(), 0);
+    }
+
+    #[test]
+    fn test_system_info_from_cpuid() {
+        let leaf = |eax, ebx, ecx, edx| CpuidResult { eax, ebx, ecx, edx };
+        let mut queries = [0u32; 0x20];
+
+        let info = SystemInfo::from_cpuid(|leaf_id, subleaf| {
+            queries[leaf_id as usize] += 1;
+            match (leaf_id, subleaf) {
+                (0x0, _) => leaf(0x20, 0, 0, 0),
+                // APIC ID 34; MONITOR + SSE4.1/SSE4.2; SSE/SSE2
+                (0x1, _) => leaf(0, 34 << 24, (1 << 3) | (1 << 19) | (1 << 20), 3 << 25),
+                (0x4, 0) => leaf(0x121, (11 << 22) | 63, 63, 0),
+                (0x4, 1) => leaf(0x143, (9 << 22) | 63, 2047, 0),
+                (0x4, _) => leaf(0, 0, 0, 0),
+                (0x5, _) => leaf(0, 0, 0b11, 0x0000_0220),
+                // AVX2 and CLFLUSHOPT
+                (0x7, _) => leaf(0, (1 << 5) | (1 << 23), 0, 0),
+                (0x1A, _) => leaf(0x2000_0001, 0, 0, 0),
+                _ => panic!("unexpected leaf {leaf_id:#x}"),
+            }
+        });
+
+        assert_eq!(info.max_leaf, 0x20);
+        assert!(info.features.sse2 && info.features.sse4_2);
+        assert!(info.features.avx2 && info.features.clflushopt);
+        assert!(!info.features.avx512f);
+        assert_eq!(info.core_info.core_type, CoreType::Efficiency);
+        assert_eq!(info.core_info.native_model_id, 1);
+        assert_eq!(info.apic_id, 34);
+        assert!(info.mwait.supports(2));
+        assert!(info.mwait.interrupt_break);
+        assert_eq!(info.caches().map(|c| c.level).sum::<u8>(), 3);
+        assert_eq!(info.caches().nth(1).unwrap().total_bytes, 1280 * 1024);
+
+        // One pass: every leaf except the leaf 0x4 subleaves is queried once
+        assert_eq!(queries[0x0], 1);
+        assert_eq!(queries[0x1], 1);
+        assert_eq!(queries[0x4], 3);
+        assert_eq!(queries[0x5], 1);
+        assert_eq!(queries[0x7], 1);
+        assert_eq!(queries[0x1A], 1);
+
+        // Leaves above the maximum are never queried
+        let legacy = SystemInfo::from_cpuid(|leaf_id, _| match leaf_id {
+            0x0 => leaf(0x1, 0, 0, 0),
+            0x1 => leaf(0, 0, 0, 1 << 26),
+            _ => panic!("queried leaf {leaf_id:#x} above the maximum"),
+        });
+        assert!(legacy.features.sse2);
+        assert_eq!(legacy.core_info.core_type, CoreType::Unknown);
+        assert_eq!(legacy.caches().count(), 0);
+        assert!(!legacy.mwait.available());
+    }
+
     #[test]
     fn test_core_affinity_values() {
         assert_eq!(CoreAffinity::Any as u64, 0x0000);
@@ -2,15 +2,42 @@
 //!
 //! IDT setup and exception handlers with full debug information
 
-use x86_64::structures::idt::{InterruptDescriptorTable, InterruptStackFrame, PageFaultErrorCode};
+use x86_64::structures::idt::{
+    HandlerFunc, InterruptDescriptorTable, InterruptStackFrame, PageFaultErrorCode,
+};
 use x86_64::registers::control::Cr2;
-use core::sync::atomic::{AtomicU64, Ordering};
+use core::fmt::Write;
+use core::sync::atomic::{AtomicBool, AtomicPtr, AtomicU64, AtomicUsize, Ordering};
+use crate::{CoherencyRuntime, ShadowRegisterRuntime};
 
 static mut IDT: InterruptDescriptorTable = InterruptDescriptorTable::new();
 
+/// Set once `init` has loaded the IDT
+static IDT_INITIALIZED: AtomicBool = AtomicBool::new(false);
+
+/// First vector available for custom handlers (0-31 are reserved for exceptions)
+pub const FIRST_USER_VECTOR: u8 = 32;
+
 /// Breakpoint counter for debugging
 static BREAKPOINT_COUNT: AtomicU64 = AtomicU64::new(0);
 
+/// Breakpoint callback stored as a raw `fn(u64, u64)` pointer (0 = unset)
+static BREAKPOINT_CALLBACK: AtomicUsize = AtomicUsize::new(0);
+
+/// Page fault resolver stored as a raw `fn(u64, u64) -> bool` pointer (0 = unset)
+static PAGE_FAULT_RESOLVER: AtomicUsize = AtomicUsize::new(0);
+
+/// Panic state dump stored as a raw `fn(&mut dyn Write)` pointer (0 = unset)
+static PANIC_DUMP: AtomicUsize = AtomicUsize::new(0);
+
+/// Shadow runtime reported by `shadow_panic_dump` (null = none)
+static PANIC_SHADOW_RUNTIME: AtomicPtr<ShadowRegisterRuntime> =
+    AtomicPtr::new(core::ptr::null_mut());
+
+/// Coherency runtime reported by `coherency_panic_dump` (null = none)
+static PANIC_COHERENCY_RUNTIME: AtomicPtr<CoherencyRuntime> =
+    AtomicPtr::new(core::ptr::null_mut());
+
 /// Initialize Interrupt Descriptor Table
 pub fn init() {
     unsafe {
@@ -35,13 +62,68 @@ pub fn init() {
 
         IDT.load();
     }
+
+    IDT_INITIALIZED.store(true, Ordering::Release);
+}
+
+/// Register a handler for a hardware interrupt vector (32-255)
+///
+/// Installs the handler into the live IDT and reloads it. Must be called
+/// after `init`. Exception vectors below 32 are rejected.
+pub fn set_handler(vector: u8, handler: HandlerFunc) -> Result<(), &'static str> {
+    if !IDT_INITIALIZED.load(Ordering::Acquire) {
+        return Err("IDT not initialized");
+    }
+
+    x86_64::instructions::interrupts::without_interrupts(|| unsafe {
+        install_handler(&mut IDT, vector, handler)?;
+        IDT.load();
+        Ok(())
+    })
+}
+
+/// Install a handler into an IDT entry after validating the vector
+fn install_handler(
+    idt: &mut InterruptDescriptorTable,
+    vector: u8,
+    handler: HandlerFunc,
+) -> Result<(), &'static str> {
+    if vector < FIRST_USER_VECTOR {
+        return Err("reserved exception vector");
+    }
+
+    idt[vector].set_handler_fn(handler);
+    Ok(())
+}
+
+/// Register a callback invoked on every breakpoint with `(count, rip)`
+///
+/// Works regardless of the `serial` feature, e.g. for debugger integration.
+pub fn on_breakpoint(cb: fn(u64, u64)) {
+    BREAKPOINT_CALLBACK.store(cb as usize, Ordering::Release);
+}
+
+/// Count a breakpoint and notify the registered callback
+///
+/// Returns the breakpoint number (0-based).
+fn notify_breakpoint(rip: u64) -> u64 {
+    let count = BREAKPOINT_COUNT.fetch_add(1, Ordering::Relaxed);
+
+    let raw = BREAKPOINT_CALLBACK.load(Ordering::Acquire);
+    if raw != 0 {
+        // Only ever stored from a valid `fn(u64, u64)` in `on_breakpoint`
+        let cb: fn(u64, u64) = unsafe { core::mem::transmute(raw) };
+        cb(count, rip);
+    }
+
+    count
 }
 
 /// Breakpoint exception handler (INT 3)
 ///
 /// Used for debugging - logs the breakpoint and continues execution
 extern "x86-interrupt" fn breakpoint_handler(stack_frame: InterruptStackFrame) {
-    let count = BREAKPOINT_COUNT.fetch_add(1, Ordering::Relaxed);
+    let count = notify_breakpoint(stack_frame.instruction_pointer.as_u64());
 
     // Log breakpoint occurrence with full stack frame
     #[cfg(feature = "serial")]
@@ -117,18 +199,47 @@ extern "x86-interrupt" fn general_protection_fault_handler(
     );
 }
 
+/// Register a resolver for recoverable page faults
+///
+/// Called with the faulting address (CR2) and the raw error code bits. If the
+/// resolver maps the page and returns true, the faulting instruction is
+/// retried; otherwise the page fault handler panics as usual. Intended for
+/// demand-mapping the MMIO regions in `memory::layout`.
+pub fn set_page_fault_resolver(f: fn(faulting_addr: u64, error: u64) -> bool) {
+    PAGE_FAULT_RESOLVER.store(f as usize, Ordering::Release);
+}
+
+/// Dispatch a page fault to the registered resolver
+///
+/// Returns true if the fault was resolved.
+fn resolve_page_fault(faulting_addr: u64, error: u64) -> bool {
+    let raw = PAGE_FAULT_RESOLVER.load(Ordering::Acquire);
+    if raw == 0 {
+        return false;
+    }
+
+    // Only ever stored from a valid resolver in `set_page_fault_resolver`
+    let resolver: fn(u64, u64) -> bool = unsafe { core::mem::transmute(raw) };
+    resolver(faulting_addr, error)
+}
+
 /// Page fault handler
 ///
-/// Occurs on invalid memory access or page table violations
+/// Occurs on invalid memory access or page table violations. Faults that
+/// the registered resolver handles return and retry the access.
 extern "x86-interrupt" fn page_fault_handler(
     stack_frame: InterruptStackFrame,
     error_code: PageFaultErrorCode,
 ) {
-    let faulting_address = Cr2::read();
+    let faulting_address = Cr2::read_raw();
+
+    if resolve_page_fault(faulting_address, error_code.bits()) {
+        return;
+    }
 
     panic!(
         "PAGE FAULT\n\
-         Faulting Address: {:?}\n\
+         Faulting Address: {:#x}\n\
          Error Code: {:?}\n\
          - Present: {}\n\
          - Write: {}\n\
@@ -231,3 +342,237 @@ extern "x86-interrupt" fn alignment_check_handler(
 pub fn get_breakpoint_count() -> u64 {
     BREAKPOINT_COUNT.load(Ordering::Relaxed)
 }
+
+/// Register a state dump for the panic handler to print after the panic message
+///
+/// Exception handlers end in `panic!`, so this is where subsystem state
+/// (shadow registers, coherency) gets reported post-mortem.
+pub fn set_panic_dump(f: fn(&mut dyn Write)) {
+    PANIC_DUMP.store(f as usize, Ordering::Release);
+}
+
+/// Run the registered panic dump into `out`
+///
+/// Returns `false` if no dump is registered. Called by the panic handler.
+pub fn run_panic_dump(out: &mut dyn Write) -> bool {
+    let raw = PANIC_DUMP.load(Ordering::Acquire);
+    if raw == 0 {
+        return false;
+    }
+
+    // Only ever stored from a valid `fn(&mut dyn Write)` in `set_panic_dump`
+    let dump: fn(&mut dyn Write) = unsafe { core::mem::transmute(raw) };
+    dump(out);
+    true
+}
+
+/// Select the shadow runtime reported by `shadow_panic_dump`
+///
+/// # Safety
+/// `runtime` must stay valid for as long as a panic can occur (or be null).
+pub unsafe fn set_panic_shadow_runtime(runtime: *const ShadowRegisterRuntime) {
+    PANIC_SHADOW_RUNTIME.store(runtime as *mut ShadowRegisterRuntime, Ordering::Release);
+}
+
+/// Default panic dump for the shadow register subsystem
+///
+/// Prints ECC statistics and every register's state for the runtime set
+/// with `set_panic_shadow_runtime`.
+pub fn shadow_panic_dump(out: &mut dyn Write) {
+    let runtime = PANIC_SHADOW_RUNTIME.load(Ordering::Acquire);
+    if runtime.is_null() {
+        let _ = writeln!(out, "Shadow Registers: no runtime registered");
+        return;
+    }
+
+    // Validity guaranteed by the caller of `set_panic_shadow_runtime`
+    let _ = unsafe { (*runtime).dump_state(out) };
+}
+
+/// Select the coherency runtime reported by `coherency_panic_dump`
+///
+/// # Safety
+/// `runtime` must stay valid for as long as a panic can occur (or be null).
+pub unsafe fn set_panic_coherency_runtime(runtime: *const CoherencyRuntime) {
+    PANIC_COHERENCY_RUNTIME.store(runtime as *mut CoherencyRuntime, Ordering::Release);
+}
+
+/// Panic dump for the cache coherency subsystem
+///
+/// Prints per-core hit/miss counts, the L3 directory state and the MESI
+/// invariant check for the runtime set with `set_panic_coherency_runtime`.
+pub fn coherency_panic_dump(out: &mut dyn Write) {
+    let runtime = PANIC_COHERENCY_RUNTIME.load(Ordering::Acquire);
+    if runtime.is_null() {
+        let _ = writeln!(out, "Coherency: no runtime registered");
+        return;
+    }
+
+    // Validity guaranteed by the caller of `set_panic_coherency_runtime`
+    let _ = unsafe { (*runtime).dump_state(out) };
+}
+
+/// Panic dump covering every subsystem: shadow registers, then coherency
+pub fn subsystem_panic_dump(out: &mut dyn Write) {
+    shadow_panic_dump(out);
+    coherency_panic_dump(out);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    extern "x86-interrupt" fn test_handler(_stack_frame: InterruptStackFrame) {}
+
+    #[test]
+    fn test_install_handler_marks_entry() {
+        let mut idt = InterruptDescriptorTable::new();
+        assert_eq!(idt[0x40].handler_addr().as_u64(), 0);
+
+        assert!(install_handler(&mut idt, 0x40, test_handler).is_ok());
+        assert_eq!(
+            idt[0x40].handler_addr().as_u64(),
+            test_handler as HandlerFunc as usize as u64
+        );
+    }
+
+    #[test]
+    fn test_install_handler_rejects_exception_vectors() {
+        let mut idt = InterruptDescriptorTable::new();
+        assert_eq!(
+            install_handler(&mut idt, 3, test_handler),
+            Err("reserved exception vector")
+        );
+        assert_eq!(
+            install_handler(&mut idt, 31, test_handler),
+            Err("reserved exception vector")
+        );
+        assert!(install_handler(&mut idt, FIRST_USER_VECTOR, test_handler).is_ok());
+    }
+
+    static LAST_COUNT: AtomicU64 = AtomicU64::new(u64::MAX);
+    static LAST_RIP: AtomicU64 = AtomicU64::new(0);
+
+    fn record_breakpoint(count: u64, rip: u64) {
+        LAST_COUNT.store(count, Ordering::SeqCst);
+        LAST_RIP.store(rip, Ordering::SeqCst);
+    }
+
+    #[test]
+    fn test_breakpoint_callback_notified() {
+        on_breakpoint(record_breakpoint);
+
+        let before = get_breakpoint_count();
+        let count = notify_breakpoint(0xFFFF_FFFF_8000_1234);
+
+        assert_eq!(count, before);
+        assert_eq!(get_breakpoint_count(), before + 1);
+        assert_eq!(LAST_COUNT.load(Ordering::SeqCst), count);
+        assert_eq!(LAST_RIP.load(Ordering::SeqCst), 0xFFFF_FFFF_8000_1234);
+    }
+
+    static RESOLVED_ADDR: AtomicU64 = AtomicU64::new(0);
+
+    fn mmio_resolver(faulting_addr: u64, error: u64) -> bool {
+        RESOLVED_ADDR.store(faulting_addr, Ordering::SeqCst);
+        let is_mmio = faulting_addr >= crate::memory::layout::L3_CACHE_BASE
+            && faulting_addr < crate::memory::layout::KERNEL_CODE_START;
+        is_mmio && (error & PageFaultErrorCode::PROTECTION_VIOLATION.bits()) == 0
+    }
+
+    #[test]
+    fn test_page_fault_resolver_dispatch() {
+        let shadow = crate::memory::layout::SHADOW_REG_BASE;
+
+        // No resolver registered yet
+        assert!(!resolve_page_fault(shadow, 0));
+
+        set_page_fault_resolver(mmio_resolver);
+
+        assert!(resolve_page_fault(shadow, 0));
+        assert_eq!(RESOLVED_ADDR.load(Ordering::SeqCst), shadow);
+
+        // Protection violations and non-MMIO addresses are not resolved
+        assert!(!resolve_page_fault(
+            shadow,
+            PageFaultErrorCode::PROTECTION_VIOLATION.bits()
+        ));
+        assert!(!resolve_page_fault(0x1000, 0));
+        assert_eq!(RESOLVED_ADDR.load(Ordering::SeqCst), 0x1000);
+    }
+
+    extern crate alloc;
+    use alloc::string::String;
+
+    /// Writer standing in for the serial port
+    struct CaptureWriter(String);
+
+    impl Write for CaptureWriter {
+        fn write_str(&mut self, s: &str) -> core::fmt::Result {
+            self.0.push_str(s);
+            Ok(())
+        }
+    }
+
+    fn test_panic_dump(out: &mut dyn Write) {
+        let _ = write!(out, "dump: L3 occupancy {}", 3);
+    }
+
+    #[test]
+    fn test_panic_dump_hook_invoked() {
+        set_panic_dump(test_panic_dump);
+
+        let mut out = CaptureWriter(String::new());
+        assert!(run_panic_dump(&mut out));
+        assert_eq!(out.0, "dump: L3 occupancy 3");
+    }
+
+    #[test]
+    fn test_shadow_panic_dump() {
+        let mut out = CaptureWriter(String::new());
+        shadow_panic_dump(&mut out);
+        assert_eq!(out.0, "Shadow Registers: no runtime registered\n");
+
+        let mut runtime = ShadowRegisterRuntime::new();
+        runtime
+            .register_fuse(
+                silent_breath_mmio::RegisterId(0x42),
+                crate::memory::layout::FUSE_BASE,
+                silent_breath_mmio::FuseMode::MTP,
+            )
+            .unwrap();
+
+        let mut out = CaptureWriter(String::new());
+        unsafe {
+            set_panic_shadow_runtime(&runtime);
+            shadow_panic_dump(&mut out);
+            set_panic_shadow_runtime(core::ptr::null());
+        }
+        assert!(out.0.starts_with("Shadow Registers:\n"));
+        assert!(out.0.contains("[0x0042] Uninitialized"));
+    }
+
+    #[test]
+    fn test_coherency_panic_dump() {
+        let mut out = CaptureWriter(String::new());
+        coherency_panic_dump(&mut out);
+        assert_eq!(out.0, "Coherency: no runtime registered\n");
+
+        let mut runtime = alloc::boxed::Box::new(CoherencyRuntime::new());
+        let mut out = CaptureWriter(String::new());
+        unsafe {
+            runtime.init_core(3);
+            set_panic_coherency_runtime(&*runtime);
+            coherency_panic_dump(&mut out);
+            set_panic_coherency_runtime(core::ptr::null());
+        }
+        assert!(out.0.starts_with("Coherency:\n  Core 3: 0 hits, 0 misses\n"));
+        assert!(out.0.contains("L3: 0 valid lines"));
+        assert!(out.0.contains("Invariants: ok"));
+    }
+
+    #[test]
+    fn test_set_handler_requires_init() {
+        assert_eq!(set_handler(0x40, test_handler), Err("IDT not initialized"));
+    }
+}
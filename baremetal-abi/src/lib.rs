@@ -5,11 +5,13 @@
 extern crate alloc;
 
 pub mod abi;
+pub mod allocator;
 pub mod boot;
 pub mod cpu;
 pub mod interrupts;
 pub mod memory;
 pub mod performance;
+pub mod selftest;
 
 // Re-export Silent-Breath-Online cache coherency system
 pub use silent_breath_mmio::{
@@ -2,6 +2,8 @@
 //!
 //! Physical and virtual memory management
 
+use crate::boot::{MemoryRegion, MemoryRegionType, MAX_MEMORY_REGIONS};
+use bootloader_api::info::MemoryRegionKind;
 use bootloader_api::BootInfo;
 
 /// Physical memory layout for i9-12900K
@@ -12,8 +14,8 @@ pub mod layout {
     pub const COHERENCY_CTRL_BASE: u64 = 0xFFFF_9000_4010_0000;
     /// Shadow registers MMIO base
     pub const SHADOW_REG_BASE: u64 = 0xFFFF_9000_5000_0000;
-    /// Hardware fuses MMIO base
-    pub const FUSE_BASE: u64 = 0xFFFF_9000_6000_0000;
+    /// Hardware fuses MMIO base (owned by the fuse manager)
+    pub const FUSE_BASE: u64 = silent_breath_mmio::fuse_manager::FUSE_BASE;
 
     /// Kernel code start
     pub const KERNEL_CODE_START: u64 = 0xFFFF_FFFF_8000_0000;
@@ -21,16 +23,109 @@ pub mod layout {
     pub const KERNEL_HEAP_START: u64 = 0xFFFF_8800_0000_0000;
     /// Kernel heap size (1 GiB)
     pub const KERNEL_HEAP_SIZE: u64 = 1024 * 1024 * 1024;
+
+    /// The MMIO windows above, for the coherency and shadow runtimes
+    pub const MMIO_LAYOUT: silent_breath_mmio::MmioLayout = silent_breath_mmio::MmioLayout {
+        l3_base: L3_CACHE_BASE as usize,
+        coherency_base: COHERENCY_CTRL_BASE as usize,
+        shadow_base: SHADOW_REG_BASE as usize,
+        fuse_base: FUSE_BASE as usize,
+    };
 }
 
+/// Memory map copied out of the boot info
+static mut MEMORY_REGIONS: [MemoryRegion; MAX_MEMORY_REGIONS] = [MemoryRegion {
+    start: 0,
+    size: 0,
+    region_type: MemoryRegionType::Reserved,
+}; MAX_MEMORY_REGIONS];
+
+/// Global physical frame allocator
+static mut FRAME_ALLOCATOR: Option<BumpFrameAllocator<'static>> = None;
+
 /// Initialize memory management
 pub fn init(boot_info: &'static mut BootInfo) {
-    // In a real implementation:
-    // 1. Parse memory map from boot_info
-    // 2. Initialize physical frame allocator
-    // 3. Set up kernel heap
-    // 4. Map MMIO regions
-    let _ = boot_info;
+    // Still to do:
+    // - Set up kernel heap
+    // - Map MMIO regions
+    unsafe {
+        let mut count = 0;
+        for region in boot_info.memory_regions.iter().take(MAX_MEMORY_REGIONS) {
+            MEMORY_REGIONS[count] = MemoryRegion {
+                start: region.start,
+                size: region.end.saturating_sub(region.start),
+                region_type: match region.kind {
+                    MemoryRegionKind::Usable => MemoryRegionType::Usable,
+                    _ => MemoryRegionType::Reserved,
+                },
+            };
+            count += 1;
+        }
+
+        init_from_regions(&MEMORY_REGIONS[..count]);
+    }
+}
+
+/// Initialize the global frame allocator from a parsed memory map
+///
+/// Used directly when booting through Multiboot2 instead of `bootloader_api`.
+pub fn init_from_regions(regions: &'static [MemoryRegion]) {
+    unsafe {
+        FRAME_ALLOCATOR = Some(BumpFrameAllocator::new(regions));
+    }
+}
+
+/// Get global frame allocator
+///
+/// # Safety
+/// Mutable access to static mut
+pub unsafe fn get_frame_allocator() -> Option<&'static mut BumpFrameAllocator<'static>> {
+    FRAME_ALLOCATOR.as_mut()
+}
+
+/// Bump allocator handing out 4 KiB physical frames from usable regions
+///
+/// Frames are never freed; the cursor only moves forward.
+pub struct BumpFrameAllocator<'a> {
+    regions: &'a [MemoryRegion],
+    region_index: usize,
+    next: u64,
+}
+
+impl<'a> BumpFrameAllocator<'a> {
+    /// Create a frame allocator over a memory map
+    pub const fn new(regions: &'a [MemoryRegion]) -> Self {
+        Self {
+            regions,
+            region_index: 0,
+            next: 0,
+        }
+    }
+
+    /// Allocate the next free 4 KiB frame
+    pub fn allocate_frame(&mut self) -> Option<PhysAddr> {
+        while let Some(region) = self.regions.get(self.region_index) {
+            if region.region_type == MemoryRegionType::Usable {
+                let frame = align_up(self.next.max(region.start), PAGE_SIZE);
+                if frame.checked_add(PAGE_SIZE).is_some_and(|end| end <= region.end()) {
+                    self.next = frame + PAGE_SIZE;
+                    return Some(PhysAddr::new(frame));
+                }
+            }
+
+            // Region exhausted, move on to the next one
+            self.region_index += 1;
+        }
+
+        None
+    }
+}
+
+/// Find the first usable region of at least `min_size` bytes
+pub fn find_usable_region(regions: &[MemoryRegion], min_size: u64) -> Option<&MemoryRegion> {
+    regions
+        .iter()
+        .find(|r| r.region_type == MemoryRegionType::Usable && r.size >= min_size)
 }
 
 /// Physical address
@@ -53,6 +148,11 @@ impl PhysAddr {
     pub const fn as_u64(self) -> u64 {
         self.0
     }
+
+    /// Get the 4 KiB frame number containing this address
+    pub const fn frame_number(self) -> u64 {
+        self.0 / PAGE_SIZE
+    }
 }
 
 impl VirtAddr {
@@ -79,6 +179,31 @@ impl VirtAddr {
             upper_bits == 0
         }
     }
+
+    /// Level 4 page table index (bits 39-47)
+    pub const fn p4_index(self) -> u16 {
+        ((self.0 >> 39) & 0x1FF) as u16
+    }
+
+    /// Level 3 page table index (bits 30-38)
+    pub const fn p3_index(self) -> u16 {
+        ((self.0 >> 30) & 0x1FF) as u16
+    }
+
+    /// Level 2 page table index (bits 21-29)
+    pub const fn p2_index(self) -> u16 {
+        ((self.0 >> 21) & 0x1FF) as u16
+    }
+
+    /// Level 1 page table index (bits 12-20)
+    pub const fn p1_index(self) -> u16 {
+        ((self.0 >> 12) & 0x1FF) as u16
+    }
+
+    /// Offset within the 4 KiB page (bits 0-11)
+    pub const fn page_offset(self) -> u16 {
+        (self.0 & 0xFFF) as u16
+    }
 }
 
 /// Page size (4 KiB)
@@ -118,6 +243,92 @@ mod tests {
         assert_eq!(align_up(0x5000, PAGE_SIZE), 0x5000);
     }
 
+    #[test]
+    fn test_mmio_layout_matches_constants() {
+        let mmio = layout::MMIO_LAYOUT;
+        assert_eq!(mmio.l3_base as u64, layout::L3_CACHE_BASE);
+        assert_eq!(mmio.coherency_base as u64, layout::COHERENCY_CTRL_BASE);
+        assert_eq!(mmio.core_coherency_base(2) as u64, layout::COHERENCY_CTRL_BASE + 0x2000);
+        assert_eq!(mmio.shadow_base as u64, layout::SHADOW_REG_BASE);
+        assert_eq!(mmio.fuse_base as u64, layout::FUSE_BASE);
+    }
+
+    #[test]
+    fn test_page_table_indices_kernel_code() {
+        let addr = VirtAddr::new(layout::KERNEL_CODE_START);
+        assert_eq!(addr.p4_index(), 511);
+        assert_eq!(addr.p3_index(), 510);
+        assert_eq!(addr.p2_index(), 0);
+        assert_eq!(addr.p1_index(), 0);
+        assert_eq!(addr.page_offset(), 0);
+    }
+
+    #[test]
+    fn test_page_table_indices_mmio() {
+        // 0xFFFF_9000_5000_0000: shadow register MMIO base
+        let addr = VirtAddr::new(layout::SHADOW_REG_BASE);
+        assert_eq!(addr.p4_index(), 288);
+        assert_eq!(addr.p3_index(), 1);
+        assert_eq!(addr.p2_index(), 128);
+        assert_eq!(addr.p1_index(), 0);
+
+        let addr = VirtAddr::new(0x0000_0000_0020_3ABC);
+        assert_eq!(addr.p4_index(), 0);
+        assert_eq!(addr.p3_index(), 0);
+        assert_eq!(addr.p2_index(), 1);
+        assert_eq!(addr.p1_index(), 3);
+        assert_eq!(addr.page_offset(), 0xABC);
+    }
+
+    #[test]
+    fn test_phys_frame_number() {
+        assert_eq!(PhysAddr::new(0x0).frame_number(), 0);
+        assert_eq!(PhysAddr::new(0xFFF).frame_number(), 0);
+        assert_eq!(PhysAddr::new(0x1000).frame_number(), 1);
+        assert_eq!(PhysAddr::new(0x10_0000).frame_number(), 0x100);
+    }
+
+    #[test]
+    fn test_find_usable_region() {
+        let regions = [
+            MemoryRegion { start: 0x0, size: 0x1000, region_type: MemoryRegionType::Usable },
+            MemoryRegion { start: 0x1000, size: 0x10_0000, region_type: MemoryRegionType::Reserved },
+            MemoryRegion { start: 0x20_0000, size: 0x10_0000, region_type: MemoryRegionType::Usable },
+        ];
+
+        assert_eq!(find_usable_region(&regions, 0x1000).unwrap().start, 0x0);
+        assert_eq!(find_usable_region(&regions, 0x2000).unwrap().start, 0x20_0000);
+        assert!(find_usable_region(&regions, 0x20_0000).is_none());
+        assert!(find_usable_region(&[], 0).is_none());
+    }
+
+    #[test]
+    fn test_bump_frame_allocator_crosses_regions() {
+        let regions = [
+            MemoryRegion { start: 0x1800, size: 0x2800, region_type: MemoryRegionType::Usable },
+            MemoryRegion { start: 0x4000, size: 0x4000, region_type: MemoryRegionType::Reserved },
+            MemoryRegion { start: 0x10_0000, size: 0x1000, region_type: MemoryRegionType::Usable },
+        ];
+        let mut allocator = BumpFrameAllocator::new(&regions);
+
+        // First region: 0x1800..0x4000 holds two aligned frames
+        assert_eq!(allocator.allocate_frame(), Some(PhysAddr::new(0x2000)));
+        assert_eq!(allocator.allocate_frame(), Some(PhysAddr::new(0x3000)));
+
+        // Reserved region is skipped
+        assert_eq!(allocator.allocate_frame(), Some(PhysAddr::new(0x10_0000)));
+
+        // Exhausted
+        assert_eq!(allocator.allocate_frame(), None);
+        assert_eq!(allocator.allocate_frame(), None);
+    }
+
+    #[test]
+    fn test_bump_frame_allocator_empty() {
+        let mut allocator = BumpFrameAllocator::new(&[]);
+        assert_eq!(allocator.allocate_frame(), None);
+    }
+
     #[test]
     fn test_canonical_addresses() {
         // Low canonical range
@@ -26,6 +26,24 @@ pub mod msr {
     pub const IA32_PERF_GLOBAL_CTRL: u32 = 0x38F;
 }
 
+/// Global control value enabling fixed counters 0-2 (bits 34:32) and PMC 0-3
+pub const GLOBAL_CTRL_ENABLE_ALL: u64 = (0x7u64 << 32) | 0xF;
+
+/// Global control value with every counter stopped
+pub const GLOBAL_CTRL_FROZEN: u64 = 0;
+
+/// Run `sample` with counters frozen, then restore the saved control word
+fn sample_frozen<T>(
+    freeze: impl FnOnce() -> u64,
+    unfreeze: impl FnOnce(u64),
+    sample: impl FnOnce() -> T,
+) -> T {
+    let saved = freeze();
+    let value = sample();
+    unfreeze(saved);
+    value
+}
+
 /// Performance event types
 #[derive(Debug, Clone, Copy)]
 #[repr(u64)]
@@ -203,8 +221,45 @@ impl PerfMonitor {
 
         // Enable fixed counters in global control
         // Bits [34:32] enable fixed counters 0-2
-        let global_ctrl = (0x7u64 << 32) | 0xF; // Enable fixed 0-2 and PMC 0-3
-        write_msr(msr::IA32_PERF_GLOBAL_CTRL, global_ctrl);
+        write_msr(msr::IA32_PERF_GLOBAL_CTRL, GLOBAL_CTRL_ENABLE_ALL);
+    }
+
+    /// Stop every counter at once
+    ///
+    /// Returns the previous global control value for `unfreeze_all`.
+    ///
+    /// # Safety
+    /// Must be called from ring 0
+    pub unsafe fn freeze_all() -> u64 {
+        let saved = read_msr(msr::IA32_PERF_GLOBAL_CTRL);
+        write_msr(msr::IA32_PERF_GLOBAL_CTRL, GLOBAL_CTRL_FROZEN);
+        saved
+    }
+
+    /// Restore a global control value saved by `freeze_all`
+    ///
+    /// # Safety
+    /// Must be called from ring 0
+    pub unsafe fn unfreeze_all(mask: u64) {
+        write_msr(msr::IA32_PERF_GLOBAL_CTRL, mask);
+    }
+
+    /// Read instructions, core cycles and ref cycles at the same instant
+    ///
+    /// # Safety
+    /// Must be called from ring 0
+    pub unsafe fn snapshot_fixed() -> [u64; 3] {
+        sample_frozen(
+            || Self::freeze_all(),
+            |saved| Self::unfreeze_all(saved),
+            || {
+                [
+                    FixedPerfCounters::instructions_retired(),
+                    FixedPerfCounters::unhalted_core_cycles(),
+                    FixedPerfCounters::unhalted_ref_cycles(),
+                ]
+            },
+        )
     }
 }
 
@@ -226,8 +281,36 @@ pub unsafe fn get_monitor() -> &'static mut PerfMonitor {
     &mut PERF_MONITOR
 }
 
+/// Fixed-function counter width on Alder Lake
+pub const FIXED_COUNTER_BITS: u32 = 48;
+
+/// Mask of the implemented fixed-function counter bits
+pub const FIXED_COUNTER_MASK: u64 = (1u64 << FIXED_COUNTER_BITS) - 1;
+
+/// Elapsed count between two fixed-counter samples
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CounterDelta {
+    /// Elapsed count, modulo the 48-bit counter width
+    pub count: u64,
+    /// End sample was below the start sample
+    ///
+    /// Either the counter wrapped (`count` is still correct for a single
+    /// wrap) or it was reset mid-measurement (`count` is meaningless).
+    pub wrapped: bool,
+}
+
+/// Compute the elapsed count between two fixed-counter samples
+pub const fn counter_delta(start: u64, end: u64) -> CounterDelta {
+    let start = start & FIXED_COUNTER_MASK;
+    let end = end & FIXED_COUNTER_MASK;
+    CounterDelta {
+        count: end.wrapping_sub(start) & FIXED_COUNTER_MASK,
+        wrapped: end < start,
+    }
+}
+
 /// Benchmark a function and return elapsed cycles
-pub fn benchmark<F, R>(f: F) -> (R, u64)
+pub fn benchmark<F, R>(f: F) -> (R, CounterDelta)
 where
     F: FnOnce() -> R,
 {
@@ -235,11 +318,23 @@ where
         let start_cycles = FixedPerfCounters::unhalted_core_cycles();
         let result = f();
         let end_cycles = FixedPerfCounters::unhalted_core_cycles();
-        (result, end_cycles - start_cycles)
+        (result, counter_delta(start_cycles, end_cycles))
     }
 }
 
+/// IPC from instruction and cycle deltas (0.0 if either counter wrapped)
+fn ipc_from_deltas(instructions: CounterDelta, cycles: CounterDelta) -> f64 {
+    if instructions.wrapped || cycles.wrapped || cycles.count == 0 {
+        return 0.0;
+    }
+
+    instructions.count as f64 / cycles.count as f64
+}
+
 /// Benchmark a function and return IPC
+///
+/// Returns 0.0 if either counter went backwards during the run, since a
+/// reset cannot be told apart from a wrap.
 pub fn benchmark_ipc<F, R>(f: F) -> (R, f64)
 where
     F: FnOnce() -> R,
@@ -253,16 +348,10 @@ where
         let end_instructions = FixedPerfCounters::instructions_retired();
         let end_cycles = FixedPerfCounters::unhalted_core_cycles();
 
-        let instructions = (end_instructions - start_instructions) as f64;
-        let cycles = (end_cycles - start_cycles) as f64;
+        let instructions = counter_delta(start_instructions, end_instructions);
+        let cycles = counter_delta(start_cycles, end_cycles);
 
-        let ipc = if cycles > 0.0 {
-            instructions / cycles
-        } else {
-            0.0
-        };
-
-        (result, ipc)
+        (result, ipc_from_deltas(instructions, cycles))
     }
 }
 
@@ -276,6 +365,38 @@ mod tests {
         assert_eq!(PerfEvent::UnhaltedCoreCycles as u64, 0x003C);
     }
 
+    #[test]
+    fn test_counter_delta_forward() {
+        assert_eq!(counter_delta(100, 350), CounterDelta { count: 250, wrapped: false });
+        assert_eq!(counter_delta(7, 7), CounterDelta { count: 0, wrapped: false });
+    }
+
+    #[test]
+    fn test_counter_delta_wrap() {
+        // Counter wrapped at 48 bits between the samples
+        let delta = counter_delta(FIXED_COUNTER_MASK - 9, 20);
+        assert_eq!(delta, CounterDelta { count: 30, wrapped: true });
+
+        // Reset mid-run: flagged, never a huge underflowed value
+        let delta = counter_delta(5_000, 10);
+        assert!(delta.wrapped);
+        assert!(delta.count <= FIXED_COUNTER_MASK);
+
+        // Bits above the counter width are ignored
+        assert_eq!(counter_delta(1 << 48, (1 << 48) + 4).count, 4);
+    }
+
+    #[test]
+    fn test_ipc_from_deltas() {
+        let instructions = counter_delta(0, 3_000);
+        let cycles = counter_delta(0, 1_000);
+        assert_eq!(ipc_from_deltas(instructions, cycles), 3.0);
+
+        assert_eq!(ipc_from_deltas(instructions, counter_delta(0, 0)), 0.0);
+        assert_eq!(ipc_from_deltas(counter_delta(10, 5), cycles), 0.0);
+        assert_eq!(ipc_from_deltas(instructions, counter_delta(10, 5)), 0.0);
+    }
+
     #[test]
     fn test_perf_counter_creation() {
         let counter = PerfCounter::new(0);
@@ -283,4 +404,29 @@ mod tests {
         assert_eq!(counter.event_select_msr, msr::IA32_PERFEVTSEL0);
         assert!(!counter.enabled);
     }
+
+    #[test]
+    fn test_global_ctrl_values() {
+        // Fixed counters 0-2 and PMC 0-3
+        assert_eq!(GLOBAL_CTRL_ENABLE_ALL, 0x7_0000_000F);
+        assert_eq!(GLOBAL_CTRL_FROZEN, 0);
+    }
+
+    #[test]
+    fn test_sample_frozen_restores_control() {
+        use core::cell::Cell;
+
+        // Mock IA32_PERF_GLOBAL_CTRL with a custom (partial) enable mask
+        let ctrl = Cell::new(0x1_0000_0003u64);
+
+        let seen = sample_frozen(
+            || ctrl.replace(GLOBAL_CTRL_FROZEN),
+            |saved| ctrl.set(saved),
+            || ctrl.get(),
+        );
+
+        // Counters were stopped while sampling, then the saved mask came back
+        assert_eq!(seen, GLOBAL_CTRL_FROZEN);
+        assert_eq!(ctrl.get(), 0x1_0000_0003);
+    }
 }
@@ -0,0 +1,280 @@
+//! Boot-time self-test for i9-12900K bare-metal
+//!
+//! Quick functional checks of each subsystem, run before the kernel
+//! relies on it
+
+use crate::cpu::{cached_features, CpuFeatures};
+use silent_breath_mmio::{
+    CacheEvent, CacheState, ECCError, HammingECC, RegisterId, ShadowRegister,
+    StateTransitionTable,
+};
+
+/// Individual self-test
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum SelfTest {
+    /// Hamming encode/decode corrects an injected single-bit error
+    Ecc = 0,
+    /// Shadow register write, commit and CRC verify
+    ShadowRegister = 1,
+    /// MESI transition table keeps two cores coherent through read, write, re-read
+    Mesi = 2,
+    /// CPUID feature bits are self-consistent
+    Cpuid = 3,
+}
+
+impl SelfTest {
+    /// Number of self-tests
+    pub const COUNT: usize = 4;
+
+    /// Every self-test, in run order
+    pub const ALL: [SelfTest; SelfTest::COUNT] = [
+        SelfTest::Ecc,
+        SelfTest::ShadowRegister,
+        SelfTest::Mesi,
+        SelfTest::Cpuid,
+    ];
+
+    /// Short name for boot logs
+    pub const fn name(self) -> &'static str {
+        match self {
+            SelfTest::Ecc => "ECC round trip",
+            SelfTest::ShadowRegister => "Shadow register",
+            SelfTest::Mesi => "MESI flow",
+            SelfTest::Cpuid => "CPUID features",
+        }
+    }
+
+    /// Run this check
+    pub fn run(self) -> Result<(), &'static str> {
+        match self {
+            SelfTest::Ecc => check_ecc(),
+            SelfTest::ShadowRegister => check_shadow_register(),
+            SelfTest::Mesi => check_mesi(),
+            SelfTest::Cpuid => check_cpu_features(cached_features()),
+        }
+    }
+}
+
+/// Per-test outcome of a self-test run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SelfTestReport {
+    results: [Result<(), &'static str>; SelfTest::COUNT],
+}
+
+impl SelfTestReport {
+    /// Report with every test marked as not run
+    pub const fn new() -> Self {
+        Self {
+            results: [Err("not run"); SelfTest::COUNT],
+        }
+    }
+
+    /// Store the outcome of `test`
+    pub fn record(&mut self, test: SelfTest, result: Result<(), &'static str>) {
+        self.results[test as usize] = result;
+    }
+
+    /// Outcome of `test`
+    pub fn result(&self, test: SelfTest) -> Result<(), &'static str> {
+        self.results[test as usize]
+    }
+
+    /// Check if `test` passed
+    pub fn passed(&self, test: SelfTest) -> bool {
+        self.result(test).is_ok()
+    }
+
+    /// Number of tests that failed or did not run
+    pub fn failed_count(&self) -> usize {
+        self.results.iter().filter(|result| result.is_err()).count()
+    }
+
+    /// Check if every test passed
+    pub fn all_passed(&self) -> bool {
+        self.failed_count() == 0
+    }
+}
+
+impl Default for SelfTestReport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Run every self-test
+pub fn run() -> SelfTestReport {
+    let mut report = SelfTestReport::new();
+    for test in SelfTest::ALL {
+        report.record(test, test.run());
+    }
+    report
+}
+
+/// Encode a word, then check the decoder restores every single-bit flip
+pub fn check_ecc() -> Result<(), &'static str> {
+    const PATTERN: u64 = 0xA5A5_5A5A_0123_4567;
+
+    let ecc = HammingECC::new();
+    let (data, parity) = ecc.encode(PATTERN);
+    if ecc.verify(data, parity) != ECCError::NoError {
+        return Err("ECC flags a clean word");
+    }
+
+    for bit in 0..u64::BITS {
+        let (corrected, syndrome) = ecc.decode(data ^ (1 << bit), parity)?;
+        if corrected != PATTERN || syndrome.error_type != ECCError::SingleBit {
+            return Err("ECC did not correct a single-bit error");
+        }
+    }
+
+    Ok(())
+}
+
+/// Write, commit and verify a scratch shadow register
+pub fn check_shadow_register() -> Result<(), &'static str> {
+    const PATTERN: u64 = 0x5E1F_7E57_C0DE_F00D;
+
+    let mut reg = ShadowRegister::new(RegisterId(0), 0);
+    reg.write(PATTERN)?;
+    reg.commit()?;
+
+    if reg.read() != PATTERN {
+        return Err("Shadow register lost the committed value");
+    }
+    if !reg.verify() {
+        return Err("Shadow register CRC mismatch after commit");
+    }
+
+    Ok(())
+}
+
+/// Drive two cores through the coherency flow and check MESI after each access
+///
+/// Four accesses: both cores read, core 0 writes, core 1 reads again. The
+/// write applies the remote invalidation in the same step, so it covers
+/// steps 3 and 4 of `CoherencyRuntime::execute_coherency_flow`.
+pub fn check_mesi() -> Result<(), &'static str> {
+    let table = StateTransitionTable::MESI;
+    let mut states = [CacheState::Invalid; 2];
+
+    // (core, is_write, expected core 0 / core 1 states afterwards)
+    let steps = [
+        (0, false, [CacheState::Shared, CacheState::Invalid]),
+        (1, false, [CacheState::Shared, CacheState::Shared]),
+        (0, true, [CacheState::Modified, CacheState::Invalid]),
+        (1, false, [CacheState::Shared, CacheState::Shared]),
+    ];
+
+    for (core, is_write, expected) in steps {
+        let (local, remote) = if is_write {
+            (CacheEvent::LocalWrite, CacheEvent::RemoteWrite)
+        } else {
+            (CacheEvent::LocalRead, CacheEvent::RemoteRead)
+        };
+        states[core] = table.transition(states[core], local);
+        states[1 - core] = table.transition(states[1 - core], remote);
+
+        // An owner (M/E) excludes every other valid copy
+        for (owner, other) in [(states[0], states[1]), (states[1], states[0])] {
+            if owner.allows_silent_write() && other.is_valid() {
+                return Err("MESI invariant violated");
+            }
+        }
+        if states != expected {
+            return Err("MESI flow reached an unexpected state");
+        }
+    }
+
+    Ok(())
+}
+
+/// Check that reported ISA extensions include what they build on
+pub fn check_cpu_features(features: &CpuFeatures) -> Result<(), &'static str> {
+    if !features.sse2 {
+        return Err("SSE2 missing (baseline for x86_64)");
+    }
+    if features.sse4_2 && !features.sse4_1 {
+        return Err("SSE4.2 reported without SSE4.1");
+    }
+    if features.avx && !features.xsave {
+        return Err("AVX reported without XSAVE");
+    }
+    if (features.avx2 || features.fma) && !features.avx {
+        return Err("AVX2/FMA reported without AVX");
+    }
+    if features.avx512f && !features.avx2 {
+        return Err("AVX-512F reported without AVX2");
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_aggregation() {
+        let mut report = SelfTestReport::new();
+        assert_eq!(report.failed_count(), SelfTest::COUNT);
+        assert_eq!(report.result(SelfTest::Ecc), Err("not run"));
+
+        for test in SelfTest::ALL {
+            report.record(test, Ok(()));
+        }
+        assert!(report.all_passed());
+
+        report.record(SelfTest::Mesi, Err("MESI invariant violated"));
+        assert_eq!(report.failed_count(), 1);
+        assert!(!report.all_passed());
+        assert!(!report.passed(SelfTest::Mesi));
+        assert!(report.passed(SelfTest::Cpuid));
+        assert_eq!(report.result(SelfTest::Mesi), Err("MESI invariant violated"));
+    }
+
+    #[test]
+    fn test_check_ecc() {
+        assert_eq!(check_ecc(), Ok(()));
+    }
+
+    #[test]
+    fn test_check_shadow_register() {
+        assert_eq!(check_shadow_register(), Ok(()));
+    }
+
+    #[test]
+    fn test_check_mesi() {
+        assert_eq!(check_mesi(), Ok(()));
+    }
+
+    #[test]
+    fn test_check_cpu_features() {
+        let mut features = CpuFeatures::from_bits(0);
+        assert!(check_cpu_features(&features).is_err());
+
+        features.sse2 = true;
+        assert_eq!(check_cpu_features(&features), Ok(()));
+
+        features.avx2 = true;
+        assert_eq!(
+            check_cpu_features(&features),
+            Err("AVX2/FMA reported without AVX")
+        );
+
+        features.avx = true;
+        features.xsave = true;
+        assert_eq!(check_cpu_features(&features), Ok(()));
+
+        // The host CPU must pass too
+        assert_eq!(check_cpu_features(&CpuFeatures::detect()), Ok(()));
+    }
+
+    #[test]
+    fn test_run_passes_on_host() {
+        let report = run();
+        for test in SelfTest::ALL {
+            assert_eq!(report.result(test), Ok(()), "{} failed", test.name());
+        }
+    }
+}
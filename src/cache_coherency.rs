@@ -1,7 +1,12 @@
 /// 4-State MESI Cache Coherency Protocol Implementation
 /// Replaces traditional ROM/Firmware with Rust MMIO Real-Time Traversal
 
-use core::sync::atomic::{AtomicU8, Ordering};
+use alloc::vec::Vec;
+use core::cell::UnsafeCell;
+use core::sync::atomic::{AtomicU32, AtomicU8, Ordering};
+
+/// Cache line payload size in bytes
+pub const CACHE_LINE_SIZE: usize = 64;
 
 /// Cache Line States (4-State Logic Gating)
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -25,6 +30,32 @@ impl From<u8> for CacheState {
     }
 }
 
+impl CacheState {
+    /// Line holds usable data (anything but Invalid)
+    #[inline(always)]
+    pub const fn is_valid(&self) -> bool {
+        !matches!(self, CacheState::Invalid)
+    }
+
+    /// Line differs from memory (Modified)
+    #[inline(always)]
+    pub const fn is_dirty(&self) -> bool {
+        matches!(self, CacheState::Modified)
+    }
+
+    /// A write needs no invalidation broadcast (Modified/Exclusive)
+    #[inline(always)]
+    pub const fn allows_silent_write(&self) -> bool {
+        matches!(self, CacheState::Modified | CacheState::Exclusive)
+    }
+
+    /// Evicting the line must write it back first (Modified)
+    #[inline(always)]
+    pub const fn needs_writeback_on_evict(&self) -> bool {
+        self.is_dirty()
+    }
+}
+
 /// Cache Line Metadata for Real-Time Traversal
 #[repr(C, align(64))]
 pub struct CacheLine {
@@ -37,9 +68,13 @@ pub struct CacheLine {
     /// Reference count for Shared state
     ref_count: AtomicU8,
     /// Data payload (64-byte cache line)
-    data: [u8; 64],
+    data: UnsafeCell<[u8; CACHE_LINE_SIZE]>,
 }
 
+// Payload access through `&self` only happens in the unsafe `read_bytes`
+// and `write_bytes`, whose callers uphold the MESI single-writer rule.
+unsafe impl Sync for CacheLine {}
+
 impl CacheLine {
     pub const fn new() -> Self {
         Self {
@@ -47,7 +82,7 @@ impl CacheLine {
             tag: 0,
             owner_core: 0xFF,
             ref_count: AtomicU8::new(0),
-            data: [0u8; 64],
+            data: UnsafeCell::new([0u8; CACHE_LINE_SIZE]),
         }
     }
 
@@ -71,17 +106,169 @@ impl CacheLine {
             .map_err(|actual| CacheState::from(actual))
     }
 
+    /// Get the owning core (0xFF if none)
+    #[inline(always)]
+    pub fn get_owner(&self) -> u8 {
+        self.owner_core
+    }
+
+    /// Get the sharer count
+    #[inline(always)]
+    pub fn get_ref_count(&self) -> u8 {
+        self.ref_count.load(Ordering::Acquire)
+    }
+
     /// Force state transition (for invalidation)
     #[inline(always)]
     pub fn force_state(&self, new_state: CacheState) {
         self.state.store(new_state as u8, Ordering::Release);
     }
+
+    /// Read `len` payload bytes starting at `offset`
+    ///
+    /// Returns `None` if the range runs past the end of the line.
+    ///
+    /// # Safety
+    /// No `write_bytes` to this line may run, on any thread, while the
+    /// returned slice is alive.
+    #[inline]
+    pub unsafe fn read_bytes(&self, offset: usize, len: usize) -> Option<&[u8]> {
+        let end = offset.checked_add(len)?;
+        if end > CACHE_LINE_SIZE {
+            return None;
+        }
+
+        let data = &*self.data.get();
+        Some(&data[offset..end])
+    }
+
+    /// Write `src` into the payload at `offset` and mark the line Modified
+    ///
+    /// # Safety
+    /// The caller must be the line's only accessor for the duration of the
+    /// call: no other `write_bytes`, no live slice from `read_bytes` and no
+    /// concurrent `data_equals`. `src` must not point into this line's
+    /// payload.
+    #[inline]
+    pub unsafe fn write_bytes(&self, offset: usize, src: &[u8]) -> Result<(), &'static str> {
+        let end = offset
+            .checked_add(src.len())
+            .ok_or("Cache line write out of bounds")?;
+        if end > CACHE_LINE_SIZE {
+            return Err("Cache line write out of bounds");
+        }
+
+        // Range checked above; exclusivity and aliasing are the caller's
+        let dst = (self.data.get() as *mut u8).add(offset);
+        core::ptr::copy_nonoverlapping(src.as_ptr(), dst, src.len());
+
+        self.force_state(CacheState::Modified);
+        Ok(())
+    }
+
+    /// Compare the payload with `other` (e.g. the memory copy)
+    ///
+    /// Uses 32-byte AVX2 compares when the CPU supports them, a scalar
+    /// loop otherwise.
+    #[inline]
+    pub fn data_equals(&self, other: &[u8; CACHE_LINE_SIZE]) -> bool {
+        // SAFETY: `write_bytes` callers guarantee no concurrent writer
+        let data = unsafe { &*self.data.get() };
+
+        #[cfg(target_arch = "x86_64")]
+        if avx2_available() {
+            // SAFETY: AVX2 support was just checked
+            return unsafe { data_equals_avx2(data, other) };
+        }
+
+        data_equals_scalar(data, other)
+    }
+
+    /// Evicting or flushing must write back: Modified and differing from `memory`
+    ///
+    /// A Modified line rewritten with the bytes memory already holds is
+    /// clean in practice, so its writeback can be elided.
+    #[inline]
+    pub fn needs_writeback(&self, memory: &[u8; CACHE_LINE_SIZE]) -> bool {
+        self.get_state().is_dirty() && !self.data_equals(memory)
+    }
+}
+
+/// Byte-by-byte line comparison
+#[inline]
+fn data_equals_scalar(a: &[u8; CACHE_LINE_SIZE], b: &[u8; CACHE_LINE_SIZE]) -> bool {
+    a.iter().zip(b.iter()).all(|(x, y)| x == y)
+}
+
+/// Line comparison in two 32-byte AVX2 compares
+///
+/// # Safety
+/// The CPU must support AVX2 (see `avx2_available`)
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+unsafe fn data_equals_avx2(a: &[u8; CACHE_LINE_SIZE], b: &[u8; CACHE_LINE_SIZE]) -> bool {
+    use core::arch::x86_64::{__m256i, _mm256_cmpeq_epi8, _mm256_loadu_si256, _mm256_movemask_epi8};
+
+    for offset in (0..CACHE_LINE_SIZE).step_by(32) {
+        let va = _mm256_loadu_si256(a.as_ptr().add(offset) as *const __m256i);
+        let vb = _mm256_loadu_si256(b.as_ptr().add(offset) as *const __m256i);
+        // All 32 bytes equal sets every mask bit
+        if _mm256_movemask_epi8(_mm256_cmpeq_epi8(va, vb)) != -1 {
+            return false;
+        }
+    }
+
+    true
+}
+
+/// Cached AVX2 detection: 0 = not probed, 1 = absent, 2 = present
+#[cfg(target_arch = "x86_64")]
+static AVX2_SUPPORT: AtomicU8 = AtomicU8::new(0);
+
+/// Check (once) that the CPU has AVX2 and the OS enabled YMM state
+#[cfg(target_arch = "x86_64")]
+fn avx2_available() -> bool {
+    use core::arch::x86_64::{__cpuid, __cpuid_count};
+
+    match AVX2_SUPPORT.load(Ordering::Relaxed) {
+        1 => return false,
+        2 => return true,
+        _ => {}
+    }
+
+    // OSXSAVE (CPUID.1:ECX[27]) and AVX (ECX[28]), then XCR0 SSE|AVX state
+    let leaf_1 = __cpuid(1);
+    let os_avx = leaf_1.ecx & (0b11 << 27) == 0b11 << 27
+        // SAFETY: OSXSAVE is set, so XGETBV is available
+        && unsafe { ymm_state_enabled() };
+    let avx2 = os_avx && __cpuid_count(7, 0).ebx & (1 << 5) != 0;
+
+    AVX2_SUPPORT.store(if avx2 { 2 } else { 1 }, Ordering::Relaxed);
+    avx2
+}
+
+/// XCR0 has SSE and AVX register state enabled
+///
+/// # Safety
+/// CPUID.1:ECX.OSXSAVE must be set
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "xsave")]
+unsafe fn ymm_state_enabled() -> bool {
+    core::arch::x86_64::_xgetbv(0) & 0b110 == 0b110
 }
 
 /// L3 Cache Directory for Multi-Core Coherency
 pub struct L3Directory {
     /// Cache lines in L3
     lines: [CacheLine; 1024],
+    /// Number of invalidation broadcasts issued
+    broadcasts: AtomicU32,
+    /// Number of valid lines evicted by an index collision
+    tag_conflicts: u32,
+    /// Number of Modified lines written back
+    writebacks: u32,
+    /// Per-slot count of Exclusive/Modified lines lost to a remote access
+    demotions: [u32; 1024],
 }
 
 impl L3Directory {
@@ -89,26 +276,162 @@ impl L3Directory {
         const INIT: CacheLine = CacheLine::new();
         Self {
             lines: [INIT; 1024],
+            broadcasts: AtomicU32::new(0),
+            tag_conflicts: 0,
+            writebacks: 0,
+            demotions: [0; 1024],
         }
     }
 
-    /// Real-Time Traversal: Step 1 - Core 1 reads data (Shared state)
+    /// Resolve the directory slot for an address
+    ///
+    /// If the slot holds a valid line for a different address, the old line
+    /// is written back (if Modified) and evicted before refilling.
     #[inline]
-    pub fn core_read(&mut self, core_id: u8, address: u64) -> Result<&[u8; 64], ()> {
-        let index = (address >> 6) % 1024;
-        let line = &self.lines[index as usize];
+    fn lookup(&mut self, address: u64) -> usize {
+        let index = ((address >> 6) % 1024) as usize;
+        let tag = address & !0x3F;
+
+        if self.lines[index].tag != tag {
+            match self.lines[index].get_state() {
+                CacheState::Invalid => {}
+                state => {
+                    if state.needs_writeback_on_evict() {
+                        // Write dirty data back to memory before eviction
+                        self.writeback(index);
+                    }
+                    self.lines[index].force_state(CacheState::Invalid);
+                    self.tag_conflicts += 1;
+                }
+            }
+
+            // Refill for the new address
+            let line = &mut self.lines[index];
+            line.tag = tag;
+            line.owner_core = 0xFF;
+            line.ref_count.store(0, Ordering::Release);
+            *line.data.get_mut() = [0u8; CACHE_LINE_SIZE];
+            self.demotions[index] = 0;
+        }
+
+        index
+    }
+
+    /// Count an owned line being taken away by `core_id`
+    #[inline(always)]
+    fn note_demotion(&mut self, index: usize, core_id: u8) {
+        let line = &self.lines[index];
+        if line.get_state().allows_silent_write() && line.owner_core != core_id {
+            self.demotions[index] = self.demotions[index].saturating_add(1);
+        }
+    }
+
+    /// Times the line holding `address` lost Exclusive/Modified to another core
+    pub fn demotion_count(&self, address: u64) -> u32 {
+        let index = ((address >> 6) % 1024) as usize;
+        if self.lines[index].tag != address & !0x3F {
+            return 0;
+        }
+        self.demotions[index]
+    }
+
+    /// Up to `n` line tags with the most remote demotions, highest first
+    ///
+    /// Lines bouncing between cores here usually point at false sharing.
+    pub fn hottest_contended_lines(&self, n: usize) -> Vec<(u64, u32)> {
+        let mut contended: Vec<(u64, u32)> = self
+            .lines
+            .iter()
+            .zip(self.demotions.iter())
+            .filter(|(_, &count)| count > 0)
+            .map(|(line, &count)| (line.tag, count))
+            .collect();
+
+        contended.sort_unstable_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        contended.truncate(n);
+        contended
+    }
+
+    /// Get a directory line by slot index
+    #[inline(always)]
+    pub fn line(&self, index: usize) -> Option<&CacheLine> {
+        self.lines.get(index)
+    }
+
+    /// Number of valid lines evicted because another address mapped to the same slot
+    pub fn tag_conflicts(&self) -> u32 {
+        self.tag_conflicts
+    }
+
+    /// Number of directory lines not in the Invalid state
+    pub fn occupancy(&self) -> usize {
+        self.lines
+            .iter()
+            .filter(|line| line.get_state().is_valid())
+            .count()
+    }
+
+    /// Line count per state, indexed by `CacheState as usize`
+    pub fn state_histogram(&self) -> [usize; 4] {
+        let mut histogram = [0usize; 4];
+        for line in self.lines.iter() {
+            histogram[line.get_state() as usize] += 1;
+        }
+        histogram
+    }
+
+    /// Owning core of an address held Modified or Exclusive
+    pub fn find_owner(&self, address: u64) -> Option<u8> {
+        let index = ((address >> 6) % 1024) as usize;
+        let line = &self.lines[index];
+
+        if line.tag != address & !0x3F {
+            return None;
+        }
+
+        if line.get_state().allows_silent_write() {
+            Some(line.owner_core)
+        } else {
+            None
+        }
+    }
+
+    /// Real-Time Traversal: Step 1 - Core 1 reads data
+    ///
+    /// A lone reader gets the line Exclusive so a later write can upgrade
+    /// to Modified silently; a second reader demotes it to Shared.
+    #[inline]
+    pub fn core_read(&mut self, core_id: u8, address: u64) -> Result<&[u8; CACHE_LINE_SIZE], ()> {
+        let index = self.lookup(address);
+        self.note_demotion(index, core_id);
+
+        // Dirty data owned by another core must be current before sharing
+        let remote_modified = self.lines[index].get_state().is_dirty()
+            && self.lines[index].owner_core != core_id;
+        if remote_modified {
+            self.writeback(index);
+        }
+
+        let line = &mut self.lines[index];
 
         match line.get_state() {
             CacheState::Invalid => {
-                // Fetch from memory, transition to Shared
-                line.force_state(CacheState::Shared);
-                line.ref_count.fetch_add(1, Ordering::AcqRel);
-                Ok(&line.data)
+                if line.ref_count.load(Ordering::Acquire) == 0 {
+                    // No other holder: fetch from memory, transition to Exclusive
+                    line.force_state(CacheState::Exclusive);
+                    line.owner_core = core_id;
+                    line.ref_count.store(1, Ordering::Release);
+                } else {
+                    // Fetch from memory, transition to Shared
+                    line.force_state(CacheState::Shared);
+                    line.ref_count.fetch_add(1, Ordering::AcqRel);
+                }
+                Ok(line.data.get_mut())
             }
             CacheState::Shared => {
                 // Already shared, increment ref count
                 line.ref_count.fetch_add(1, Ordering::AcqRel);
-                Ok(&line.data)
+                Ok(line.data.get_mut())
             }
             CacheState::Exclusive | CacheState::Modified => {
                 // Transition to Shared if another core reads
@@ -116,52 +439,48 @@ impl L3Directory {
                     line.force_state(CacheState::Shared);
                     line.ref_count.store(2, Ordering::Release);
                 }
-                Ok(&line.data)
+                Ok(line.data.get_mut())
             }
         }
     }
 
     /// Real-Time Traversal: Step 3 - Core 1 writes (Invalidates other cores)
     #[inline]
-    pub fn core_write(&mut self, core_id: u8, address: u64) -> Result<&mut [u8; 64], ()> {
-        let index = (address >> 6) % 1024;
+    pub fn core_write(&mut self, core_id: u8, address: u64) -> Result<&mut [u8; CACHE_LINE_SIZE], ()> {
+        let index = self.lookup(address);
+        self.note_demotion(index, core_id);
 
         // Get state and owner first, before mutable borrow
-        let current_state = self.lines[index as usize].get_state();
-        let owner_core = self.lines[index as usize].owner_core;
+        let current_state = self.lines[index].get_state();
+        let owner_core = self.lines[index].owner_core;
+        let holders = self.lines[index].ref_count.load(Ordering::Acquire);
 
-        // Handle state transitions that need broadcast/writeback
-        match current_state {
-            CacheState::Shared => {
-                // Invalidate all other cores' copies via L3
-                compiler_fence(); // Inlined broadcast_invalidate
-            }
-            CacheState::Modified if owner_core != core_id => {
+        // Silent upgrade only if no other core can hold a copy
+        let silent = match current_state {
+            CacheState::Exclusive | CacheState::Modified => owner_core == core_id,
+            CacheState::Invalid => holders == 0,
+            CacheState::Shared => false,
+        };
+
+        if !silent {
+            if current_state == CacheState::Modified {
                 // Writeback from other core, then acquire
-                compiler_fence(); // Inlined writeback_and_acquire
+                self.writeback(index);
             }
-            _ => {}
+            // Invalidate all other cores' copies via L3
+            self.broadcast_invalidate(core_id, address);
         }
 
         // Now get mutable reference and apply state changes
-        let line = &mut self.lines[index as usize];
-
-        match current_state {
-            CacheState::Shared => {
-                line.force_state(CacheState::Modified);
-                line.owner_core = core_id;
-                line.ref_count.store(0, Ordering::Release);
-            }
-            CacheState::Exclusive | CacheState::Invalid => {
-                line.force_state(CacheState::Modified);
-                line.owner_core = core_id;
-            }
-            CacheState::Modified => {
-                // Already handled above
-            }
+        let line = &mut self.lines[index];
+        line.force_state(CacheState::Modified);
+        line.owner_core = core_id;
+        if !silent {
+            // Every other copy is gone
+            line.ref_count.store(0, Ordering::Release);
         }
 
-        Ok(&mut line.data)
+        Ok(line.data.get_mut())
     }
 
     /// Step 4: Broadcast invalidation to other cores
@@ -170,14 +489,84 @@ impl L3Directory {
         // In real hardware, this would send invalidation messages
         // via the cache coherency bus to all other cores
         // Here we mark the operation as complete for real-time guarantees
+        self.broadcasts.fetch_add(1, Ordering::Relaxed);
         compiler_fence();
     }
 
-    /// Writeback modified data and transfer ownership
+    /// Number of invalidation broadcasts issued by writes
+    pub fn broadcast_count(&self) -> u32 {
+        self.broadcasts.load(Ordering::Relaxed)
+    }
+
+    /// Export per-line tag, state, owner and sharer count
+    pub fn export_lines(
+        &self,
+        tags: &mut [u64; 1024],
+        states: &mut [u8; 1024],
+        owners: &mut [u8; 1024],
+        ref_counts: &mut [u8; 1024],
+    ) {
+        for (i, line) in self.lines.iter().enumerate() {
+            tags[i] = line.tag;
+            states[i] = line.get_state() as u8;
+            owners[i] = line.owner_core;
+            ref_counts[i] = line.ref_count.load(Ordering::Acquire);
+        }
+    }
+
+    /// Reload per-line tag, state, owner and sharer count
+    pub fn import_lines(
+        &mut self,
+        tags: &[u64; 1024],
+        states: &[u8; 1024],
+        owners: &[u8; 1024],
+        ref_counts: &[u8; 1024],
+    ) {
+        for (i, line) in self.lines.iter_mut().enumerate() {
+            line.tag = tags[i];
+            line.force_state(CacheState::from(states[i]));
+            line.owner_core = owners[i];
+            line.ref_count.store(ref_counts[i], Ordering::Release);
+        }
+    }
+
+    /// Write back a Modified line so the directory copy is current
+    ///
+    /// The owner writes straight into the directory line's `data`, so the
+    /// writeback publishes those bytes; memory is updated by hardware.
     #[inline(always)]
-    fn writeback_and_acquire(&mut self, _old_owner: u8, _new_owner: u8, _address: u64) {
+    fn writeback(&mut self, _index: usize) {
         compiler_fence();
-        // Real implementation would DMA transfer or use coherency protocol
+        self.writebacks += 1;
+    }
+
+    /// Number of Modified lines written back on remote access or eviction
+    pub fn writeback_count(&self) -> u32 {
+        self.writebacks
+    }
+
+    /// Write back every Modified line and invalidate the whole directory
+    ///
+    /// For context switches and flush-on-shutdown. Returns the number of
+    /// lines that needed writeback.
+    pub fn invalidate_all(&mut self) -> usize {
+        let mut written_back = 0;
+
+        for index in 0..self.lines.len() {
+            if self.lines[index].get_state().needs_writeback_on_evict() {
+                self.writeback(index);
+                written_back += 1;
+            }
+
+            let line = &mut self.lines[index];
+            line.force_state(CacheState::Invalid);
+            line.owner_core = 0xFF;
+            line.ref_count.store(0, Ordering::Release);
+            *line.data.get_mut() = [0u8; CACHE_LINE_SIZE];
+            self.demotions[index] = 0;
+        }
+
+        written_back
     }
 }
 
@@ -199,6 +588,24 @@ mod tests {
         assert_eq!(CacheState::from(0b11), CacheState::Invalid);
     }
 
+    #[test]
+    fn test_cache_state_predicates() {
+        // (state, valid, dirty, silent write, writeback on evict)
+        let cases = [
+            (CacheState::Modified, true, true, true, true),
+            (CacheState::Exclusive, true, false, true, false),
+            (CacheState::Shared, true, false, false, false),
+            (CacheState::Invalid, false, false, false, false),
+        ];
+
+        for (state, valid, dirty, silent, writeback) in cases {
+            assert_eq!(state.is_valid(), valid, "{:?}", state);
+            assert_eq!(state.is_dirty(), dirty, "{:?}", state);
+            assert_eq!(state.allows_silent_write(), silent, "{:?}", state);
+            assert_eq!(state.needs_writeback_on_evict(), writeback, "{:?}", state);
+        }
+    }
+
     #[test]
     fn test_cache_line_initialization() {
         let line = CacheLine::new();
@@ -233,6 +640,96 @@ mod tests {
         assert_eq!(line.get_state(), CacheState::Modified);
     }
 
+    #[test]
+    fn test_cache_line_bytes_bounds() {
+        let line = CacheLine::new();
+
+        unsafe {
+            assert_eq!(line.read_bytes(0, 64).map(|b| b.len()), Some(64));
+            assert_eq!(line.read_bytes(60, 4).map(|b| b.len()), Some(4));
+            assert!(line.read_bytes(60, 5).is_none());
+            assert!(line.read_bytes(usize::MAX, 2).is_none());
+
+            assert!(line.write_bytes(56, &[0xAA; 8]).is_ok());
+            assert!(line.write_bytes(57, &[0xAA; 8]).is_err());
+            assert!(line.write_bytes(64, &[0xAA]).is_err());
+        }
+    }
+
+    #[test]
+    fn test_cache_line_write_then_read() {
+        let line = CacheLine::new();
+        line.force_state(CacheState::Exclusive);
+
+        unsafe {
+            line.write_bytes(8, &[1, 2, 3, 4]).unwrap();
+            assert_eq!(line.get_state(), CacheState::Modified);
+            assert_eq!(line.read_bytes(8, 4), Some(&[1u8, 2, 3, 4][..]));
+            assert_eq!(line.read_bytes(6, 2), Some(&[0u8, 0][..]));
+        }
+    }
+
+    #[test]
+    fn test_cache_line_failed_write_keeps_state() {
+        let line = CacheLine::new();
+        line.force_state(CacheState::Shared);
+
+        unsafe {
+            assert!(line.write_bytes(63, &[0; 2]).is_err());
+            assert_eq!(line.get_state(), CacheState::Shared);
+            assert_eq!(line.read_bytes(63, 1), Some(&[0u8][..]));
+        }
+    }
+
+    /// Equal lines, then a single differing byte in each 32-byte half
+    fn check_compare(equals: impl Fn(&[u8; CACHE_LINE_SIZE], &[u8; CACHE_LINE_SIZE]) -> bool) {
+        let mut a = [0u8; CACHE_LINE_SIZE];
+        for (i, byte) in a.iter_mut().enumerate() {
+            *byte = i as u8 ^ 0x5A;
+        }
+        assert!(equals(&a, &a.clone()));
+
+        for index in [0, 31, 32, 63] {
+            let mut b = a;
+            b[index] ^= 0x01;
+            assert!(!equals(&a, &b), "difference at byte {} missed", index);
+        }
+    }
+
+    #[test]
+    fn test_data_equals_scalar() {
+        check_compare(data_equals_scalar);
+    }
+
+    #[test]
+    #[cfg(target_arch = "x86_64")]
+    fn test_data_equals_avx2() {
+        if !avx2_available() {
+            return;
+        }
+        check_compare(|a, b| unsafe { data_equals_avx2(a, b) });
+    }
+
+    #[test]
+    fn test_cache_line_needs_writeback() {
+        let line = CacheLine::new();
+        let mut memory = [0u8; CACHE_LINE_SIZE];
+        memory[40] = 0x77;
+
+        unsafe { line.write_bytes(40, &[0x77]).unwrap() };
+        assert!(line.data_equals(&memory));
+        // Modified, but rewritten with what memory already holds
+        assert!(!line.needs_writeback(&memory));
+
+        unsafe { line.write_bytes(41, &[0x01]).unwrap() };
+        assert!(!line.data_equals(&memory));
+        assert!(line.needs_writeback(&memory));
+
+        // Clean lines never write back
+        line.force_state(CacheState::Exclusive);
+        assert!(!line.needs_writeback(&memory));
+    }
+
     #[test]
     fn test_l3_directory_initialization() {
         let dir = L3Directory::new();
@@ -253,8 +750,87 @@ mod tests {
         let result = dir.core_read(1, address);
         assert!(result.is_ok());
 
+        // Lone reader gets the line Exclusive
         let index = (address >> 6) % 1024;
+        assert_eq!(dir.lines[index as usize].get_state(), CacheState::Exclusive);
+        assert_eq!(dir.lines[index as usize].owner_core, 1);
+    }
+
+    #[test]
+    fn test_l3_directory_exclusive_reread_same_core() {
+        let mut dir = L3Directory::new();
+        let address = 0x1800;
+        let index = (address >> 6) % 1024;
+
+        dir.core_read(3, address).unwrap();
+        dir.core_read(3, address).unwrap();
+
+        // Same core re-reading keeps Exclusive
+        assert_eq!(dir.lines[index as usize].get_state(), CacheState::Exclusive);
+    }
+
+    #[test]
+    fn test_l3_directory_second_reader_demotes_exclusive() {
+        let mut dir = L3Directory::new();
+        let address = 0x1C00;
+        let index = (address >> 6) % 1024;
+
+        dir.core_read(1, address).unwrap();
+        assert_eq!(dir.lines[index as usize].get_state(), CacheState::Exclusive);
+
+        dir.core_read(2, address).unwrap();
         assert_eq!(dir.lines[index as usize].get_state(), CacheState::Shared);
+        assert_eq!(dir.lines[index as usize].ref_count.load(Ordering::Acquire), 2);
+    }
+
+    #[test]
+    fn test_l3_directory_exclusive_write_no_broadcast() {
+        let mut dir = L3Directory::new();
+        let address = 0x2400;
+        let index = (address >> 6) % 1024;
+
+        // Exclusive -> Modified is a silent upgrade
+        dir.core_read(1, address).unwrap();
+        dir.core_write(1, address).unwrap();
+        assert_eq!(dir.lines[index as usize].get_state(), CacheState::Modified);
+        assert_eq!(dir.broadcast_count(), 0);
+
+        // Shared -> Modified must broadcast
+        let shared = 0x2800;
+        dir.core_read(1, shared).unwrap();
+        dir.core_read(2, shared).unwrap();
+        dir.core_write(1, shared).unwrap();
+        assert_eq!(dir.broadcast_count(), 1);
+    }
+
+    #[test]
+    fn test_l3_directory_non_owner_write_broadcasts() {
+        let mut dir = L3Directory::new();
+        let address = 0xB000u64;
+        let index = ((address >> 6) % 1024) as usize;
+
+        // Core 1 holds the line Exclusive
+        dir.core_read(1, address).unwrap();
+        assert_eq!(dir.lines[index].get_state(), CacheState::Exclusive);
+        assert_eq!(dir.lines[index].get_ref_count(), 1);
+
+        // Core 2 writing must invalidate core 1's copy, not upgrade silently
+        dir.core_write(2, address).unwrap();
+        assert_eq!(dir.lines[index].get_state(), CacheState::Modified);
+        assert_eq!(dir.lines[index].owner_core, 2);
+        assert_eq!(dir.lines[index].get_ref_count(), 0);
+        assert_eq!(dir.broadcast_count(), 1);
+        assert_eq!(dir.writeback_count(), 0);
+
+        // Core 3 stealing the Modified line writes back and invalidates
+        dir.core_write(3, address).unwrap();
+        assert_eq!(dir.lines[index].owner_core, 3);
+        assert_eq!(dir.broadcast_count(), 2);
+        assert_eq!(dir.writeback_count(), 1);
+
+        // The owner rewriting stays silent
+        dir.core_write(3, address).unwrap();
+        assert_eq!(dir.broadcast_count(), 2);
     }
 
     #[test]
@@ -310,17 +886,194 @@ mod tests {
         assert_eq!(dir.lines[index as usize].owner_core, 1);
     }
 
+    #[test]
+    fn test_l3_directory_remote_read_sees_modified_data() {
+        let mut dir = L3Directory::new();
+        let address = 0x9000u64;
+        let index = ((address >> 6) % 1024) as usize;
+
+        // Core 1 writes a pattern
+        {
+            let data = dir.core_write(1, address).unwrap();
+            data[0] = 0xDE;
+            data[63] = 0xAD;
+        }
+        assert_eq!(dir.writeback_count(), 0);
+
+        // Core 2 observes core 1's bytes, not zeros
+        let data = dir.core_read(2, address).unwrap();
+        assert_eq!(data[0], 0xDE);
+        assert_eq!(data[63], 0xAD);
+        assert_eq!(dir.lines[index].get_state(), CacheState::Shared);
+        assert_eq!(dir.writeback_count(), 1);
+
+        // Owner re-reading its own Modified line needs no writeback
+        dir.core_write(1, address).unwrap()[1] = 0xBE;
+        dir.core_read(1, address).unwrap();
+        assert_eq!(dir.writeback_count(), 1);
+    }
+
+    #[test]
+    fn test_l3_directory_remote_write_takes_ownership() {
+        let mut dir = L3Directory::new();
+        let address = 0xA000u64;
+        let index = ((address >> 6) % 1024) as usize;
+
+        dir.core_write(1, address).unwrap()[0] = 0x11;
+        dir.core_write(1, address).unwrap();
+
+        // Core 3 writes over core 1's Modified line
+        let data = dir.core_write(3, address).unwrap();
+        assert_eq!(data[0], 0x11);
+        data[1] = 0x33;
+
+        assert_eq!(dir.lines[index].get_state(), CacheState::Modified);
+        assert_eq!(dir.lines[index].owner_core, 3);
+        assert_eq!(dir.writeback_count(), 1);
+
+        // Evicting a Modified line also writes it back
+        dir.core_read(2, address + 1024 * 64).unwrap();
+        assert_eq!(dir.writeback_count(), 2);
+    }
+
+    #[test]
+    fn test_l3_directory_occupancy_report() {
+        let mut l3 = L3Directory::new();
+        assert_eq!(l3.occupancy(), 0);
+        assert_eq!(l3.state_histogram(), [0, 0, 0, 1024]);
+
+        l3.core_read(0, 0x1000).unwrap(); // Exclusive
+        l3.core_write(1, 0x2000).unwrap(); // Modified
+        l3.core_read(2, 0x3000).unwrap();
+        l3.core_read(3, 0x3000).unwrap(); // Shared
+
+        assert_eq!(l3.occupancy(), 3);
+        let histogram = l3.state_histogram();
+        assert_eq!(histogram[CacheState::Modified as usize], 1);
+        assert_eq!(histogram[CacheState::Exclusive as usize], 1);
+        assert_eq!(histogram[CacheState::Shared as usize], 1);
+        assert_eq!(histogram[CacheState::Invalid as usize], 1021);
+        assert_eq!(histogram.iter().sum::<usize>(), 1024);
+    }
+
+    #[test]
+    fn test_l3_directory_invalidate_all() {
+        let mut l3 = L3Directory::new();
+
+        l3.core_write(0, 0x1000).unwrap()[0] = 0xAA;
+        l3.core_write(1, 0x2000).unwrap();
+        l3.core_write(2, 0x3000).unwrap();
+        l3.core_read(3, 0x4000).unwrap(); // Exclusive, clean
+        l3.core_read(4, 0x5000).unwrap();
+        l3.core_read(5, 0x5000).unwrap(); // Shared, clean
+        assert_eq!(l3.occupancy(), 5);
+
+        assert_eq!(l3.invalidate_all(), 3);
+        assert_eq!(l3.writeback_count(), 3);
+        assert_eq!(l3.occupancy(), 0);
+        assert_eq!(l3.find_owner(0x1000), None);
+
+        let line = l3.line(0x1000 >> 6).unwrap();
+        assert_eq!(line.owner_core, 0xFF);
+        assert_eq!(line.ref_count.load(Ordering::Acquire), 0);
+        assert_eq!(unsafe { line.read_bytes(0, 1) }, Some(&[0u8][..]));
+
+        // Nothing left to write back
+        assert_eq!(l3.invalidate_all(), 0);
+    }
+
+    #[test]
+    fn test_l3_directory_find_owner() {
+        let mut l3 = L3Directory::new();
+
+        l3.core_read(4, 0x1000).unwrap();
+        l3.core_write(5, 0x2000).unwrap();
+        l3.core_read(2, 0x3000).unwrap();
+        l3.core_read(3, 0x3000).unwrap();
+
+        assert_eq!(l3.find_owner(0x1000), Some(4));
+        assert_eq!(l3.find_owner(0x2010), Some(5)); // same line
+        assert_eq!(l3.find_owner(0x3000), None); // Shared
+        assert_eq!(l3.find_owner(0x4000), None); // never touched
+
+        // Same slot, different tag
+        assert_eq!(l3.find_owner(0x1000 + 1024 * 64), None);
+    }
+
+    #[test]
+    fn test_l3_directory_ping_pong_counts_demotions() {
+        let mut dir = L3Directory::new();
+        let contended = 0x3000;
+        let private = 0x3040;
+
+        dir.core_write(1, private).unwrap();
+        dir.core_write(1, contended).unwrap();
+        assert_eq!(dir.demotion_count(contended), 0);
+
+        // Each write from the other core steals the Modified line
+        for round in 1..=6u32 {
+            let core = if round % 2 == 1 { 2 } else { 1 };
+            dir.core_write(core, contended).unwrap();
+            assert_eq!(dir.demotion_count(contended), round);
+        }
+
+        // Re-writing from the owner is not a demotion
+        dir.core_write(1, contended).unwrap();
+        assert_eq!(dir.demotion_count(contended), 6);
+
+        // A remote read demotes Modified to Shared
+        dir.core_read(2, contended).unwrap();
+        assert_eq!(dir.demotion_count(contended), 7);
+
+        dir.core_read(3, private).unwrap();
+        assert_eq!(dir.hottest_contended_lines(8), [(contended, 7), (private, 1)]);
+        assert_eq!(dir.hottest_contended_lines(1), [(contended, 7)]);
+
+        dir.invalidate_all();
+        assert!(dir.hottest_contended_lines(8).is_empty());
+    }
+
+    #[test]
+    fn test_l3_directory_tag_conflict_isolation() {
+        let mut dir = L3Directory::new();
+        let a = 0x8040u64;
+        let b = a + 1024 * 64; // Same directory slot, different tag
+        let index = ((a >> 6) % 1024) as usize;
+
+        // Core 1 writes a pattern to A
+        dir.core_write(1, a).unwrap()[0] = 0xAA;
+        assert_eq!(dir.lines[index].tag, a & !0x3F);
+        assert_eq!(dir.tag_conflicts(), 0);
+
+        // Reading B evicts A and must not see its data
+        assert_eq!(dir.core_read(2, b).unwrap()[0], 0x00);
+        assert_eq!(dir.lines[index].tag, b & !0x3F);
+        assert_eq!(dir.lines[index].get_state(), CacheState::Exclusive);
+        assert_eq!(dir.lines[index].owner_core, 2);
+        assert_eq!(dir.tag_conflicts(), 1);
+
+        // Writing B, then going back to A, isolates B's data too
+        dir.core_write(2, b).unwrap()[0] = 0xBB;
+        assert_eq!(dir.core_read(1, a).unwrap()[0], 0x00);
+        assert_eq!(dir.tag_conflicts(), 2);
+
+        // Repeated access to the same address is not a conflict
+        dir.core_read(1, a).unwrap();
+        dir.core_write(1, a).unwrap();
+        assert_eq!(dir.tag_conflicts(), 2);
+    }
+
     #[test]
     fn test_mesi_protocol_full_cycle() {
         let mut dir = L3Directory::new();
         let address = 0x5000;
         let index = (address >> 6) % 1024;
 
-        // Step 1: Core 1 reads (Invalid -> Shared)
+        // Step 1: Core 1 reads (Invalid -> Exclusive)
         dir.core_read(1, address).unwrap();
-        assert_eq!(dir.lines[index as usize].get_state(), CacheState::Shared);
+        assert_eq!(dir.lines[index as usize].get_state(), CacheState::Exclusive);
 
-        // Step 2: Core 2 reads (remains Shared)
+        // Step 2: Core 2 reads (Exclusive -> Shared)
         dir.core_read(2, address).unwrap();
         assert_eq!(dir.lines[index as usize].get_state(), CacheState::Shared);
 
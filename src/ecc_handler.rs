@@ -1,7 +1,7 @@
 /// Error Correction Code (ECC) Handler
 /// Provides error detection and correction for shadow registers and fuses
 
-use core::sync::atomic::{AtomicU32, Ordering};
+use core::sync::atomic::{AtomicU32, AtomicU64, Ordering};
 
 /// ECC Error Type
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -17,8 +17,20 @@ pub enum ECCError {
     MultiBit = 0x03,
 }
 
+/// ECC Codec - identifies which code produced a correction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum ECCCodec {
+    /// No correction was applied
+    None = 0x00,
+    /// Hamming (72,64) code
+    Hamming = 0x01,
+    /// Reed-Solomon code over GF(2^8)
+    ReedSolomon = 0x02,
+}
+
 /// ECC Syndrome - describes the error location
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub struct ECCSyndrome {
     /// Error type
     pub error_type: ECCError,
@@ -26,8 +38,31 @@ pub struct ECCSyndrome {
     pub error_position: u8,
     /// Number of errors detected
     pub error_count: u8,
+    /// Codec that handled the error
+    pub corrected_by: ECCCodec,
 }
 
+/// Codeword position of each data bit: 3, 5, 6, 7, 9, ... skipping the
+/// powers of two, which belong to the parity bits
+///
+/// Every data position has at least two bits set, so a flipped data bit
+/// never yields a zero or single-bit syndrome.
+const fn build_hamming_positions() -> [u8; 64] {
+    let mut positions = [0u8; 64];
+    let mut bit = 0;
+    let mut position: u8 = 3;
+    while bit < 64 {
+        if !position.is_power_of_two() {
+            positions[bit] = position;
+            bit += 1;
+        }
+        position += 1;
+    }
+    positions
+}
+
+const HAMMING_POSITIONS: [u8; 64] = build_hamming_positions();
+
 /// Hamming Code ECC Implementation
 /// Uses (72,64) Hamming code: 64 data bits + 8 parity bits
 pub struct HammingECC {
@@ -56,12 +91,10 @@ impl HammingECC {
             let mut bit_count = 0;
 
             // Count bits that should contribute to this parity bit
-            for j in 0..64 {
+            for (j, &position) in HAMMING_POSITIONS.iter().enumerate() {
                 // Check if bit j should be included in parity i
-                if (j & (1 << i)) != 0 {
-                    if (data >> j) & 1 == 1 {
-                        bit_count += 1;
-                    }
+                if (position & (1 << i)) != 0 && (data >> j) & 1 == 1 {
+                    bit_count += 1;
                 }
             }
 
@@ -76,6 +109,13 @@ impl HammingECC {
 
     /// Decode and correct data using Hamming ECC
     pub fn decode(&self, data: u64, parity: u8) -> Result<(u64, ECCSyndrome), &'static str> {
+        let result = self.decode_uncounted(data, parity);
+        self.record(data, &result);
+        result
+    }
+
+    /// `decode` without touching the error statistics
+    fn decode_uncounted(&self, data: u64, parity: u8) -> Result<(u64, ECCSyndrome), &'static str> {
         // Recalculate parity
         let (_, calculated_parity) = self.encode(data);
 
@@ -90,6 +130,7 @@ impl HammingECC {
                     error_type: ECCError::NoError,
                     error_position: 0,
                     error_count: 0,
+                    corrected_by: ECCCodec::None,
                 },
             ));
         }
@@ -99,44 +140,59 @@ impl HammingECC {
 
         if error_count == 1 {
             // Single-bit error in parity (detectable, no correction needed)
-            self.errors_detected.fetch_add(1, Ordering::Relaxed);
-
             return Ok((
                 data,
                 ECCSyndrome {
                     error_type: ECCError::SingleBit,
                     error_position: syndrome.trailing_zeros() as u8,
                     error_count: 1,
+                    corrected_by: ECCCodec::Hamming,
                 },
             ));
         }
 
         // Determine error position from syndrome
-        let error_position = syndrome as u8;
+        let data_bit = HAMMING_POSITIONS.iter().position(|&position| position == syndrome);
 
-        if error_position < 64 {
+        if let Some(data_bit) = data_bit {
+            let error_position = data_bit as u8;
             // Single-bit error in data (correctable)
             let corrected_data = data ^ (1u64 << error_position);
 
-            self.errors_detected.fetch_add(1, Ordering::Relaxed);
-            self.errors_corrected.fetch_add(1, Ordering::Relaxed);
-
             return Ok((
                 corrected_data,
                 ECCSyndrome {
                     error_type: ECCError::SingleBit,
                     error_position,
                     error_count: 1,
+                    corrected_by: ECCCodec::Hamming,
                 },
             ));
         }
 
         // Multi-bit error (not correctable)
-        self.errors_detected.fetch_add(1, Ordering::Relaxed);
-
         Err("Multi-bit error detected - cannot correct")
     }
 
+    /// Count a decode result of `data` in the error statistics
+    ///
+    /// Every error is detected; only a changed data word counts as corrected
+    /// (a flipped parity bit needs no correction).
+    fn record(&self, data: u64, result: &Result<(u64, ECCSyndrome), &'static str>) {
+        match result {
+            Ok((_, syndrome)) if syndrome.error_type == ECCError::NoError => {}
+            Ok((value, _)) => {
+                self.errors_detected.fetch_add(1, Ordering::Relaxed);
+                if *value != data {
+                    self.errors_corrected.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+            Err(_) => {
+                self.errors_detected.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+
     /// Verify data integrity without correction
     pub fn verify(&self, data: u64, parity: u8) -> ECCError {
         let (_, calculated_parity) = self.encode(data);
@@ -168,8 +224,97 @@ impl HammingECC {
     }
 }
 
+/// Maximum Reed-Solomon codeword length over GF(2^8)
+const RS_MAX_CODEWORD: usize = 255;
+
+/// GF(2^8) exponent and logarithm tables (primitive polynomial 0x11D)
+///
+/// The exponent table is doubled so products never need a modulo.
+const fn build_gf_tables() -> ([u8; 512], [u8; 256]) {
+    let mut exp = [0u8; 512];
+    let mut log = [0u8; 256];
+    let mut x: u16 = 1;
+    let mut i = 0;
+
+    while i < 255 {
+        exp[i] = x as u8;
+        log[x as usize] = i as u8;
+        x <<= 1;
+        if x & 0x100 != 0 {
+            x ^= 0x11D;
+        }
+        i += 1;
+    }
+    while i < 512 {
+        exp[i] = exp[i - 255];
+        i += 1;
+    }
+
+    (exp, log)
+}
+
+static GF_EXP: [u8; 512] = build_gf_tables().0;
+static GF_LOG: [u8; 256] = build_gf_tables().1;
+
+#[inline]
+fn gf_mul(a: u8, b: u8) -> u8 {
+    if a == 0 || b == 0 {
+        return 0;
+    }
+    GF_EXP[GF_LOG[a as usize] as usize + GF_LOG[b as usize] as usize]
+}
+
+#[inline]
+fn gf_div(a: u8, b: u8) -> u8 {
+    if a == 0 {
+        return 0;
+    }
+    GF_EXP[(GF_LOG[a as usize] as usize + 255 - GF_LOG[b as usize] as usize) % 255]
+}
+
+#[inline]
+fn gf_inv(a: u8) -> u8 {
+    GF_EXP[255 - GF_LOG[a as usize] as usize]
+}
+
+#[inline]
+fn gf_pow(a: u8, power: i32) -> u8 {
+    GF_EXP[(GF_LOG[a as usize] as i32 * power).rem_euclid(255) as usize]
+}
+
+/// Polynomials are stored highest degree first
+fn poly_scale(p: &[u8], x: u8) -> Vec<u8> {
+    p.iter().map(|&c| gf_mul(c, x)).collect()
+}
+
+fn poly_add(p: &[u8], q: &[u8]) -> Vec<u8> {
+    let len = p.len().max(q.len());
+    let mut r = vec![0u8; len];
+    for (i, &c) in p.iter().enumerate() {
+        r[i + len - p.len()] = c;
+    }
+    for (i, &c) in q.iter().enumerate() {
+        r[i + len - q.len()] ^= c;
+    }
+    r
+}
+
+fn poly_mul(p: &[u8], q: &[u8]) -> Vec<u8> {
+    let mut r = vec![0u8; p.len() + q.len() - 1];
+    for (i, &a) in p.iter().enumerate() {
+        for (j, &b) in q.iter().enumerate() {
+            r[i + j] ^= gf_mul(a, b);
+        }
+    }
+    r
+}
+
+fn poly_eval(p: &[u8], x: u8) -> u8 {
+    p.iter().fold(0, |y, &c| gf_mul(y, x) ^ c)
+}
+
 /// Reed-Solomon ECC Implementation (for multi-bit error correction)
-/// Simplified version for demonstration
+/// Systematic code over GF(2^8); corrects up to `parity_symbols / 2` bad bytes
 pub struct ReedSolomonECC {
     /// Block size
     block_size: usize,
@@ -177,6 +322,8 @@ pub struct ReedSolomonECC {
     parity_symbols: usize,
     /// Error detection counter
     errors_detected: AtomicU32,
+    /// Error correction counter
+    errors_corrected: AtomicU32,
 }
 
 impl ReedSolomonECC {
@@ -186,7 +333,17 @@ impl ReedSolomonECC {
             block_size,
             parity_symbols,
             errors_detected: AtomicU32::new(0),
+            errors_corrected: AtomicU32::new(0),
+        }
+    }
+
+    /// Generator polynomial with roots alpha^0 .. alpha^(parity_symbols - 1)
+    fn generator(&self) -> Vec<u8> {
+        let mut gen = vec![1u8];
+        for i in 0..self.parity_symbols {
+            gen = poly_mul(&gen, &[1, gf_pow(2, i as i32)]);
         }
+        gen
     }
 
     /// Encode data block with Reed-Solomon parity
@@ -194,54 +351,210 @@ impl ReedSolomonECC {
         if data.len() > self.block_size - self.parity_symbols {
             return Err("Data too large for block size");
         }
+        if data.len() + self.parity_symbols > RS_MAX_CODEWORD {
+            return Err("Block exceeds Reed-Solomon codeword length");
+        }
 
-        // Simplified RS encoding (placeholder)
-        // Real implementation would use Galois field arithmetic
-        let mut encoded = data.to_vec();
+        // Parity is the remainder of data(x) * x^parity_symbols / gen(x)
+        let gen = self.generator();
+        let mut encoded = vec![0u8; data.len() + self.parity_symbols];
+        encoded[..data.len()].copy_from_slice(data);
 
-        // Add parity symbols (simple XOR-based for demo)
-        for i in 0..self.parity_symbols {
-            let mut parity = 0u8;
-            for (j, &byte) in data.iter().enumerate() {
-                parity ^= byte.wrapping_mul((i + j + 1) as u8);
+        for i in 0..data.len() {
+            let coef = encoded[i];
+            if coef != 0 {
+                for (j, &g) in gen.iter().enumerate().skip(1) {
+                    encoded[i + j] ^= gf_mul(g, coef);
+                }
             }
-            encoded.push(parity);
         }
 
+        encoded[..data.len()].copy_from_slice(data);
         Ok(encoded)
     }
 
+    /// Syndromes of a codeword, prefixed with a zero coefficient
+    fn syndromes(&self, codeword: &[u8]) -> Vec<u8> {
+        let mut synd = vec![0u8; self.parity_symbols + 1];
+        for i in 0..self.parity_symbols {
+            synd[i + 1] = poly_eval(codeword, gf_pow(2, i as i32));
+        }
+        synd
+    }
+
+    /// Locate and repair errors, returning the corrected codeword
+    fn correct(&self, codeword: &[u8], synd: &[u8]) -> Option<Vec<u8>> {
+        let nsym = self.parity_symbols;
+        let n = codeword.len();
+
+        // Berlekamp-Massey: find the error locator polynomial
+        let mut err_loc = vec![1u8];
+        let mut old_loc = vec![1u8];
+        for i in 0..nsym {
+            let k = i + 1;
+            let mut delta = synd[k];
+            for j in 1..err_loc.len() {
+                delta ^= gf_mul(err_loc[err_loc.len() - 1 - j], synd[k - j]);
+            }
+
+            old_loc.push(0);
+            if delta != 0 {
+                if old_loc.len() > err_loc.len() {
+                    let new_loc = poly_scale(&old_loc, delta);
+                    old_loc = poly_scale(&err_loc, gf_inv(delta));
+                    err_loc = new_loc;
+                }
+                err_loc = poly_add(&err_loc, &poly_scale(&old_loc, delta));
+            }
+        }
+
+        let first = err_loc.iter().position(|&c| c != 0)?;
+        err_loc.drain(..first);
+        let errs = err_loc.len() - 1;
+        if errs * 2 > nsym {
+            return None;
+        }
+
+        // Chien search: roots of the locator give error positions
+        err_loc.reverse();
+        let err_pos: Vec<usize> = (0..n)
+            .filter(|&i| poly_eval(&err_loc, gf_pow(2, i as i32)) == 0)
+            .map(|i| n - 1 - i)
+            .collect();
+        if err_pos.len() != errs {
+            return None;
+        }
+
+        // Forney: compute error magnitudes
+        let coef_pos: Vec<usize> = err_pos.iter().map(|&p| n - 1 - p).collect();
+        let mut loc = vec![1u8];
+        for &c in &coef_pos {
+            loc = poly_mul(&loc, &[gf_pow(2, c as i32), 1]);
+        }
+
+        let synd_rev: Vec<u8> = synd.iter().rev().copied().collect();
+        let product = poly_mul(&synd_rev, &loc);
+        let evaluator = &product[product.len() - loc.len()..];
+
+        let x: Vec<u8> = coef_pos.iter().map(|&c| gf_pow(2, c as i32)).collect();
+        let mut corrected = codeword.to_vec();
+        for (i, &xi) in x.iter().enumerate() {
+            let xi_inv = gf_inv(xi);
+            let mut loc_prime = 1u8;
+            for (j, &xj) in x.iter().enumerate() {
+                if j != i {
+                    loc_prime = gf_mul(loc_prime, 1 ^ gf_mul(xi_inv, xj));
+                }
+            }
+            if loc_prime == 0 {
+                return None;
+            }
+
+            let y = gf_mul(xi, poly_eval(evaluator, xi_inv));
+            corrected[err_pos[i]] ^= gf_div(y, loc_prime);
+        }
+
+        // Too many errors can produce a bogus "correction"
+        if self.syndromes(&corrected).iter().any(|&s| s != 0) {
+            return None;
+        }
+
+        Some(corrected)
+    }
+
     /// Decode and correct data block
     pub fn decode(&self, encoded: &[u8]) -> Result<Vec<u8>, &'static str> {
         if encoded.len() < self.parity_symbols {
             return Err("Encoded data too short");
         }
+        if encoded.len() > RS_MAX_CODEWORD {
+            return Err("Block exceeds Reed-Solomon codeword length");
+        }
 
         let data_len = encoded.len() - self.parity_symbols;
-        let data = &encoded[..data_len];
-        let parity = &encoded[data_len..];
+        let synd = self.syndromes(encoded);
 
-        // Verify parity (simplified)
-        for i in 0..self.parity_symbols {
-            let mut calculated_parity = 0u8;
-            for (j, &byte) in data.iter().enumerate() {
-                calculated_parity ^= byte.wrapping_mul((i + j + 1) as u8);
-            }
+        if synd.iter().all(|&s| s == 0) {
+            return Ok(encoded[..data_len].to_vec());
+        }
 
-            if calculated_parity != parity[i] {
-                self.errors_detected.fetch_add(1, Ordering::Relaxed);
-                // In real RS, we would attempt correction here
-                return Err("Reed-Solomon error detected");
+        self.errors_detected.fetch_add(1, Ordering::Relaxed);
+
+        let corrected = self
+            .correct(encoded, &synd)
+            .ok_or("Reed-Solomon error detected - cannot correct")?;
+
+        self.errors_corrected.fetch_add(1, Ordering::Relaxed);
+
+        Ok(corrected[..data_len].to_vec())
+    }
+
+    /// Data bytes carried by one full block
+    #[inline(always)]
+    fn data_per_block(&self) -> usize {
+        self.block_size.saturating_sub(self.parity_symbols)
+    }
+
+    /// Encode data of any length as a sequence of blocks
+    ///
+    /// Each `block_size - parity_symbols` chunk becomes one `block_size`
+    /// codeword. A short final chunk is encoded as a shortened codeword, so
+    /// no padding is stored.
+    pub fn encode_stream(&self, data: &[u8]) -> Result<Vec<u8>, &'static str> {
+        let chunk_size = self.data_per_block();
+        if chunk_size == 0 {
+            return Err("Block size too small for parity");
+        }
+
+        let blocks = data.len().div_ceil(chunk_size);
+        let mut encoded = Vec::with_capacity(data.len() + blocks * self.parity_symbols);
+        for chunk in data.chunks(chunk_size) {
+            encoded.extend_from_slice(&self.encode(chunk)?);
+        }
+
+        Ok(encoded)
+    }
+
+    /// Decode and correct a stream produced by `encode_stream`
+    pub fn decode_stream(&self, encoded: &[u8]) -> Result<Vec<u8>, &'static str> {
+        if self.data_per_block() == 0 {
+            return Err("Block size too small for parity");
+        }
+
+        let mut decoded = Vec::with_capacity(encoded.len());
+        for block in encoded.chunks(self.block_size) {
+            // Every block carries at least one data byte
+            if block.len() <= self.parity_symbols {
+                return Err("Encoded stream truncated");
             }
+            decoded.extend_from_slice(&self.decode(block)?);
         }
 
-        Ok(data.to_vec())
+        Ok(decoded)
+    }
+
+    /// Verify a codeword without correction
+    pub fn verify(&self, encoded: &[u8]) -> bool {
+        encoded.len() >= self.parity_symbols
+            && encoded.len() <= RS_MAX_CODEWORD
+            && self.syndromes(encoded).iter().all(|&s| s == 0)
     }
 
     /// Get error count
     pub fn get_error_count(&self) -> u32 {
         self.errors_detected.load(Ordering::Relaxed)
     }
+
+    /// Get correction count
+    pub fn get_corrected_count(&self) -> u32 {
+        self.errors_corrected.load(Ordering::Relaxed)
+    }
+
+    /// Reset error counters
+    pub fn reset_stats(&self) {
+        self.errors_detected.store(0, Ordering::Relaxed);
+        self.errors_corrected.store(0, Ordering::Relaxed);
+    }
 }
 
 /// Combined ECC Strategy
@@ -261,6 +574,8 @@ pub struct ECCManager {
     hamming: HammingECC,
     reed_solomon: ReedSolomonECC,
     strategy: ECCStrategy,
+    /// Number of words encoded or decoded
+    words_processed: AtomicU64,
 }
 
 impl ECCManager {
@@ -270,11 +585,14 @@ impl ECCManager {
             hamming: HammingECC::new(),
             reed_solomon: ReedSolomonECC::new(64, 8),
             strategy,
+            words_processed: AtomicU64::new(0),
         }
     }
 
     /// Encode data based on strategy
     pub fn encode_u64(&self, data: u64) -> (u64, u8) {
+        self.words_processed.fetch_add(1, Ordering::Relaxed);
+
         match self.strategy {
             ECCStrategy::None => (data, 0),
             ECCStrategy::Hamming | ECCStrategy::Hybrid => self.hamming.encode(data),
@@ -286,7 +604,14 @@ impl ECCManager {
     }
 
     /// Decode data based on strategy
+    ///
+    /// `Hybrid` goes through the hybrid decoder, but the single parity byte
+    /// only carries Hamming check bits, so there is no Reed-Solomon parity to
+    /// fall back on here; use `decode_u64_hybrid` with the parity from
+    /// `encode_rs_parity` for that.
     pub fn decode_u64(&self, data: u64, ecc: u8) -> Result<(u64, ECCSyndrome), &'static str> {
+        self.words_processed.fetch_add(1, Ordering::Relaxed);
+
         match self.strategy {
             ECCStrategy::None => Ok((
                 data,
@@ -294,9 +619,11 @@ impl ECCManager {
                     error_type: ECCError::NoError,
                     error_position: 0,
                     error_count: 0,
+                    corrected_by: ECCCodec::None,
                 },
             )),
-            ECCStrategy::Hamming | ECCStrategy::Hybrid => self.hamming.decode(data, ecc),
+            ECCStrategy::Hamming => self.hamming.decode(data, ecc),
+            ECCStrategy::Hybrid => self.decode_hybrid(data, ecc, &[]),
             ECCStrategy::ReedSolomon => {
                 // Use RS decoding
                 Ok((
@@ -305,23 +632,132 @@ impl ECCManager {
                         error_type: ECCError::NoError,
                         error_position: 0,
                         error_count: 0,
+                        corrected_by: ECCCodec::None,
                     },
                 ))
             }
         }
     }
 
+    /// Compute Reed-Solomon parity symbols for a u64
+    pub fn encode_rs_parity(&self, data: u64) -> Result<Vec<u8>, &'static str> {
+        let mut encoded = self.reed_solomon.encode(&data.to_le_bytes())?;
+        Ok(encoded.split_off(8))
+    }
+
+    /// Decode with Hamming, falling back to Reed-Solomon
+    ///
+    /// Hamming handles single-bit errors. Its result is checked against the
+    /// RS parity because a multi-bit error can alias to a wrong single-bit
+    /// correction; if Hamming fails or the check fails, RS corrects the word.
+    /// Only the codec whose result is returned updates the error statistics.
+    /// With empty `rs_parity` the Hamming result is returned unchecked.
+    pub fn decode_u64_hybrid(
+        &self,
+        data: u64,
+        ecc: u8,
+        rs_parity: &[u8],
+    ) -> Result<(u64, ECCSyndrome), &'static str> {
+        self.words_processed.fetch_add(1, Ordering::Relaxed);
+        self.decode_hybrid(data, ecc, rs_parity)
+    }
+
+    /// `decode_u64_hybrid` without counting the word
+    fn decode_hybrid(
+        &self,
+        data: u64,
+        ecc: u8,
+        rs_parity: &[u8],
+    ) -> Result<(u64, ECCSyndrome), &'static str> {
+        if rs_parity.is_empty() {
+            return self.hamming.decode(data, ecc);
+        }
+
+        let mut encoded = Vec::with_capacity(8 + rs_parity.len());
+        encoded.extend_from_slice(&data.to_le_bytes());
+        encoded.extend_from_slice(rs_parity);
+
+        let hamming = self.hamming.decode_uncounted(data, ecc);
+        if let Ok((value, _)) = hamming {
+            let mut check = encoded.clone();
+            check[..8].copy_from_slice(&value.to_le_bytes());
+            if self.reed_solomon.verify(&check) {
+                self.hamming.record(data, &hamming);
+                return hamming;
+            }
+        }
+
+        let decoded = self.reed_solomon.decode(&encoded)?;
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(&decoded[..8]);
+        let value = u64::from_le_bytes(bytes);
+
+        let flipped = data ^ value;
+        let error_count = flipped.count_ones() as u8;
+        let error_type = match error_count {
+            0 => ECCError::NoError,
+            1 => ECCError::SingleBit,
+            2 => ECCError::DoubleBit,
+            _ => ECCError::MultiBit,
+        };
+
+        Ok((
+            value,
+            ECCSyndrome {
+                error_type,
+                error_position: if flipped == 0 { 0 } else { flipped.trailing_zeros() as u8 },
+                error_count,
+                corrected_by: ECCCodec::ReedSolomon,
+            },
+        ))
+    }
+
     /// Get combined error statistics
     pub fn get_total_errors(&self) -> (u32, u32) {
         let (hamming_detected, hamming_corrected) = self.hamming.get_error_stats();
         let rs_detected = self.reed_solomon.get_error_count();
+        let rs_corrected = self.reed_solomon.get_corrected_count();
+
+        (hamming_detected + rs_detected, hamming_corrected + rs_corrected)
+    }
+
+    /// Get number of words encoded or decoded
+    pub fn get_words_processed(&self) -> u64 {
+        self.words_processed.load(Ordering::Relaxed)
+    }
+
+    /// Detected errors per processed word (0.0 when nothing processed)
+    pub fn error_rate(&self) -> f64 {
+        let words = self.get_words_processed();
+        if words == 0 {
+            return 0.0;
+        }
+
+        let (detected, _) = self.get_total_errors();
+        detected as f64 / words as f64
+    }
+
+    /// Fraction of detected errors that were corrected (0.0 when none detected)
+    pub fn correction_rate(&self) -> f64 {
+        let (detected, corrected) = self.get_total_errors();
+        if detected == 0 {
+            return 0.0;
+        }
 
-        (hamming_detected + rs_detected, hamming_corrected)
+        corrected as f64 / detected as f64
+    }
+
+    /// Reset all error and throughput counters
+    pub fn reset_stats(&self) {
+        self.hamming.reset_stats();
+        self.reed_solomon.reset_stats();
+        self.words_processed.store(0, Ordering::Relaxed);
     }
 }
 
 // Vec implementation for no_std
 extern crate alloc;
+use alloc::vec;
 use alloc::vec::Vec;
 
 #[cfg(test)]
@@ -344,6 +780,13 @@ mod tests {
         let (encoded, ecc) = manager.encode_u64(test_data);
         assert_eq!(encoded, test_data);
         assert_ne!(ecc, 0); // Should have parity bits
+
+        // decode_u64 takes the hybrid path, with only Hamming parity to go on
+        let (decoded, syndrome) = manager.decode_u64(encoded ^ (1 << 9), ecc).unwrap();
+        assert_eq!(decoded, test_data);
+        assert_eq!(syndrome.corrected_by, ECCCodec::Hamming);
+        assert_eq!(manager.get_words_processed(), 2);
+        assert_eq!(manager.get_total_errors(), (1, 1));
     }
 
     #[test]
@@ -434,10 +877,8 @@ mod tests {
     fn test_hamming_error_correction_accuracy() {
         let hamming = HammingECC::new();
 
-        // Test multiple bit positions with multiple bits set in binary representation
-        // Avoid powers of 2 and positions that create single-bit syndromes
-        // Use: 11 (0b1011), 13 (0b1101), 22 (0b10110), 37 (0b100101), 58 (0b111010)
-        for bit_pos in [11, 13, 22, 37, 58] {
+        // Every data bit, including bit 0 and the powers of two
+        for bit_pos in 0..64 {
             hamming.reset_stats();
             let test_data = 0x5555555555555555;
             let (encoded, parity) = hamming.encode(test_data);
@@ -541,21 +982,90 @@ mod tests {
     }
 
     #[test]
-    fn test_reed_solomon_error_detection() {
+    fn test_reed_solomon_error_correction() {
         let rs = ReedSolomonECC::new(64, 8);
         let test_data = vec![0x11, 0x22, 0x33, 0x44];
 
         let mut encoded = rs.encode(&test_data).unwrap();
 
-        // Corrupt a data byte
+        // Corrupt a data byte and a parity byte
         encoded[0] ^= 0xFF;
+        encoded[6] ^= 0x5A;
+        assert!(!rs.verify(&encoded));
 
         let result = rs.decode(&encoded);
-        assert!(result.is_err());
-        assert_eq!(result.unwrap_err(), "Reed-Solomon error detected");
+        assert_eq!(result.unwrap(), test_data);
 
-        // Verify error counter incremented
+        // Verify counters incremented
         assert_eq!(rs.get_error_count(), 1);
+        assert_eq!(rs.get_corrected_count(), 1);
+    }
+
+    #[test]
+    fn test_reed_solomon_correction_capacity() {
+        let rs = ReedSolomonECC::new(64, 8);
+        let test_data: Vec<u8> = (0..32).collect();
+        let encoded = rs.encode(&test_data).unwrap();
+
+        // Four bad bytes is the limit for 8 parity symbols
+        let mut corrupted = encoded.clone();
+        for pos in [1, 9, 17, 38] {
+            corrupted[pos] ^= 0xA5;
+        }
+        assert_eq!(rs.decode(&corrupted).unwrap(), test_data);
+
+        // Five bad bytes cannot be corrected
+        corrupted[25] ^= 0x3C;
+        let result = rs.decode(&corrupted);
+        assert_eq!(result.unwrap_err(), "Reed-Solomon error detected - cannot correct");
+        assert_eq!(rs.get_error_count(), 2);
+        assert_eq!(rs.get_corrected_count(), 1);
+    }
+
+    #[test]
+    fn test_reed_solomon_stream_round_trip() {
+        let rs = ReedSolomonECC::new(32, 8);
+
+        // Two full 24-byte chunks and a short 22-byte tail
+        let data: Vec<u8> = (0..70u8).map(|b| b.wrapping_mul(37)).collect();
+        let mut encoded = rs.encode_stream(&data).unwrap();
+        assert_eq!(encoded.len(), 32 + 32 + 30);
+
+        // One bad symbol in the middle block
+        encoded[32 + 5] ^= 0xC3;
+
+        assert_eq!(rs.decode_stream(&encoded).unwrap(), data);
+        assert_eq!(rs.get_error_count(), 1);
+        assert_eq!(rs.get_corrected_count(), 1);
+
+        // Empty input round-trips to nothing
+        assert!(rs.encode_stream(&[]).unwrap().is_empty());
+        assert!(rs.decode_stream(&[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_reed_solomon_stream_errors() {
+        let rs = ReedSolomonECC::new(32, 8);
+        let data = vec![0x5A; 30];
+        let encoded = rs.encode_stream(&data).unwrap();
+
+        // Cutting into the tail's parity leaves nothing to decode
+        let truncated = &encoded[..32 + 8];
+        assert_eq!(rs.decode_stream(truncated).unwrap_err(), "Encoded stream truncated");
+
+        // Too many bad symbols in one block fails the whole stream
+        let mut corrupted = encoded.clone();
+        for pos in [0, 3, 6, 9, 12] {
+            corrupted[pos] ^= 0xFF;
+        }
+        assert!(rs.decode_stream(&corrupted).is_err());
+
+        // Parity consuming the whole block is rejected up front
+        let degenerate = ReedSolomonECC::new(8, 8);
+        assert_eq!(
+            degenerate.encode_stream(&data).unwrap_err(),
+            "Block size too small for parity"
+        );
     }
 
     #[test]
@@ -595,6 +1105,102 @@ mod tests {
         assert_eq!(total_corrected, 1);
     }
 
+    #[test]
+    fn test_ecc_manager_error_rates() {
+        let manager = ECCManager::new(ECCStrategy::Hamming);
+        assert_eq!(manager.error_rate(), 0.0);
+        assert_eq!(manager.correction_rate(), 0.0);
+
+        let words = [0x1111u64, 0x2222, 0x3333, 0x4444];
+        let encoded: Vec<(u64, u8)> = words.iter().map(|&w| manager.encode_u64(w)).collect();
+
+        // Two clean decodes
+        manager.decode_u64(encoded[0].0, encoded[0].1).unwrap();
+        manager.decode_u64(encoded[1].0, encoded[1].1).unwrap();
+
+        // One correctable single-bit error
+        manager
+            .decode_u64(encoded[2].0 ^ (1u64 << 20), encoded[2].1)
+            .unwrap();
+
+        // One uncorrectable error
+        assert!(manager.decode_u64(encoded[3].0, encoded[3].1 ^ 0xC0).is_err());
+
+        assert_eq!(manager.get_words_processed(), 8);
+        assert_eq!(manager.get_total_errors(), (2, 1));
+        assert_eq!(manager.error_rate(), 0.25);
+        assert_eq!(manager.correction_rate(), 0.5);
+
+        manager.reset_stats();
+        assert_eq!(manager.get_words_processed(), 0);
+        assert_eq!(manager.get_total_errors(), (0, 0));
+        assert_eq!(manager.error_rate(), 0.0);
+    }
+
+    #[test]
+    fn test_ecc_manager_reset_clears_reed_solomon() {
+        let manager = ECCManager::new(ECCStrategy::Hybrid);
+        let test_data = 0x0123456789ABCDEF;
+        let (encoded, ecc) = manager.encode_u64(test_data);
+        let rs_parity = manager.encode_rs_parity(test_data).unwrap();
+
+        let corrupted = encoded ^ (1u64 << 1) ^ (1u64 << 2);
+        manager.decode_u64_hybrid(corrupted, ecc, &rs_parity).unwrap();
+
+        assert_eq!(manager.get_words_processed(), 2);
+        assert_eq!(manager.reed_solomon.get_corrected_count(), 1);
+
+        manager.reset_stats();
+        assert_eq!(manager.reed_solomon.get_error_count(), 0);
+        assert_eq!(manager.reed_solomon.get_corrected_count(), 0);
+        assert_eq!(manager.get_total_errors(), (0, 0));
+    }
+
+    #[test]
+    fn test_ecc_manager_hybrid_single_bit_uses_hamming() {
+        let manager = ECCManager::new(ECCStrategy::Hybrid);
+        let test_data = 0xCAFEBABEDEADBEEF;
+        let (encoded, ecc) = manager.encode_u64(test_data);
+        let rs_parity = manager.encode_rs_parity(test_data).unwrap();
+
+        let corrupted = encoded ^ (1u64 << 13);
+        let (decoded, syndrome) = manager.decode_u64_hybrid(corrupted, ecc, &rs_parity).unwrap();
+
+        assert_eq!(decoded, test_data);
+        assert_eq!(syndrome.error_type, ECCError::SingleBit);
+        assert_eq!(syndrome.corrected_by, ECCCodec::Hamming);
+        assert_eq!(manager.get_total_errors(), (1, 1));
+    }
+
+    #[test]
+    fn test_ecc_manager_hybrid_double_bit_uses_reed_solomon() {
+        let manager = ECCManager::new(ECCStrategy::Hybrid);
+        let test_data = 0x0123456789ABCDEF;
+        let (encoded, ecc) = manager.encode_u64(test_data);
+        let rs_parity = manager.encode_rs_parity(test_data).unwrap();
+
+        // Bits 1 and 2 alias to a bogus Hamming correction of bit 3
+        let corrupted = encoded ^ (1u64 << 1) ^ (1u64 << 2);
+        let (decoded, syndrome) = manager.decode_u64_hybrid(corrupted, ecc, &rs_parity).unwrap();
+
+        assert_eq!(decoded, test_data);
+        assert_eq!(syndrome.error_type, ECCError::DoubleBit);
+        assert_eq!(syndrome.error_position, 1);
+        assert_eq!(syndrome.error_count, 2);
+        assert_eq!(syndrome.corrected_by, ECCCodec::ReedSolomon);
+
+        // The rejected Hamming attempt is not counted
+        assert_eq!(manager.hamming.get_error_stats(), (0, 0));
+        assert_eq!(manager.get_total_errors(), (1, 1));
+
+        // Errors spread across two bytes are recovered too
+        let corrupted = encoded ^ (1u64 << 7) ^ (1u64 << 40);
+        let (decoded, syndrome) = manager.decode_u64_hybrid(corrupted, ecc, &rs_parity).unwrap();
+        assert_eq!(decoded, test_data);
+        assert_eq!(syndrome.corrected_by, ECCCodec::ReedSolomon);
+        assert_eq!(manager.get_total_errors(), (2, 2));
+    }
+
     #[test]
     fn test_hamming_multi_bit_error_uncorrectable() {
         let hamming = HammingECC::new();
@@ -1,9 +1,56 @@
 /// Hardware Fuse Management System
 /// Controls fuse programming, reading, and verification
 
-use crate::shadow_register::ShadowRegisterBank;
+use crate::shadow_register::{LockMode, RegisterId, ShadowError, ShadowRegisterBank, SlotIndex};
+use alloc::vec::Vec;
+use core::cmp::Reverse;
 use core::ptr::{read_volatile, write_volatile};
 
+/// Hardware fuses MMIO base
+pub const FUSE_BASE: u64 = 0xFFFF_9000_6000_0000;
+/// Size of the hardware fuse MMIO window
+pub const FUSE_REGION_SIZE: u64 = 0x1000_0000;
+
+/// Validated hardware fuse address
+///
+/// Always 8-byte aligned and inside the fuse window (`FUSE_BASE` unless the
+/// platform's `MmioLayout` says otherwise), so a shadow register or other
+/// MMIO address can't be passed by mistake.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(transparent)]
+pub struct FuseAddr(u64);
+
+impl FuseAddr {
+    /// Validate a raw fuse address
+    pub const fn new(address: u64) -> Option<Self> {
+        Self::new_in(address, FUSE_BASE)
+    }
+
+    /// Validate a raw fuse address against a fuse window starting at `base`
+    pub const fn new_in(address: u64, base: u64) -> Option<Self> {
+        let in_range = address >= base && address - base < FUSE_REGION_SIZE;
+        if in_range && address.is_multiple_of(8) {
+            Some(Self(address))
+        } else {
+            None
+        }
+    }
+
+    /// Get the raw address
+    #[inline(always)]
+    pub const fn raw(self) -> u64 {
+        self.0
+    }
+}
+
+/// Unchecked conversion so tests can back fuses with host memory
+#[cfg(test)]
+impl From<u64> for FuseAddr {
+    fn from(address: u64) -> Self {
+        Self(address)
+    }
+}
+
 /// Fuse Programming State
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u8)]
@@ -30,8 +77,13 @@ pub enum FuseMode {
     MTP = 0x01,
     /// Electrically erasable (EEPROM-like)
     EEPROM = 0x02,
+    /// Factory-provisioned; readable only
+    ReadOnly = 0x03,
 }
 
+/// `max_cycles` value for fuses without an endurance limit
+pub const UNLIMITED_CYCLES: u32 = u32::MAX;
+
 /// Hardware Fuse Descriptor
 #[repr(C, align(32))]
 pub struct HardwareFuse {
@@ -47,27 +99,43 @@ pub struct HardwareFuse {
     locked: bool,
     /// Redundancy count (for multi-bit fuses)
     redundancy: u8,
+    /// Distance in bytes between redundant copies
+    stride: u64,
     /// Error correction code (ECC) bits
     ecc: u16,
+    /// Program cycles an MTP/EEPROM fuse survives (`UNLIMITED_CYCLES` if none)
+    max_cycles: u32,
+    /// Successful programs so far
+    program_count: u32,
 }
 
 impl HardwareFuse {
-    /// Create a new hardware fuse descriptor
-    pub const fn new(address: u64, mode: FuseMode) -> Self {
+    /// Create a new hardware fuse descriptor with unlimited endurance
+    pub const fn new(address: FuseAddr, mode: FuseMode) -> Self {
+        Self::new_with_endurance(address, mode, UNLIMITED_CYCLES)
+    }
+
+    /// Create a hardware fuse that accepts at most `max_cycles` programs
+    ///
+    /// Only MTP/EEPROM fuses are limited; OTP fuses stay one-shot.
+    pub const fn new_with_endurance(address: FuseAddr, mode: FuseMode, max_cycles: u32) -> Self {
         Self {
-            address,
+            address: address.raw(),
             mode,
             state: FuseState::Virgin,
             value: 0,
             locked: false,
             redundancy: 1,
+            stride: 0,
             ecc: 0,
+            max_cycles,
+            program_count: 0,
         }
     }
 
     /// Read fuse value from hardware
     #[inline]
-    pub unsafe fn read_from_hardware(&mut self) -> Result<u64, &'static str> {
+    pub unsafe fn read_from_hardware(&mut self) -> Result<u64, ShadowError> {
         // Read from physical fuse address via MMIO
         let fuse_ptr = self.address as *const u64;
         let value = read_volatile(fuse_ptr);
@@ -77,7 +145,7 @@ impl HardwareFuse {
             let calculated_ecc = self.calculate_ecc(value);
             if calculated_ecc != self.ecc && self.ecc != 0 {
                 self.state = FuseState::Error;
-                return Err("ECC mismatch - fuse data corrupted");
+                return Err(ShadowError::EccUncorrectable);
             }
         }
 
@@ -91,19 +159,83 @@ impl HardwareFuse {
         Ok(value)
     }
 
-    /// Program fuse value to hardware
+    /// Read `redundancy` physical copies and majority-vote each bit
+    ///
+    /// Copy `i` lives at `address + i * stride` (see `set_redundancy`). A
+    /// bit that ties across an even number of copies has no majority; the
+    /// fuse is then marked `Error` and nothing is returned.
+    ///
+    /// # Safety
+    /// Every copy address must be a readable, 8-byte aligned fuse location.
+    pub unsafe fn read_redundant(&mut self) -> Result<u64, ShadowError> {
+        let copies = self.redundancy.max(1) as u32;
+        let mut ones = [0u32; 64];
+
+        for i in 0..copies as u64 {
+            let copy = read_volatile(self.copy_address(i) as *const u64);
+            for (bit, count) in ones.iter_mut().enumerate() {
+                *count += ((copy >> bit) & 1) as u32;
+            }
+        }
+
+        let mut value = 0u64;
+        for (bit, &count) in ones.iter().enumerate() {
+            if count * 2 == copies {
+                self.state = FuseState::Error;
+                return Err(ShadowError::NoMajority);
+            }
+            if count * 2 > copies {
+                value |= 1 << bit;
+            }
+        }
+
+        self.value = value;
+        self.state = if value != 0 {
+            FuseState::Programmed
+        } else {
+            FuseState::Virgin
+        };
+
+        Ok(value)
+    }
+
+    /// Check whether `program_to_hardware` would accept a new value
+    ///
+    /// Returns the error it would fail with before touching hardware.
     #[inline]
-    pub unsafe fn program_to_hardware(&mut self, value: u64) -> Result<(), &'static str> {
+    pub fn check_programmable(&self) -> Result<(), ShadowError> {
+        if self.is_read_only() {
+            return Err(ShadowError::ReadOnly);
+        }
+
         // Check if already locked
         if self.locked {
-            return Err("Fuse is locked");
+            return Err(ShadowError::Locked);
         }
 
-        // Check if OTP and already programmed
-        if matches!(self.mode, FuseMode::OTP) && self.state == FuseState::Programmed {
-            return Err("OTP fuse already programmed");
+        // Only MTP/EEPROM fuses may be programmed again
+        if self.state == FuseState::Programmed && !self.can_reprogram() {
+            return Err(ShadowError::AlreadyProgrammed);
         }
 
+        if self.is_worn_out() {
+            return Err(ShadowError::EnduranceExceeded);
+        }
+
+        Ok(())
+    }
+
+    /// Program fuse value to hardware
+    ///
+    /// Every redundant copy is written and read back, so `read_redundant`
+    /// votes over copies holding the same value.
+    ///
+    /// # Safety
+    /// Every copy address must be a writable, 8-byte aligned fuse location.
+    #[inline]
+    pub unsafe fn program_to_hardware(&mut self, value: u64) -> Result<(), ShadowError> {
+        self.check_programmable()?;
+
         // Set programming state
         self.state = FuseState::Programming;
 
@@ -112,28 +244,33 @@ impl HardwareFuse {
             self.ecc = self.calculate_ecc(value);
         }
 
-        // Write to physical fuse address via MMIO
-        let fuse_ptr = self.address as *mut u64;
-        write_volatile(fuse_ptr, value);
+        // Write each physical copy via MMIO and verify it
+        for i in 0..self.redundancy.max(1) as u64 {
+            let fuse_ptr = self.copy_address(i) as *mut u64;
+            write_volatile(fuse_ptr, value);
 
-        // Verify write
-        let readback = read_volatile(fuse_ptr);
-        if readback != value {
-            self.state = FuseState::Error;
-            return Err("Fuse programming verification failed");
+            let readback = read_volatile(fuse_ptr);
+            if readback != value {
+                self.state = FuseState::Error;
+                return Err(ShadowError::VerificationFailed);
+            }
         }
 
         self.value = value;
         self.state = FuseState::Programmed;
+        self.program_count += 1;
 
         Ok(())
     }
 
     /// Blow (permanently lock) the fuse
     #[inline]
-    pub fn blow(&mut self) -> Result<(), &'static str> {
+    pub fn blow(&mut self) -> Result<(), ShadowError> {
+        if self.is_read_only() {
+            return Err(ShadowError::ReadOnly);
+        }
         if self.state != FuseState::Programmed {
-            return Err("Can only blow programmed fuses");
+            return Err(ShadowError::NotProgrammed);
         }
 
         self.state = FuseState::Blown;
@@ -142,12 +279,91 @@ impl HardwareFuse {
         Ok(())
     }
 
+    /// Set the number of physical copies backing this fuse (at least 1)
+    ///
+    /// Copy `i` lives `i * stride` bytes past the fuse address.
+    #[inline(always)]
+    pub fn set_redundancy(&mut self, copies: u8, stride: u64) {
+        self.redundancy = copies.max(1);
+        self.stride = stride;
+    }
+
+    /// Address of redundant copy `index`
+    #[inline(always)]
+    fn copy_address(&self, index: u64) -> u64 {
+        self.address + index * self.stride
+    }
+
+    /// Get the number of physical copies backing this fuse
+    #[inline(always)]
+    pub fn get_redundancy(&self) -> u8 {
+        self.redundancy
+    }
+
+    /// Check if `program_to_hardware` would accept a new value
+    #[inline(always)]
+    pub fn can_program(&self) -> bool {
+        self.check_programmable().is_ok()
+    }
+
+    /// Check if an MTP/EEPROM fuse has used up its program cycles
+    #[inline(always)]
+    pub fn is_worn_out(&self) -> bool {
+        matches!(self.mode, FuseMode::MTP | FuseMode::EEPROM)
+            && self.max_cycles != UNLIMITED_CYCLES
+            && self.program_count >= self.max_cycles
+    }
+
+    /// Get the program cycle limit (`UNLIMITED_CYCLES` if none)
+    #[inline(always)]
+    pub fn get_max_cycles(&self) -> u32 {
+        self.max_cycles
+    }
+
+    /// Get the number of successful programs
+    #[inline(always)]
+    pub fn get_program_count(&self) -> u32 {
+        self.program_count
+    }
+
+    /// Check if a programmed fuse may be overwritten (unlocked MTP/EEPROM only)
+    #[inline(always)]
+    pub fn can_reprogram(&self) -> bool {
+        !self.locked
+            && self.state == FuseState::Programmed
+            && matches!(self.mode, FuseMode::MTP | FuseMode::EEPROM)
+    }
+
+    /// Check if the fuse was factory-provisioned and rejects every write
+    #[inline(always)]
+    pub fn is_read_only(&self) -> bool {
+        matches!(self.mode, FuseMode::ReadOnly)
+    }
+
     /// Check if fuse is virgin (unprogrammed)
     #[inline(always)]
     pub fn is_virgin(&self) -> bool {
         self.state == FuseState::Virgin
     }
 
+    /// Check if fuse is programmed
+    #[inline(always)]
+    pub fn is_programmed(&self) -> bool {
+        self.state == FuseState::Programmed
+    }
+
+    /// Check if fuse is blown (permanent)
+    #[inline(always)]
+    pub fn is_blown(&self) -> bool {
+        self.state == FuseState::Blown
+    }
+
+    /// Check if fuse is in the error state
+    #[inline(always)]
+    pub fn has_error(&self) -> bool {
+        self.state == FuseState::Error
+    }
+
     /// Check if fuse is locked
     #[inline(always)]
     pub fn is_locked(&self) -> bool {
@@ -187,6 +403,19 @@ impl HardwareFuse {
     }
 }
 
+/// Outcome of `FuseManager::commit_all`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommitReport {
+    /// Fuses programmed with their shadow value
+    pub committed: usize,
+    /// Already-programmed OTP fuses left untouched
+    pub skipped_otp: usize,
+    /// Factory-provisioned read-only fuses, never programmed
+    pub skipped_read_only: usize,
+    /// Fuse indices that failed, with the reason
+    pub failed: Vec<(SlotIndex, ShadowError)>,
+}
+
 /// Fuse Manager - manages all hardware fuses
 pub struct FuseManager {
     /// Array of hardware fuses
@@ -195,23 +424,43 @@ pub struct FuseManager {
     count: usize,
     /// Shadow register bank for syncing
     shadow_bank: ShadowRegisterBank,
+    /// Write count at which `wear_warnings` reports a register (0 = off)
+    warn_threshold: u32,
+    /// Registers locked by `lock_registers_ordered` and not yet released
+    ordered_locks: Vec<RegisterId>,
 }
 
 impl FuseManager {
     /// Create a new fuse manager
     pub const fn new() -> Self {
-        const INIT: HardwareFuse = HardwareFuse::new(0, FuseMode::OTP);
+        const INIT: HardwareFuse = HardwareFuse::new(FuseAddr(FUSE_BASE), FuseMode::OTP);
         Self {
             fuses: [INIT; 128],
             count: 0,
             shadow_bank: ShadowRegisterBank::new(),
+            warn_threshold: 0,
+            ordered_locks: Vec::new(),
         }
     }
 
-    /// Add a new fuse
-    pub fn add_fuse(&mut self, address: u64, mode: FuseMode) -> Result<usize, &'static str> {
+    /// Add a new fuse, using its slot index as the shadow register id
+    pub fn add_fuse(
+        &mut self,
+        address: FuseAddr,
+        mode: FuseMode,
+    ) -> Result<SlotIndex, ShadowError> {
+        self.add_fuse_with_id(RegisterId(self.count as u32), address, mode)
+    }
+
+    /// Add a new fuse whose shadow register has the caller-chosen `id`
+    pub fn add_fuse_with_id(
+        &mut self,
+        id: RegisterId,
+        address: FuseAddr,
+        mode: FuseMode,
+    ) -> Result<SlotIndex, ShadowError> {
         if self.count >= 128 {
-            return Err("Fuse manager is full");
+            return Err(ShadowError::Full);
         }
 
         let index = self.count;
@@ -219,56 +468,71 @@ impl FuseManager {
         self.count += 1;
 
         // Create corresponding shadow register
-        self.shadow_bank.add_register(index as u32, address)?;
+        self.shadow_bank.add_register(id, address.raw())?;
 
-        Ok(index)
+        Ok(SlotIndex(index))
     }
 
     /// Load fuse value into shadow register
-    pub unsafe fn load_to_shadow(&mut self, fuse_index: usize) -> Result<(), &'static str> {
-        if fuse_index >= self.count {
-            return Err("Invalid fuse index");
+    pub unsafe fn load_to_shadow(&mut self, fuse_index: SlotIndex) -> Result<(), ShadowError> {
+        if fuse_index.0 >= self.count {
+            return Err(ShadowError::InvalidIndex);
         }
 
         // Read from hardware fuse
-        let fuse = &mut self.fuses[fuse_index];
-        let value = fuse.read_from_hardware()?;
+        let fuse = &mut self.fuses[fuse_index.0];
+        let value = match fuse.read_from_hardware() {
+            Ok(value) => value,
+            Err(err) => {
+                // The shadow copy can no longer be trusted either
+                if err == ShadowError::EccUncorrectable {
+                    if let Some(shadow_reg) = self.shadow_bank.get_by_index(fuse_index) {
+                        shadow_reg.poison();
+                    }
+                }
+                return Err(err);
+            }
+        };
 
-        // Write to shadow register
+        // Write to shadow register (a clean reload clears poison)
         if let Some(shadow_reg) = self.shadow_bank.get_by_index_mut(fuse_index) {
-            shadow_reg.write(value)?;
-            shadow_reg.commit()?;
+            if shadow_reg.is_poisoned() {
+                shadow_reg.clear_poison(value);
+            } else {
+                shadow_reg.write(value)?;
+                shadow_reg.commit()?;
+            }
         }
 
         Ok(())
     }
 
     /// Commit shadow register to fuse
-    pub unsafe fn commit_to_fuse(&mut self, fuse_index: usize) -> Result<(), &'static str> {
-        if fuse_index >= self.count {
-            return Err("Invalid fuse index");
+    pub unsafe fn commit_to_fuse(&mut self, fuse_index: SlotIndex) -> Result<(), ShadowError> {
+        if fuse_index.0 >= self.count {
+            return Err(ShadowError::InvalidIndex);
         }
 
         // Get shadow register value
         let shadow_value = if let Some(shadow_reg) = self.shadow_bank.get_by_index(fuse_index) {
-            shadow_reg.read()
+            shadow_reg.try_read()?
         } else {
-            return Err("Shadow register not found");
+            return Err(ShadowError::NotFound);
         };
 
         // Program to hardware fuse
-        let fuse = &mut self.fuses[fuse_index];
+        let fuse = &mut self.fuses[fuse_index.0];
         fuse.program_to_hardware(shadow_value)?;
 
         Ok(())
     }
 
     /// Load all fuses into shadow registers
-    pub unsafe fn load_all(&mut self) -> Result<usize, &'static str> {
+    pub unsafe fn load_all(&mut self) -> Result<usize, ShadowError> {
         let mut loaded = 0;
 
         for i in 0..self.count {
-            if self.load_to_shadow(i).is_ok() {
+            if self.load_to_shadow(SlotIndex(i)).is_ok() {
                 loaded += 1;
             }
         }
@@ -277,24 +541,40 @@ impl FuseManager {
     }
 
     /// Commit all shadow registers to fuses
-    pub unsafe fn commit_all(&mut self) -> Result<usize, &'static str> {
-        let mut committed = 0;
+    ///
+    /// Programmed OTP fuses are expected to refuse a second write and
+    /// read-only fuses are never written; both are counted as skipped. Any
+    /// other failure is reported per fuse index.
+    pub unsafe fn commit_all(&mut self) -> Result<CommitReport, ShadowError> {
+        let mut report = CommitReport::default();
 
         for i in 0..self.count {
-            if self.commit_to_fuse(i).is_ok() {
-                committed += 1;
+            let slot = SlotIndex(i);
+            if self.fuses[i].is_read_only() {
+                report.skipped_read_only += 1;
+                continue;
+            }
+            match self.commit_to_fuse(slot) {
+                Ok(()) => report.committed += 1,
+                Err(ShadowError::AlreadyProgrammed) => report.skipped_otp += 1,
+                Err(err) => report.failed.push((slot, err)),
             }
         }
 
-        Ok(committed)
+        Ok(report)
     }
 
     /// Verify all fuses against shadow registers
+    ///
+    /// Read-protected registers cannot be compared and are skipped.
     pub fn verify_all(&self) -> bool {
         for i in 0..self.count {
             let fuse_value = self.fuses[i].get_value();
 
-            if let Some(shadow_reg) = self.shadow_bank.get_by_index(i) {
+            if let Some(shadow_reg) = self.shadow_bank.get_by_index(SlotIndex(i)) {
+                if shadow_reg.is_read_protected() {
+                    continue;
+                }
                 if shadow_reg.read() != fuse_value {
                     return false;
                 }
@@ -307,6 +587,100 @@ impl FuseManager {
         true
     }
 
+    /// Lock the shadow registers for several fuses in ascending id order
+    ///
+    /// Every transaction takes locks in the same global order, so two
+    /// transactions touching overlapping fuses cannot deadlock each other.
+    /// All ids are checked before any lock is taken. A register that is
+    /// already locked is never taken over: the locks this call took are
+    /// released again and `ShadowError::Locked` is returned.
+    pub fn lock_registers_ordered(&mut self, ids: &[RegisterId]) -> Result<(), ShadowError> {
+        if ids.iter().any(|&id| self.shadow_bank.get_register(id).is_none()) {
+            return Err(ShadowError::NotFound);
+        }
+
+        let mut order = ids.to_vec();
+        order.sort_unstable();
+        order.dedup();
+        for (taken, &id) in order.iter().enumerate() {
+            let reg = match self.shadow_bank.get_register_mut(id) {
+                Some(reg) => reg,
+                None => continue,
+            };
+            if reg.get_lock_mode() != LockMode::None {
+                for &locked in order[..taken].iter().rev() {
+                    if let Some(reg) = self.shadow_bank.get_register_mut(locked) {
+                        reg.unlock();
+                    }
+                }
+                return Err(ShadowError::Locked);
+            }
+            reg.lock(LockMode::WriteProtect);
+        }
+
+        self.ordered_locks.extend(order);
+        Ok(())
+    }
+
+    /// Unlock the shadow registers taken by `lock_registers_ordered`
+    ///
+    /// Registers in `ids` that were locked some other way keep their lock.
+    pub fn unlock_registers(&mut self, ids: &[RegisterId]) -> Result<(), ShadowError> {
+        if ids.iter().any(|&id| self.shadow_bank.get_register(id).is_none()) {
+            return Err(ShadowError::NotFound);
+        }
+
+        for &id in ids {
+            let position = match self.ordered_locks.iter().position(|&locked| locked == id) {
+                Some(position) => position,
+                None => continue,
+            };
+            self.ordered_locks.swap_remove(position);
+            if let Some(reg) = self.shadow_bank.get_register_mut(id) {
+                reg.unlock();
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Shadow register write counts per fuse slot, most-written first
+    ///
+    /// Slots with equal counts stay in index order.
+    pub fn wear_report(&self) -> Vec<(SlotIndex, u32)> {
+        let mut report: Vec<(SlotIndex, u32)> = (0..self.count)
+            .filter_map(|i| {
+                let reg = self.shadow_bank.get_by_index(SlotIndex(i))?;
+                Some((SlotIndex(i), reg.get_write_count()))
+            })
+            .collect();
+        report.sort_by_key(|&(_, writes)| Reverse(writes));
+        report
+    }
+
+    /// Set the write count at which `wear_warnings` flags a slot (0 disables)
+    #[inline(always)]
+    pub fn set_warn_threshold(&mut self, writes: u32) {
+        self.warn_threshold = writes;
+    }
+
+    /// Get the wear warning threshold (0 if disabled)
+    #[inline(always)]
+    pub fn get_warn_threshold(&self) -> u32 {
+        self.warn_threshold
+    }
+
+    /// Entries of `wear_report` at or above the warning threshold
+    pub fn wear_warnings(&self) -> Vec<(SlotIndex, u32)> {
+        if self.warn_threshold == 0 {
+            return Vec::new();
+        }
+
+        let mut report = self.wear_report();
+        report.retain(|&(_, writes)| writes >= self.warn_threshold);
+        report
+    }
+
     /// Get fuse count
     #[inline(always)]
     pub fn count(&self) -> usize {
@@ -315,9 +689,9 @@ impl FuseManager {
 
     /// Get fuse by index
     #[inline(always)]
-    pub fn get_fuse(&self, index: usize) -> Option<&HardwareFuse> {
-        if index < self.count {
-            Some(&self.fuses[index])
+    pub fn get_fuse(&self, index: SlotIndex) -> Option<&HardwareFuse> {
+        if index.0 < self.count {
+            Some(&self.fuses[index.0])
         } else {
             None
         }
@@ -325,9 +699,9 @@ impl FuseManager {
 
     /// Get mutable fuse by index
     #[inline(always)]
-    pub fn get_fuse_mut(&mut self, index: usize) -> Option<&mut HardwareFuse> {
-        if index < self.count {
-            Some(&mut self.fuses[index])
+    pub fn get_fuse_mut(&mut self, index: SlotIndex) -> Option<&mut HardwareFuse> {
+        if index.0 < self.count {
+            Some(&mut self.fuses[index.0])
         } else {
             None
         }
@@ -349,6 +723,37 @@ impl FuseManager {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::shadow_register::{LockMode, RegisterState};
+
+    /// Helper: Fuse address at `offset` into the fuse window
+    fn fuse_addr(offset: u64) -> FuseAddr {
+        FuseAddr::new(FUSE_BASE + offset).unwrap()
+    }
+
+    #[test]
+    fn test_fuse_addr_accepts_fuse_window() {
+        assert_eq!(FuseAddr::new(FUSE_BASE).unwrap().raw(), FUSE_BASE);
+        assert!(FuseAddr::new(FUSE_BASE + 0x1000).is_some());
+        assert!(FuseAddr::new(FUSE_BASE + FUSE_REGION_SIZE - 8).is_some());
+    }
+
+    #[test]
+    fn test_fuse_addr_rejects_other_addresses() {
+        // Below, past the end of, or misaligned within the fuse window
+        assert!(FuseAddr::new(0).is_none());
+        assert!(FuseAddr::new(0x1000).is_none());
+        assert!(FuseAddr::new(FUSE_BASE - 8).is_none());
+        assert!(FuseAddr::new(FUSE_BASE + FUSE_REGION_SIZE).is_none());
+        assert!(FuseAddr::new(u64::MAX).is_none());
+        assert!(FuseAddr::new(FUSE_BASE + 4).is_none());
+
+        // Shadow register MMIO space is not a fuse address
+        assert!(FuseAddr::new(0xFFFF_9000_5000_0000).is_none());
+
+        // Another platform's window
+        assert!(FuseAddr::new_in(0x6000_0008, 0x6000_0000).is_some());
+        assert!(FuseAddr::new_in(FUSE_BASE, 0x6000_0000).is_none());
+    }
 
     #[test]
     fn test_fuse_manager_initialization() {
@@ -361,13 +766,13 @@ mod tests {
         let mut manager = FuseManager::new();
 
         // Add OTP fuse
-        let result = manager.add_fuse(0x1000, FuseMode::OTP);
+        let result = manager.add_fuse(fuse_addr(0x1000), FuseMode::OTP);
         assert!(result.is_ok());
-        assert_eq!(result.unwrap(), 0);
+        assert_eq!(result.unwrap(), SlotIndex(0));
         assert_eq!(manager.count(), 1);
 
         // Verify fuse was added correctly
-        let fuse = manager.get_fuse(0);
+        let fuse = manager.get_fuse(SlotIndex(0));
         assert!(fuse.is_some());
         assert!(fuse.unwrap().is_virgin());
     }
@@ -377,9 +782,9 @@ mod tests {
         let mut manager = FuseManager::new();
 
         // Add MTP fuse
-        let result = manager.add_fuse(0x2000, FuseMode::MTP);
+        let result = manager.add_fuse(fuse_addr(0x2000), FuseMode::MTP);
         assert!(result.is_ok());
-        assert_eq!(result.unwrap(), 0);
+        assert_eq!(result.unwrap(), SlotIndex(0));
         assert_eq!(manager.count(), 1);
     }
 
@@ -388,9 +793,9 @@ mod tests {
         let mut manager = FuseManager::new();
 
         // Add EEPROM fuse
-        let result = manager.add_fuse(0x3000, FuseMode::EEPROM);
+        let result = manager.add_fuse(fuse_addr(0x3000), FuseMode::EEPROM);
         assert!(result.is_ok());
-        assert_eq!(result.unwrap(), 0);
+        assert_eq!(result.unwrap(), SlotIndex(0));
         assert_eq!(manager.count(), 1);
     }
 
@@ -399,49 +804,66 @@ mod tests {
         let mut manager = FuseManager::new();
 
         // Add multiple fuses
-        assert_eq!(manager.add_fuse(0x1000, FuseMode::OTP).unwrap(), 0);
-        assert_eq!(manager.add_fuse(0x2000, FuseMode::MTP).unwrap(), 1);
-        assert_eq!(manager.add_fuse(0x3000, FuseMode::EEPROM).unwrap(), 2);
+        assert_eq!(manager.add_fuse(fuse_addr(0x1000), FuseMode::OTP).unwrap(), SlotIndex(0));
+        assert_eq!(manager.add_fuse(fuse_addr(0x2000), FuseMode::MTP).unwrap(), SlotIndex(1));
+        assert_eq!(manager.add_fuse(fuse_addr(0x3000), FuseMode::EEPROM).unwrap(), SlotIndex(2));
         assert_eq!(manager.count(), 3);
     }
 
+    #[test]
+    fn test_add_fuse_with_id() {
+        let mut manager = FuseManager::new();
+
+        let slot_a = manager.add_fuse_with_id(RegisterId(10), fuse_addr(0x1000), FuseMode::MTP);
+        let slot_b = manager.add_fuse_with_id(RegisterId(20), fuse_addr(0x2000), FuseMode::MTP);
+        assert_eq!(slot_a, Ok(SlotIndex(0)));
+        assert_eq!(slot_b, Ok(SlotIndex(1)));
+
+        let bank = manager.get_shadow_bank();
+        assert_eq!(bank.index_of(RegisterId(20)), Some(SlotIndex(1)));
+        assert_eq!(
+            bank.get_register(RegisterId(20)).unwrap().get_fuse_address(),
+            fuse_addr(0x2000).raw()
+        );
+    }
+
     #[test]
     fn test_fuse_manager_full() {
         let mut manager = FuseManager::new();
 
         // Fill the manager to capacity (128 fuses)
         for i in 0..128 {
-            let result = manager.add_fuse(i as u64 * 0x1000, FuseMode::OTP);
+            let result = manager.add_fuse(fuse_addr(i as u64 * 0x1000), FuseMode::OTP);
             assert!(result.is_ok());
         }
 
         // Should fail when full
-        let result = manager.add_fuse(0x100000, FuseMode::OTP);
+        let result = manager.add_fuse(fuse_addr(0x100000), FuseMode::OTP);
         assert!(result.is_err());
-        assert_eq!(result.unwrap_err(), "Fuse manager is full");
+        assert_eq!(result.unwrap_err(), ShadowError::Full);
     }
 
     #[test]
     fn test_get_fuse() {
         let mut manager = FuseManager::new();
-        manager.add_fuse(0x1000, FuseMode::OTP).unwrap();
+        manager.add_fuse(fuse_addr(0x1000), FuseMode::OTP).unwrap();
 
         // Get valid fuse
-        let fuse = manager.get_fuse(0);
+        let fuse = manager.get_fuse(SlotIndex(0));
         assert!(fuse.is_some());
 
         // Get invalid fuse
-        let fuse = manager.get_fuse(1);
+        let fuse = manager.get_fuse(SlotIndex(1));
         assert!(fuse.is_none());
     }
 
     #[test]
     fn test_get_fuse_mut() {
         let mut manager = FuseManager::new();
-        manager.add_fuse(0x1000, FuseMode::OTP).unwrap();
+        manager.add_fuse(fuse_addr(0x1000), FuseMode::OTP).unwrap();
 
         // Get mutable fuse
-        let fuse = manager.get_fuse_mut(0);
+        let fuse = manager.get_fuse_mut(SlotIndex(0));
         assert!(fuse.is_some());
 
         // Verify state
@@ -450,7 +872,7 @@ mod tests {
 
     #[test]
     fn test_hardware_fuse_initialization() {
-        let fuse = HardwareFuse::new(0x1000, FuseMode::OTP);
+        let fuse = HardwareFuse::new(fuse_addr(0x1000), FuseMode::OTP);
         assert_eq!(fuse.get_state(), FuseState::Virgin);
         assert_eq!(fuse.get_value(), 0);
         assert!(!fuse.is_locked());
@@ -460,14 +882,14 @@ mod tests {
     #[test]
     fn test_fuse_blow() {
         let mut manager = FuseManager::new();
-        manager.add_fuse(0x1000, FuseMode::OTP).unwrap();
+        manager.add_fuse(fuse_addr(0x1000), FuseMode::OTP).unwrap();
 
-        let fuse = manager.get_fuse_mut(0).unwrap();
+        let fuse = manager.get_fuse_mut(SlotIndex(0)).unwrap();
 
         // Can't blow virgin fuse
         let result = fuse.blow();
         assert!(result.is_err());
-        assert_eq!(result.unwrap_err(), "Can only blow programmed fuses");
+        assert_eq!(result.unwrap_err(), ShadowError::NotProgrammed);
     }
 
     #[test]
@@ -481,23 +903,116 @@ mod tests {
     #[test]
     fn test_verify_all_with_fuses() {
         let mut manager = FuseManager::new();
-        manager.add_fuse(0x1000, FuseMode::OTP).unwrap();
-        manager.add_fuse(0x2000, FuseMode::MTP).unwrap();
+        manager.add_fuse(fuse_addr(0x1000), FuseMode::OTP).unwrap();
+        manager.add_fuse(fuse_addr(0x2000), FuseMode::MTP).unwrap();
 
         // Commit shadow registers to establish valid CRCs
         let shadow_bank = manager.get_shadow_bank_mut();
-        shadow_bank.get_register_mut(0).unwrap().write(0).unwrap();
-        shadow_bank.get_register_mut(0).unwrap().commit().unwrap();
-        shadow_bank.get_register_mut(1).unwrap().write(0).unwrap();
-        shadow_bank.get_register_mut(1).unwrap().commit().unwrap();
+        shadow_bank.get_register_mut(RegisterId(0)).unwrap().write(0).unwrap();
+        shadow_bank.get_register_mut(RegisterId(0)).unwrap().commit().unwrap();
+        shadow_bank.get_register_mut(RegisterId(1)).unwrap().write(0).unwrap();
+        shadow_bank.get_register_mut(RegisterId(1)).unwrap().commit().unwrap();
 
         // All fuses should verify (virgin state matches shadow)
         assert!(manager.verify_all());
     }
 
+    #[test]
+    fn test_verify_all_skips_read_protected() {
+        let mut manager = FuseManager::new();
+        manager.add_fuse(fuse_addr(0x1000), FuseMode::OTP).unwrap();
+        manager.add_fuse(fuse_addr(0x2000), FuseMode::MTP).unwrap();
+
+        let shadow_bank = manager.get_shadow_bank_mut();
+        for id in 0..2 {
+            let reg = shadow_bank.get_register_mut(RegisterId(id)).unwrap();
+            reg.write(0x5EC0).unwrap();
+            reg.commit().unwrap();
+        }
+
+        // Both registers now differ from their virgin fuses
+        assert!(!manager.verify_all());
+
+        // Hiding one still leaves the other mismatched
+        let shadow_bank = manager.get_shadow_bank_mut();
+        shadow_bank.lock_register(RegisterId(0), LockMode::ReadWriteProtect).unwrap();
+        assert!(!manager.verify_all());
+
+        // With both hidden there is nothing left to compare
+        let shadow_bank = manager.get_shadow_bank_mut();
+        shadow_bank.lock_register(RegisterId(1), LockMode::ReadWriteProtect).unwrap();
+        assert!(manager.verify_all());
+
+        // Write protection alone does not hide the mismatch
+        let shadow_bank = manager.get_shadow_bank_mut();
+        shadow_bank.lock_register(RegisterId(1), LockMode::WriteProtect).unwrap();
+        assert!(!manager.verify_all());
+    }
+
+    #[test]
+    fn test_load_to_shadow_poisons_on_uncorrectable_ecc() {
+        let mut memory = [0u64; 3];
+        let cell = memory.as_mut_ptr();
+
+        let mut manager = FuseManager::new();
+        manager.add_fuse((cell as u64).into(), FuseMode::MTP).unwrap();
+
+        unsafe {
+            let fuse = manager.get_fuse_mut(SlotIndex(0)).unwrap();
+            fuse.set_redundancy(3, 8);
+            fuse.program_to_hardware(0x1234).unwrap();
+            manager.load_to_shadow(SlotIndex(0)).unwrap();
+
+            // Flip a bit behind the stored ECC
+            cell.write_volatile(cell.read_volatile() ^ (1 << 4));
+            assert_eq!(
+                manager.load_to_shadow(SlotIndex(0)),
+                Err(ShadowError::EccUncorrectable)
+            );
+            let reg = manager.get_shadow_bank().get_by_index(SlotIndex(0)).unwrap();
+            assert_eq!(reg.get_state(), RegisterState::Poisoned);
+
+            // A clean reload clears the poison
+            cell.write_volatile(cell.read_volatile() ^ (1 << 4));
+            manager.load_to_shadow(SlotIndex(0)).unwrap();
+            let reg = manager.get_shadow_bank().get_by_index(SlotIndex(0)).unwrap();
+            assert_eq!(reg.get_state(), RegisterState::Loaded);
+            assert_eq!(reg.read(), 0x1234);
+        }
+    }
+
+    #[test]
+    fn test_wear_report_orders_by_write_count() {
+        let mut manager = FuseManager::new();
+        for i in 0..4 {
+            manager.add_fuse(fuse_addr(0x1000 + i * 8), FuseMode::MTP).unwrap();
+        }
+
+        let bank = manager.get_shadow_bank();
+        for (id, writes) in [(0, 2), (1, 5), (2, 0), (3, 2)] {
+            let reg = bank.get_register(RegisterId(id)).unwrap();
+            for value in 0..writes {
+                reg.write(value as u64).unwrap();
+            }
+        }
+
+        assert_eq!(
+            manager.wear_report(),
+            [(SlotIndex(1), 5), (SlotIndex(0), 2), (SlotIndex(3), 2), (SlotIndex(2), 0)]
+        );
+
+        // Warnings are off until a threshold is set
+        assert!(manager.wear_warnings().is_empty());
+        manager.set_warn_threshold(2);
+        assert_eq!(
+            manager.wear_warnings(),
+            [(SlotIndex(1), 5), (SlotIndex(0), 2), (SlotIndex(3), 2)]
+        );
+    }
+
     #[test]
     fn test_fuse_state_transitions() {
-        let fuse = HardwareFuse::new(0x1000, FuseMode::OTP);
+        let fuse = HardwareFuse::new(fuse_addr(0x1000), FuseMode::OTP);
 
         // Initial state
         assert_eq!(fuse.get_state(), FuseState::Virgin);
@@ -505,6 +1020,274 @@ mod tests {
         assert!(!fuse.is_locked());
     }
 
+    #[test]
+    fn test_fuse_state_predicates() {
+        let mut fuse = HardwareFuse::new(fuse_addr(0x1000), FuseMode::MTP);
+
+        let cases = [
+            (FuseState::Virgin, false, false, false),
+            (FuseState::Programming, false, false, false),
+            (FuseState::Programmed, true, false, false),
+            (FuseState::Blown, false, true, false),
+            (FuseState::Error, false, false, true),
+        ];
+        for (state, programmed, blown, error) in cases {
+            fuse.state = state;
+            assert_eq!(fuse.is_programmed(), programmed);
+            assert_eq!(fuse.is_blown(), blown);
+            assert_eq!(fuse.has_error(), error);
+            assert_eq!(fuse.is_virgin(), state == FuseState::Virgin);
+        }
+    }
+
+    #[test]
+    fn test_fuse_can_reprogram() {
+        for mode in [FuseMode::OTP, FuseMode::MTP, FuseMode::EEPROM] {
+            let rewritable = !matches!(mode, FuseMode::OTP);
+            let mut fuse = HardwareFuse::new(fuse_addr(0x1000), mode);
+
+            // Nothing to reprogram yet
+            assert!(!fuse.can_reprogram());
+            assert!(fuse.can_program());
+
+            fuse.state = FuseState::Programmed;
+            assert_eq!(fuse.can_reprogram(), rewritable);
+            assert_eq!(fuse.can_program(), rewritable);
+
+            // Blowing locks every mode
+            fuse.blow().unwrap();
+            assert!(fuse.is_blown());
+            assert!(!fuse.can_reprogram());
+            assert!(!fuse.can_program());
+
+            // A locked programmed fuse stays locked
+            fuse.state = FuseState::Programmed;
+            assert!(!fuse.can_reprogram());
+        }
+    }
+
+    #[test]
+    fn test_read_only_fuse_rejects_writes() {
+        let mut memory = [0x5A5A_0000_1234u64; 1];
+        let addr = memory.as_mut_ptr() as u64;
+        let mut fuse = HardwareFuse::new(addr.into(), FuseMode::ReadOnly);
+        assert!(fuse.is_read_only());
+        assert!(!fuse.can_program());
+
+        unsafe {
+            assert_eq!(fuse.read_from_hardware(), Ok(0x5A5A_0000_1234));
+            assert!(fuse.is_programmed());
+
+            assert_eq!(fuse.program_to_hardware(0xFFFF), Err(ShadowError::ReadOnly));
+            assert_eq!(ShadowError::ReadOnly.as_str(), "Read-only fuse");
+        }
+        assert_eq!(fuse.blow(), Err(ShadowError::ReadOnly));
+        assert!(!fuse.can_reprogram());
+        assert!(!fuse.is_blown());
+
+        // Hardware is untouched
+        assert_eq!(memory[0], 0x5A5A_0000_1234);
+    }
+
+    #[test]
+    fn test_program_to_hardware_endurance_limit() {
+        let mut memory = [0u64; 1];
+        let addr = memory.as_mut_ptr() as u64;
+        let mut fuse = HardwareFuse::new_with_endurance(addr.into(), FuseMode::MTP, 3);
+        assert_eq!(fuse.get_max_cycles(), 3);
+
+        unsafe {
+            for value in 1..=3 {
+                assert!(fuse.can_program());
+                fuse.program_to_hardware(value).unwrap();
+            }
+            assert_eq!(fuse.get_program_count(), 3);
+            assert!(fuse.is_worn_out());
+            assert!(!fuse.can_program());
+
+            // Past the limit the fuse keeps its last value
+            assert_eq!(fuse.program_to_hardware(4), Err(ShadowError::EnduranceExceeded));
+            assert_eq!(fuse.get_value(), 3);
+            assert_eq!(fuse.get_program_count(), 3);
+            assert_eq!(fuse.get_state(), FuseState::Programmed);
+        }
+        assert_eq!(ShadowError::EnduranceExceeded.as_str(), "Endurance limit reached");
+    }
+
+    #[test]
+    fn test_program_to_hardware_endurance_ignores_otp() {
+        let mut memory = [0u64; 1];
+        let addr = memory.as_mut_ptr() as u64;
+
+        // A zero-cycle limit does not stop the single OTP program
+        let mut fuse = HardwareFuse::new_with_endurance(addr.into(), FuseMode::OTP, 0);
+        assert!(!fuse.is_worn_out());
+
+        unsafe {
+            fuse.program_to_hardware(0x42).unwrap();
+            assert_eq!(fuse.program_to_hardware(0x43), Err(ShadowError::AlreadyProgrammed));
+        }
+
+        // Default fuses never wear out
+        let fuse = HardwareFuse::new(fuse_addr(0x1000), FuseMode::MTP);
+        assert_eq!(fuse.get_max_cycles(), UNLIMITED_CYCLES);
+        assert!(!fuse.is_worn_out());
+    }
+
+    #[test]
+    fn test_program_to_hardware_reprogram_rule() {
+        let mut memory = [0u64; 2];
+
+        for (slot, mode) in [(0, FuseMode::OTP), (1, FuseMode::EEPROM)] {
+            let addr = &mut memory[slot] as *mut u64 as u64;
+            let mut fuse = HardwareFuse::new(addr.into(), mode);
+
+            unsafe {
+                fuse.program_to_hardware(0x1234).unwrap();
+                assert!(fuse.is_programmed());
+
+                let second = fuse.program_to_hardware(0x5678);
+                if fuse.can_reprogram() {
+                    assert!(second.is_ok());
+                    assert_eq!(fuse.get_value(), 0x5678);
+                } else {
+                    assert_eq!(second, Err(ShadowError::AlreadyProgrammed));
+                    assert_eq!(fuse.get_value(), 0x1234);
+                }
+            }
+        }
+        assert_eq!(memory, [0x1234, 0x5678]);
+    }
+
+    #[test]
+    fn test_read_redundant_majority_recovers_value() {
+        // Three copies, one stride apart; the middle copy took bit flips
+        let good = 0xDEAD_BEEF_0000_1234u64;
+        let mut memory = [good, good ^ 0x8000_0000_0001_0F00, good];
+        let ptr = memory.as_mut_ptr();
+
+        let mut fuse = HardwareFuse::new((ptr as u64).into(), FuseMode::OTP);
+        fuse.set_redundancy(3, 8);
+
+        let value = unsafe { fuse.read_redundant() }.unwrap();
+        assert_eq!(value, good);
+        assert_eq!(fuse.get_value(), value);
+        assert!(fuse.is_programmed());
+
+        // Single copy reads straight through
+        fuse.set_redundancy(0, 8);
+        assert_eq!(fuse.get_redundancy(), 1);
+        unsafe {
+            ptr.write(0x55);
+            assert_eq!(fuse.read_redundant(), Ok(0x55));
+        }
+    }
+
+    #[test]
+    fn test_read_redundant_even_tie_is_error() {
+        let mut memory = [0x0F0Fu64, 0x0F0F, 0x0F0F, 0x0F0E];
+        let ptr = memory.as_mut_ptr();
+
+        let mut fuse = HardwareFuse::new((ptr as u64).into(), FuseMode::MTP);
+        fuse.set_redundancy(4, 8);
+
+        unsafe {
+            // 3 of 4 agree on every bit
+            assert_eq!(fuse.read_redundant(), Ok(0x0F0F));
+
+            // 2 vs 2 on bit 0
+            ptr.add(2).write(0x0F0E);
+            assert_eq!(fuse.read_redundant(), Err(ShadowError::NoMajority));
+        }
+        assert!(fuse.has_error());
+    }
+
+    #[test]
+    fn test_program_writes_every_redundant_copy() {
+        let mut memory = [0u64; 6];
+        let ptr = memory.as_mut_ptr();
+
+        // Copies every other word, leaving the words between untouched
+        let mut fuse = HardwareFuse::new((ptr as u64).into(), FuseMode::MTP);
+        fuse.set_redundancy(3, 16);
+
+        unsafe {
+            fuse.program_to_hardware(0xC0FFEE).unwrap();
+            assert_eq!(fuse.read_redundant(), Ok(0xC0FFEE));
+
+            // One corrupted copy is outvoted by the other two
+            ptr.add(2).write(0);
+            assert_eq!(fuse.read_redundant(), Ok(0xC0FFEE));
+        }
+        assert_eq!(memory, [0xC0FFEE, 0, 0, 0, 0xC0FFEE, 0]);
+    }
+
+    #[test]
+    fn test_commit_all_report() {
+        let mut memory = [0u64; 4];
+        let base = memory.as_mut_ptr();
+        let addr = |slot: usize| unsafe { base.add(slot) as u64 }.into();
+
+        let mut manager = FuseManager::new();
+        manager.add_fuse(addr(0), FuseMode::OTP).unwrap(); // virgin OTP
+        manager.add_fuse(addr(1), FuseMode::OTP).unwrap(); // programmed OTP
+        manager.add_fuse(addr(2), FuseMode::MTP).unwrap(); // programmed MTP
+        manager.add_fuse(addr(3), FuseMode::MTP).unwrap(); // blown MTP
+
+        unsafe {
+            manager.get_fuse_mut(SlotIndex(1)).unwrap().program_to_hardware(0x11).unwrap();
+            manager.get_fuse_mut(SlotIndex(2)).unwrap().program_to_hardware(0x22).unwrap();
+            let blown = manager.get_fuse_mut(SlotIndex(3)).unwrap();
+            blown.program_to_hardware(0x33).unwrap();
+            blown.blow().unwrap();
+        }
+
+        let bank = manager.get_shadow_bank_mut();
+        for id in 0..4 {
+            let reg = bank.get_register_mut(RegisterId(id)).unwrap();
+            reg.write(0xA0 + id as u64).unwrap();
+            reg.commit().unwrap();
+        }
+
+        let report = unsafe { manager.commit_all() }.unwrap();
+        assert_eq!(report.committed, 2);
+        assert_eq!(report.skipped_otp, 1);
+        assert_eq!(report.failed, [(SlotIndex(3), ShadowError::Locked)]);
+
+        // Only the virgin OTP and the MTP fuse took the shadow value
+        assert_eq!(manager.get_fuse(SlotIndex(0)).unwrap().get_value(), 0xA0);
+        assert_eq!(manager.get_fuse(SlotIndex(1)).unwrap().get_value(), 0x11);
+        assert_eq!(manager.get_fuse(SlotIndex(2)).unwrap().get_value(), 0xA2);
+        assert_eq!(manager.get_fuse(SlotIndex(3)).unwrap().get_value(), 0x33);
+    }
+
+    #[test]
+    fn test_commit_all_skips_read_only() {
+        let mut memory = [0u64, 0x5A5A];
+        let base = memory.as_mut_ptr();
+        let addr = |slot: usize| unsafe { base.add(slot) as u64 }.into();
+
+        let mut manager = FuseManager::new();
+        manager.add_fuse(addr(0), FuseMode::MTP).unwrap();
+        manager.add_fuse(addr(1), FuseMode::ReadOnly).unwrap();
+
+        let bank = manager.get_shadow_bank_mut();
+        for id in 0..2 {
+            let reg = bank.get_register_mut(RegisterId(id)).unwrap();
+            reg.write(0xB0 + id as u64).unwrap();
+            reg.commit().unwrap();
+        }
+        // Even a read-protected shadow doesn't turn the skip into a failure
+        bank.lock_register(RegisterId(1), LockMode::ReadWriteProtect).unwrap();
+
+        let report = unsafe { manager.commit_all() }.unwrap();
+        assert_eq!(report.committed, 1);
+        assert_eq!(report.skipped_read_only, 1);
+        assert_eq!(report.skipped_otp, 0);
+        assert!(report.failed.is_empty());
+        assert_eq!(memory, [0xB0, 0x5A5A]);
+    }
+
     #[test]
     fn test_shadow_bank_integration() {
         let manager = FuseManager::new();
@@ -517,12 +1300,111 @@ mod tests {
     #[test]
     fn test_shadow_bank_integration_with_fuses() {
         let mut manager = FuseManager::new();
-        manager.add_fuse(0x1000, FuseMode::OTP).unwrap();
-        manager.add_fuse(0x2000, FuseMode::MTP).unwrap();
+        manager.add_fuse(fuse_addr(0x1000), FuseMode::OTP).unwrap();
+        manager.add_fuse(fuse_addr(0x2000), FuseMode::MTP).unwrap();
 
         let shadow_bank = manager.get_shadow_bank();
 
         // Shadow bank should have same count as fuses
         assert_eq!(shadow_bank.count(), 2);
     }
+
+    #[test]
+    fn test_lock_registers_ordered_unsorted_duplicate_ids() {
+        let mut manager = FuseManager::new();
+        for i in 0..8 {
+            manager.add_fuse(fuse_addr(0x1000 * (i + 1)), FuseMode::MTP).unwrap();
+        }
+
+        // Input order and duplicates don't change which registers end up locked
+        let ids = [7, 2, 5, 2, 0].map(RegisterId);
+        manager.lock_registers_ordered(&ids).unwrap();
+
+        let bank = manager.get_shadow_bank();
+        for i in 0..8 {
+            let locked = bank.get_register(RegisterId(i)).unwrap().get_state()
+                == RegisterState::Locked;
+            assert_eq!(locked, ids.contains(&RegisterId(i)), "register {i}");
+        }
+
+        manager.unlock_registers(&ids).unwrap();
+        let bank = manager.get_shadow_bank();
+        for &id in &ids {
+            assert_ne!(bank.get_register(id).unwrap().get_state(), RegisterState::Locked);
+        }
+    }
+
+    #[test]
+    fn test_lock_and_unlock_registers() {
+        let mut manager = FuseManager::new();
+        for i in 0..4 {
+            manager.add_fuse(fuse_addr(0x1000 * (i + 1)), FuseMode::MTP).unwrap();
+        }
+
+        manager.lock_registers_ordered(&[RegisterId(3), RegisterId(1)]).unwrap();
+        let bank = manager.get_shadow_bank();
+        assert_eq!(bank.get_register(RegisterId(1)).unwrap().get_state(), RegisterState::Locked);
+        assert_eq!(bank.get_register(RegisterId(3)).unwrap().get_state(), RegisterState::Locked);
+        assert_ne!(bank.get_register(RegisterId(0)).unwrap().get_state(), RegisterState::Locked);
+        assert_ne!(bank.get_register(RegisterId(2)).unwrap().get_state(), RegisterState::Locked);
+
+        manager.unlock_registers(&[RegisterId(3), RegisterId(1)]).unwrap();
+        let bank = manager.get_shadow_bank();
+        assert_ne!(bank.get_register(RegisterId(1)).unwrap().get_state(), RegisterState::Locked);
+        assert_ne!(bank.get_register(RegisterId(3)).unwrap().get_state(), RegisterState::Locked);
+    }
+
+    #[test]
+    fn test_lock_registers_ordered_keeps_existing_lock() {
+        let mut manager = FuseManager::new();
+        for i in 0..4 {
+            manager.add_fuse(fuse_addr(0x1000 * (i + 1)), FuseMode::MTP).unwrap();
+        }
+        manager
+            .get_shadow_bank_mut()
+            .get_register_mut(RegisterId(2))
+            .unwrap()
+            .lock_by(3, LockMode::ReadWriteProtect);
+
+        // Register 1 is taken first, then released when 2 turns out locked
+        assert_eq!(
+            manager.lock_registers_ordered(&[3, 2, 1].map(RegisterId)),
+            Err(ShadowError::Locked)
+        );
+        let bank = manager.get_shadow_bank();
+        assert_ne!(bank.get_register(RegisterId(1)).unwrap().get_state(), RegisterState::Locked);
+        assert_ne!(bank.get_register(RegisterId(3)).unwrap().get_state(), RegisterState::Locked);
+
+        let reg = bank.get_register(RegisterId(2)).unwrap();
+        assert_eq!(reg.get_lock_mode(), LockMode::ReadWriteProtect);
+        assert_eq!(reg.get_lock_owner(), Some(3));
+        assert_eq!(reg.try_read(), Err(ShadowError::ReadProtected));
+
+        // Only locks taken by lock_registers_ordered are released
+        manager.lock_registers_ordered(&[3, 1].map(RegisterId)).unwrap();
+        manager.unlock_registers(&[1, 2, 3].map(RegisterId)).unwrap();
+        let bank = manager.get_shadow_bank();
+        assert_ne!(bank.get_register(RegisterId(1)).unwrap().get_state(), RegisterState::Locked);
+        assert_ne!(bank.get_register(RegisterId(3)).unwrap().get_state(), RegisterState::Locked);
+        let reg = bank.get_register(RegisterId(2)).unwrap();
+        assert_eq!(reg.get_lock_mode(), LockMode::ReadWriteProtect);
+        assert_eq!(reg.get_lock_owner(), Some(3));
+    }
+
+    #[test]
+    fn test_lock_registers_ordered_unknown_id() {
+        let mut manager = FuseManager::new();
+        manager.add_fuse(fuse_addr(0x1000), FuseMode::MTP).unwrap();
+
+        // Nothing is locked when any id is missing
+        assert_eq!(
+            manager.lock_registers_ordered(&[RegisterId(0), RegisterId(9)]),
+            Err(ShadowError::NotFound)
+        );
+        assert_ne!(
+            manager.get_shadow_bank().get_register(RegisterId(0)).unwrap().get_state(),
+            RegisterState::Locked
+        );
+        assert_eq!(manager.unlock_registers(&[RegisterId(9)]), Err(ShadowError::NotFound));
+    }
 }
@@ -1,4 +1,4 @@
-#![no_std]
+#![cfg_attr(not(feature = "std"), no_std)]
 
 extern crate alloc;
 
@@ -17,17 +17,29 @@ pub mod shadow_mmio;
 pub mod version_control;
 pub mod shadow_runtime;
 
+// Test support
+#[cfg(any(test, feature = "fault_injection"))]
+pub mod fault_injection;
+
 // Re-export main cache coherency types
-pub use cache_coherency::{CacheLine, CacheState, L3Directory};
-pub use mmio::{CoherencyOp, MMIOCoherency};
-pub use runtime::{CoherencyRuntime, CoreCacheController};
-pub use state_machine::{CacheEvent, CoherencyStateMachine};
+pub use cache_coherency::{CacheLine, CacheState, L3Directory, CACHE_LINE_SIZE};
+pub use mmio::{CoherencyOp, MMIOCoherency, MmioLayout, MmioToken, WriteCombineBuffer};
+pub use runtime::{CoherencyRuntime, CoherencySnapshot, CoreCacheController, TraceEntry};
+pub use state_machine::{CacheEvent, CoherencyStateMachine, StateTransitionTable};
 
 // Re-export main shadow register types
-pub use shadow_register::{RegisterState, ShadowRegister, ShadowRegisterBank};
-pub use fuse_manager::{FuseManager, FuseMode, FuseState, HardwareFuse};
-pub use sync_manager::{SyncDirection, SyncManager, SyncPolicy, SyncResult};
-pub use ecc_handler::{ECCError, ECCManager, ECCStrategy, HammingECC};
-pub use shadow_mmio::{ShadowMMIOController, ShadowRegisterMMIO, MMIOCommand};
+pub use shadow_register::{
+    BitField, LockMode, RegisterId, RegisterState, ShadowError, ShadowRegister,
+    ShadowRegisterBank, ShadowRegisterBankView, ShadowRegisterView, SlotIndex,
+    StateChangeCallback, WideShadowRegister, CRC32C_POLY, CRC32_IEEE_POLY,
+};
+pub use fuse_manager::{
+    CommitReport, FuseAddr, FuseManager, FuseMode, FuseState, HardwareFuse, UNLIMITED_CYCLES,
+};
+pub use sync_manager::{ConflictResolver, SyncDirection, SyncManager, SyncPolicy, SyncResult};
+pub use ecc_handler::{ECCCodec, ECCError, ECCManager, ECCStrategy, HammingECC};
+pub use shadow_mmio::{Capabilities, ShadowMMIOController, ShadowRegisterMMIO, MMIOCommand};
 pub use version_control::{VersionedShadowRegister, VersionHistory, VersionEntry};
-pub use shadow_runtime::{ShadowRegisterRuntime, VersionedShadowRuntime};
+pub use shadow_runtime::{ScrubReport, ShadowRegisterRuntime, VersionedShadowRuntime};
+#[cfg(any(test, feature = "fault_injection"))]
+pub use fault_injection::{FaultInjector, XorShift64};
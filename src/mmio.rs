@@ -1,14 +1,87 @@
 /// Memory-Mapped I/O Register Interface
 /// Direct hardware access for ROM/Firmware replacement
 
+use crate::fuse_manager::FUSE_BASE;
+use crate::shadow_mmio::SHADOW_REG_BASE;
 use core::ptr::{read_volatile, write_volatile};
+use core::sync::atomic::{fence, Ordering};
+use core::task::Poll;
 
 /// MMIO Base Addresses (platform-specific)
 pub const L3_CACHE_BASE: usize = 0x4000_0000;
 pub const COHERENCY_CTL_BASE: usize = 0x4010_0000;
 pub const CORE_STATUS_BASE: usize = 0x4020_0000;
 
+/// Spacing between per-core coherency register blocks
+pub const CORE_MMIO_STRIDE: usize = 0x1000;
+
+/// MMIO base addresses of one platform
+///
+/// Runtimes take a layout instead of the compile-time bases so the same
+/// code runs on other memory maps, or against mock buffers in tests.
+/// `ShadowRegisterRuntime` validates fuse addresses against `fuse_base`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MmioLayout {
+    /// L3 cache window
+    pub l3_base: usize,
+    /// Core 0 coherency register block (core N at `N * CORE_MMIO_STRIDE`)
+    pub coherency_base: usize,
+    /// Shadow register MMIO block
+    pub shadow_base: usize,
+    /// Hardware fuse window
+    pub fuse_base: usize,
+}
+
+impl MmioLayout {
+    /// This crate's platform constants
+    pub const DEFAULT: MmioLayout = MmioLayout {
+        l3_base: L3_CACHE_BASE,
+        coherency_base: COHERENCY_CTL_BASE,
+        shadow_base: SHADOW_REG_BASE,
+        fuse_base: FUSE_BASE as usize,
+    };
+
+    /// Replace the L3 cache base
+    pub const fn with_l3_base(mut self, base: usize) -> Self {
+        self.l3_base = base;
+        self
+    }
+
+    /// Replace the coherency register base
+    pub const fn with_coherency_base(mut self, base: usize) -> Self {
+        self.coherency_base = base;
+        self
+    }
+
+    /// Replace the shadow register MMIO base
+    pub const fn with_shadow_base(mut self, base: usize) -> Self {
+        self.shadow_base = base;
+        self
+    }
+
+    /// Replace the fuse window base
+    pub const fn with_fuse_base(mut self, base: usize) -> Self {
+        self.fuse_base = base;
+        self
+    }
+
+    /// Coherency register block of `core_id`
+    pub const fn core_coherency_base(&self, core_id: u8) -> usize {
+        self.coherency_base + core_id as usize * CORE_MMIO_STRIDE
+    }
+}
+
+impl Default for MmioLayout {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
 /// MMIO Register for Cache Coherency Control
+///
+/// Writing `control` starts the operation on the line in `address`, so the
+/// address must reach the device first. Use `issue` rather than the two
+/// raw writes.
 #[repr(C)]
 pub struct CoherencyRegister {
     /// Control register: [3:0] = operation, [7:4] = core_id
@@ -46,12 +119,33 @@ impl CoherencyRegister {
         write_volatile(&mut self.address as *mut u64, addr);
     }
 
+    /// Start operation `op_ctrl` on `addr`
+    ///
+    /// Writes the address, fences, then writes the control word that
+    /// triggers the operation, so the device never acts on a stale address.
+    ///
+    /// # Safety
+    /// `self` must be a mapped coherency register block.
+    #[inline(always)]
+    pub unsafe fn issue(&mut self, op_ctrl: u32, addr: u64) {
+        issue_ordered(self, op_ctrl, addr);
+    }
+
     /// Check if operation is complete
     #[inline(always)]
     pub unsafe fn is_busy(&self) -> bool {
         (read_volatile(&self.status as *const u32) & 0x1) != 0
     }
 
+    /// Check if the last operation reported an error
+    ///
+    /// # Safety
+    /// `self` must be a mapped coherency register block.
+    #[inline(always)]
+    pub unsafe fn has_error(&self) -> bool {
+        (read_volatile(&self.status as *const u32) & 0x2) != 0
+    }
+
     /// Get current cache state from hardware
     #[inline(always)]
     pub unsafe fn get_hw_state(&self) -> u8 {
@@ -59,8 +153,36 @@ impl CoherencyRegister {
     }
 }
 
+/// The two writes that start a coherency operation
+trait OperationTrigger {
+    unsafe fn write_address(&mut self, addr: u64);
+    unsafe fn write_control(&mut self, value: u32);
+}
+
+impl OperationTrigger for CoherencyRegister {
+    #[inline(always)]
+    unsafe fn write_address(&mut self, addr: u64) {
+        CoherencyRegister::write_address(self, addr);
+    }
+
+    #[inline(always)]
+    unsafe fn write_control(&mut self, value: u32) {
+        CoherencyRegister::write_control(self, value);
+    }
+}
+
+/// Address first, then a full fence, then the triggering control word
+#[inline(always)]
+unsafe fn issue_ordered<T: OperationTrigger>(regs: &mut T, op_ctrl: u32, addr: u64) {
+    regs.write_address(addr);
+    // Volatile stores alone may be reordered on a weakly-ordered path
+    fence(Ordering::SeqCst);
+    regs.write_control(op_ctrl);
+}
+
 /// MMIO Operations for Cache Coherency
 #[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum CoherencyOp {
     Read = 0x1,
     Write = 0x2,
@@ -68,6 +190,51 @@ pub enum CoherencyOp {
     Flush = 0x4,
 }
 
+impl CoherencyOp {
+    /// Highest core ID the 4-bit control field can address
+    pub const MAX_CORE_ID: u8 = 0xF;
+
+    /// Pack a control word: [3:0] = operation, [7:4] = core_id
+    ///
+    /// Core IDs above `MAX_CORE_ID` would spill into the bits above the
+    /// field, so they are rejected.
+    #[inline(always)]
+    pub const fn encode(self, core_id: u8) -> Result<u32, &'static str> {
+        if core_id > Self::MAX_CORE_ID {
+            return Err("Core ID out of range");
+        }
+
+        Ok((self as u32) | ((core_id as u32) << 4))
+    }
+
+    /// Unpack a control word into its operation and core ID
+    ///
+    /// Fails if the operation bits don't name a `CoherencyOp`.
+    #[inline]
+    pub const fn decode(ctrl: u32) -> Result<(CoherencyOp, u8), &'static str> {
+        let op = match ctrl & 0xF {
+            0x1 => CoherencyOp::Read,
+            0x2 => CoherencyOp::Write,
+            0x3 => CoherencyOp::Invalidate,
+            0x4 => CoherencyOp::Flush,
+            _ => return Err("Unknown coherency operation"),
+        };
+
+        Ok((op, ((ctrl >> 4) & 0xF) as u8))
+    }
+}
+
+/// Handle for an operation started by `MMIOCoherency::mmio_cache_read_async`
+///
+/// The controller runs one operation at a time, so a token is only
+/// meaningful until the next operation is posted.
+#[derive(Debug, PartialEq, Eq)]
+pub struct MmioToken {
+    pub op: CoherencyOp,
+    pub core_id: u8,
+    pub address: u64,
+}
+
 /// Real-Time MMIO Accessor
 pub struct MMIOCoherency {
     reg: *mut CoherencyRegister,
@@ -81,64 +248,225 @@ impl MMIOCoherency {
         }
     }
 
+    /// Initialize MMIO interface, rejecting a misaligned base
+    ///
+    /// `CoherencyRegister` holds a u64, so a base that isn't 8-byte
+    /// aligned would take an alignment-check fault on first access.
+    ///
+    /// # Safety
+    /// `base_addr` must be a mapped coherency register block.
+    pub unsafe fn try_new(base_addr: usize) -> Result<Self, &'static str> {
+        if !base_addr.is_multiple_of(core::mem::align_of::<CoherencyRegister>()) {
+            return Err("Misaligned MMIO base");
+        }
+
+        Ok(Self::new(base_addr))
+    }
+
+    /// Base address of the register block
+    #[inline(always)]
+    pub fn base(&self) -> usize {
+        self.reg as usize
+    }
+
     /// Execute cache read via MMIO (Step 1 & 2 from your flow)
+    ///
+    /// Fails without touching the registers if `core_id` is out of range.
     #[inline]
     pub unsafe fn mmio_cache_read(&mut self, core_id: u8, address: u64) -> Result<(), ()> {
-        let reg = &mut *self.reg;
-
-        // Write operation: read request from specific core
-        let ctrl = (CoherencyOp::Read as u32) | ((core_id as u32) << 4);
-        reg.write_control(ctrl);
-        reg.write_address(address);
+        // Read request from specific core
+        self.post(CoherencyOp::Read, core_id, address).map_err(|_| ())?;
 
         // Spin until operation completes (real-time guarantee)
-        while reg.is_busy() {
-            core::hint::spin_loop();
-        }
+        self.wait_idle();
 
         Ok(())
     }
 
     /// Execute cache write via MMIO (Step 3 from your flow)
+    ///
+    /// Fails without touching the registers if `core_id` is out of range.
     #[inline]
     pub unsafe fn mmio_cache_write(&mut self, core_id: u8, address: u64) -> Result<(), ()> {
-        let reg = &mut *self.reg;
-
         // Write operation: triggers invalidation broadcast
-        let ctrl = (CoherencyOp::Write as u32) | ((core_id as u32) << 4);
-        reg.write_control(ctrl);
-        reg.write_address(address);
+        self.post(CoherencyOp::Write, core_id, address).map_err(|_| ())?;
 
         // Spin until invalidation completes
-        while reg.is_busy() {
-            core::hint::spin_loop();
-        }
+        self.wait_idle();
 
         Ok(())
     }
 
     /// Invalidate cache line (Step 4 from your flow)
+    ///
+    /// Fails without touching the registers if `core_id` is out of range.
     #[inline]
     pub unsafe fn mmio_invalidate(&mut self, core_id: u8, address: u64) -> Result<(), ()> {
-        let reg = &mut *self.reg;
-
-        let ctrl = (CoherencyOp::Invalidate as u32) | ((core_id as u32) << 4);
-        reg.write_control(ctrl);
-        reg.write_address(address);
+        self.post(CoherencyOp::Invalidate, core_id, address).map_err(|_| ())?;
 
         // Real-time spin-wait
-        while reg.is_busy() {
-            core::hint::spin_loop();
-        }
+        self.wait_idle();
 
         Ok(())
     }
 
+    /// Start a cache read without waiting for it to complete
+    ///
+    /// Check completion with `poll`, doing other work in between.
+    ///
+    /// # Safety
+    /// The accessor must point at a mapped coherency register block.
+    #[inline]
+    pub unsafe fn mmio_cache_read_async(
+        &mut self,
+        core_id: u8,
+        address: u64,
+    ) -> Result<MmioToken, &'static str> {
+        self.post(CoherencyOp::Read, core_id, address)?;
+
+        Ok(MmioToken {
+            op: CoherencyOp::Read,
+            core_id,
+            address,
+        })
+    }
+
+    /// Check once whether the operation behind `token` has completed
+    ///
+    /// # Safety
+    /// The accessor must point at a mapped coherency register block.
+    #[inline]
+    pub unsafe fn poll(&self, _token: &MmioToken) -> Poll<Result<(), &'static str>> {
+        let reg = &*self.reg;
+
+        if reg.is_busy() {
+            Poll::Pending
+        } else if reg.has_error() {
+            Poll::Ready(Err("MMIO operation failed"))
+        } else {
+            Poll::Ready(Ok(()))
+        }
+    }
+
     /// Read current cache state from hardware
     #[inline(always)]
     pub unsafe fn read_hw_state(&self) -> u8 {
         (*self.reg).get_hw_state()
     }
+
+    /// Post an operation without waiting for completion
+    #[inline(always)]
+    unsafe fn post(
+        &mut self,
+        op: CoherencyOp,
+        core_id: u8,
+        address: u64,
+    ) -> Result<(), &'static str> {
+        let ctrl = op.encode(core_id)?;
+
+        (*self.reg).issue(ctrl, address);
+
+        Ok(())
+    }
+
+    /// Spin until the controller drains all posted operations
+    #[inline(always)]
+    unsafe fn wait_idle(&self) {
+        while (*self.reg).is_busy() {
+            core::hint::spin_loop();
+        }
+    }
+}
+
+/// Queued coherency operation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WriteCombineEntry {
+    pub address: u64,
+    pub op: CoherencyOp,
+    pub core_id: u8,
+}
+
+/// Write-combining buffer for bulk coherency operations
+///
+/// Queues up to `N` operations and issues them back-to-back on `flush`,
+/// spinning on the busy bit only once after the last one. Operations are
+/// applied in the order they were queued.
+pub struct WriteCombineBuffer<const N: usize> {
+    entries: [WriteCombineEntry; N],
+    len: usize,
+}
+
+impl<const N: usize> WriteCombineBuffer<N> {
+    /// Create an empty buffer
+    pub const fn new() -> Self {
+        Self {
+            entries: [WriteCombineEntry {
+                address: 0,
+                op: CoherencyOp::Read,
+                core_id: 0,
+            }; N],
+            len: 0,
+        }
+    }
+
+    /// Queue an operation (fails if the buffer is full or `core_id` is out of range)
+    pub fn push(&mut self, core_id: u8, address: u64, op: CoherencyOp) -> Result<(), &'static str> {
+        if self.len == N {
+            return Err("Write-combine buffer full");
+        }
+        op.encode(core_id)?;
+
+        self.entries[self.len] = WriteCombineEntry { address, op, core_id };
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Queued operations in issue order
+    pub fn pending(&self) -> &[WriteCombineEntry] {
+        &self.entries[..self.len]
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    /// Issue every queued operation in order and empty the buffer
+    ///
+    /// Returns the number of operations flushed. The busy bit is polled
+    /// once, after the last operation has been posted; an empty buffer
+    /// touches no registers.
+    ///
+    /// # Safety
+    /// `mmio` must point at a mapped coherency register block.
+    pub unsafe fn flush(&mut self, mmio: &mut MMIOCoherency) -> usize {
+        let count = self.len;
+        if count == 0 {
+            return 0;
+        }
+
+        for entry in &self.entries[..count] {
+            // Core IDs were validated by `push`
+            let _ = mmio.post(entry.op, entry.core_id, entry.address);
+        }
+        mmio.wait_idle();
+
+        self.len = 0;
+        count
+    }
+}
+
+impl<const N: usize> Default for WriteCombineBuffer<N> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 unsafe impl Send for MMIOCoherency {}
@@ -226,6 +554,50 @@ mod tests {
         }
     }
 
+    /// Records the trigger writes in the order they arrive
+    struct RecordingTrigger {
+        writes: alloc::vec::Vec<(&'static str, u64)>,
+    }
+
+    impl OperationTrigger for RecordingTrigger {
+        unsafe fn write_address(&mut self, addr: u64) {
+            self.writes.push(("address", addr));
+        }
+
+        unsafe fn write_control(&mut self, value: u32) {
+            self.writes.push(("control", value as u64));
+        }
+    }
+
+    #[test]
+    fn test_issue_writes_address_before_control() {
+        let mut mock = RecordingTrigger {
+            writes: alloc::vec::Vec::new(),
+        };
+        let ctrl = CoherencyOp::Write.encode(2).unwrap();
+
+        unsafe {
+            issue_ordered(&mut mock, ctrl, 0x4000);
+            issue_ordered(&mut mock, ctrl, 0x5000);
+        }
+        assert_eq!(
+            mock.writes,
+            [
+                ("address", 0x4000),
+                ("control", ctrl as u64),
+                ("address", 0x5000),
+                ("control", ctrl as u64),
+            ]
+        );
+
+        let mut reg = create_mock_register();
+        unsafe {
+            reg.issue(ctrl, 0x6000);
+            assert_eq!(reg.read_address(), 0x6000);
+            assert_eq!(reg.read_control(), ctrl);
+        }
+    }
+
     #[test]
     fn test_coherency_op_enum_values() {
         assert_eq!(CoherencyOp::Read as u8, 0x1);
@@ -234,6 +606,31 @@ mod tests {
         assert_eq!(CoherencyOp::Flush as u8, 0x4);
     }
 
+    #[test]
+    fn test_mmio_layout_builder() {
+        let layout = MmioLayout::default();
+        assert_eq!(layout, MmioLayout::DEFAULT);
+        assert_eq!(layout.l3_base, L3_CACHE_BASE);
+        assert_eq!(layout.coherency_base, COHERENCY_CTL_BASE);
+        assert_eq!(layout.core_coherency_base(3), COHERENCY_CTL_BASE + 0x3000);
+
+        let custom = layout
+            .with_l3_base(0x1000_0000)
+            .with_coherency_base(0x2000_0000)
+            .with_shadow_base(0x3000_0000)
+            .with_fuse_base(0x4000_0000);
+        assert_eq!(
+            custom,
+            MmioLayout {
+                l3_base: 0x1000_0000,
+                coherency_base: 0x2000_0000,
+                shadow_base: 0x3000_0000,
+                fuse_base: 0x4000_0000,
+            }
+        );
+        assert_eq!(custom.core_coherency_base(15), 0x2000_F000);
+    }
+
     #[test]
     fn test_mmio_coherency_initialization() {
         let reg = create_mock_register();
@@ -248,6 +645,29 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_mmio_coherency_try_new_alignment() {
+        let reg = create_mock_register();
+        let reg_ptr = Box::into_raw(reg);
+        let base = reg_ptr as usize;
+
+        unsafe {
+            let mmio = MMIOCoherency::try_new(base).unwrap();
+            assert_eq!(mmio.base(), base);
+            assert!(MMIOCoherency::try_new(COHERENCY_CTL_BASE).is_ok());
+
+            for offset in [1, 4, 7] {
+                assert_eq!(
+                    MMIOCoherency::try_new(base + offset).err(),
+                    Some("Misaligned MMIO base")
+                );
+            }
+
+            // Cleanup
+            let _ = Box::from_raw(reg_ptr);
+        }
+    }
+
     #[test]
     fn test_mmio_cache_read_operation() {
         let reg = create_mock_register();
@@ -350,10 +770,7 @@ mod tests {
     #[test]
     fn test_mmio_control_register_format() {
         // Test control register packing format
-        let core_id = 5u8;
-        let operation = CoherencyOp::Write as u32;
-
-        let ctrl = operation | ((core_id as u32) << 4);
+        let ctrl = CoherencyOp::Write.encode(5).unwrap();
 
         // Extract operation (bits [3:0])
         let extracted_op = ctrl & 0xF;
@@ -364,6 +781,56 @@ mod tests {
         assert_eq!(extracted_core, 5);
     }
 
+    #[test]
+    fn test_coherency_op_encode_decode_round_trip() {
+        let ops = [
+            CoherencyOp::Read,
+            CoherencyOp::Write,
+            CoherencyOp::Invalidate,
+            CoherencyOp::Flush,
+        ];
+
+        for op in ops {
+            for core_id in 0..=CoherencyOp::MAX_CORE_ID {
+                let ctrl = op.encode(core_id).unwrap();
+                assert_eq!(ctrl >> 8, 0);
+                assert_eq!(CoherencyOp::decode(ctrl), Ok((op, core_id)));
+            }
+        }
+
+        // Unknown operation bits are rejected
+        assert_eq!(CoherencyOp::decode(0x50), Err("Unknown coherency operation"));
+        assert_eq!(CoherencyOp::decode(0x0F), Err("Unknown coherency operation"));
+    }
+
+    #[test]
+    fn test_coherency_op_encode_rejects_high_core_id() {
+        assert_eq!(CoherencyOp::Read.encode(16), Err("Core ID out of range"));
+        assert_eq!(CoherencyOp::Flush.encode(u8::MAX), Err("Core ID out of range"));
+
+        let reg = create_mock_register();
+        let reg_ptr = Box::into_raw(reg);
+
+        unsafe {
+            let mut mmio = MMIOCoherency::new(reg_ptr as usize);
+
+            // Rejected before any register is written
+            assert_eq!(mmio.mmio_cache_read(16, 0x1000), Err(()));
+            assert_eq!(mmio.mmio_cache_write(0x20, 0x1000), Err(()));
+            assert_eq!(mmio.mmio_invalidate(0xFF, 0x1000), Err(()));
+            assert_eq!(mmio.mmio_cache_read_async(16, 0x1000), Err("Core ID out of range"));
+            assert_eq!((*reg_ptr).read_control(), 0);
+            assert_eq!((*reg_ptr).read_address(), 0);
+
+            let mut wcb = WriteCombineBuffer::<2>::new();
+            assert_eq!(wcb.push(16, 0x1000, CoherencyOp::Write), Err("Core ID out of range"));
+            assert!(wcb.is_empty());
+
+            // Cleanup
+            let _ = Box::from_raw(reg_ptr);
+        }
+    }
+
     #[test]
     fn test_coherency_register_volatile_semantics() {
         let mut reg = create_mock_register();
@@ -424,4 +891,116 @@ mod tests {
             let _ = Box::from_raw(reg_ptr);
         }
     }
+
+    #[test]
+    fn test_write_combine_flush_applies_in_order() {
+        let reg = create_mock_register();
+        let reg_ptr = Box::into_raw(reg);
+
+        unsafe {
+            let mut mmio = MMIOCoherency::new(reg_ptr as usize);
+            let mut wcb = WriteCombineBuffer::<4>::new();
+
+            wcb.push(1, 0x1000, CoherencyOp::Write).unwrap();
+            wcb.push(2, 0x2000, CoherencyOp::Write).unwrap();
+            wcb.push(6, 0x3000, CoherencyOp::Write).unwrap();
+            assert_eq!(wcb.len(), 3);
+            assert_eq!(wcb.pending()[0].address, 0x1000);
+
+            assert_eq!(wcb.flush(&mut mmio), 3);
+            assert!(wcb.is_empty());
+
+            // Registers hold the last entry issued
+            let expected_ctrl = (CoherencyOp::Write as u32) | (6u32 << 4);
+            assert_eq!((*reg_ptr).read_control(), expected_ctrl);
+            assert_eq!((*reg_ptr).read_address(), 0x3000);
+
+            // Cleanup
+            let _ = Box::from_raw(reg_ptr);
+        }
+    }
+
+    #[test]
+    fn test_write_combine_full_and_empty_flush() {
+        let reg = create_mock_register();
+        let reg_ptr = Box::into_raw(reg);
+
+        unsafe {
+            let mut mmio = MMIOCoherency::new(reg_ptr as usize);
+            let mut wcb = WriteCombineBuffer::<2>::new();
+
+            // Empty flush leaves the registers alone
+            assert_eq!(wcb.flush(&mut mmio), 0);
+            assert_eq!((*reg_ptr).read_control(), 0);
+
+            wcb.push(0, 0x1000, CoherencyOp::Write).unwrap();
+            wcb.push(0, 0x2000, CoherencyOp::Invalidate).unwrap();
+            assert!(wcb.is_full());
+            assert_eq!(wcb.push(0, 0x3000, CoherencyOp::Write), Err("Write-combine buffer full"));
+
+            assert_eq!(wcb.flush(&mut mmio), 2);
+            assert_eq!((*reg_ptr).read_control(), CoherencyOp::Invalidate as u32);
+            assert_eq!((*reg_ptr).read_address(), 0x2000);
+
+            // Cleanup
+            let _ = Box::from_raw(reg_ptr);
+        }
+    }
+
+    #[test]
+    fn test_mmio_cache_read_async_polls_until_idle() {
+        let reg = create_mock_register();
+        let reg_ptr = Box::into_raw(reg);
+
+        unsafe {
+            let mut mmio = MMIOCoherency::new(reg_ptr as usize);
+            let token = mmio.mmio_cache_read_async(3, 0x8000).unwrap();
+
+            assert_eq!(token.op, CoherencyOp::Read);
+            assert_eq!((*reg_ptr).read_control(), (CoherencyOp::Read as u32) | (3u32 << 4));
+            assert_eq!((*reg_ptr).read_address(), 0x8000);
+
+            // Simulated controller stays busy for three polls
+            (*reg_ptr).status = 0x1;
+            let mut polls = 0;
+            let result = loop {
+                match mmio.poll(&token) {
+                    Poll::Ready(result) => break result,
+                    Poll::Pending => {
+                        polls += 1;
+                        if polls == 3 {
+                            (*reg_ptr).status = 0;
+                        }
+                    }
+                }
+            };
+
+            assert_eq!(polls, 3);
+            assert_eq!(result, Ok(()));
+
+            // Cleanup
+            let _ = Box::from_raw(reg_ptr);
+        }
+    }
+
+    #[test]
+    fn test_mmio_poll_reports_error() {
+        let reg = create_mock_register();
+        let reg_ptr = Box::into_raw(reg);
+
+        unsafe {
+            let mut mmio = MMIOCoherency::new(reg_ptr as usize);
+            let token = mmio.mmio_cache_read_async(0, 0x1000).unwrap();
+
+            // Busy wins over the error bit until the operation finishes
+            (*reg_ptr).status = 0x3;
+            assert_eq!(mmio.poll(&token), Poll::Pending);
+
+            (*reg_ptr).status = 0x2;
+            assert_eq!(mmio.poll(&token), Poll::Ready(Err("MMIO operation failed")));
+
+            // Cleanup
+            let _ = Box::from_raw(reg_ptr);
+        }
+    }
 }
@@ -2,40 +2,90 @@
 /// Demonstrates the complete 5-step cache coherency flow
 
 use crate::cache_coherency::{CacheLine, CacheState, L3Directory};
-use crate::mmio::{MMIOCoherency, COHERENCY_CTL_BASE};
-use crate::state_machine::{CacheEvent, CoherencyStateMachine};
+use crate::mmio::{MMIOCoherency, MmioLayout};
+use crate::state_machine::{CacheEvent, CoherencyStateMachine, StateTransitionTable};
 use alloc::boxed::Box;
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+use core::sync::atomic::{AtomicU64, Ordering};
+
+/// Default L1 size in lines used by the runtime model
+pub const L1_DEFAULT_LINES: usize = 64;
+
+/// Number of cores `CoherencyRuntime` models (ids `0..RUNTIME_CORES`)
+///
+/// `CoherencySnapshot::core_mask` holds one bit per core, so this can't
+/// exceed 8.
+pub const RUNTIME_CORES: usize = 8;
 
 /// Per-Core Cache Controller
-pub struct CoreCacheController {
+///
+/// `CoherencyRuntime` keeps every core at `L1_DEFAULT_LINES`: its
+/// invariant check and snapshots match L1 copies across cores by index,
+/// which only works when all cores share one L1 size.
+pub struct CoreCacheController<const LINES: usize = L1_DEFAULT_LINES> {
     core_id: u8,
-    l1_cache: [CacheLine; 64],
+    l1_cache: [CacheLine; LINES],
     mmio: MMIOCoherency,
     state_machine: CoherencyStateMachine,
+    /// Reads served from a valid L1 line
+    hits: AtomicU64,
+    /// Reads that fetched the line via MMIO
+    misses: AtomicU64,
 }
 
 impl CoreCacheController {
-    /// Initialize core cache controller
+    /// Initialize core cache controller with the default L1 size
     pub unsafe fn new(core_id: u8) -> Self {
+        Self::with_lines(core_id)
+    }
+}
+
+impl<const LINES: usize> CoreCacheController<LINES> {
+    /// Initialize core cache controller with `LINES` L1 lines
+    pub unsafe fn with_lines(core_id: u8) -> Self {
+        Self::with_layout(core_id, &MmioLayout::DEFAULT)
+    }
+
+    /// Initialize core cache controller on `layout`'s coherency registers
+    ///
+    /// # Safety
+    /// The core's coherency register block in `layout` must be mapped
+    /// before the controller issues MMIO.
+    pub unsafe fn with_layout(core_id: u8, layout: &MmioLayout) -> Self {
         const INIT: CacheLine = CacheLine::new();
         Self {
             core_id,
-            l1_cache: [INIT; 64],
-            mmio: MMIOCoherency::new(COHERENCY_CTL_BASE + (core_id as usize * 0x1000)),
+            l1_cache: [INIT; LINES],
+            mmio: MMIOCoherency::new(layout.core_coherency_base(core_id)),
             state_machine: CoherencyStateMachine::new(),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
         }
     }
 
+    /// L1 line index for an address
+    #[inline(always)]
+    fn line_index(address: u64) -> usize {
+        ((address >> 6) % LINES as u64) as usize
+    }
+
+    /// Current state of the L1 line holding `address`
+    #[inline(always)]
+    fn line_state(&self, address: u64) -> CacheState {
+        self.l1_cache[Self::line_index(address)].get_state()
+    }
+
     /// Step 1 & 2: Core reads data (becomes Shared)
     #[inline]
     pub unsafe fn read(&mut self, address: u64) -> Result<u64, ()> {
-        let cache_idx = (address >> 6) % 64;
-        let line = &self.l1_cache[cache_idx as usize];
+        let line = &self.l1_cache[Self::line_index(address)];
 
         match line.get_state() {
             CacheState::Invalid => {
                 // Trigger MMIO read to fetch from L3
                 self.mmio.mmio_cache_read(self.core_id, address)?;
+                self.misses.fetch_add(1, Ordering::Relaxed);
 
                 // Transition Invalid → Shared
                 line.force_state(CacheState::Shared);
@@ -44,16 +94,51 @@ impl CoreCacheController {
             }
             CacheState::Shared | CacheState::Exclusive | CacheState::Modified => {
                 // Cache hit - no state change needed
+                self.hits.fetch_add(1, Ordering::Relaxed);
                 Ok(address)
             }
         }
     }
 
+    /// Warm a line into L1 without returning data
+    ///
+    /// An Invalid line is fetched via MMIO and becomes Shared, so the next
+    /// `read` hits. Valid lines are left untouched.
+    #[inline]
+    pub unsafe fn prefetch(&mut self, address: u64) -> Result<(), ()> {
+        let line = &self.l1_cache[Self::line_index(address)];
+
+        if !line.get_state().is_valid() {
+            self.mmio.mmio_cache_read(self.core_id, address)?;
+            line.force_state(CacheState::Shared);
+        }
+
+        Ok(())
+    }
+
+    /// Get read statistics as (hits, misses)
+    pub fn stats(&self) -> (u64, u64) {
+        (
+            self.hits.load(Ordering::Relaxed),
+            self.misses.load(Ordering::Relaxed),
+        )
+    }
+
+    /// Fraction of reads that hit in L1 (0.0 before any read)
+    pub fn hit_rate(&self) -> f64 {
+        let (hits, misses) = self.stats();
+        let total = hits + misses;
+        if total == 0 {
+            return 0.0;
+        }
+
+        hits as f64 / total as f64
+    }
+
     /// Step 3: Core writes data (invalidates other cores)
     #[inline]
     pub unsafe fn write(&mut self, address: u64, _value: u64) -> Result<(), ()> {
-        let cache_idx = (address >> 6) % 64;
-        let line = &self.l1_cache[cache_idx as usize];
+        let line = &self.l1_cache[Self::line_index(address)];
 
         let current_state = line.get_state();
 
@@ -72,62 +157,465 @@ impl CoreCacheController {
     /// Step 4: Handle invalidation from another core's write
     #[inline]
     pub unsafe fn handle_invalidation(&mut self, address: u64) {
-        let cache_idx = (address >> 6) % 64;
-        let line = &self.l1_cache[cache_idx as usize];
+        let line = &self.l1_cache[Self::line_index(address)];
 
         // Transition to Invalid when another core writes
         line.force_state(CacheState::Invalid);
     }
+
+    /// Handle another core reading a line this core may hold
+    ///
+    /// Modified/Exclusive lines downgrade to Shared; a Modified line's data
+    /// is written back through L3 as part of the remote fetch.
+    ///
+    /// # Safety
+    /// Issues no MMIO; unsafe to match the other per-core coherency operations.
+    #[inline]
+    pub unsafe fn handle_remote_read(&mut self, address: u64) {
+        let line = &self.l1_cache[Self::line_index(address)];
+
+        let current_state = line.get_state();
+        if !current_state.is_valid() {
+            return;
+        }
+
+        let next_state = self.state_machine.transition(current_state, CacheEvent::RemoteRead);
+        line.force_state(next_state);
+    }
+
+    /// Write back a dirty line but keep it cached (Modified → Exclusive)
+    ///
+    /// Returns true if the line held dirty data to write back.
+    #[inline]
+    pub fn flush(&mut self, address: u64) -> bool {
+        self.apply_writeback_event(address, CacheEvent::Flush)
+    }
+
+    /// Drop a line from L1 (any → Invalid)
+    ///
+    /// Returns true if the line held dirty data to write back.
+    #[inline]
+    pub fn evict(&mut self, address: u64) -> bool {
+        self.apply_writeback_event(address, CacheEvent::Evict)
+    }
+
+    /// Run `event` through the transition table, reporting writeback
+    #[inline]
+    fn apply_writeback_event(&mut self, address: u64, event: CacheEvent) -> bool {
+        let line = &self.l1_cache[Self::line_index(address)];
+
+        let current_state = line.get_state();
+        let writeback = StateTransitionTable::needs_writeback(current_state, event);
+        line.force_state(self.state_machine.transition(current_state, event));
+
+        writeback
+    }
+}
+
+/// Captured coherency state for checkpoint-restart
+///
+/// Plain `repr(C)` data so it can be persisted or handed across FFI.
+/// States are stored as raw `CacheState` values.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct CoherencySnapshot {
+    /// Bit N set if core N was initialized
+    pub core_mask: u8,
+    /// Per-core L1 line states
+    pub l1_states: [[u8; L1_DEFAULT_LINES]; RUNTIME_CORES],
+    /// L3 directory line tags
+    pub l3_tags: [u64; 1024],
+    /// L3 directory line states
+    pub l3_states: [u8; 1024],
+    /// L3 directory line owners
+    pub l3_owners: [u8; 1024],
+    /// L3 directory sharer counts
+    pub l3_ref_counts: [u8; 1024],
+}
+
+/// One recorded L1 transition
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TraceEntry {
+    pub core_id: u8,
+    pub address: u64,
+    pub event: CacheEvent,
+    pub from: CacheState,
+    pub to: CacheState,
+}
+
+/// Bounded transition trace; the oldest entry is dropped when full
+struct TraceLog {
+    entries: VecDeque<TraceEntry>,
+    capacity: usize,
+}
+
+impl TraceLog {
+    /// Append a transition; peers that never held the line are skipped
+    fn record(
+        &mut self,
+        core_id: usize,
+        address: u64,
+        event: CacheEvent,
+        from: CacheState,
+        to: CacheState,
+    ) {
+        if from == CacheState::Invalid && to == CacheState::Invalid {
+            return;
+        }
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(TraceEntry {
+            core_id: core_id as u8,
+            address,
+            event,
+            from,
+            to,
+        });
+    }
 }
 
 /// Multi-Core Coherency Runtime
 /// Demonstrates the complete 5-step flow from your example
 pub struct CoherencyRuntime {
-    cores: [Option<CoreCacheController>; 8],
+    cores: [Option<CoreCacheController>; RUNTIME_CORES],
     l3_directory: L3Directory,
+    /// Transition trace, `None` unless tracing is enabled
+    trace: Option<TraceLog>,
+    /// MMIO bases cores are initialized with
+    layout: MmioLayout,
 }
 
 impl CoherencyRuntime {
     pub const fn new() -> Self {
+        Self::with_layout(MmioLayout::DEFAULT)
+    }
+
+    /// Create a runtime whose cores use `layout`'s MMIO bases
+    pub const fn with_layout(layout: MmioLayout) -> Self {
+        const NO_CORE: Option<CoreCacheController> = None;
         Self {
-            cores: [None, None, None, None, None, None, None, None],
+            cores: [NO_CORE; RUNTIME_CORES],
             l3_directory: L3Directory::new(),
+            trace: None,
+            layout,
+        }
+    }
+
+    /// Record L1 transitions of `access` and the coherency flow
+    ///
+    /// Keeps the last `capacity` entries; 0 turns tracing off. Re-enabling
+    /// discards anything not yet drained.
+    pub fn enable_tracing(&mut self, capacity: usize) {
+        self.trace = if capacity == 0 {
+            None
+        } else {
+            Some(TraceLog {
+                entries: VecDeque::with_capacity(capacity),
+                capacity,
+            })
+        };
+    }
+
+    /// Take the recorded transitions, oldest first
+    pub fn drain_trace(&mut self) -> Vec<TraceEntry> {
+        match self.trace.as_mut() {
+            Some(log) => log.entries.drain(..).collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// L1 state of `address` on a core (Invalid if the core is absent)
+    fn core_line_state(&self, core_index: usize, address: u64) -> CacheState {
+        match self.cores[core_index] {
+            Some(ref core) => core.line_state(address),
+            None => CacheState::Invalid,
+        }
+    }
+
+    /// Trace `event` on a core that was in `from` before it
+    fn trace_event(
+        &mut self,
+        core_index: usize,
+        address: u64,
+        event: CacheEvent,
+        from: CacheState,
+    ) {
+        if self.trace.is_none() || self.cores[core_index].is_none() {
+            return;
+        }
+
+        let to = self.core_line_state(core_index, address);
+        if let Some(log) = self.trace.as_mut() {
+            log.record(core_index, address, event, from, to);
         }
     }
 
     /// Initialize core
     pub unsafe fn init_core(&mut self, core_id: u8) {
-        if (core_id as usize) < 8 {
-            self.cores[core_id as usize] = Some(CoreCacheController::new(core_id));
+        if (core_id as usize) < RUNTIME_CORES {
+            let controller = CoreCacheController::with_layout(core_id, &self.layout);
+            self.cores[core_id as usize] = Some(controller);
         }
     }
 
+    /// Capture L1 states of every initialized core and the L3 directory
+    pub fn snapshot(&self) -> CoherencySnapshot {
+        let mut snap = CoherencySnapshot {
+            core_mask: 0,
+            l1_states: [[CacheState::Invalid as u8; L1_DEFAULT_LINES]; RUNTIME_CORES],
+            l3_tags: [0; 1024],
+            l3_states: [0; 1024],
+            l3_owners: [0; 1024],
+            l3_ref_counts: [0; 1024],
+        };
+
+        for (core_id, core) in self.cores.iter().enumerate() {
+            if let Some(controller) = core {
+                snap.core_mask |= 1 << core_id;
+                for (i, line) in controller.l1_cache.iter().enumerate() {
+                    snap.l1_states[core_id][i] = line.get_state() as u8;
+                }
+            }
+        }
+
+        self.l3_directory.export_lines(
+            &mut snap.l3_tags,
+            &mut snap.l3_states,
+            &mut snap.l3_owners,
+            &mut snap.l3_ref_counts,
+        );
+
+        snap
+    }
+
+    /// Reload state captured by `snapshot`
+    ///
+    /// L1 states are only restored for cores that are initialized in both
+    /// the runtime and the snapshot.
+    pub fn restore(&mut self, snap: &CoherencySnapshot) {
+        for (core_id, core) in self.cores.iter_mut().enumerate() {
+            if let Some(controller) = core {
+                if snap.core_mask & (1 << core_id) == 0 {
+                    continue;
+                }
+                for (i, line) in controller.l1_cache.iter().enumerate() {
+                    line.force_state(CacheState::from(snap.l1_states[core_id][i]));
+                }
+            }
+        }
+
+        self.l3_directory.import_lines(
+            &snap.l3_tags,
+            &snap.l3_states,
+            &snap.l3_owners,
+            &snap.l3_ref_counts,
+        );
+    }
+
+    /// Check the global MESI invariants across all cores and the L3 directory
+    ///
+    /// L1 lines carry no tag, so copies are matched by L1 index. Returns a
+    /// description of the first violation found.
+    pub fn check_invariants(&self) -> Result<(), &'static str> {
+        // At most one M/E holder, and never alongside a sharer
+        for index in 0..L1_DEFAULT_LINES {
+            let mut owners = 0;
+            let mut sharers = 0;
+
+            for controller in self.cores.iter().flatten() {
+                match controller.l1_cache[index].get_state() {
+                    CacheState::Modified | CacheState::Exclusive => owners += 1,
+                    CacheState::Shared => sharers += 1,
+                    CacheState::Invalid => {}
+                }
+            }
+
+            if owners > 1 {
+                return Err("Multiple cores hold the same line Modified/Exclusive");
+            }
+            if owners == 1 && sharers > 0 {
+                return Err("Line is Modified/Exclusive in one core and Shared in another");
+            }
+        }
+
+        // L1 copies must agree with the directory
+        for index in 0..1024 {
+            let line = match self.l3_directory.line(index) {
+                Some(line) => line,
+                None => break,
+            };
+
+            match line.get_state() {
+                CacheState::Modified | CacheState::Exclusive => {
+                    let owner = line.get_owner() as usize;
+                    if owner >= self.cores.len() {
+                        return Err("L3 line is Modified/Exclusive without an owner core");
+                    }
+
+                    for (core_id, core) in self.cores.iter().enumerate() {
+                        if let Some(controller) = core {
+                            let state = controller.l1_cache[index % L1_DEFAULT_LINES].get_state();
+                            if core_id != owner && state.is_valid() {
+                                return Err("Core holds a copy of a line owned by another core in L3");
+                            }
+                        }
+                    }
+                }
+                CacheState::Shared => {
+                    if line.get_ref_count() == 0 {
+                        return Err("L3 line is Shared with no sharers");
+                    }
+                }
+                CacheState::Invalid => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Write a human-readable state dump (per-core stats and L3 directory)
+    ///
+    /// Intended for post-mortem output, e.g. from a panic handler, so it
+    /// does not allocate.
+    pub fn dump_state(&self, out: &mut dyn core::fmt::Write) -> core::fmt::Result {
+        writeln!(out, "Coherency:")?;
+        for (core_id, core) in self.cores.iter().enumerate() {
+            if let Some(controller) = core {
+                let (hits, misses) = controller.stats();
+                writeln!(out, "  Core {}: {} hits, {} misses", core_id, hits, misses)?;
+            }
+        }
+
+        let directory = &self.l3_directory;
+        let histogram = directory.state_histogram();
+        writeln!(
+            out,
+            "  L3: {} valid lines (M={} E={} S={})",
+            directory.occupancy(),
+            histogram[CacheState::Modified as usize],
+            histogram[CacheState::Exclusive as usize],
+            histogram[CacheState::Shared as usize]
+        )?;
+        writeln!(
+            out,
+            "  L3: {} broadcasts, {} writebacks, {} tag conflicts",
+            directory.broadcast_count(),
+            directory.writeback_count(),
+            directory.tag_conflicts()
+        )?;
+        match self.check_invariants() {
+            Ok(()) => writeln!(out, "  Invariants: ok"),
+            Err(violation) => writeln!(out, "  Invariants: VIOLATED ({})", violation),
+        }
+    }
+
+    /// Perform a read or write on one core and notify every peer core
+    ///
+    /// Peers downgrade to Shared before a read is served and are
+    /// invalidated after a write. Only L1 states are updated. When tracing,
+    /// the accessing core and every peer holding the line are recorded.
+    ///
+    /// # Safety
+    /// The core's MMIO coherency registers must be mapped.
+    pub unsafe fn access(
+        &mut self,
+        core_id: u8,
+        address: u64,
+        is_write: bool,
+    ) -> Result<(), &'static str> {
+        let core_index = core_id as usize;
+        if core_index >= self.cores.len() || self.cores[core_index].is_none() {
+            return Err("Core not initialized");
+        }
+
+        if !is_write {
+            for (peer_id, peer) in self.cores.iter_mut().enumerate() {
+                if let Some(peer) = peer {
+                    if peer_id != core_index {
+                        let from = peer.line_state(address);
+                        peer.handle_remote_read(address);
+                        if let Some(log) = self.trace.as_mut() {
+                            let to = peer.line_state(address);
+                            log.record(peer_id, address, CacheEvent::RemoteRead, from, to);
+                        }
+                    }
+                }
+            }
+        }
+
+        let from = self.core_line_state(core_index, address);
+        if let Some(ref mut core) = self.cores[core_index] {
+            let result = if is_write {
+                core.write(address, 0)
+            } else {
+                core.read(address).map(|_| ())
+            };
+            result.map_err(|_| "MMIO operation failed")?;
+        }
+        let event = if is_write {
+            CacheEvent::LocalWrite
+        } else {
+            CacheEvent::LocalRead
+        };
+        self.trace_event(core_index, address, event, from);
+
+        if is_write {
+            for (peer_id, peer) in self.cores.iter_mut().enumerate() {
+                if let Some(peer) = peer {
+                    if peer_id != core_index {
+                        let from = peer.line_state(address);
+                        peer.handle_invalidation(address);
+                        if let Some(log) = self.trace.as_mut() {
+                            let to = peer.line_state(address);
+                            log.record(peer_id, address, CacheEvent::RemoteWrite, from, to);
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     /// Execute the complete 5-step coherency flow
     pub unsafe fn execute_coherency_flow(&mut self, address: u64) -> Result<(), ()> {
         // Step 1: Core 1 reads data → stored in L1, L2, L3 (Shared state)
+        let from = self.core_line_state(1, address);
         if let Some(ref mut core1) = self.cores[1] {
             core1.read(address)?;
         }
+        self.trace_event(1, address, CacheEvent::LocalRead, from);
 
         // Step 2: Core 2 reads same data → also Shared across all levels
+        let from = self.core_line_state(2, address);
         if let Some(ref mut core2) = self.cores[2] {
             core2.read(address)?;
         }
+        self.trace_event(2, address, CacheEvent::LocalRead, from);
 
         // Step 3: Core 1 writes to data → invalidates Core 2's copy via L3
-        if let Some(ref mut core1) = self.cores[1] {
-            core1.write(address, 0xDEADBEEF)?;
+        if self.cores[1].is_some() {
+            let from = self.core_line_state(1, address);
+            if let Some(ref mut core1) = self.cores[1] {
+                core1.write(address, 0xDEADBEEF)?;
+            }
+            self.trace_event(1, address, CacheEvent::LocalWrite, from);
 
             // Step 4: Core 2's cache line marked Invalid
+            let from = self.core_line_state(2, address);
             if let Some(ref mut core2) = self.cores[2] {
                 core2.handle_invalidation(address);
             }
+            self.trace_event(2, address, CacheEvent::RemoteWrite, from);
         }
 
         // Step 5: Core 2 reads again → fetches from Core 1 or L3
+        let from = self.core_line_state(2, address);
         if let Some(ref mut core2) = self.cores[2] {
             core2.read(address)?;
         }
+        self.trace_event(2, address, CacheEvent::LocalRead, from);
 
         Ok(())
     }
@@ -139,7 +627,7 @@ pub unsafe extern "C" fn mmio_coherency_init() -> *mut CoherencyRuntime {
     let runtime = Box::leak(Box::new(CoherencyRuntime::new()));
 
     // Initialize all cores
-    for core_id in 0..8 {
+    for core_id in 0..RUNTIME_CORES as u8 {
         runtime.init_core(core_id);
     }
 
@@ -168,12 +656,31 @@ mod tests {
     extern crate alloc;
     use alloc::boxed::Box;
     use crate::cache_coherency::CacheState;
+    use crate::mmio::CoherencyRegister;
 
     /// Helper: Create CoherencyRuntime in memory
     fn create_mock_runtime() -> Box<CoherencyRuntime> {
         Box::new(CoherencyRuntime::new())
     }
 
+    /// Helper: Create a zeroed (never busy) MMIO register block
+    fn create_mock_register() -> Box<CoherencyRegister> {
+        Box::new(CoherencyRegister {
+            control: 0,
+            address: 0,
+            status: 0,
+            data: [0; 16],
+        })
+    }
+
+    /// Helper: Point a controller at a mock register instead of the layout base
+    unsafe fn attach_mock_mmio<const LINES: usize>(
+        controller: &mut CoreCacheController<LINES>,
+        reg: &mut CoherencyRegister,
+    ) {
+        controller.mmio = MMIOCoherency::new(reg as *mut CoherencyRegister as usize);
+    }
+
     #[test]
     fn test_core_cache_controller_initialization() {
         unsafe {
@@ -213,6 +720,103 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_core_cache_controller_custom_line_count() {
+        unsafe {
+            let mut controller = CoreCacheController::<32>::with_lines(2);
+            assert_eq!(controller.l1_cache.len(), 32);
+
+            // Line 32 wraps to index 0, line 33 to index 1
+            assert_eq!(CoreCacheController::<32>::line_index(0x0000), 0);
+            assert_eq!(CoreCacheController::<32>::line_index(32 * 64), 0);
+            assert_eq!(CoreCacheController::<32>::line_index(33 * 64 + 0x3F), 1);
+
+            // Invalidating a wrapped address hits the aliased line
+            controller.l1_cache[0].force_state(CacheState::Shared);
+            controller.handle_invalidation(32 * 64);
+            assert_eq!(controller.l1_cache[0].get_state(), CacheState::Invalid);
+
+            // Default controller does not wrap at 32
+            assert_eq!(CoreCacheController::<L1_DEFAULT_LINES>::line_index(32 * 64), 32);
+        }
+    }
+
+    #[test]
+    fn test_core_cache_controller_hit_miss_stats() {
+        unsafe {
+            let mut reg = create_mock_register();
+            let mut controller = CoreCacheController::new(1);
+            attach_mock_mmio(&mut controller, &mut reg);
+
+            assert_eq!(controller.stats(), (0, 0));
+            assert_eq!(controller.hit_rate(), 0.0);
+
+            // Cold read misses and goes to MMIO
+            controller.read(0x4000).unwrap();
+            assert_eq!(controller.stats(), (0, 1));
+            assert_eq!(reg.read_address(), 0x4000);
+
+            // Warm read to the same line hits
+            controller.read(0x4008).unwrap();
+            assert_eq!(controller.stats(), (1, 1));
+            assert_eq!(controller.hit_rate(), 0.5);
+
+            controller.read(0x4010).unwrap();
+            controller.read(0x4018).unwrap();
+            assert_eq!(controller.stats(), (3, 1));
+            assert_eq!(controller.hit_rate(), 0.75);
+        }
+    }
+
+    #[test]
+    fn test_core_cache_controller_prefetch() {
+        unsafe {
+            let mut reg = create_mock_register();
+            let mut controller = CoreCacheController::new(4);
+            attach_mock_mmio(&mut controller, &mut reg);
+
+            let address = 0x6040;
+            let idx = CoreCacheController::<L1_DEFAULT_LINES>::line_index(address);
+
+            controller.prefetch(address).unwrap();
+            assert_eq!(controller.l1_cache[idx].get_state(), CacheState::Shared);
+            assert_eq!(reg.read_address(), address);
+            assert_eq!(controller.stats(), (0, 0));
+
+            // The next read is a hit
+            controller.read(address).unwrap();
+            assert_eq!(controller.stats(), (1, 0));
+
+            // Prefetching a Modified line leaves it Modified
+            controller.l1_cache[idx].force_state(CacheState::Modified);
+            controller.prefetch(address).unwrap();
+            assert_eq!(controller.l1_cache[idx].get_state(), CacheState::Modified);
+        }
+    }
+
+    #[test]
+    fn test_controller_custom_layout() {
+        let mut reg = create_mock_register();
+        let base = &mut *reg as *mut CoherencyRegister as usize;
+        let layout = MmioLayout::DEFAULT.with_coherency_base(base);
+
+        unsafe {
+            let controller = CoreCacheController::<L1_DEFAULT_LINES>::with_layout(0, &layout);
+            assert_eq!(controller.mmio.base(), base);
+
+            // The runtime hands its layout to every core it initializes
+            let mut runtime = Box::new(CoherencyRuntime::with_layout(layout));
+            runtime.init_core(0);
+            runtime.init_core(2);
+            assert_eq!(runtime.cores[0].as_ref().unwrap().mmio.base(), base);
+            assert_eq!(runtime.cores[2].as_ref().unwrap().mmio.base(), base + 0x2000);
+
+            // Core 0 talks to the mock block without any patching
+            runtime.access(0, 0x4000, false).unwrap();
+            assert_eq!(reg.read_address(), 0x4000);
+        }
+    }
+
     #[test]
     fn test_core_cache_handle_invalidation() {
         unsafe {
@@ -233,6 +837,34 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_core_cache_flush_and_evict() {
+        unsafe {
+            let mut controller = CoreCacheController::new(2);
+            let index = |address: u64| ((address >> 6) % 64) as usize;
+
+            for (state, flushed, dirty) in [
+                (CacheState::Modified, CacheState::Exclusive, true),
+                (CacheState::Exclusive, CacheState::Exclusive, false),
+                (CacheState::Shared, CacheState::Shared, false),
+                (CacheState::Invalid, CacheState::Invalid, false),
+            ] {
+                let address = 0x7000;
+                controller.l1_cache[index(address)].force_state(state);
+                assert_eq!(controller.flush(address), dirty);
+                assert_eq!(controller.l1_cache[index(address)].get_state(), flushed);
+
+                // A flushed line is clean, so evicting it needs no writeback
+                assert!(!controller.evict(address));
+                assert_eq!(controller.l1_cache[index(address)].get_state(), CacheState::Invalid);
+
+                controller.l1_cache[index(address)].force_state(state);
+                assert_eq!(controller.evict(address), dirty);
+                assert_eq!(controller.l1_cache[index(address)].get_state(), CacheState::Invalid);
+            }
+        }
+    }
+
     #[test]
     fn test_core_cache_handle_multiple_invalidations() {
         unsafe {
@@ -448,6 +1080,309 @@ mod tests {
         assert!(runtime.cores.iter().all(|c| c.is_none()));
     }
 
+    #[test]
+    fn test_snapshot_restore_round_trip() {
+        unsafe {
+            let mut runtime = create_mock_runtime();
+            runtime.init_core(1);
+            runtime.init_core(2);
+
+            // Replay the 5-step flow's state changes (the MMIO base is not
+            // mapped on the host, so drive the L1 lines and L3 directly)
+            let address = 0x6000u64;
+            let idx = ((address >> 6) % 64) as usize;
+            runtime.l3_directory.core_read(1, address).unwrap();
+            runtime.l3_directory.core_read(2, address).unwrap();
+            runtime.l3_directory.core_write(1, address).unwrap();
+            runtime.cores[1].as_ref().unwrap().l1_cache[idx].force_state(CacheState::Modified);
+            runtime.cores[2].as_ref().unwrap().l1_cache[idx].force_state(CacheState::Invalid);
+
+            let snap = runtime.snapshot();
+            assert_eq!(snap.core_mask, 0b0000_0110);
+            assert_eq!(snap.l1_states[1][idx], CacheState::Modified as u8);
+            assert_eq!(snap.l1_states[2][idx], CacheState::Invalid as u8);
+
+            // Mutate further
+            runtime.l3_directory.core_read(2, address).unwrap();
+            runtime.l3_directory.core_write(3, 0x7000).unwrap();
+            runtime.cores[1].as_ref().unwrap().l1_cache[idx].force_state(CacheState::Shared);
+            runtime.cores[2].as_ref().unwrap().l1_cache[idx].force_state(CacheState::Shared);
+            let mutated = runtime.snapshot();
+            assert_ne!(mutated.l3_states, snap.l3_states);
+
+            // Restore and compare
+            runtime.restore(&snap);
+            let restored = runtime.snapshot();
+            assert_eq!(restored.core_mask, snap.core_mask);
+            assert_eq!(restored.l1_states, snap.l1_states);
+            assert_eq!(restored.l3_tags, snap.l3_tags);
+            assert_eq!(restored.l3_states, snap.l3_states);
+            assert_eq!(restored.l3_owners, snap.l3_owners);
+            assert_eq!(restored.l3_ref_counts, snap.l3_ref_counts);
+        }
+    }
+
+    #[test]
+    fn test_snapshot_skips_uninitialized_cores() {
+        unsafe {
+            let mut runtime = create_mock_runtime();
+            runtime.init_core(0);
+            runtime.cores[0].as_ref().unwrap().l1_cache[5].force_state(CacheState::Exclusive);
+
+            let mut snap = runtime.snapshot();
+            assert_eq!(snap.core_mask, 0b0000_0001);
+
+            // Core 3 data in the snapshot is ignored since it isn't initialized
+            snap.l1_states[3][0] = CacheState::Modified as u8;
+            runtime.restore(&snap);
+            assert!(runtime.cores[3].is_none());
+            let controller = runtime.cores[0].as_ref().unwrap();
+            assert_eq!(controller.l1_cache[5].get_state(), CacheState::Exclusive);
+        }
+    }
+
+    #[test]
+    fn test_handle_remote_read_downgrades() {
+        unsafe {
+            let mut controller = CoreCacheController::new(0);
+            let address = 0x2040u64;
+            let idx = ((address >> 6) % 64) as usize;
+
+            for state in [CacheState::Modified, CacheState::Exclusive, CacheState::Shared] {
+                controller.l1_cache[idx].force_state(state);
+                controller.handle_remote_read(address);
+                assert_eq!(controller.l1_cache[idx].get_state(), CacheState::Shared);
+            }
+
+            // Lines this core does not hold stay Invalid
+            controller.l1_cache[idx].force_state(CacheState::Invalid);
+            controller.handle_remote_read(address);
+            assert_eq!(controller.l1_cache[idx].get_state(), CacheState::Invalid);
+        }
+    }
+
+    #[test]
+    fn test_access_remote_read_downgrades_writer() {
+        let mut reg_a = create_mock_register();
+        let mut reg_b = create_mock_register();
+
+        unsafe {
+            let mut runtime = create_mock_runtime();
+            runtime.init_core(0);
+            runtime.init_core(1);
+            attach_mock_mmio(runtime.cores[0].as_mut().unwrap(), &mut reg_a);
+            attach_mock_mmio(runtime.cores[1].as_mut().unwrap(), &mut reg_b);
+
+            let address = 0x3000u64;
+            let idx = ((address >> 6) % 64) as usize;
+            let state = |runtime: &CoherencyRuntime, core: usize| {
+                runtime.cores[core].as_ref().unwrap().l1_cache[idx].get_state()
+            };
+
+            // Core A writes: Modified, core B invalidated
+            runtime.access(0, address, true).unwrap();
+            assert_eq!(state(&runtime, 0), CacheState::Modified);
+            assert_eq!(state(&runtime, 1), CacheState::Invalid);
+
+            // Core B reads: A downgrades to Shared, B fetches Shared
+            runtime.access(1, address, false).unwrap();
+            assert_eq!(state(&runtime, 0), CacheState::Shared);
+            assert_eq!(state(&runtime, 1), CacheState::Shared);
+            assert_eq!(runtime.check_invariants(), Ok(()));
+
+            // Uninitialized or out-of-range cores are rejected
+            assert_eq!(runtime.access(2, address, false), Err("Core not initialized"));
+            assert_eq!(runtime.access(8, address, true), Err("Core not initialized"));
+        }
+    }
+
+    #[test]
+    fn test_coherency_flow_trace() {
+        let mut reg_1 = create_mock_register();
+        let mut reg_2 = create_mock_register();
+
+        unsafe {
+            let mut runtime = create_mock_runtime();
+            runtime.init_core(1);
+            runtime.init_core(2);
+            attach_mock_mmio(runtime.cores[1].as_mut().unwrap(), &mut reg_1);
+            attach_mock_mmio(runtime.cores[2].as_mut().unwrap(), &mut reg_2);
+
+            // Tracing is off by default
+            let address = 0xA000u64;
+            runtime.execute_coherency_flow(address).unwrap();
+            assert!(runtime.drain_trace().is_empty());
+
+            for core in runtime.cores.iter().flatten() {
+                core.l1_cache[CoreCacheController::<L1_DEFAULT_LINES>::line_index(address)]
+                    .force_state(CacheState::Invalid);
+            }
+            runtime.enable_tracing(16);
+            runtime.execute_coherency_flow(address).unwrap();
+
+            use CacheEvent::*;
+            use CacheState::*;
+            let entry = |core_id, event, from, to| TraceEntry {
+                core_id,
+                address,
+                event,
+                from,
+                to,
+            };
+            assert_eq!(
+                runtime.drain_trace(),
+                [
+                    entry(1, LocalRead, Invalid, Shared),
+                    entry(2, LocalRead, Invalid, Shared),
+                    entry(1, LocalWrite, Shared, Modified),
+                    entry(2, RemoteWrite, Shared, Invalid),
+                    entry(2, LocalRead, Invalid, Shared),
+                ]
+            );
+            assert!(runtime.drain_trace().is_empty());
+        }
+    }
+
+    #[test]
+    fn test_access_trace_keeps_latest_entries() {
+        let mut reg_a = create_mock_register();
+        let mut reg_b = create_mock_register();
+
+        unsafe {
+            let mut runtime = create_mock_runtime();
+            runtime.init_core(0);
+            runtime.init_core(1);
+            attach_mock_mmio(runtime.cores[0].as_mut().unwrap(), &mut reg_a);
+            attach_mock_mmio(runtime.cores[1].as_mut().unwrap(), &mut reg_b);
+            runtime.enable_tracing(2);
+
+            let address = 0x3000u64;
+            runtime.access(0, address, true).unwrap();
+            runtime.access(1, address, false).unwrap();
+
+            // Core 0's write fell out; the remote downgrade and read remain
+            let trace = runtime.drain_trace();
+            assert_eq!(trace.len(), 2);
+            assert_eq!(
+                (trace[0].core_id, trace[0].event, trace[0].from, trace[0].to),
+                (0, CacheEvent::RemoteRead, CacheState::Modified, CacheState::Shared)
+            );
+            assert_eq!(
+                (trace[1].core_id, trace[1].event, trace[1].from, trace[1].to),
+                (1, CacheEvent::LocalRead, CacheState::Invalid, CacheState::Shared)
+            );
+        }
+    }
+
+    #[test]
+    fn test_check_invariants_consistent_state() {
+        unsafe {
+            let mut runtime = create_mock_runtime();
+            runtime.init_core(1);
+            runtime.init_core(2);
+            assert_eq!(runtime.check_invariants(), Ok(()));
+
+            // Two readers share the line
+            let address = 0x6000u64;
+            let idx = ((address >> 6) % 64) as usize;
+            runtime.l3_directory.core_read(1, address).unwrap();
+            runtime.l3_directory.core_read(2, address).unwrap();
+            runtime.cores[1].as_ref().unwrap().l1_cache[idx].force_state(CacheState::Shared);
+            runtime.cores[2].as_ref().unwrap().l1_cache[idx].force_state(CacheState::Shared);
+            assert_eq!(runtime.check_invariants(), Ok(()));
+
+            // Core 1 writes and core 2 is invalidated
+            runtime.l3_directory.core_write(1, address).unwrap();
+            runtime.cores[1].as_ref().unwrap().l1_cache[idx].force_state(CacheState::Modified);
+            runtime.cores[2].as_ref().unwrap().l1_cache[idx].force_state(CacheState::Invalid);
+            assert_eq!(runtime.check_invariants(), Ok(()));
+        }
+    }
+
+    #[test]
+    fn test_dump_state() {
+        use alloc::string::String;
+
+        unsafe {
+            let mut runtime = create_mock_runtime();
+            runtime.init_core(1);
+            runtime.init_core(2);
+            runtime.l3_directory.core_read(1, 0x6000).unwrap();
+            runtime.l3_directory.core_read(2, 0x6000).unwrap();
+            runtime.l3_directory.core_write(1, 0x7000).unwrap();
+
+            let mut out = String::new();
+            runtime.dump_state(&mut out).unwrap();
+
+            assert!(out.starts_with("Coherency:\n"));
+            assert!(out.contains("  Core 1: 0 hits, 0 misses\n"));
+            assert!(!out.contains("Core 0:"));
+            assert!(out.contains("L3: 2 valid lines (M=1 E=0 S=1)"));
+            assert!(out.contains("Invariants: ok"));
+        }
+    }
+
+    #[test]
+    fn test_check_invariants_multiple_owners() {
+        unsafe {
+            let mut runtime = create_mock_runtime();
+            runtime.init_core(0);
+            runtime.init_core(3);
+
+            runtime.cores[0].as_ref().unwrap().l1_cache[4].force_state(CacheState::Modified);
+            runtime.cores[3].as_ref().unwrap().l1_cache[4].force_state(CacheState::Exclusive);
+            assert_eq!(
+                runtime.check_invariants(),
+                Err("Multiple cores hold the same line Modified/Exclusive")
+            );
+        }
+    }
+
+    #[test]
+    fn test_check_invariants_modified_with_sharer() {
+        unsafe {
+            let mut runtime = create_mock_runtime();
+            runtime.init_core(1);
+            runtime.init_core(2);
+
+            // Core 2 was never invalidated after core 1's write
+            runtime.cores[1].as_ref().unwrap().l1_cache[9].force_state(CacheState::Modified);
+            runtime.cores[2].as_ref().unwrap().l1_cache[9].force_state(CacheState::Shared);
+            assert_eq!(
+                runtime.check_invariants(),
+                Err("Line is Modified/Exclusive in one core and Shared in another")
+            );
+        }
+    }
+
+    #[test]
+    fn test_check_invariants_directory_mismatch() {
+        unsafe {
+            let mut runtime = create_mock_runtime();
+            runtime.init_core(1);
+            runtime.init_core(2);
+
+            // L3 says core 1 owns the line, but core 2 still has a copy
+            let address = 0x8040u64;
+            let idx = ((address >> 6) % 64) as usize;
+            runtime.l3_directory.core_write(1, address).unwrap();
+            runtime.cores[2].as_ref().unwrap().l1_cache[idx].force_state(CacheState::Shared);
+            assert_eq!(
+                runtime.check_invariants(),
+                Err("Core holds a copy of a line owned by another core in L3")
+            );
+
+            // A Shared directory line with no sharers is also flagged
+            let mut snap = runtime.snapshot();
+            snap.l1_states[2][idx] = CacheState::Invalid as u8;
+            let l3_idx = ((address >> 6) % 1024) as usize;
+            snap.l3_states[l3_idx] = CacheState::Shared as u8;
+            snap.l3_ref_counts[l3_idx] = 0;
+            runtime.restore(&snap);
+            assert_eq!(runtime.check_invariants(), Err("L3 line is Shared with no sharers"));
+        }
+    }
+
     #[test]
     fn test_multi_core_independent_cache_lines() {
         unsafe {
@@ -493,3 +1428,139 @@ mod tests {
         }
     }
 }
+
+/// Property tests: random multi-core access sequences must keep MESI intact
+#[cfg(all(test, feature = "std"))]
+mod proptests {
+    use super::*;
+    use crate::mmio::CoherencyRegister;
+    use proptest::prelude::*;
+
+    /// Cores taking part in the generated sequences
+    const CORES: u8 = 4;
+    /// Distinct lines touched, kept small so cores keep colliding
+    const LINES: u64 = 4;
+
+    #[derive(Debug, Clone, Copy)]
+    enum Op {
+        Read,
+        Write,
+        Flush,
+        Evict,
+    }
+
+    #[derive(Debug, Clone, Copy)]
+    struct Step {
+        core: u8,
+        line: u64,
+        op: Op,
+    }
+
+    impl Step {
+        fn address(&self) -> u64 {
+            0x10_0000 + self.line * 64
+        }
+    }
+
+    /// One random operation on a random core and line
+    fn step() -> impl Strategy<Value = Step> {
+        let op = prop_oneof![
+            Just(Op::Read),
+            Just(Op::Write),
+            Just(Op::Flush),
+            Just(Op::Evict),
+        ];
+        (0..CORES, 0..LINES, op).prop_map(|(core, line, op)| Step { core, line, op })
+    }
+
+    /// Runtime with `CORES` cores, each on its own zeroed (never busy) register block
+    ///
+    /// The register vector must outlive the runtime and is never resized.
+    fn mock_runtime() -> (Box<CoherencyRuntime>, Vec<CoherencyRegister>) {
+        let mut regs: Vec<_> = (0..CORES)
+            .map(|_| CoherencyRegister {
+                control: 0,
+                address: 0,
+                status: 0,
+                data: [0; 16],
+            })
+            .collect();
+
+        let mut runtime = Box::new(CoherencyRuntime::new());
+        for (core_id, reg) in regs.iter_mut().enumerate() {
+            unsafe {
+                runtime.init_core(core_id as u8);
+                let base = reg as *mut CoherencyRegister as usize;
+                runtime.cores[core_id].as_mut().unwrap().mmio = MMIOCoherency::new(base);
+            }
+        }
+
+        (runtime, regs)
+    }
+
+    fn apply(runtime: &mut CoherencyRuntime, step: Step) -> Result<(), &'static str> {
+        let address = step.address();
+        let core = runtime.cores[step.core as usize].as_mut().ok_or("Core not initialized")?;
+
+        match step.op {
+            Op::Read => unsafe { runtime.access(step.core, address, false) },
+            Op::Write => unsafe { runtime.access(step.core, address, true) },
+            Op::Flush => {
+                core.flush(address);
+                Ok(())
+            }
+            Op::Evict => {
+                core.evict(address);
+                Ok(())
+            }
+        }
+    }
+
+    fn line_state(runtime: &CoherencyRuntime, core: u8, address: u64) -> CacheState {
+        runtime.core_line_state(core as usize, address)
+    }
+
+    proptest! {
+        #[test]
+        fn random_accesses_keep_mesi_invariants(steps in prop::collection::vec(step(), 1..128)) {
+            let (mut runtime, _regs) = mock_runtime();
+
+            for (i, step) in steps.iter().enumerate() {
+                apply(&mut runtime, *step).unwrap();
+                prop_assert_eq!(runtime.check_invariants(), Ok(()), "step {}: {:?}", i, step);
+
+                // Per-operation postconditions on the touched line
+                let address = step.address();
+                let own = line_state(&runtime, step.core, address);
+                match step.op {
+                    Op::Read => prop_assert!(own.is_valid(), "step {}: {:?}", i, step),
+                    Op::Write => {
+                        prop_assert_eq!(own, CacheState::Modified);
+                        for peer in (0..CORES).filter(|&peer| peer != step.core) {
+                            let state = line_state(&runtime, peer, address);
+                            prop_assert_eq!(state, CacheState::Invalid);
+                        }
+                    }
+                    Op::Flush => prop_assert!(!own.is_dirty(), "step {}: {:?}", i, step),
+                    Op::Evict => prop_assert_eq!(own, CacheState::Invalid),
+                }
+            }
+        }
+
+        #[test]
+        fn traced_transitions_follow_the_mesi_table(steps in prop::collection::vec(step(), 1..64)) {
+            let (mut runtime, _regs) = mock_runtime();
+            runtime.enable_tracing(1024);
+
+            for step in &steps {
+                apply(&mut runtime, *step).unwrap();
+            }
+
+            // Every recorded access-path transition is the table's answer
+            for entry in runtime.drain_trace() {
+                let expected = StateTransitionTable::MESI.transition(entry.from, entry.event);
+                prop_assert_eq!(entry.to, expected, "{:?}", entry);
+            }
+        }
+    }
+}
@@ -2,7 +2,7 @@
 /// Provides memory-mapped I/O access to shadow registers and fuses
 
 use crate::fuse_manager::FuseManager;
-use crate::shadow_register::{RegisterState, ShadowRegisterBank};
+use crate::shadow_register::{RegisterId, RegisterState, ShadowError, ShadowRegisterBank};
 use crate::sync_manager::{SyncDirection, SyncManager, SyncPolicy};
 use core::ptr::{read_volatile, write_volatile};
 
@@ -38,6 +38,57 @@ pub struct ShadowRegisterMMIO {
 
     /// ECC register (8-bit parity)
     pub ecc: u32,
+
+    /// Capability register (read-only, offset `CAPABILITIES_OFFSET`)
+    /// [15:0]  = Number of shadow registers
+    /// [31:16] = Number of fuses
+    /// [39:32] = Interface version (0 = not reported)
+    /// [40]    = ECC supported
+    /// [63:41] = Reserved
+    pub capabilities: u64,
+}
+
+/// Byte offset of `ShadowRegisterMMIO::capabilities` in the register block
+pub const CAPABILITIES_OFFSET: usize = 0x20;
+
+/// Hardware block capabilities, decoded from the capability register
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Capabilities {
+    /// Shadow registers implemented by the block
+    pub num_registers: u16,
+    /// Fuses implemented by the block
+    pub num_fuses: u16,
+    /// Block maintains ECC parity
+    pub ecc_supported: bool,
+    /// Interface version (0 on blocks that predate the capability register)
+    pub version: u8,
+}
+
+impl Capabilities {
+    /// Decode a capability register value (reserved bits ignored)
+    pub const fn from_word(word: u64) -> Self {
+        Self {
+            num_registers: word as u16,
+            num_fuses: (word >> 16) as u16,
+            version: (word >> 32) as u8,
+            ecc_supported: word & (1 << 40) != 0,
+        }
+    }
+
+    /// Encode as a capability register value
+    pub const fn to_word(&self) -> u64 {
+        self.num_registers as u64
+            | (self.num_fuses as u64) << 16
+            | (self.version as u64) << 32
+            | (self.ecc_supported as u64) << 40
+    }
+
+    /// Check if the block filled in the capability register
+    ///
+    /// Older blocks read back 0 here; treat their sizes as unknown.
+    pub const fn is_reported(&self) -> bool {
+        self.version != 0
+    }
 }
 
 /// MMIO Commands
@@ -125,6 +176,15 @@ impl ShadowRegisterMMIO {
         ((status >> 8) & 0xFF) as u8
     }
 
+    /// Read the block's capability register
+    ///
+    /// # Safety
+    /// `self` must be a mapped `ShadowRegisterMMIO` block
+    #[inline]
+    pub unsafe fn read_capabilities(&self) -> Capabilities {
+        Capabilities::from_word(read_volatile(&self.capabilities as *const u64))
+    }
+
     /// Execute a command and wait for completion
     #[inline]
     pub unsafe fn execute_command(
@@ -169,15 +229,62 @@ impl ShadowMMIOController {
     pub unsafe fn new(
         shadow_bank: *mut ShadowRegisterBank,
         fuse_manager: *mut FuseManager,
+    ) -> Self {
+        Self::with_base(SHADOW_REG_BASE, shadow_bank, fuse_manager)
+    }
+
+    /// Create a new MMIO controller on the register block at `base`
+    ///
+    /// # Safety
+    /// `base` must be a mapped `ShadowRegisterMMIO` block, and both
+    /// pointers must stay valid for the controller's lifetime.
+    pub unsafe fn with_base(
+        base: usize,
+        shadow_bank: *mut ShadowRegisterBank,
+        fuse_manager: *mut FuseManager,
     ) -> Self {
         Self {
-            mmio: SHADOW_REG_BASE as *mut ShadowRegisterMMIO,
+            mmio: base as *mut ShadowRegisterMMIO,
             shadow_bank,
             fuse_manager,
             sync_manager: SyncManager::new(),
         }
     }
 
+    /// Create a controller at `base`, rejecting a misaligned base
+    ///
+    /// `ShadowRegisterMMIO` holds u64 registers, so a base that isn't
+    /// 8-byte aligned would take an alignment-check fault on first access.
+    ///
+    /// # Safety
+    /// Same as `with_base`.
+    pub unsafe fn try_with_base(
+        base: usize,
+        shadow_bank: *mut ShadowRegisterBank,
+        fuse_manager: *mut FuseManager,
+    ) -> Result<Self, &'static str> {
+        if !base.is_multiple_of(core::mem::align_of::<ShadowRegisterMMIO>()) {
+            return Err("Misaligned MMIO base");
+        }
+
+        Ok(Self::with_base(base, shadow_bank, fuse_manager))
+    }
+
+    /// Base address of the register block
+    #[inline(always)]
+    pub fn base(&self) -> usize {
+        self.mmio as usize
+    }
+
+    /// Read the hardware block's capabilities
+    ///
+    /// # Safety
+    /// The register block at `base()` must be mapped
+    #[inline]
+    pub unsafe fn probe(&self) -> Capabilities {
+        (*self.mmio).read_capabilities()
+    }
+
     /// Read shadow register via MMIO
     #[inline]
     pub unsafe fn mmio_read(&mut self, register_id: u8) -> Result<u64, &'static str> {
@@ -213,10 +320,36 @@ impl ShadowMMIOController {
     }
 
     /// Rollback shadow register via MMIO
+    ///
+    /// The cached shadow bank register is rolled back as well so software
+    /// state tracks hardware. It is checked first: if it is missing,
+    /// poisoned or has no undo history, no command reaches the hardware.
     #[inline]
     pub unsafe fn mmio_rollback(&mut self, register_id: u8) -> Result<(), &'static str> {
+        let shadow_reg = if self.shadow_bank.is_null() {
+            None
+        } else {
+            match (*self.shadow_bank).get_register_mut(RegisterId(register_id as u32)) {
+                Some(shadow_reg) => {
+                    if shadow_reg.is_poisoned() {
+                        return Err(ShadowError::Poisoned.as_str());
+                    }
+                    if shadow_reg.undo_depth() == 0 {
+                        return Err(ShadowError::NoUndoHistory.as_str());
+                    }
+                    Some(shadow_reg)
+                }
+                None => return Err("Shadow register not found"),
+            }
+        };
+
         let mmio = &mut *self.mmio;
         mmio.execute_command(MMIOCommand::Rollback, register_id)?;
+
+        if let Some(shadow_reg) = shadow_reg {
+            shadow_reg.rollback()?;
+        }
+
         Ok(())
     }
 
@@ -274,13 +407,19 @@ impl ShadowMMIOController {
             return Err("Fuse manager not initialized");
         }
 
+        // Notify hardware of the sync
+        let mmio = &mut *self.mmio;
+        mmio.execute_command(MMIOCommand::Sync, register_id)?;
+
         // Use sync manager to perform sync
-        self.sync_manager.sync_register(
-            &mut *self.fuse_manager,
-            register_id as u32,
-            direction,
-            policy,
-        )
+        self.sync_manager
+            .sync_register(
+                &mut *self.fuse_manager,
+                RegisterId(register_id as u32),
+                direction,
+                policy,
+            )
+            .map_err(Into::into)
     }
 
     /// Get register state via MMIO
@@ -333,6 +472,44 @@ impl ShadowMMIOController {
 
         Ok(committed)
     }
+
+    /// Batch rollback multiple registers
+    pub unsafe fn mmio_batch_rollback(&mut self, register_ids: &[u8]) -> Result<usize, &'static str> {
+        let mut rolled_back = 0;
+
+        for &id in register_ids {
+            if self.mmio_rollback(id).is_ok() {
+                rolled_back += 1;
+            }
+        }
+
+        Ok(rolled_back)
+    }
+
+    /// Batch verify multiple registers
+    ///
+    /// A register passes only if the hardware check succeeds and the cached
+    /// shadow register (when a bank is attached) exists and verifies.
+    pub unsafe fn mmio_batch_verify(
+        &mut self,
+        register_ids: &[u8],
+    ) -> Result<Vec<(u8, bool)>, &'static str> {
+        let mut results = Vec::with_capacity(register_ids.len());
+
+        for &id in register_ids {
+            let mut valid = self.mmio_verify(id).unwrap_or(false);
+
+            if valid && !self.shadow_bank.is_null() {
+                valid = (*self.shadow_bank)
+                    .get_register(RegisterId(id as u32))
+                    .is_some_and(|reg| reg.verify());
+            }
+
+            results.push((id, valid));
+        }
+
+        Ok(results)
+    }
 }
 
 unsafe impl Send for ShadowMMIOController {}
@@ -355,6 +532,7 @@ mod tests {
             address: 0,
             status: 0,
             ecc: 0,
+            capabilities: 0,
         })
     }
 
@@ -578,6 +756,258 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_shadow_mmio_controller_try_with_base_alignment() {
+        let mut mmio = create_mock_shadow_register();
+        let mut bank = Box::new(ShadowRegisterBank::new());
+        let mut fuses = Box::new(FuseManager::new());
+        let base = &mut *mmio as *mut ShadowRegisterMMIO as usize;
+
+        unsafe {
+            let controller =
+                ShadowMMIOController::try_with_base(base, &mut *bank, &mut *fuses).unwrap();
+            assert_eq!(controller.base(), base);
+
+            for offset in [1, 2, 6] {
+                let result =
+                    ShadowMMIOController::try_with_base(base + offset, &mut *bank, &mut *fuses);
+                assert_eq!(result.err(), Some("Misaligned MMIO base"));
+            }
+        }
+    }
+
+    /// Helper: Controller wired to a mock MMIO block instead of SHADOW_REG_BASE
+    unsafe fn create_mock_controller(
+        mmio: &mut ShadowRegisterMMIO,
+        shadow_bank: *mut ShadowRegisterBank,
+        fuse_manager: *mut FuseManager,
+    ) -> ShadowMMIOController {
+        let base = mmio as *mut ShadowRegisterMMIO as usize;
+        ShadowMMIOController::with_base(base, shadow_bank, fuse_manager)
+    }
+
+    #[test]
+    fn test_mmio_rollback_restores_shadow_bank() {
+        let mut mmio = create_mock_shadow_register();
+        let mut bank = Box::new(ShadowRegisterBank::new());
+        bank.add_register(RegisterId(7), 0x7000).unwrap();
+
+        {
+            let reg = bank.get_register_mut(RegisterId(7)).unwrap();
+            reg.write(0x1111).unwrap();
+            reg.commit().unwrap();
+            reg.write(0x2222).unwrap();
+            reg.commit().unwrap();
+        }
+
+        let bank_ptr = &mut *bank as *mut ShadowRegisterBank;
+
+        unsafe {
+            let mut controller = create_mock_controller(&mut mmio, bank_ptr, core::ptr::null_mut());
+            assert!(controller.mmio_rollback(7).is_ok());
+
+            // Hardware saw the rollback command
+            let ctrl = mmio.read_control();
+            assert_eq!((ctrl & 0xFF) as u8, MMIOCommand::Rollback as u8);
+            assert_eq!(((ctrl >> 8) & 0xFF) as u8, 7);
+
+            // Software state tracks hardware
+            let reg = (*bank_ptr).get_register(RegisterId(7)).unwrap();
+            assert_eq!(reg.read(), 0x1111);
+            assert_eq!(reg.get_state(), RegisterState::Committed);
+            assert!(reg.verify());
+
+            // Unknown registers are reported
+            assert_eq!(controller.mmio_rollback(9), Err("Shadow register not found"));
+        }
+    }
+
+    #[test]
+    fn test_mmio_rollback_checks_shadow_before_hardware() {
+        let mut mmio = create_mock_shadow_register();
+        let mut bank = Box::new(ShadowRegisterBank::new());
+        bank.add_register(RegisterId(1), 0x1000).unwrap();
+        bank.add_register(RegisterId(2), 0x2000).unwrap();
+        {
+            let reg = bank.get_register_mut(RegisterId(2)).unwrap();
+            reg.write(0xAAAA).unwrap();
+            reg.commit().unwrap();
+            reg.poison();
+        }
+
+        let bank_ptr = &mut *bank as *mut ShadowRegisterBank;
+
+        unsafe {
+            let mut controller = create_mock_controller(&mut mmio, bank_ptr, core::ptr::null_mut());
+
+            // Missing, never committed or poisoned: hardware is left alone
+            assert_eq!(controller.mmio_rollback(9), Err("Shadow register not found"));
+            assert_eq!(
+                controller.mmio_rollback(1),
+                Err(ShadowError::NoUndoHistory.as_str())
+            );
+            assert_eq!(controller.mmio_rollback(2), Err(ShadowError::Poisoned.as_str()));
+            assert_eq!(mmio.read_control(), 0);
+
+            assert_eq!(controller.mmio_batch_rollback(&[1, 2, 9]), Ok(0));
+            assert_eq!(mmio.read_control(), 0);
+        }
+    }
+
+    #[test]
+    fn test_mmio_rollback_hardware_error_leaves_bank() {
+        let mut mmio = create_mock_shadow_register();
+        let mut bank = Box::new(ShadowRegisterBank::new());
+        bank.add_register(RegisterId(1), 0x1000).unwrap();
+        {
+            let reg = bank.get_register_mut(RegisterId(1)).unwrap();
+            reg.write(0xAAAA).unwrap();
+            reg.commit().unwrap();
+        }
+
+        mmio.status = 0x2;
+        let bank_ptr = &mut *bank as *mut ShadowRegisterBank;
+
+        unsafe {
+            let mut controller = create_mock_controller(&mut mmio, bank_ptr, core::ptr::null_mut());
+            assert_eq!(controller.mmio_rollback(1), Err("MMIO command failed"));
+            assert_eq!((*bank_ptr).get_register(RegisterId(1)).unwrap().read(), 0xAAAA);
+        }
+    }
+
+    /// Helper: Bank with registers 1..=3 committed twice each
+    fn create_committed_bank() -> Box<ShadowRegisterBank> {
+        let mut bank = Box::new(ShadowRegisterBank::new());
+        for id in 1..=3u32 {
+            bank.add_register(RegisterId(id), id as u64 * 0x1000).unwrap();
+            let reg = bank.get_register_mut(RegisterId(id)).unwrap();
+            reg.write(id as u64 * 0x10).unwrap();
+            reg.commit().unwrap();
+            reg.write(id as u64 * 0x20).unwrap();
+            reg.commit().unwrap();
+        }
+        bank
+    }
+
+    #[test]
+    fn test_mmio_batch_rollback() {
+        let mut mmio = create_mock_shadow_register();
+        let mut bank = create_committed_bank();
+        let bank_ptr = &mut *bank as *mut ShadowRegisterBank;
+
+        unsafe {
+            let mut controller = create_mock_controller(&mut mmio, bank_ptr, core::ptr::null_mut());
+
+            // IDs 8 and 9 are not in the bank and are skipped
+            let rolled_back = controller.mmio_batch_rollback(&[1, 8, 3, 9]).unwrap();
+            assert_eq!(rolled_back, 2);
+
+            assert_eq!((*bank_ptr).get_register(RegisterId(1)).unwrap().read(), 0x10);
+            assert_eq!((*bank_ptr).get_register(RegisterId(2)).unwrap().read(), 0x40);
+            assert_eq!((*bank_ptr).get_register(RegisterId(3)).unwrap().read(), 0x30);
+        }
+    }
+
+    #[test]
+    fn test_mmio_batch_verify() {
+        let mut mmio = create_mock_shadow_register();
+        let mut bank = create_committed_bank();
+        let bank_ptr = &mut *bank as *mut ShadowRegisterBank;
+
+        unsafe {
+            let mut controller = create_mock_controller(&mut mmio, bank_ptr, core::ptr::null_mut());
+
+            let results = controller.mmio_batch_verify(&[1, 2, 42, 3]).unwrap();
+            assert_eq!(results, [(1, true), (2, true), (42, false), (3, true)]);
+
+            // A hardware error fails every register but does not abort the batch
+            mmio.status = 0x2;
+            let results = controller.mmio_batch_verify(&[1, 2]).unwrap();
+            assert_eq!(results, [(1, false), (2, false)]);
+        }
+    }
+
+    #[test]
+    fn test_mmio_sync_issues_sync_command() {
+        use crate::fuse_manager::FuseMode;
+
+        let mut mmio = create_mock_shadow_register();
+        let mut fuse_memory = Box::new([0u64; 4]);
+        let mut fuse_manager = Box::new(FuseManager::new());
+        fuse_manager
+            .add_fuse((&mut fuse_memory[0] as *mut u64 as u64).into(), FuseMode::MTP)
+            .unwrap();
+        fuse_memory[0] = 0xFEED;
+
+        let fuse_ptr = &mut *fuse_manager as *mut FuseManager;
+
+        unsafe {
+            let mut controller = create_mock_controller(&mut mmio, core::ptr::null_mut(), fuse_ptr);
+            let result = controller.mmio_sync(
+                0,
+                SyncDirection::FuseToShadow,
+                SyncPolicy::ForceOverwrite,
+            );
+            assert!(result.is_ok());
+
+            let ctrl = mmio.read_control();
+            assert_eq!((ctrl & 0xFF) as u8, MMIOCommand::Sync as u8);
+            assert_eq!(((ctrl >> 8) & 0xFF) as u8, 0);
+
+            // Sync manager loaded the fuse into the shadow register
+            let reg = (*fuse_ptr).get_shadow_bank().get_register(RegisterId(0)).unwrap();
+            assert_eq!(reg.read(), 0xFEED);
+            assert_eq!(controller.sync_manager.get_sync_count(), 1);
+        }
+    }
+
+    #[test]
+    fn test_capabilities_decode() {
+        // 128 registers, 64 fuses, version 2, ECC
+        let word: u64 = (1 << 40) | (2 << 32) | (64 << 16) | 128;
+        let caps = Capabilities::from_word(word);
+        assert_eq!(
+            caps,
+            Capabilities {
+                num_registers: 128,
+                num_fuses: 64,
+                ecc_supported: true,
+                version: 2,
+            }
+        );
+        assert!(caps.is_reported());
+        assert_eq!(caps.to_word(), word);
+
+        // Reserved bits are ignored; an all-zero word means "not reported"
+        assert_eq!(Capabilities::from_word(word | 0xFFFF_FE00_0000_0000), caps);
+        assert!(!Capabilities::from_word(0).is_reported());
+    }
+
+    #[test]
+    fn test_capabilities_register_offset() {
+        assert_eq!(
+            core::mem::offset_of!(ShadowRegisterMMIO, capabilities),
+            CAPABILITIES_OFFSET
+        );
+    }
+
+    #[test]
+    fn test_controller_probe_reads_capabilities() {
+        let mut mmio = create_mock_shadow_register();
+        let mut bank = Box::new(ShadowRegisterBank::new());
+        let mut fuses = Box::new(FuseManager::new());
+        mmio.capabilities = (1 << 32) | (16 << 16) | 32;
+
+        unsafe {
+            let controller = create_mock_controller(&mut mmio, &mut *bank, &mut *fuses);
+            let caps = controller.probe();
+            assert_eq!(caps.num_registers, 32);
+            assert_eq!(caps.num_fuses, 16);
+            assert_eq!(caps.version, 1);
+            assert!(!caps.ecc_supported);
+        }
+    }
+
     #[test]
     fn test_mmio_command_all_values() {
         // Ensure all 11 commands have unique values
@@ -634,6 +1064,7 @@ mod tests {
                 (RegisterState::Modified, 2),
                 (RegisterState::Committed, 3),
                 (RegisterState::Locked, 4),
+                (RegisterState::Poisoned, 5),
             ];
 
             for (expected_state, state_val) in states {
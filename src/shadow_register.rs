@@ -1,7 +1,95 @@
 /// Shadow Register Management System
 /// Comprehensive hardware fuse shadow register implementation
 
-use core::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use core::fmt;
+use core::ptr;
+use core::sync::atomic::{fence, AtomicBool, AtomicPtr, AtomicU32, AtomicU64, Ordering};
+
+/// Shadow Register Error - failure reasons for the shadow register subsystem
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum ShadowError {
+    /// Register or fuse does not exist
+    NotFound = 0x01,
+    /// Register or fuse is locked
+    Locked = 0x02,
+    /// Register is write-protected
+    WriteProtected = 0x03,
+    /// Bank or manager is at capacity
+    Full = 0x04,
+    /// Shadow and fuse values disagree
+    Conflict = 0x05,
+    /// Stored ECC does not match the data
+    EccUncorrectable = 0x06,
+    /// Index is out of range
+    InvalidIndex = 0x07,
+    /// Commit requested with nothing staged
+    NoPendingChanges = 0x08,
+    /// One-time programmable fuse was already programmed
+    AlreadyProgrammed = 0x09,
+    /// Fuse readback did not match the programmed value
+    VerificationFailed = 0x0A,
+    /// Fuse must be programmed first
+    NotProgrammed = 0x0B,
+    /// Another sync is running
+    SyncInProgress = 0x0C,
+    /// Stored CRC32 does not match the register value
+    ChecksumMismatch = 0x0D,
+    /// Buffer is too small or truncated
+    BufferTooSmall = 0x0E,
+    /// Redundant fuse copies tie on at least one bit
+    NoMajority = 0x0F,
+    /// Register value is locked against reads
+    ReadProtected = 0x10,
+    /// Register data is unrecoverable until reloaded from its fuse
+    Poisoned = 0x11,
+    /// Fuse reached its program/erase cycle limit
+    EnduranceExceeded = 0x12,
+    /// Rollback requested with no committed value left to restore
+    NoUndoHistory = 0x13,
+    /// Fuse was provisioned at the factory and never accepts writes
+    ReadOnly = 0x14,
+}
+
+impl ShadowError {
+    /// Get a human-readable description
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            ShadowError::NotFound => "Register not found",
+            ShadowError::Locked => "Register is locked",
+            ShadowError::WriteProtected => "Register is write-protected",
+            ShadowError::Full => "Capacity exceeded",
+            ShadowError::Conflict => "Sync conflict detected",
+            ShadowError::EccUncorrectable => "ECC mismatch - data corrupted",
+            ShadowError::InvalidIndex => "Invalid index",
+            ShadowError::NoPendingChanges => "No pending changes to commit",
+            ShadowError::AlreadyProgrammed => "OTP fuse already programmed",
+            ShadowError::VerificationFailed => "Fuse programming verification failed",
+            ShadowError::NotProgrammed => "Can only blow programmed fuses",
+            ShadowError::SyncInProgress => "Sync already in progress",
+            ShadowError::ChecksumMismatch => "Register checksum mismatch",
+            ShadowError::BufferTooSmall => "Buffer too small",
+            ShadowError::NoMajority => "Redundant fuse copies have no majority",
+            ShadowError::ReadProtected => "Register is read-protected",
+            ShadowError::Poisoned => "Register is poisoned",
+            ShadowError::EnduranceExceeded => "Endurance limit reached",
+            ShadowError::NoUndoHistory => "No more undo history",
+            ShadowError::ReadOnly => "Read-only fuse",
+        }
+    }
+}
+
+impl fmt::Display for ShadowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<ShadowError> for &'static str {
+    fn from(err: ShadowError) -> Self {
+        err.as_str()
+    }
+}
 
 /// Register State - tracks the lifecycle of a shadow register
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -17,6 +105,8 @@ pub enum RegisterState {
     Committed = 0x03,
     /// Register is locked (cannot be modified)
     Locked = 0x04,
+    /// Register data failed verification or ECC and must be reloaded from fuse
+    Poisoned = 0x05,
     /// Register has detected error
     Error = 0xFF,
 }
@@ -29,16 +119,158 @@ impl From<u8> for RegisterState {
             0x02 => RegisterState::Modified,
             0x03 => RegisterState::Committed,
             0x04 => RegisterState::Locked,
+            0x05 => RegisterState::Poisoned,
             _ => RegisterState::Error,
         }
     }
 }
 
+/// Lock Mode - how much access a locked register still allows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum LockMode {
+    /// Unlocked
+    None = 0x00,
+    /// Writes are rejected, reads still allowed
+    WriteProtect = 0x01,
+    /// Reads and writes are rejected (e.g. provisioned secure fuses)
+    ReadWriteProtect = 0x02,
+}
+
+/// User-assigned shadow register identifier
+///
+/// Distinct from `SlotIndex`: ids are chosen by the caller of
+/// `ShadowRegisterBank::add_register` and need not follow insertion order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct RegisterId(pub u32);
+
+/// Position of a register in its bank (and of the matching fuse slot)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct SlotIndex(pub usize);
+
+/// Named bitfield within a packed 64-bit fuse register
+///
+/// Only built through `new`/`try_new`, so the field always lies within the
+/// register and the shifts in `mask`, `extract` and `insert` never overflow.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BitField {
+    /// Bit position of the field's least significant bit
+    offset: u8,
+    /// Field width in bits
+    width: u8,
+}
+
+impl BitField {
+    /// Describe a `width`-bit field starting at bit `offset`
+    ///
+    /// Panics if the field doesn't fit in 64 bits; in a `const` this is a
+    /// compile error.
+    pub const fn new(offset: u8, width: u8) -> Self {
+        match Self::try_new(offset, width) {
+            Some(field) => field,
+            None => panic!("BitField does not fit in a 64-bit register"),
+        }
+    }
+
+    /// Describe a field, or `None` if `offset + width` exceeds 64 bits
+    pub const fn try_new(offset: u8, width: u8) -> Option<Self> {
+        if offset as u32 >= u64::BITS || offset as u32 + width as u32 > u64::BITS {
+            return None;
+        }
+        Some(Self { offset, width })
+    }
+
+    /// Bit position of the field's least significant bit
+    #[inline(always)]
+    pub const fn offset(self) -> u8 {
+        self.offset
+    }
+
+    /// Field width in bits
+    #[inline(always)]
+    pub const fn width(self) -> u8 {
+        self.width
+    }
+
+    /// Register bits covered by the field
+    #[inline(always)]
+    pub const fn mask(self) -> u64 {
+        let low = if self.width >= 64 {
+            u64::MAX
+        } else {
+            (1u64 << self.width) - 1
+        };
+        low << self.offset
+    }
+
+    /// Field value held in `reg_value`
+    #[inline(always)]
+    pub const fn extract(self, reg_value: u64) -> u64 {
+        (reg_value & self.mask()) >> self.offset
+    }
+
+    /// `reg_value` with the field replaced by `field_value` (truncated to width)
+    #[inline(always)]
+    pub const fn insert(self, reg_value: u64, field_value: u64) -> u64 {
+        (reg_value & !self.mask()) | ((field_value << self.offset) & self.mask())
+    }
+}
+
+/// Reflected CRC32 (IEEE 802.3) polynomial, the default register checksum
+pub const CRC32_IEEE_POLY: u32 = 0xEDB88320;
+/// Reflected CRC32C (Castagnoli) polynomial
+pub const CRC32C_POLY: u32 = 0x82F63B78;
+
+/// Feed the little-endian bytes of `value` into a running reflected CRC32
+#[inline]
+const fn crc32_update(mut crc: u32, value: u64, poly: u32) -> u32 {
+    let bytes = value.to_le_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        crc ^= bytes[i] as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            if (crc & 1) != 0 {
+                crc = (crc >> 1) ^ poly;
+            } else {
+                crc >>= 1;
+            }
+            bit += 1;
+        }
+        i += 1;
+    }
+    crc
+}
+
+/// Checksum of a zero value, what a never-committed register holds
+const fn zero_value_crc(poly: u32) -> u32 {
+    !crc32_update(0xFFFFFFFF, 0, poly)
+}
+
+/// Sentinel for a register with no recorded lock owner
+const NO_LOCK_OWNER: u32 = u32::MAX;
+
+/// Committed values each register keeps for `rollback`
+pub const UNDO_DEPTH: usize = 4;
+
 /// Shadow Register - holds a copy of hardware fuse data
+///
+/// # Commit ordering
+///
+/// The committed value and its checksum are published as a pair under a
+/// seqlock. A writer takes the `committing` guard, snapshots the current
+/// pair into `prior_value`/`prior_checksum`, makes `commit_seq` odd, stores
+/// value then checksum, and makes `commit_seq` even again. `read` and
+/// `verify` serve the prior pair while the stamp is odd and retry if it
+/// moved, so they see the old pair or the new one, never a torn mix. They
+/// never wait on the writer, which keeps them safe in an interrupt handler
+/// that preempted a commit on the same core.
 #[repr(C, align(64))]
 pub struct ShadowRegister {
     /// Register ID (unique identifier)
-    id: u32,
+    id: RegisterId,
     /// Current register value
     value: AtomicU64,
     /// Shadow copy for atomic updates
@@ -49,81 +281,329 @@ pub struct ShadowRegister {
     version: AtomicU32,
     /// CRC32 checksum for error detection
     checksum: AtomicU32,
+    /// Reflected CRC32 polynomial used for `checksum`
+    crc_poly: u32,
     /// Physical fuse address
     fuse_addr: u64,
     /// Write protection flag
     write_protected: bool,
-    /// Backup value for rollback
-    backup_value: u64,
+    /// Check the checksum on every `try_read`
+    verify_on_read: bool,
+    /// Previously committed values, a ring of `UNDO_DEPTH` entries
+    undo_stack: [AtomicU64; UNDO_DEPTH],
+    /// Ring slot the next commit pushes into
+    undo_top: AtomicU32,
+    /// Entries available to `rollback` (at most `UNDO_DEPTH`)
+    undo_len: AtomicU32,
+    /// Core ID holding the lock (`NO_LOCK_OWNER` if none)
+    lock_owner: AtomicU32,
+    /// Access still allowed while locked
+    lock_mode: LockMode,
+    /// State before the first staged write (restored by `discard`)
+    staged_from_state: AtomicU32,
+    /// Version before the first staged write (restored by `discard`)
+    staged_from_version: AtomicU32,
+    /// Successful `write`/`write_masked` calls, for wear tracking
+    write_count: AtomicU32,
+    /// Held by the writer publishing a new value/checksum pair
+    committing: AtomicBool,
+    /// Seqlock stamp, odd while a commit is in progress
+    commit_seq: AtomicU32,
+    /// Committed value before the commit in progress
+    prior_value: AtomicU64,
+    /// Checksum of `prior_value`
+    prior_checksum: AtomicU32,
 }
 
 impl ShadowRegister {
-    /// Create a new shadow register
-    pub const fn new(id: u32, fuse_addr: u64) -> Self {
+    /// Create a new shadow register (IEEE CRC32 checksum)
+    pub const fn new(id: RegisterId, fuse_addr: u64) -> Self {
+        Self::new_with_poly(id, fuse_addr, CRC32_IEEE_POLY)
+    }
+
+    /// Create a new shadow register checksummed with reflected polynomial `poly`
+    pub const fn new_with_poly(id: RegisterId, fuse_addr: u64, poly: u32) -> Self {
         Self {
             id,
             value: AtomicU64::new(0),
             shadow_value: AtomicU64::new(0),
             state: AtomicU32::new(RegisterState::Uninitialized as u32),
             version: AtomicU32::new(0),
-            checksum: AtomicU32::new(0),
+            checksum: AtomicU32::new(zero_value_crc(poly)),
+            crc_poly: poly,
             fuse_addr,
             write_protected: false,
-            backup_value: 0,
+            verify_on_read: false,
+            undo_stack: [const { AtomicU64::new(0) }; UNDO_DEPTH],
+            undo_top: AtomicU32::new(0),
+            undo_len: AtomicU32::new(0),
+            lock_owner: AtomicU32::new(NO_LOCK_OWNER),
+            lock_mode: LockMode::None,
+            staged_from_state: AtomicU32::new(RegisterState::Uninitialized as u32),
+            staged_from_version: AtomicU32::new(0),
+            write_count: AtomicU32::new(0),
+            committing: AtomicBool::new(false),
+            commit_seq: AtomicU32::new(0),
+            prior_value: AtomicU64::new(0),
+            prior_checksum: AtomicU32::new(zero_value_crc(poly)),
+        }
+    }
+
+    /// Consistent snapshot of the committed value and its checksum
+    #[inline]
+    pub(crate) fn committed_pair(&self) -> (u64, u32) {
+        loop {
+            let seq = self.commit_seq.load(Ordering::Acquire);
+            let pair = if seq & 1 == 0 {
+                (self.value.load(Ordering::Relaxed), self.checksum.load(Ordering::Relaxed))
+            } else {
+                // Mid-commit: the prior pair is stable until the stamp moves
+                (
+                    self.prior_value.load(Ordering::Relaxed),
+                    self.prior_checksum.load(Ordering::Relaxed),
+                )
+            };
+
+            // Keep the loads above from sinking below the stamp re-check
+            fence(Ordering::Acquire);
+            if self.commit_seq.load(Ordering::Relaxed) == seq {
+                return pair;
+            }
+            core::hint::spin_loop();
+        }
+    }
+
+    /// Commit step 1: take the writer guard and open the seqlock
+    ///
+    /// Returns false if another commit holds the guard.
+    #[inline]
+    fn begin_commit(&self) -> bool {
+        if self
+            .committing
+            .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            return false;
+        }
+
+        self.prior_value
+            .store(self.value.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.prior_checksum
+            .store(self.checksum.load(Ordering::Relaxed), Ordering::Relaxed);
+        // Release: readers that see the odd stamp also see the prior pair
+        self.commit_seq.fetch_add(1, Ordering::Release);
+        // The odd stamp is visible before any of the stores that follow
+        fence(Ordering::Release);
+        true
+    }
+
+    /// Commit step 2: store the new committed value
+    #[inline(always)]
+    fn store_committed(&self, value: u64) {
+        self.value.store(value, Ordering::Relaxed);
+    }
+
+    /// Commit step 3: store the checksum of the new value
+    #[inline(always)]
+    fn store_checksum(&self, crc: u32) {
+        self.checksum.store(crc, Ordering::Relaxed);
+    }
+
+    /// Commit step 4: close the seqlock and release the writer guard
+    #[inline]
+    fn end_commit(&self) {
+        self.commit_seq.fetch_add(1, Ordering::Release);
+        self.committing.store(false, Ordering::Release);
+    }
+
+    /// Publish `value` and its checksum as the committed pair
+    #[inline]
+    fn publish(&self, value: u64) {
+        let crc = self.calculate_crc32(value);
+        while !self.begin_commit() {
+            core::hint::spin_loop();
         }
+        self.store_committed(value);
+        self.store_checksum(crc);
+        self.end_commit();
     }
 
     /// Get current register value
+    ///
+    /// Ignores read protection; use `try_read` where the lock mode matters.
     #[inline(always)]
     pub fn read(&self) -> u64 {
-        self.value.load(Ordering::Acquire)
+        self.committed_pair().0
+    }
+
+    /// Get current register value, honouring read protection
+    ///
+    /// With `set_verify_on_read` enabled this is `try_read_verified`.
+    #[inline]
+    pub fn try_read(&self) -> Result<u64, ShadowError> {
+        if self.verify_on_read {
+            return self.try_read_verified();
+        }
+        if self.is_read_protected() {
+            return Err(ShadowError::ReadProtected);
+        }
+
+        Ok(self.read())
+    }
+
+    /// Get current register value after checking it against its checksum
+    ///
+    /// A mismatch poisons the register (see `verify`), so the corruption is
+    /// caught on the access that would have returned it.
+    #[inline]
+    pub fn try_read_verified(&self) -> Result<u64, ShadowError> {
+        if self.is_poisoned() {
+            return Err(ShadowError::Poisoned);
+        }
+        if !self.verify() {
+            return Err(ShadowError::ChecksumMismatch);
+        }
+        if self.is_read_protected() {
+            return Err(ShadowError::ReadProtected);
+        }
+
+        Ok(self.read())
     }
 
     /// Write to shadow register (staged write)
     #[inline]
-    pub fn write(&self, new_value: u64) -> Result<(), &'static str> {
+    pub fn write(&self, new_value: u64) -> Result<(), ShadowError> {
+        // Check if locked
+        let current_state = RegisterState::from(self.state.load(Ordering::Acquire) as u8);
+        if current_state == RegisterState::Locked {
+            return Err(ShadowError::Locked);
+        }
+        if current_state == RegisterState::Poisoned {
+            return Err(ShadowError::Poisoned);
+        }
+
         // Check write protection
         if self.write_protected {
-            return Err("Register is write-protected");
+            return Err(ShadowError::WriteProtected);
         }
 
+        // Write to shadow value
+        self.shadow_value.store(new_value, Ordering::Release);
+
+        // Update state to Modified
+        self.mark_modified();
+
+        // Increment version
+        self.bump_version();
+
+        Ok(())
+    }
+
+    /// Write only the bits selected by `mask` (staged write)
+    ///
+    /// Bits outside `mask` keep their current shadow value.
+    #[inline]
+    pub fn write_masked(&self, value: u64, mask: u64) -> Result<(), ShadowError> {
         // Check if locked
         let current_state = RegisterState::from(self.state.load(Ordering::Acquire) as u8);
         if current_state == RegisterState::Locked {
-            return Err("Register is locked");
+            return Err(ShadowError::Locked);
+        }
+        if current_state == RegisterState::Poisoned {
+            return Err(ShadowError::Poisoned);
         }
 
-        // Write to shadow value
-        self.shadow_value.store(new_value, Ordering::Release);
+        // Check write protection
+        if self.write_protected {
+            return Err(ShadowError::WriteProtected);
+        }
+
+        // Merge into the shadow value without losing concurrent updates
+        let _ = self
+            .shadow_value
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |old| {
+                Some((old & !mask) | (value & mask))
+            });
 
         // Update state to Modified
-        self.state.store(RegisterState::Modified as u32, Ordering::Release);
+        self.mark_modified();
 
         // Increment version
-        self.version.fetch_add(1, Ordering::AcqRel);
+        self.bump_version();
+
+        Ok(())
+    }
+
+    /// Read one bitfield of the committed value
+    #[inline]
+    pub fn read_field(&self, field: BitField) -> Result<u64, ShadowError> {
+        self.try_read().map(|value| field.extract(value))
+    }
+
+    /// Stage a write of one bitfield, leaving the other bits untouched
+    #[inline]
+    pub fn write_field(&self, field: BitField, value: u64) -> Result<(), ShadowError> {
+        self.write_masked(field.insert(0, value), field.mask())
+    }
+
+    /// Increment the version, clamping at `u32::MAX` instead of wrapping
+    #[inline]
+    fn bump_version(&self) {
+        let _ = self
+            .version
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |v| Some(v.saturating_add(1)));
+    }
+
+    /// Enter the Modified state, remembering where the staging started
+    ///
+    /// Called after every successful write, so it also counts writes.
+    #[inline]
+    fn mark_modified(&self) {
+        self.write_count.fetch_add(1, Ordering::AcqRel);
+
+        let previous = self.state.swap(RegisterState::Modified as u32, Ordering::AcqRel);
+        if previous != RegisterState::Modified as u32 {
+            self.staged_from_state.store(previous, Ordering::Release);
+            self.staged_from_version
+                .store(self.version.load(Ordering::Acquire), Ordering::Release);
+        }
+    }
+
+    /// Drop staged writes, restoring the shadow to the committed value
+    ///
+    /// State and version return to what they were before the first staged
+    /// write, so a discarded staging leaves no trace.
+    #[inline]
+    pub fn discard(&mut self) -> Result<(), ShadowError> {
+        if self.get_state() != RegisterState::Modified {
+            return Err(ShadowError::NoPendingChanges);
+        }
+
+        self.shadow_value
+            .store(self.value.load(Ordering::Acquire), Ordering::Release);
+        self.version
+            .store(self.staged_from_version.load(Ordering::Acquire), Ordering::Release);
+        self.state
+            .store(self.staged_from_state.load(Ordering::Acquire), Ordering::Release);
 
         Ok(())
     }
 
     /// Commit shadow value to active register
     #[inline]
-    pub fn commit(&mut self) -> Result<(), &'static str> {
+    pub fn commit(&mut self) -> Result<(), ShadowError> {
         let current_state = RegisterState::from(self.state.load(Ordering::Acquire) as u8);
 
         if current_state != RegisterState::Modified {
-            return Err("No pending changes to commit");
+            return Err(ShadowError::NoPendingChanges);
         }
 
-        // Backup current value for rollback
-        self.backup_value = self.value.load(Ordering::Acquire);
+        // Previous value becomes the rollback target
+        self.push_undo(self.value.load(Ordering::Acquire));
 
-        // Atomic commit
+        // Publish value and checksum together
         let shadow_val = self.shadow_value.load(Ordering::Acquire);
-        self.value.store(shadow_val, Ordering::Release);
-
-        // Update checksum
-        let crc = self.calculate_crc32(shadow_val);
-        self.checksum.store(crc, Ordering::Release);
+        self.publish(shadow_val);
 
         // Update state
         self.state.store(RegisterState::Committed as u32, Ordering::Release);
@@ -131,19 +611,103 @@ impl ShadowRegister {
         Ok(())
     }
 
-    /// Rollback to previous value
+    /// Atomically replace the committed value if it still equals `expected`
+    ///
+    /// Combines `write` and `commit` into a single CAS on the active value.
+    /// On failure the actual committed value is returned so callers can retry.
+    /// Locked or write-protected registers always fail, as does a call that
+    /// races another commit.
+    #[inline]
+    pub fn compare_and_commit(&self, expected: u64, new: u64) -> Result<(), u64> {
+        if self.write_protected
+            || matches!(self.get_state(), RegisterState::Locked | RegisterState::Poisoned)
+        {
+            return Err(self.read());
+        }
+
+        let crc = self.calculate_crc32(new);
+        if !self.begin_commit() {
+            return Err(self.read());
+        }
+        if let Err(actual) =
+            self.value
+                .compare_exchange(expected, new, Ordering::Relaxed, Ordering::Relaxed)
+        {
+            self.end_commit();
+            return Err(actual);
+        }
+        self.store_checksum(crc);
+
+        // Previous value becomes the rollback target
+        self.push_undo(expected);
+        self.shadow_value.store(new, Ordering::Release);
+        self.end_commit();
+
+        self.bump_version();
+        self.state.store(RegisterState::Committed as u32, Ordering::Release);
+
+        Ok(())
+    }
+
+    /// Remember a committed value for `rollback`, dropping the oldest when full
+    #[inline]
+    fn push_undo(&self, value: u64) {
+        let top = self.undo_top.load(Ordering::Acquire) as usize;
+        self.undo_stack[top].store(value, Ordering::Release);
+        self.undo_top
+            .store(((top + 1) % UNDO_DEPTH) as u32, Ordering::Release);
+        let _ = self.undo_len.fetch_update(Ordering::AcqRel, Ordering::Acquire, |len| {
+            Some((len + 1).min(UNDO_DEPTH as u32))
+        });
+    }
+
+    /// Take the most recently pushed value off the undo stack
+    #[inline]
+    fn pop_undo(&mut self) -> Option<u64> {
+        let len = *self.undo_len.get_mut();
+        if len == 0 {
+            return None;
+        }
+
+        let top = (*self.undo_top.get_mut() as usize + UNDO_DEPTH - 1) % UNDO_DEPTH;
+        *self.undo_top.get_mut() = top as u32;
+        *self.undo_len.get_mut() = len - 1;
+        Some(*self.undo_stack[top].get_mut())
+    }
+
+    /// Forget every value `rollback` could restore
+    #[inline]
+    fn clear_undo(&mut self) {
+        *self.undo_top.get_mut() = 0;
+        *self.undo_len.get_mut() = 0;
+    }
+
+    /// Number of commits `rollback` can still undo
+    #[inline(always)]
+    pub fn undo_depth(&self) -> usize {
+        self.undo_len.load(Ordering::Acquire) as usize
+    }
+
+    /// Rollback to the value before the last commit
+    ///
+    /// Each commit pushes the value it replaced onto a `UNDO_DEPTH`-entry
+    /// stack, so repeated rollbacks walk back through committed values.
     #[inline]
-    pub fn rollback(&mut self) -> Result<(), &'static str> {
-        // Restore backup value
-        self.value.store(self.backup_value, Ordering::Release);
-        self.shadow_value.store(self.backup_value, Ordering::Release);
+    pub fn rollback(&mut self) -> Result<(), ShadowError> {
+        // Only a reload from fuse clears poison
+        if self.is_poisoned() {
+            return Err(ShadowError::Poisoned);
+        }
 
-        // Recalculate checksum
-        let crc = self.calculate_crc32(self.backup_value);
-        self.checksum.store(crc, Ordering::Release);
+        // Restore the previous committed value
+        let backup = self.pop_undo().ok_or(ShadowError::NoUndoHistory)?;
+        self.publish(backup);
+        self.shadow_value.store(backup, Ordering::Release);
 
-        // Decrement version
-        self.version.fetch_sub(1, Ordering::AcqRel);
+        // Decrement version, never below 0
+        let _ = self
+            .version
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |v| Some(v.saturating_sub(1)));
 
         // Update state
         self.state.store(RegisterState::Committed as u32, Ordering::Release);
@@ -151,28 +715,188 @@ impl ShadowRegister {
         Ok(())
     }
 
+    /// Check the committed value against its checksum without side effects
+    #[inline]
+    pub fn checksum_matches(&self) -> bool {
+        let (current_value, stored_crc) = self.committed_pair();
+        self.calculate_crc32(current_value) == stored_crc
+    }
+
     /// Verify register integrity using CRC32
+    ///
+    /// A mismatch poisons the register unless it is still Uninitialized.
+    /// New registers carry the checksum of their zero value, so they verify
+    /// before the first commit. Use `checksum_matches` to check without
+    /// poisoning.
     #[inline]
     pub fn verify(&self) -> bool {
-        let current_value = self.value.load(Ordering::Acquire);
-        let stored_crc = self.checksum.load(Ordering::Acquire);
-        let calculated_crc = self.calculate_crc32(current_value);
+        if self.checksum_matches() {
+            return true;
+        }
+
+        if self.get_state() != RegisterState::Uninitialized {
+            self.poison();
+        }
+        false
+    }
+
+    /// Mark the register data as unrecoverable
+    ///
+    /// Reads and writes are refused until `clear_poison`.
+    #[inline]
+    pub fn poison(&self) {
+        self.state.store(RegisterState::Poisoned as u32, Ordering::Release);
+    }
+
+    /// Replace a corrupted committed value with a corrected `candidate`
+    ///
+    /// Accepted only if `candidate` matches the stored checksum; that proves
+    /// the data is intact again, so this also lifts poison. Returns whether
+    /// the repair was applied.
+    #[inline]
+    pub fn repair(&mut self, candidate: u64) -> bool {
+        if self.calculate_crc32(candidate) != self.checksum.load(Ordering::Acquire) {
+            return false;
+        }
+
+        // Same checksum, so only the value changes
+        self.publish(candidate);
+        if self.is_poisoned() {
+            self.shadow_value.store(candidate, Ordering::Release);
+            let state = if self.lock_mode == LockMode::None {
+                RegisterState::Committed
+            } else {
+                RegisterState::Locked
+            };
+            self.state.store(state as u32, Ordering::Release);
+        }
+
+        true
+    }
+
+    /// Flip bits of the committed value behind the checksum (fault injection)
+    #[cfg(test)]
+    pub(crate) fn inject_bit_flips(&self, mask: u64) {
+        self.value.fetch_xor(mask, Ordering::AcqRel);
+    }
+
+    /// Check if the register is poisoned
+    #[inline(always)]
+    pub fn is_poisoned(&self) -> bool {
+        self.get_state() == RegisterState::Poisoned
+    }
+
+    /// Leave the Poisoned state with a value freshly reloaded from the fuse
+    ///
+    /// No effect unless the register is poisoned.
+    #[inline]
+    pub fn clear_poison(&mut self, fuse_value: u64) {
+        if !self.is_poisoned() {
+            return;
+        }
+
+        self.publish(fuse_value);
+        self.shadow_value.store(fuse_value, Ordering::Release);
+        // Values committed before the corruption are not trusted
+        self.clear_undo();
 
-        stored_crc == calculated_crc
+        // A lock taken while poisoned applies again
+        let state = if self.lock_mode == LockMode::None {
+            RegisterState::Loaded
+        } else {
+            RegisterState::Locked
+        };
+        self.state.store(state as u32, Ordering::Release);
+    }
+
+    /// Lock register with `mode` without recording an owner
+    ///
+    /// `LockMode::None` is the same as `unlock`.
+    #[inline]
+    pub fn lock(&mut self, mode: LockMode) {
+        self.lock_with_owner(NO_LOCK_OWNER, mode);
     }
 
-    /// Lock register (prevent modifications)
+    /// Lock register with `mode` on behalf of a core
     #[inline]
-    pub fn lock(&mut self) {
-        self.state.store(RegisterState::Locked as u32, Ordering::Release);
-        self.write_protected = true;
+    pub fn lock_by(&mut self, core_id: u32, mode: LockMode) {
+        self.lock_with_owner(core_id, mode);
+    }
+
+    #[inline]
+    fn lock_with_owner(&mut self, owner: u32, mode: LockMode) {
+        if mode == LockMode::None {
+            self.unlock();
+            return;
+        }
+
+        self.lock_owner.store(owner, Ordering::Release);
+        self.lock_mode = mode;
+        // Poison outranks the lock state
+        if !self.is_poisoned() {
+            self.state.store(RegisterState::Locked as u32, Ordering::Release);
+        }
     }
 
     /// Unlock register
     #[inline]
     pub fn unlock(&mut self) {
-        self.state.store(RegisterState::Committed as u32, Ordering::Release);
-        self.write_protected = false;
+        self.lock_owner.store(NO_LOCK_OWNER, Ordering::Release);
+        self.lock_mode = LockMode::None;
+        if !self.is_poisoned() {
+            self.state.store(RegisterState::Committed as u32, Ordering::Release);
+        }
+    }
+
+    /// Get the current lock mode
+    #[inline(always)]
+    pub fn get_lock_mode(&self) -> LockMode {
+        self.lock_mode
+    }
+
+    /// Check if reads are currently rejected
+    #[inline(always)]
+    pub fn is_read_protected(&self) -> bool {
+        self.lock_mode == LockMode::ReadWriteProtect
+    }
+
+    /// Get the core holding the lock, if one was recorded
+    #[inline]
+    pub fn get_lock_owner(&self) -> Option<u32> {
+        match self.lock_owner.load(Ordering::Acquire) {
+            NO_LOCK_OWNER => None,
+            core_id => Some(core_id),
+        }
+    }
+
+    /// Discard all state, keeping only the ID, fuse address and polynomial
+    #[inline]
+    fn reset(&mut self) {
+        *self = ShadowRegister::new_with_poly(self.id, self.fuse_addr, self.crc_poly);
+    }
+
+    /// Enable or disable write protection
+    #[inline]
+    pub fn set_write_protected(&mut self, protected: bool) {
+        self.write_protected = protected;
+    }
+
+    /// Check if write protection is enabled
+    #[inline(always)]
+    pub fn is_write_protected(&self) -> bool {
+        self.write_protected
+    }
+
+    /// Make every `try_read` verify the checksum first
+    #[inline]
+    pub fn set_verify_on_read(&mut self, enabled: bool) {
+        self.verify_on_read = enabled;
+    }
+
+    /// Check if `try_read` verifies the checksum
+    #[inline(always)]
+    pub fn is_verify_on_read(&self) -> bool {
+        self.verify_on_read
     }
 
     /// Get current state
@@ -187,25 +911,31 @@ impl ShadowRegister {
         self.version.load(Ordering::Acquire)
     }
 
+    /// Version counter has reached `u32::MAX` and no longer advances
+    ///
+    /// Version-checked sync can't see further changes; archive the history
+    /// and reset the register.
+    #[inline(always)]
+    pub fn version_saturated(&self) -> bool {
+        self.get_version() == u32::MAX
+    }
+
+    /// Get the number of successful writes (not reset by `discard`)
+    #[inline(always)]
+    pub fn get_write_count(&self) -> u32 {
+        self.write_count.load(Ordering::Acquire)
+    }
+
     /// Calculate CRC32 checksum
     #[inline]
     fn calculate_crc32(&self, value: u64) -> u32 {
-        // Simple CRC32 implementation
-        let mut crc: u32 = 0xFFFFFFFF;
-        let bytes = value.to_le_bytes();
-
-        for byte in bytes.iter() {
-            crc ^= *byte as u32;
-            for _ in 0..8 {
-                if (crc & 1) != 0 {
-                    crc = (crc >> 1) ^ 0xEDB88320;
-                } else {
-                    crc >>= 1;
-                }
-            }
-        }
+        !crc32_update(0xFFFFFFFF, value, self.crc_poly)
+    }
 
-        !crc
+    /// Get the CRC32 polynomial used for the checksum
+    #[inline(always)]
+    pub fn get_crc_poly(&self) -> u32 {
+        self.crc_poly
     }
 
     /// Get fuse address
@@ -216,40 +946,77 @@ impl ShadowRegister {
 
     /// Get register ID
     #[inline(always)]
-    pub fn get_id(&self) -> u32 {
+    pub fn get_id(&self) -> RegisterId {
         self.id
     }
 }
 
+/// Serialized bank header size (register count, u32 LE)
+pub const BANK_HEADER_SIZE: usize = 4;
+
+/// Serialized register record size
+///
+/// Layout (little-endian): id u32, value u64, state u8, version u32,
+/// checksum u32, fuse_addr u64, crc_poly u32, lock_mode u8, lock_owner u32.
+pub const REGISTER_RECORD_SIZE: usize = 38;
+
+/// State change callback - receives register ID, old state and new state
+pub type StateChangeCallback = fn(id: RegisterId, old: RegisterState, new: RegisterState);
+
 /// Shadow Register Bank - manages multiple shadow registers
 pub struct ShadowRegisterBank {
     /// Array of shadow registers
     registers: [ShadowRegister; 256],
     /// Number of active registers
     count: usize,
+    /// Registered `StateChangeCallback` (null if none)
+    state_change_callback: AtomicPtr<()>,
 }
 
 impl ShadowRegisterBank {
     /// Create a new shadow register bank
     pub const fn new() -> Self {
-        const INIT: ShadowRegister = ShadowRegister::new(0, 0);
+        const INIT: ShadowRegister = ShadowRegister::new(RegisterId(0), 0);
         Self {
             registers: [INIT; 256],
             count: 0,
+            state_change_callback: AtomicPtr::new(ptr::null_mut()),
         }
     }
 
     /// Add a new shadow register
-    pub fn add_register(&mut self, id: u32, fuse_addr: u64) -> Result<usize, &'static str> {
+    pub fn add_register(
+        &mut self,
+        id: RegisterId,
+        fuse_addr: u64,
+    ) -> Result<SlotIndex, ShadowError> {
+        self.add_register_with_poly(id, fuse_addr, CRC32_IEEE_POLY)
+    }
+
+    /// Add a new shadow register checksummed with reflected polynomial `poly`
+    pub fn add_register_with_poly(
+        &mut self,
+        id: RegisterId,
+        fuse_addr: u64,
+        poly: u32,
+    ) -> Result<SlotIndex, ShadowError> {
         if self.count >= 256 {
-            return Err("Register bank is full");
+            return Err(ShadowError::Full);
         }
 
         let index = self.count;
-        self.registers[index] = ShadowRegister::new(id, fuse_addr);
+        self.registers[index] = ShadowRegister::new_with_poly(id, fuse_addr, poly);
         self.count += 1;
 
-        Ok(index)
+        Ok(SlotIndex(index))
+    }
+
+    /// Slot holding the register with `id`
+    pub fn index_of(&self, id: RegisterId) -> Option<SlotIndex> {
+        self.registers[..self.count]
+            .iter()
+            .position(|reg| reg.get_id() == id)
+            .map(SlotIndex)
     }
 
     /// Get number of active registers
@@ -258,14 +1025,14 @@ impl ShadowRegisterBank {
     }
 
     /// Get register by ID
-    pub fn get_register(&self, id: u32) -> Option<&ShadowRegister> {
+    pub fn get_register(&self, id: RegisterId) -> Option<&ShadowRegister> {
         self.registers[..self.count]
             .iter()
             .find(|reg| reg.get_id() == id)
     }
 
     /// Get mutable register by ID
-    pub fn get_register_mut(&mut self, id: u32) -> Option<&mut ShadowRegister> {
+    pub fn get_register_mut(&mut self, id: RegisterId) -> Option<&mut ShadowRegister> {
         self.registers[..self.count]
             .iter_mut()
             .find(|reg| reg.get_id() == id)
@@ -273,9 +1040,9 @@ impl ShadowRegisterBank {
 
     /// Get register by index
     #[inline(always)]
-    pub fn get_by_index(&self, index: usize) -> Option<&ShadowRegister> {
-        if index < self.count {
-            Some(&self.registers[index])
+    pub fn get_by_index(&self, index: SlotIndex) -> Option<&ShadowRegister> {
+        if index.0 < self.count {
+            Some(&self.registers[index.0])
         } else {
             None
         }
@@ -283,9 +1050,9 @@ impl ShadowRegisterBank {
 
     /// Get mutable register by index
     #[inline(always)]
-    pub fn get_by_index_mut(&mut self, index: usize) -> Option<&mut ShadowRegister> {
-        if index < self.count {
-            Some(&mut self.registers[index])
+    pub fn get_by_index_mut(&mut self, index: SlotIndex) -> Option<&mut ShadowRegister> {
+        if index.0 < self.count {
+            Some(&mut self.registers[index.0])
         } else {
             None
         }
@@ -299,12 +1066,14 @@ impl ShadowRegisterBank {
     }
 
     /// Commit all modified registers
-    pub fn commit_all(&mut self) -> Result<usize, &'static str> {
+    pub fn commit_all(&mut self) -> Result<usize, ShadowError> {
         let mut committed = 0;
 
-        for reg in &mut self.registers[..self.count] {
-            if reg.get_state() == RegisterState::Modified {
-                reg.commit()?;
+        for index in 0..self.count {
+            if self.registers[index].get_state() == RegisterState::Modified {
+                self.registers[index].commit()?;
+                let id = self.registers[index].get_id();
+                self.notify(id, RegisterState::Modified, RegisterState::Committed);
                 committed += 1;
             }
         }
@@ -312,20 +1081,472 @@ impl ShadowRegisterBank {
         Ok(committed)
     }
 
-    /// Get count of active registers
-    #[inline(always)]
-    pub fn count(&self) -> usize {
-        self.count
+    /// Commit a single register by ID
+    pub fn commit_register(&mut self, id: RegisterId) -> Result<(), ShadowError> {
+        let reg = self.get_register_mut(id).ok_or(ShadowError::NotFound)?;
+        let old = reg.get_state();
+        reg.commit()?;
+        self.notify(id, old, RegisterState::Committed);
+        Ok(())
     }
-}
 
-#[cfg(test)]
+    /// Rollback a single register by ID
+    pub fn rollback_register(&mut self, id: RegisterId) -> Result<(), ShadowError> {
+        let reg = self.get_register_mut(id).ok_or(ShadowError::NotFound)?;
+        let old = reg.get_state();
+        reg.rollback()?;
+        self.notify(id, old, RegisterState::Committed);
+        Ok(())
+    }
+
+    /// Return a register to its freshly added, Uninitialized state
+    ///
+    /// Value, staged value, undo history, version, locks and write
+    /// protection are cleared and the checksum is recomputed for the zero
+    /// value. Locked registers are refused unless `force` is set.
+    pub fn reset_register(&mut self, id: RegisterId, force: bool) -> Result<(), ShadowError> {
+        let reg = self.get_register_mut(id).ok_or(ShadowError::NotFound)?;
+        if reg.get_lock_mode() != LockMode::None && !force {
+            return Err(ShadowError::Locked);
+        }
+
+        let old = reg.get_state();
+        reg.reset();
+        self.notify(id, old, RegisterState::Uninitialized);
+        Ok(())
+    }
+
+    /// Lock a single register by ID
+    pub fn lock_register(&mut self, id: RegisterId, mode: LockMode) -> Result<(), ShadowError> {
+        let reg = self.get_register_mut(id).ok_or(ShadowError::NotFound)?;
+        let old = reg.get_state();
+        reg.lock(mode);
+        let new = reg.get_state();
+        self.notify(id, old, new);
+        Ok(())
+    }
+
+    /// Number of bytes `serialize` writes for the current bank
+    #[inline(always)]
+    pub fn serialized_size(&self) -> usize {
+        BANK_HEADER_SIZE + self.count * REGISTER_RECORD_SIZE
+    }
+
+    /// Serialize all active registers into `out`
+    ///
+    /// Returns the number of bytes written. Lock modes and owners are kept;
+    /// staged (uncommitted) values and write counts are not persisted.
+    /// Read-protected registers are saved as zero (with the checksum of
+    /// zero), so a snapshot never carries a value its lock hides.
+    pub fn serialize(&self, out: &mut [u8]) -> Result<usize, ShadowError> {
+        let size = self.serialized_size();
+        if out.len() < size {
+            return Err(ShadowError::BufferTooSmall);
+        }
+
+        out[..BANK_HEADER_SIZE].copy_from_slice(&(self.count as u32).to_le_bytes());
+
+        let records = out[BANK_HEADER_SIZE..size].chunks_exact_mut(REGISTER_RECORD_SIZE);
+        for (reg, record) in self.registers[..self.count].iter().zip(records) {
+            let (value, checksum) = if reg.is_read_protected() {
+                (0, zero_value_crc(reg.crc_poly))
+            } else {
+                (reg.read(), reg.checksum.load(Ordering::Acquire))
+            };
+            let owner = reg.lock_owner.load(Ordering::Acquire);
+
+            record[0..4].copy_from_slice(&reg.id.0.to_le_bytes());
+            record[4..12].copy_from_slice(&value.to_le_bytes());
+            record[12] = reg.get_state() as u8;
+            record[13..17].copy_from_slice(&reg.get_version().to_le_bytes());
+            record[17..21].copy_from_slice(&checksum.to_le_bytes());
+            record[21..29].copy_from_slice(&reg.fuse_addr.to_le_bytes());
+            record[29..33].copy_from_slice(&reg.crc_poly.to_le_bytes());
+            record[33] = reg.lock_mode as u8;
+            record[34..38].copy_from_slice(&owner.to_le_bytes());
+        }
+
+        Ok(size)
+    }
+
+    /// Rebuild the bank from bytes produced by `serialize`
+    ///
+    /// Every record is checksum-verified before the bank is touched, so a
+    /// truncated or corrupted buffer leaves the current registers intact.
+    /// A record with an unknown lock mode counts as corrupted.
+    /// Registers that were never committed hold zero; their records are
+    /// accepted whether they carry the checksum of zero or, as older
+    /// serializations did, a zero checksum.
+    pub fn deserialize(&mut self, bytes: &[u8]) -> Result<(), ShadowError> {
+        let header = bytes.get(..BANK_HEADER_SIZE).ok_or(ShadowError::BufferTooSmall)?;
+        let count = u32::from_le_bytes([header[0], header[1], header[2], header[3]]) as usize;
+        if count > self.registers.len() {
+            return Err(ShadowError::Full);
+        }
+
+        let body = bytes
+            .get(BANK_HEADER_SIZE..BANK_HEADER_SIZE + count * REGISTER_RECORD_SIZE)
+            .ok_or(ShadowError::BufferTooSmall)?;
+
+        // Validate everything first
+        for record in body.chunks_exact(REGISTER_RECORD_SIZE) {
+            match Self::decode_record(record) {
+                Some(reg) if reg.verify() => {}
+                _ => return Err(ShadowError::ChecksumMismatch),
+            }
+        }
+
+        for (index, record) in body.chunks_exact(REGISTER_RECORD_SIZE).enumerate() {
+            if let Some(reg) = Self::decode_record(record) {
+                self.registers[index] = reg;
+            }
+        }
+        self.count = count;
+
+        Ok(())
+    }
+
+    /// Build a register from one serialized record (`None` for an unknown lock mode)
+    fn decode_record(record: &[u8]) -> Option<ShadowRegister> {
+        let u32_at = |at: usize| {
+            u32::from_le_bytes([record[at], record[at + 1], record[at + 2], record[at + 3]])
+        };
+        let u64_at = |at: usize| {
+            let mut buf = [0u8; 8];
+            buf.copy_from_slice(&record[at..at + 8]);
+            u64::from_le_bytes(buf)
+        };
+
+        let lock_mode = match record[33] {
+            0x00 => LockMode::None,
+            0x01 => LockMode::WriteProtect,
+            0x02 => LockMode::ReadWriteProtect,
+            _ => return None,
+        };
+
+        let value = u64_at(4);
+        let mut reg = ShadowRegister::new_with_poly(RegisterId(u32_at(0)), u64_at(21), u32_at(29));
+        reg.value.store(value, Ordering::Release);
+        reg.shadow_value.store(value, Ordering::Release);
+        reg.state.store(record[12] as u32, Ordering::Release);
+        reg.version.store(u32_at(13), Ordering::Release);
+        // Never committed and saved without a checksum: keep the one `new` set
+        let checksum = u32_at(17);
+        if value != 0 || checksum != 0 {
+            reg.checksum.store(checksum, Ordering::Release);
+        }
+        reg.lock_mode = lock_mode;
+        reg.lock_owner.store(u32_at(34), Ordering::Release);
+        Some(reg)
+    }
+
+    /// Install a callback for bank-level state changes (`None` removes it)
+    ///
+    /// Invoked by `commit_all`, `commit_register`, `rollback_register` and
+    /// `lock_register`. Changes made directly on a `ShadowRegister` bypass it.
+    pub fn set_state_change_callback(&self, callback: Option<StateChangeCallback>) {
+        let raw = callback.map_or(ptr::null_mut(), |cb| cb as *mut ());
+        self.state_change_callback.store(raw, Ordering::Release);
+    }
+
+    /// Invoke the state change callback, if one is installed
+    #[inline]
+    fn notify(&self, id: RegisterId, old: RegisterState, new: RegisterState) {
+        let raw = self.state_change_callback.load(Ordering::Acquire);
+        if !raw.is_null() {
+            // SAFETY: only `set_state_change_callback` stores non-null values,
+            // and it stores them from a `StateChangeCallback`
+            let callback = unsafe { core::mem::transmute::<*mut (), StateChangeCallback>(raw) };
+            callback(id, old, new);
+        }
+    }
+
+    /// Get count of active registers
+    #[inline(always)]
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Read-only view for diagnostics
+    pub fn view(&self) -> ShadowRegisterBankView<'_> {
+        ShadowRegisterBankView { bank: self }
+    }
+}
+
+/// Read-only handle to a shadow register
+///
+/// `ShadowRegister::write`, `compare_and_commit` and `poison` work through
+/// `&self`, so handing out `&ShadowRegister` would still let the holder
+/// stage or commit values. This wrapper only forwards the getters.
+#[derive(Clone, Copy)]
+pub struct ShadowRegisterView<'a> {
+    register: &'a ShadowRegister,
+}
+
+impl<'a> ShadowRegisterView<'a> {
+    /// Read the current value (0 while read-protected)
+    #[inline(always)]
+    pub fn read(&self) -> u64 {
+        self.try_read().unwrap_or(0)
+    }
+
+    /// Read the current value, failing if read-protected
+    ///
+    /// With verify-on-read enabled this also fails on a poisoned register or
+    /// a checksum mismatch, but unlike `ShadowRegister::try_read` it never
+    /// poisons the register itself.
+    #[inline]
+    pub fn try_read(&self) -> Result<u64, ShadowError> {
+        if self.register.is_verify_on_read() {
+            if self.register.is_poisoned() {
+                return Err(ShadowError::Poisoned);
+            }
+            if !self.register.checksum_matches() {
+                return Err(ShadowError::ChecksumMismatch);
+            }
+        }
+        if self.register.is_read_protected() {
+            return Err(ShadowError::ReadProtected);
+        }
+
+        Ok(self.register.read())
+    }
+
+    /// Read one bitfield of the current value
+    #[inline(always)]
+    pub fn read_field(&self, field: BitField) -> Result<u64, ShadowError> {
+        self.register.read_field(field)
+    }
+
+    /// Check the committed value against its checksum
+    ///
+    /// Side-effect free: a mismatch is reported but does not poison.
+    #[inline(always)]
+    pub fn verify(&self) -> bool {
+        self.register.checksum_matches()
+    }
+
+    /// Check if the register is poisoned
+    #[inline(always)]
+    pub fn is_poisoned(&self) -> bool {
+        self.register.is_poisoned()
+    }
+
+    /// Check if reads are refused under the current lock mode
+    #[inline(always)]
+    pub fn is_read_protected(&self) -> bool {
+        self.register.is_read_protected()
+    }
+
+    /// Get current state
+    #[inline(always)]
+    pub fn get_state(&self) -> RegisterState {
+        self.register.get_state()
+    }
+
+    /// Get version number
+    #[inline(always)]
+    pub fn get_version(&self) -> u32 {
+        self.register.get_version()
+    }
+
+    /// Get the number of successful writes
+    #[inline(always)]
+    pub fn get_write_count(&self) -> u32 {
+        self.register.get_write_count()
+    }
+
+    /// Get the current lock mode
+    #[inline(always)]
+    pub fn get_lock_mode(&self) -> LockMode {
+        self.register.get_lock_mode()
+    }
+
+    /// Get the core holding the lock, if one was recorded
+    #[inline(always)]
+    pub fn get_lock_owner(&self) -> Option<u32> {
+        self.register.get_lock_owner()
+    }
+
+    /// Check if write protection is enabled
+    #[inline(always)]
+    pub fn is_write_protected(&self) -> bool {
+        self.register.is_write_protected()
+    }
+
+    /// Get fuse address
+    #[inline(always)]
+    pub fn get_fuse_address(&self) -> u64 {
+        self.register.get_fuse_address()
+    }
+
+    /// Get register ID
+    #[inline(always)]
+    pub fn get_id(&self) -> RegisterId {
+        self.register.get_id()
+    }
+}
+
+/// Read-only view of a `ShadowRegisterBank` for diagnostics
+///
+/// Borrows the bank, so values stay live without copying 256 registers,
+/// while commit, rollback, lock and writes stay out of reach.
+#[derive(Clone, Copy)]
+pub struct ShadowRegisterBankView<'a> {
+    bank: &'a ShadowRegisterBank,
+}
+
+impl<'a> ShadowRegisterBankView<'a> {
+    /// Get register by ID
+    pub fn get_register(&self, id: RegisterId) -> Option<ShadowRegisterView<'a>> {
+        self.bank
+            .get_register(id)
+            .map(|register| ShadowRegisterView { register })
+    }
+
+    /// Iterate over the active registers in slot order
+    pub fn iter(&self) -> impl Iterator<Item = ShadowRegisterView<'a>> + 'a {
+        self.bank.registers[..self.bank.count]
+            .iter()
+            .map(|register| ShadowRegisterView { register })
+    }
+
+    /// Check all registers against their checksums without poisoning
+    pub fn verify_all(&self) -> bool {
+        self.iter().all(|reg| reg.verify())
+    }
+
+    /// Get count of active registers
+    #[inline(always)]
+    pub fn count(&self) -> usize {
+        self.bank.count()
+    }
+}
+
+/// Logical register wider than 64 bits, built from `WORDS` shadow registers
+///
+/// Word `i` lives at `fuse_addr + 8 * i` and keeps its own CRC, version and
+/// undo stack. Each word commits atomically, but the words commit one after
+/// another, so a combined CRC32 over all committed words (lowest word
+/// first) catches a mix of old and new words.
+pub struct WideShadowRegister<const WORDS: usize> {
+    id: RegisterId,
+    words: [ShadowRegister; WORDS],
+    /// CRC32 over every committed word, updated after each commit/rollback
+    checksum: u32,
+}
+
+impl<const WORDS: usize> WideShadowRegister<WORDS> {
+    /// Width of the logical register in bits
+    pub const BITS: usize = WORDS * 64;
+
+    /// Create a wide register (IEEE CRC32 checksums)
+    pub fn new(id: RegisterId, fuse_addr: u64) -> Self {
+        let mut reg = Self {
+            id,
+            words: core::array::from_fn(|i| ShadowRegister::new(id, fuse_addr + 8 * i as u64)),
+            checksum: 0,
+        };
+        reg.checksum = reg.calculate_checksum();
+        reg
+    }
+
+    /// Get the committed value, lowest word first
+    pub fn read(&self) -> [u64; WORDS] {
+        core::array::from_fn(|i| self.words[i].read())
+    }
+
+    /// Stage a new value in every word
+    ///
+    /// If any word refuses the write, the words already staged are
+    /// discarded so the register never holds a partial staging.
+    pub fn write(&mut self, values: &[u64; WORDS]) -> Result<(), ShadowError> {
+        for (i, &value) in values.iter().enumerate() {
+            if let Err(err) = self.words[i].write(value) {
+                for word in &mut self.words[..i] {
+                    let _ = word.discard();
+                }
+                return Err(err);
+            }
+        }
+        Ok(())
+    }
+
+    /// Commit every word and refresh the combined checksum
+    pub fn commit(&mut self) -> Result<(), ShadowError> {
+        if self
+            .words
+            .iter()
+            .any(|word| word.get_state() != RegisterState::Modified)
+        {
+            return Err(ShadowError::NoPendingChanges);
+        }
+
+        for word in &mut self.words {
+            word.commit()?;
+        }
+        self.checksum = self.calculate_checksum();
+        Ok(())
+    }
+
+    /// Roll every word back to its value before the last commit
+    ///
+    /// Nothing changes unless every word can roll back.
+    pub fn rollback(&mut self) -> Result<(), ShadowError> {
+        for word in &self.words {
+            if word.is_poisoned() {
+                return Err(ShadowError::Poisoned);
+            }
+            if word.undo_depth() == 0 {
+                return Err(ShadowError::NoUndoHistory);
+            }
+        }
+
+        for word in &mut self.words {
+            word.rollback()?;
+        }
+        self.checksum = self.calculate_checksum();
+        Ok(())
+    }
+
+    /// Verify every word and the combined checksum
+    pub fn verify(&self) -> bool {
+        self.words.iter().all(ShadowRegister::verify) && self.calculate_checksum() == self.checksum
+    }
+
+    /// CRC32 over the committed words, lowest first
+    fn calculate_checksum(&self) -> u32 {
+        !self.words.iter().fold(0xFFFFFFFF, |crc, word| {
+            crc32_update(crc, word.read(), word.get_crc_poly())
+        })
+    }
+
+    /// Get register ID
+    #[inline(always)]
+    pub fn get_id(&self) -> RegisterId {
+        self.id
+    }
+
+    /// Get version (number of commits not rolled back)
+    #[inline(always)]
+    pub fn get_version(&self) -> u32 {
+        self.words.first().map_or(0, ShadowRegister::get_version)
+    }
+
+    /// Get one word's shadow register
+    #[inline]
+    pub fn word(&self, index: usize) -> Option<&ShadowRegister> {
+        self.words.get(index)
+    }
+}
+
+#[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_shadow_register_initialization() {
-        let reg = ShadowRegister::new(1, 0x1000);
+        let reg = ShadowRegister::new(RegisterId(1), 0x1000);
         assert_eq!(reg.get_state(), RegisterState::Uninitialized);
         assert_eq!(reg.read(), 0);
         assert_eq!(reg.get_version(), 0);
@@ -333,7 +1554,7 @@ mod tests {
 
     #[test]
     fn test_shadow_register_write() {
-        let reg = ShadowRegister::new(1, 0x1000);
+        let reg = ShadowRegister::new(RegisterId(1), 0x1000);
 
         // Write to shadow register
         assert!(reg.write(0xDEADBEEF).is_ok());
@@ -343,7 +1564,7 @@ mod tests {
 
     #[test]
     fn test_shadow_register_read_after_commit() {
-        let mut reg = ShadowRegister::new(1, 0x1000);
+        let mut reg = ShadowRegister::new(RegisterId(1), 0x1000);
         reg.write(0x12345678).unwrap();
         reg.commit().unwrap();
 
@@ -352,7 +1573,7 @@ mod tests {
 
     #[test]
     fn test_shadow_register_commit() {
-        let mut reg = ShadowRegister::new(1, 0x1000);
+        let mut reg = ShadowRegister::new(RegisterId(1), 0x1000);
 
         // Write and commit
         reg.write(0xCAFEBABE).unwrap();
@@ -364,7 +1585,7 @@ mod tests {
 
     #[test]
     fn test_shadow_register_rollback() {
-        let mut reg = ShadowRegister::new(1, 0x1000);
+        let mut reg = ShadowRegister::new(RegisterId(1), 0x1000);
 
         // Write and commit to set backup value
         reg.write(0x1111).unwrap();
@@ -385,13 +1606,157 @@ mod tests {
         assert_eq!(reg.get_state(), RegisterState::Committed);
     }
 
+    #[test]
+    fn test_shadow_register_write_count() {
+        let mut reg = ShadowRegister::new(RegisterId(1), 0x1000);
+        assert_eq!(reg.get_write_count(), 0);
+
+        reg.write(0x1).unwrap();
+        assert_eq!(reg.get_write_count(), 1);
+        reg.write(0x2).unwrap();
+        reg.write_masked(0x4, 0x4).unwrap();
+        assert_eq!(reg.get_write_count(), 3);
+
+        // Commit and discard are not writes
+        reg.commit().unwrap();
+        reg.write(0x8).unwrap();
+        reg.discard().unwrap();
+        assert_eq!(reg.get_write_count(), 4);
+
+        // Rejected writes are not counted
+        reg.lock(LockMode::WriteProtect);
+        assert!(reg.write(0x10).is_err());
+        assert_eq!(reg.get_write_count(), 4);
+    }
+
+    #[test]
+    fn test_shadow_register_discard() {
+        let mut reg = ShadowRegister::new(RegisterId(1), 0x1000);
+        assert_eq!(reg.discard(), Err(ShadowError::NoPendingChanges));
+
+        reg.write(0x1111).unwrap();
+        reg.commit().unwrap();
+
+        // Several staged writes are dropped together
+        reg.write(0x2222).unwrap();
+        reg.write_masked(0xFF, 0xFF).unwrap();
+        assert_eq!(reg.get_version(), 3);
+        assert!(reg.discard().is_ok());
+
+        assert_eq!(reg.read(), 0x1111);
+        assert_eq!(reg.get_state(), RegisterState::Committed);
+        assert_eq!(reg.get_version(), 1);
+        assert!(reg.verify());
+
+        // Nothing left to commit; the next write starts from the committed value
+        assert_eq!(reg.commit(), Err(ShadowError::NoPendingChanges));
+        reg.write_masked(0x0F, 0x0F).unwrap();
+        reg.commit().unwrap();
+        assert_eq!(reg.read(), 0x111F);
+    }
+
+    #[test]
+    fn test_shadow_register_verify_failure_poisons() {
+        let mut reg = ShadowRegister::new(RegisterId(1), 0x1000);
+
+        // The zero value a fresh register holds is already checksummed
+        assert!(reg.verify());
+        assert_eq!(reg.get_state(), RegisterState::Uninitialized);
+
+        reg.write(0x1111).unwrap();
+        reg.commit().unwrap();
+        assert!(reg.verify());
+
+        // Corrupt the committed value behind the checksum
+        reg.value.store(0x1110, Ordering::Release);
+        assert!(!reg.verify());
+        assert!(reg.is_poisoned());
+
+        // Nothing but a reload gets it out again
+        assert_eq!(reg.write(0x2222), Err(ShadowError::Poisoned));
+        assert_eq!(reg.write_masked(0x2, 0x2), Err(ShadowError::Poisoned));
+        assert_eq!(reg.compare_and_commit(0x1110, 0x2222), Err(0x1110));
+        assert_eq!(reg.rollback(), Err(ShadowError::Poisoned));
+        reg.lock(LockMode::WriteProtect);
+        assert_eq!(reg.get_state(), RegisterState::Poisoned);
+        reg.unlock();
+        assert_eq!(reg.get_state(), RegisterState::Poisoned);
+
+        reg.clear_poison(0x1111);
+        assert_eq!(reg.get_state(), RegisterState::Loaded);
+        assert_eq!(reg.read(), 0x1111);
+        assert!(reg.verify());
+        assert!(reg.write(0x2222).is_ok());
+
+        // Clearing a healthy register changes nothing
+        reg.clear_poison(0xDEAD);
+        assert_eq!(reg.get_state(), RegisterState::Modified);
+        assert_eq!(reg.read(), 0x1111);
+    }
+
+    #[test]
+    fn test_shadow_register_repair() {
+        let mut reg = ShadowRegister::new(RegisterId(1), 0x1000);
+        reg.write(0x1111).unwrap();
+        reg.commit().unwrap();
+
+        reg.inject_bit_flips(1 << 8);
+        assert!(!reg.verify());
+        assert!(reg.is_poisoned());
+
+        // A candidate that doesn't match the checksum is refused
+        assert!(!reg.repair(0x1112));
+        assert!(reg.is_poisoned());
+
+        assert!(reg.repair(0x1111));
+        assert_eq!(reg.get_state(), RegisterState::Committed);
+        assert_eq!(reg.read(), 0x1111);
+        assert!(reg.verify());
+    }
+
+    #[test]
+    fn test_shadow_register_compare_and_commit_success() {
+        let mut reg = ShadowRegister::new(RegisterId(1), 0x1000);
+        reg.write(0x1111).unwrap();
+        reg.commit().unwrap();
+
+        assert_eq!(reg.compare_and_commit(0x1111, 0x2222), Ok(()));
+        assert_eq!(reg.read(), 0x2222);
+        assert_eq!(reg.get_state(), RegisterState::Committed);
+        assert_eq!(reg.get_version(), 2);
+        assert!(reg.verify());
+
+        // Rollback restores the value replaced by the CAS
+        reg.rollback().unwrap();
+        assert_eq!(reg.read(), 0x1111);
+        assert!(reg.verify());
+    }
+
+    #[test]
+    fn test_shadow_register_compare_and_commit_failure() {
+        let mut reg = ShadowRegister::new(RegisterId(1), 0x1000);
+        reg.write(0x1111).unwrap();
+        reg.commit().unwrap();
+
+        // Stale expected value reports the actual value and changes nothing
+        assert_eq!(reg.compare_and_commit(0x9999, 0x2222), Err(0x1111));
+        assert_eq!(reg.read(), 0x1111);
+        assert_eq!(reg.get_version(), 1);
+        assert!(reg.verify());
+
+        // Locked registers reject even a matching expected value
+        reg.lock(LockMode::WriteProtect);
+        assert_eq!(reg.compare_and_commit(0x1111, 0x2222), Err(0x1111));
+        assert_eq!(reg.read(), 0x1111);
+    }
+
     #[test]
     fn test_shadow_register_lock() {
-        let mut reg = ShadowRegister::new(1, 0x1000);
+        let mut reg = ShadowRegister::new(RegisterId(1), 0x1000);
         reg.write(0x5555).unwrap();
 
         // Lock the register
-        reg.lock();
+        reg.lock(LockMode::WriteProtect);
         assert_eq!(reg.get_state(), RegisterState::Locked);
 
         // Write should fail when locked
@@ -402,9 +1767,253 @@ mod tests {
         assert!(reg.write(0x7777).is_ok());
     }
 
+    #[test]
+    fn test_shadow_register_lock_modes() {
+        let mut reg = ShadowRegister::new(RegisterId(1), 0x1000);
+        reg.write(0x5555).unwrap();
+        reg.commit().unwrap();
+
+        // None: reads and writes allowed
+        reg.lock(LockMode::None);
+        assert_eq!(reg.get_lock_mode(), LockMode::None);
+        assert_eq!(reg.get_state(), RegisterState::Committed);
+        assert_eq!(reg.try_read(), Ok(0x5555));
+        assert!(reg.write(0x6666).is_ok());
+        reg.commit().unwrap();
+
+        // WriteProtect: reads allowed, writes rejected
+        reg.lock(LockMode::WriteProtect);
+        assert_eq!(reg.get_lock_mode(), LockMode::WriteProtect);
+        assert!(!reg.is_read_protected());
+        assert_eq!(reg.try_read(), Ok(0x6666));
+        assert_eq!(reg.write(0x7777), Err(ShadowError::Locked));
+
+        // ReadWriteProtect: both rejected
+        reg.lock(LockMode::ReadWriteProtect);
+        assert!(reg.is_read_protected());
+        assert_eq!(reg.try_read(), Err(ShadowError::ReadProtected));
+        assert_eq!(reg.write(0x7777), Err(ShadowError::Locked));
+        assert_eq!(reg.write_masked(0xFF, 0xFF), Err(ShadowError::Locked));
+
+        // Locking with None releases the lock entirely
+        reg.lock(LockMode::None);
+        assert_eq!(reg.get_lock_mode(), LockMode::None);
+        assert_eq!(reg.get_state(), RegisterState::Committed);
+        assert_eq!(reg.try_read(), Ok(0x6666));
+        assert!(reg.write(0x7777).is_ok());
+    }
+
+    #[test]
+    fn test_shadow_register_unlock_clears_read_protection() {
+        let mut reg = ShadowRegister::new(RegisterId(1), 0x1000);
+        reg.write(0xABCD).unwrap();
+        reg.commit().unwrap();
+
+        reg.lock_by(2, LockMode::ReadWriteProtect);
+        assert_eq!(reg.get_lock_owner(), Some(2));
+        assert_eq!(reg.try_read(), Err(ShadowError::ReadProtected));
+
+        reg.unlock();
+        assert_eq!(reg.get_lock_mode(), LockMode::None);
+        assert_eq!(reg.try_read(), Ok(0xABCD));
+    }
+
+    #[test]
+    fn test_shadow_register_write_masked() {
+        let mut reg = ShadowRegister::new(RegisterId(1), 0x1000);
+        reg.write(0xAABB_CCDD_EEFF_0011).unwrap();
+        reg.commit().unwrap();
+
+        // Update the low byte only
+        assert!(reg.write_masked(0x0000_0000_0000_0042, 0xFF).is_ok());
+        assert_eq!(reg.get_state(), RegisterState::Modified);
+        assert_eq!(reg.get_version(), 2);
+
+        reg.commit().unwrap();
+        assert_eq!(reg.read(), 0xAABB_CCDD_EEFF_0042);
+
+        // Value bits outside the mask are ignored
+        reg.write_masked(0xFFFF_FFFF_FFFF_FF00, 0xFF00).unwrap();
+        reg.commit().unwrap();
+        assert_eq!(reg.read(), 0xAABB_CCDD_EEFF_FF42);
+    }
+
+    #[test]
+    fn test_bitfield_extract_insert() {
+        let field = BitField::new(10, 3);
+        assert_eq!(field.mask(), 0b111 << 10);
+
+        let reg_value = 0xFFFF_0000_0000_1C00 | 0x3FF;
+        assert_eq!(field.extract(reg_value), 0b111);
+
+        // Only bits 10..13 change; the oversized value is truncated to 3 bits
+        let updated = field.insert(reg_value, 0b1010);
+        assert_eq!(field.extract(updated), 0b010);
+        assert_eq!(updated & !field.mask(), reg_value & !field.mask());
+
+        // Full-width field
+        let all = BitField::new(0, 64);
+        assert_eq!(all.mask(), u64::MAX);
+        assert_eq!(all.insert(0, 0x1234), 0x1234);
+
+        // Fields must end at or before bit 64
+        let top = BitField::new(63, 1);
+        assert_eq!(top.mask(), 1 << 63);
+        assert_eq!((top.offset(), top.width()), (63, 1));
+        assert!(BitField::try_new(64, 0).is_none());
+        assert!(BitField::try_new(60, 5).is_none());
+        assert!(BitField::try_new(1, 64).is_none());
+        assert!(BitField::try_new(255, 255).is_none());
+    }
+
+    #[test]
+    fn test_shadow_register_read_write_field() {
+        let mut reg = ShadowRegister::new(RegisterId(1), 0x1000);
+        reg.write(0xAAAA_AAAA_AAAA_AAAA).unwrap();
+        reg.commit().unwrap();
+
+        let field = BitField::new(10, 3);
+        assert_eq!(reg.read_field(field), Ok(0b010));
+
+        reg.write_field(field, 0b101).unwrap();
+        reg.commit().unwrap();
+        assert_eq!(reg.read_field(field), Ok(0b101));
+        assert_eq!(reg.read() & !field.mask(), 0xAAAA_AAAA_AAAA_AAAA & !field.mask());
+
+        reg.lock(LockMode::ReadWriteProtect);
+        assert_eq!(reg.read_field(field), Err(ShadowError::ReadProtected));
+        assert_eq!(reg.write_field(field, 0), Err(ShadowError::Locked));
+    }
+
+    #[test]
+    fn test_shadow_register_write_masked_locked() {
+        let mut reg = ShadowRegister::new(RegisterId(1), 0x1000);
+        reg.write(0x1234).unwrap();
+        reg.commit().unwrap();
+
+        reg.lock(LockMode::WriteProtect);
+        assert_eq!(reg.write_masked(0xFF, 0xFF), Err(ShadowError::Locked));
+        assert_eq!(reg.get_state(), RegisterState::Locked);
+        assert_eq!(reg.get_version(), 1);
+    }
+
+    #[test]
+    fn test_shadow_register_lock_owner() {
+        let mut reg = ShadowRegister::new(RegisterId(1), 0x1000);
+        assert_eq!(reg.get_lock_owner(), None);
+
+        reg.lock_by(3, LockMode::WriteProtect);
+        assert_eq!(reg.get_state(), RegisterState::Locked);
+        assert_eq!(reg.get_lock_owner(), Some(3));
+        assert_eq!(reg.write(0x1234), Err(ShadowError::Locked));
+
+        // Unlock clears the owner
+        reg.unlock();
+        assert_eq!(reg.get_lock_owner(), None);
+
+        // Plain lock records no owner
+        reg.lock(LockMode::WriteProtect);
+        assert_eq!(reg.get_lock_owner(), None);
+    }
+
+    #[test]
+    fn test_shadow_register_write_protected() {
+        let mut reg = ShadowRegister::new(RegisterId(1), 0x1000);
+
+        reg.set_write_protected(true);
+        assert!(reg.is_write_protected());
+        assert_eq!(reg.write(0x1234), Err(ShadowError::WriteProtected));
+
+        // Unlocking does not lift write protection
+        reg.unlock();
+        assert_eq!(reg.write(0x1234), Err(ShadowError::WriteProtected));
+
+        reg.set_write_protected(false);
+        assert!(reg.write(0x1234).is_ok());
+    }
+
+    #[test]
+    fn test_read_during_commit_sees_consistent_pair() {
+        let mut reg = ShadowRegister::new(RegisterId(0), 0x1000);
+        reg.write(0x1111).unwrap();
+        reg.commit().unwrap();
+
+        // Replay commit one step at a time, reading like an interrupt would
+        assert!(reg.begin_commit());
+        assert!(!reg.begin_commit());
+        assert_eq!(reg.compare_and_commit(0x1111, 0x3333), Err(0x1111));
+
+        reg.store_committed(0x2222);
+        // Value is updated but its checksum is not: readers keep the old pair
+        assert_eq!(reg.value.load(Ordering::Acquire), 0x2222);
+        assert_eq!(reg.read(), 0x1111);
+        assert!(reg.verify());
+
+        reg.store_checksum(reg.calculate_crc32(0x2222));
+        assert_eq!(reg.read(), 0x1111);
+        assert!(reg.verify());
+
+        reg.end_commit();
+        assert_eq!(reg.read(), 0x2222);
+        assert!(reg.verify());
+        assert!(!reg.is_poisoned());
+
+        // The guard is free again
+        assert_eq!(reg.compare_and_commit(0x2222, 0x3333), Ok(()));
+        assert_eq!(reg.read(), 0x3333);
+        assert!(reg.verify());
+    }
+
+    #[test]
+    fn test_verify_on_read_catches_corruption() {
+        let mut reg = ShadowRegister::new(RegisterId(0), 0x1000);
+        reg.write(0xC1EA).unwrap();
+        reg.commit().unwrap();
+        reg.set_verify_on_read(true);
+        assert!(reg.is_verify_on_read());
+
+        // Clean read passes the check
+        assert_eq!(reg.try_read(), Ok(0xC1EA));
+        assert!(!reg.is_poisoned());
+
+        // Corruption is caught by the next read, not a later `verify`
+        reg.inject_bit_flips(1 << 7);
+        assert_eq!(reg.try_read(), Err(ShadowError::ChecksumMismatch));
+        assert!(reg.is_poisoned());
+        assert_eq!(reg.try_read(), Err(ShadowError::Poisoned));
+
+        // Without the flag the corrupted value would have been returned
+        let mut plain = ShadowRegister::new(RegisterId(1), 0x1000);
+        plain.write(0xC1EA).unwrap();
+        plain.commit().unwrap();
+        plain.inject_bit_flips(1 << 7);
+        assert_eq!(plain.try_read(), Ok(0xC1EA ^ (1 << 7)));
+    }
+
+    #[test]
+    fn test_verify_on_read_before_first_commit() {
+        let mut reg = ShadowRegister::new(RegisterId(0), 0x1000);
+        reg.set_verify_on_read(true);
+        assert_eq!(reg.try_read(), Ok(0));
+
+        // A staged but uncommitted value leaves the zero pair in place
+        reg.write(0x5A5A).unwrap();
+        assert_eq!(reg.try_read(), Ok(0));
+        assert_eq!(reg.get_state(), RegisterState::Modified);
+
+        reg.commit().unwrap();
+        assert_eq!(reg.try_read(), Ok(0x5A5A));
+
+        // Same with a non-default polynomial
+        let mut castagnoli = ShadowRegister::new_with_poly(RegisterId(1), 0x1000, CRC32C_POLY);
+        castagnoli.set_verify_on_read(true);
+        assert_eq!(castagnoli.try_read(), Ok(0));
+        assert!(!castagnoli.is_poisoned());
+    }
+
     #[test]
     fn test_shadow_register_checksum() {
-        let mut reg = ShadowRegister::new(1, 0x1000);
+        let mut reg = ShadowRegister::new(RegisterId(1), 0x1000);
         reg.write(0xABCDEF00).unwrap();
         reg.commit().unwrap();
 
@@ -412,6 +2021,63 @@ mod tests {
         assert!(reg.verify());
     }
 
+    #[test]
+    fn test_shadow_register_crc_poly_selection() {
+        // Little-endian bytes of the value spell "12345678"
+        let value = u64::from_le_bytes(*b"12345678");
+
+        let mut ieee = ShadowRegister::new(RegisterId(1), 0x1000);
+        let mut castagnoli = ShadowRegister::new_with_poly(RegisterId(2), 0x2000, CRC32C_POLY);
+        assert_eq!(ieee.get_crc_poly(), CRC32_IEEE_POLY);
+        assert_eq!(castagnoli.get_crc_poly(), CRC32C_POLY);
+
+        // Reference values for the same input under each polynomial
+        assert_eq!(ieee.calculate_crc32(value), 0x9AE0_DAAF);
+        assert_eq!(castagnoli.calculate_crc32(value), 0x6087_809A);
+
+        for reg in [&mut ieee, &mut castagnoli] {
+            reg.write(value).unwrap();
+            reg.commit().unwrap();
+            assert!(reg.verify());
+        }
+        assert_eq!(ieee.checksum.load(Ordering::Acquire), 0x9AE0_DAAF);
+        assert_eq!(castagnoli.checksum.load(Ordering::Acquire), 0x6087_809A);
+    }
+
+    #[test]
+    fn test_shadow_register_bank_mixed_crc_polys() {
+        let mut bank = ShadowRegisterBank::new();
+        bank.add_register(RegisterId(1), 0x1000).unwrap();
+        bank.add_register_with_poly(RegisterId(2), 0x2000, CRC32C_POLY).unwrap();
+        for id in [RegisterId(1), RegisterId(2)] {
+            let reg = bank.get_register_mut(id).unwrap();
+            reg.write(0xFEED_F00D).unwrap();
+            reg.commit().unwrap();
+        }
+        assert!(bank.verify_all());
+
+        // The polynomial survives a round trip, so checksums still verify
+        let mut buf = [0u8; 128];
+        let written = bank.serialize(&mut buf).unwrap();
+        let mut restored = ShadowRegisterBank::new();
+        restored.deserialize(&buf[..written]).unwrap();
+        assert!(restored.verify_all());
+        assert_eq!(
+            restored.get_register(RegisterId(2)).unwrap().get_crc_poly(),
+            CRC32C_POLY
+        );
+    }
+
+    #[test]
+    fn test_shadow_error_strings() {
+        assert_eq!(ShadowError::Locked.as_str(), "Register is locked");
+        assert_eq!(<&'static str>::from(ShadowError::Full), "Capacity exceeded");
+        assert_eq!(alloc::format!("{}", ShadowError::Conflict), "Sync conflict detected");
+        assert_eq!(ShadowError::NotFound as u8, 0x01);
+        assert_eq!(ShadowError::SyncInProgress as u8, 0x0C);
+        assert_eq!(ShadowError::ReadProtected.as_str(), "Register is read-protected");
+    }
+
     #[test]
     fn test_register_state_from_u8() {
         assert_eq!(RegisterState::from(0), RegisterState::Uninitialized);
@@ -419,7 +2085,8 @@ mod tests {
         assert_eq!(RegisterState::from(2), RegisterState::Modified);
         assert_eq!(RegisterState::from(3), RegisterState::Committed);
         assert_eq!(RegisterState::from(4), RegisterState::Locked);
-        assert_eq!(RegisterState::from(5), RegisterState::Error);
+        assert_eq!(RegisterState::from(5), RegisterState::Poisoned);
+        assert_eq!(RegisterState::from(6), RegisterState::Error);
         assert_eq!(RegisterState::from(99), RegisterState::Error);
     }
 
@@ -434,17 +2101,17 @@ mod tests {
         let mut bank = ShadowRegisterBank::new();
 
         // Add first register
-        assert!(bank.add_register(1, 0x1000).is_ok());
+        assert!(bank.add_register(RegisterId(1), 0x1000).is_ok());
         assert_eq!(bank.count(), 1);
 
         // Add second register
-        assert!(bank.add_register(2, 0x2000).is_ok());
+        assert!(bank.add_register(RegisterId(2), 0x2000).is_ok());
         assert_eq!(bank.count(), 2);
 
         // Check we can retrieve them
-        assert!(bank.get_register(1).is_some());
-        assert!(bank.get_register(2).is_some());
-        assert!(bank.get_register(3).is_none());
+        assert!(bank.get_register(RegisterId(1)).is_some());
+        assert!(bank.get_register(RegisterId(2)).is_some());
+        assert!(bank.get_register(RegisterId(3)).is_none());
     }
 
     #[test]
@@ -453,48 +2120,81 @@ mod tests {
 
         // Fill the bank to capacity (256 registers)
         for i in 0..256 {
-            assert!(bank.add_register(i as u32, i as u64 * 0x1000).is_ok());
+            assert!(bank.add_register(RegisterId(i as u32), i as u64 * 0x1000).is_ok());
         }
 
         // Should fail when full
-        assert!(bank.add_register(256, 0x100000).is_err());
+        assert_eq!(bank.add_register(RegisterId(256), 0x100000), Err(ShadowError::Full));
     }
 
     #[test]
     fn test_shadow_register_bank_get_register() {
         let mut bank = ShadowRegisterBank::new();
-        bank.add_register(42, 0xCAFE).unwrap();
+        bank.add_register(RegisterId(42), 0xCAFE).unwrap();
 
-        let reg = bank.get_register(42);
+        let reg = bank.get_register(RegisterId(42));
         assert!(reg.is_some());
     }
 
     #[test]
     fn test_shadow_register_bank_get_register_mut() {
         let mut bank = ShadowRegisterBank::new();
-        bank.add_register(10, 0x1000).unwrap();
+        bank.add_register(RegisterId(10), 0x1000).unwrap();
 
         {
-            let reg = bank.get_register_mut(10).unwrap();
+            let reg = bank.get_register_mut(RegisterId(10)).unwrap();
             reg.write(0xBEEF).unwrap();
             reg.commit().unwrap();
         }
 
-        let reg_read = bank.get_register(10).unwrap();
+        let reg_read = bank.get_register(RegisterId(10)).unwrap();
         assert_eq!(reg_read.read(), 0xBEEF);
     }
 
+    #[test]
+    fn test_shadow_register_bank_index_of() {
+        let mut bank = ShadowRegisterBank::new();
+
+        // Ids deliberately out of insertion order
+        assert_eq!(bank.add_register(RegisterId(30), 0x3000), Ok(SlotIndex(0)));
+        assert_eq!(bank.add_register(RegisterId(10), 0x1000), Ok(SlotIndex(1)));
+        assert_eq!(bank.add_register(RegisterId(20), 0x2000), Ok(SlotIndex(2)));
+
+        assert_eq!(bank.index_of(RegisterId(30)), Some(SlotIndex(0)));
+        assert_eq!(bank.index_of(RegisterId(10)), Some(SlotIndex(1)));
+        assert_eq!(bank.index_of(RegisterId(20)), Some(SlotIndex(2)));
+        assert_eq!(bank.index_of(RegisterId(0)), None);
+        assert_eq!(bank.index_of(RegisterId(1)), None);
+    }
+
+    #[test]
+    fn test_shadow_register_bank_index_of_matches_get_by_index() {
+        let mut bank = ShadowRegisterBank::new();
+        bank.add_register(RegisterId(7), 0x7000).unwrap();
+        bank.add_register(RegisterId(3), 0x3000).unwrap();
+
+        for id in [RegisterId(7), RegisterId(3)] {
+            let slot = bank.index_of(id).unwrap();
+            let reg = bank.get_by_index(slot).unwrap();
+            assert_eq!(reg.get_id(), id);
+            assert_eq!(reg.get_fuse_address(), id.0 as u64 * 0x1000);
+        }
+
+        // An id is not a slot: id 3 lives in slot 1, slot 3 is empty
+        assert!(bank.get_by_index(SlotIndex(3)).is_none());
+    }
+
     #[test]
     fn test_shadow_register_bank_verify_all() {
         let mut bank = ShadowRegisterBank::new();
-        bank.add_register(1, 0x1000).unwrap();
-        bank.add_register(2, 0x2000).unwrap();
+        bank.add_register(RegisterId(1), 0x1000).unwrap();
+        bank.add_register(RegisterId(2), 0x2000).unwrap();
 
         // Write and commit both registers (commit calculates checksums)
-        bank.get_register_mut(1).unwrap().write(0x1111).unwrap();
-        bank.get_register_mut(1).unwrap().commit().unwrap();
-        bank.get_register_mut(2).unwrap().write(0x2222).unwrap();
-        bank.get_register_mut(2).unwrap().commit().unwrap();
+        bank.get_register_mut(RegisterId(1)).unwrap().write(0x1111).unwrap();
+        bank.get_register_mut(RegisterId(1)).unwrap().commit().unwrap();
+        bank.get_register_mut(RegisterId(2)).unwrap().write(0x2222).unwrap();
+        bank.get_register_mut(RegisterId(2)).unwrap().commit().unwrap();
 
         // All should verify
         assert!(bank.verify_all());
@@ -503,25 +2203,326 @@ mod tests {
     #[test]
     fn test_shadow_register_bank_commit_all() {
         let mut bank = ShadowRegisterBank::new();
-        bank.add_register(1, 0x1000).unwrap();
-        bank.add_register(2, 0x2000).unwrap();
+        bank.add_register(RegisterId(1), 0x1000).unwrap();
+        bank.add_register(RegisterId(2), 0x2000).unwrap();
 
         // Write to both
-        bank.get_register_mut(1).unwrap().write(0xAAAA).unwrap();
-        bank.get_register_mut(2).unwrap().write(0xBBBB).unwrap();
+        bank.get_register_mut(RegisterId(1)).unwrap().write(0xAAAA).unwrap();
+        bank.get_register_mut(RegisterId(2)).unwrap().write(0xBBBB).unwrap();
 
         // Commit all
         let committed = bank.commit_all().unwrap();
         assert_eq!(committed, 2);
 
         // Check states
-        assert_eq!(bank.get_register(1).unwrap().get_state(), RegisterState::Committed);
-        assert_eq!(bank.get_register(2).unwrap().get_state(), RegisterState::Committed);
+        assert_eq!(bank.get_register(RegisterId(1)).unwrap().get_state(), RegisterState::Committed);
+        assert_eq!(bank.get_register(RegisterId(2)).unwrap().get_state(), RegisterState::Committed);
+    }
+
+    #[test]
+    fn test_shadow_register_bank_state_change_callback() {
+        static CALLS: AtomicU32 = AtomicU32::new(0);
+        static COMMITS: AtomicU32 = AtomicU32::new(0);
+        static LAST_ID: AtomicU32 = AtomicU32::new(0);
+
+        fn on_change(id: RegisterId, _old: RegisterState, new: RegisterState) {
+            CALLS.fetch_add(1, Ordering::SeqCst);
+            if new == RegisterState::Committed {
+                COMMITS.fetch_add(1, Ordering::SeqCst);
+            }
+            LAST_ID.store(id.0, Ordering::SeqCst);
+        }
+
+        let mut bank = ShadowRegisterBank::new();
+        bank.add_register(RegisterId(1), 0x1000).unwrap();
+        bank.add_register(RegisterId(2), 0x2000).unwrap();
+        bank.add_register(RegisterId(3), 0x3000).unwrap();
+        bank.set_state_change_callback(Some(on_change));
+
+        // Single commit
+        bank.get_register_mut(RegisterId(1)).unwrap().write(0xAAAA).unwrap();
+        bank.commit_register(RegisterId(1)).unwrap();
+        assert_eq!(CALLS.load(Ordering::SeqCst), 1);
+        assert_eq!(LAST_ID.load(Ordering::SeqCst), 1);
+
+        // Failed commit does not notify
+        assert_eq!(bank.commit_register(RegisterId(1)), Err(ShadowError::NoPendingChanges));
+        assert_eq!(bank.commit_register(RegisterId(9)), Err(ShadowError::NotFound));
+        assert_eq!(CALLS.load(Ordering::SeqCst), 1);
+
+        // commit_all notifies once per committed register
+        bank.get_register_mut(RegisterId(2)).unwrap().write(0xBBBB).unwrap();
+        bank.get_register_mut(RegisterId(3)).unwrap().write(0xCCCC).unwrap();
+        assert_eq!(bank.commit_all().unwrap(), 2);
+        assert_eq!(CALLS.load(Ordering::SeqCst), 3);
+        assert_eq!(COMMITS.load(Ordering::SeqCst), 3);
+
+        // Rollback and lock notify as well
+        bank.rollback_register(RegisterId(2)).unwrap();
+        bank.lock_register(RegisterId(3), LockMode::WriteProtect).unwrap();
+        assert_eq!(CALLS.load(Ordering::SeqCst), 5);
+        assert_eq!(LAST_ID.load(Ordering::SeqCst), 3);
+
+        // Removing the callback stops notifications
+        bank.set_state_change_callback(None);
+        bank.get_register_mut(RegisterId(1)).unwrap().write(0xDDDD).unwrap();
+        bank.commit_register(RegisterId(1)).unwrap();
+        assert_eq!(CALLS.load(Ordering::SeqCst), 5);
+    }
+
+    #[test]
+    fn test_shadow_register_bank_serialize_round_trip() {
+        let mut bank = ShadowRegisterBank::new();
+        for (id, value) in [(1u32, 0xAAAAu64), (2, 0xBBBB_0000_CCCC), (7, u64::MAX)] {
+            bank.add_register(RegisterId(id), 0x1000 * id as u64).unwrap();
+            let reg = bank.get_register_mut(RegisterId(id)).unwrap();
+            reg.write(value).unwrap();
+            reg.commit().unwrap();
+        }
+        bank.lock_register(RegisterId(7), LockMode::WriteProtect).unwrap();
+
+        let mut buf = [0u8; 256];
+        let written = bank.serialize(&mut buf).unwrap();
+        assert_eq!(written, BANK_HEADER_SIZE + 3 * REGISTER_RECORD_SIZE);
+        assert_eq!(written, bank.serialized_size());
+
+        let mut restored = ShadowRegisterBank::new();
+        restored.deserialize(&buf[..written]).unwrap();
+        assert_eq!(restored.count(), 3);
+        assert!(restored.verify_all());
+
+        for id in [1, 2, 7] {
+            let original = bank.get_register(RegisterId(id)).unwrap();
+            let loaded = restored.get_register(RegisterId(id)).unwrap();
+            assert_eq!(loaded.read(), original.read());
+            assert_eq!(loaded.get_state(), original.get_state());
+            assert_eq!(loaded.get_version(), original.get_version());
+            assert_eq!(loaded.get_fuse_address(), original.get_fuse_address());
+            assert_eq!(loaded.get_lock_mode(), original.get_lock_mode());
+        }
+        assert_eq!(
+            restored.get_register(RegisterId(7)).unwrap().get_state(),
+            RegisterState::Locked
+        );
+    }
+
+    #[test]
+    fn test_shadow_register_bank_round_trip_read_protected() {
+        let mut bank = ShadowRegisterBank::new();
+        bank.add_register(RegisterId(1), 0x1000).unwrap();
+        bank.add_register(RegisterId(2), 0x2000).unwrap();
+        for (id, value) in [(1u32, 0x5EC2E7u64), (2, 0xAAAA)] {
+            let reg = bank.get_register_mut(RegisterId(id)).unwrap();
+            reg.write(value).unwrap();
+            reg.commit().unwrap();
+        }
+        bank.get_register_mut(RegisterId(1)).unwrap().lock_by(3, LockMode::ReadWriteProtect);
+
+        let mut buf = [0u8; 128];
+        let written = bank.serialize(&mut buf).unwrap();
+
+        // The protected value never reaches the snapshot
+        let at = BANK_HEADER_SIZE + 4;
+        assert_eq!(&buf[at..at + 8], &[0u8; 8]);
+        assert!(!buf[..written].windows(3).any(|w| w == [0xE7, 0xC2, 0x5E]));
+
+        let mut restored = ShadowRegisterBank::new();
+        restored.deserialize(&buf[..written]).unwrap();
+        assert!(restored.verify_all());
+
+        let protected = restored.get_register(RegisterId(1)).unwrap();
+        assert_eq!(protected.get_state(), RegisterState::Locked);
+        assert_eq!(protected.get_lock_mode(), LockMode::ReadWriteProtect);
+        assert_eq!(protected.get_lock_owner(), Some(3));
+        assert_eq!(protected.try_read(), Err(ShadowError::ReadProtected));
+
+        let open = restored.get_register(RegisterId(2)).unwrap();
+        assert_eq!(open.get_lock_mode(), LockMode::None);
+        assert_eq!(open.get_lock_owner(), None);
+        assert_eq!(open.read(), 0xAAAA);
+
+        // Unknown lock modes are rejected
+        buf[BANK_HEADER_SIZE + 33] = 0x7F;
+        assert_eq!(restored.deserialize(&buf[..written]), Err(ShadowError::ChecksumMismatch));
+    }
+
+    #[test]
+    fn test_shadow_register_bank_deserialize_rejects_bad_input() {
+        let mut bank = ShadowRegisterBank::new();
+        for id in 1..=3u32 {
+            bank.add_register(RegisterId(id), 0x1000).unwrap();
+            let reg = bank.get_register_mut(RegisterId(id)).unwrap();
+            reg.write(id as u64 * 0x1111).unwrap();
+            reg.commit().unwrap();
+        }
+
+        let mut buf = [0u8; 128];
+        let written = bank.serialize(&mut buf).unwrap();
+
+        // Output buffer too small
+        assert_eq!(bank.serialize(&mut buf[..written - 1]), Err(ShadowError::BufferTooSmall));
+
+        let mut restored = ShadowRegisterBank::new();
+        restored.add_register(RegisterId(42), 0x4200).unwrap();
+
+        // Truncated input
+        assert_eq!(restored.deserialize(&buf[..2]), Err(ShadowError::BufferTooSmall));
+        assert_eq!(
+            restored.deserialize(&buf[..written - 1]),
+            Err(ShadowError::BufferTooSmall)
+        );
+
+        // Flip a value bit in the second record
+        buf[BANK_HEADER_SIZE + REGISTER_RECORD_SIZE + 4] ^= 0x01;
+        assert_eq!(
+            restored.deserialize(&buf[..written]),
+            Err(ShadowError::ChecksumMismatch)
+        );
+
+        // Rejected input leaves the bank untouched
+        assert_eq!(restored.count(), 1);
+        assert!(restored.get_register(RegisterId(42)).is_some());
+    }
+
+    #[test]
+    fn test_shadow_register_bank_round_trip_uncommitted() {
+        let mut bank = ShadowRegisterBank::new();
+        bank.add_register(RegisterId(1), 0x1000).unwrap();
+        bank.add_register(RegisterId(2), 0x2000).unwrap();
+        bank.add_register(RegisterId(3), 0x3000).unwrap();
+        // 1 stays Uninitialized, 2 is staged only, 3 is committed
+        bank.get_register(RegisterId(2)).unwrap().write(0x2222).unwrap();
+        let reg = bank.get_register_mut(RegisterId(3)).unwrap();
+        reg.write(0x3333).unwrap();
+        reg.commit().unwrap();
+
+        let mut buf = [0u8; 128];
+        let written = bank.serialize(&mut buf).unwrap();
+
+        let mut restored = ShadowRegisterBank::new();
+        restored.deserialize(&buf[..written]).unwrap();
+        assert_eq!(restored.count(), 3);
+        assert!(restored.verify_all());
+        let first = restored.get_register(RegisterId(1)).unwrap();
+        assert_eq!(first.get_state(), RegisterState::Uninitialized);
+        assert_eq!(first.read(), 0);
+        assert_eq!(restored.get_register(RegisterId(3)).unwrap().read(), 0x3333);
+
+        // Older serializations stored a zero checksum for uncommitted registers
+        for index in 0..2 {
+            let at = BANK_HEADER_SIZE + index * REGISTER_RECORD_SIZE + 17;
+            buf[at..at + 4].fill(0);
+        }
+        let mut legacy = ShadowRegisterBank::new();
+        legacy.deserialize(&buf[..written]).unwrap();
+        assert_eq!(legacy.count(), 3);
+        assert!(legacy.verify_all());
+
+        // A zero checksum still doesn't vouch for a non-zero value
+        let at = BANK_HEADER_SIZE + 2 * REGISTER_RECORD_SIZE + 17;
+        buf[at..at + 4].fill(0);
+        assert_eq!(legacy.deserialize(&buf[..written]), Err(ShadowError::ChecksumMismatch));
+    }
+
+    #[test]
+    fn test_shadow_register_bank_reset_register() {
+        let mut bank = ShadowRegisterBank::new();
+        bank.add_register(RegisterId(7), 0x1000).unwrap();
+        {
+            let reg = bank.get_register_mut(RegisterId(7)).unwrap();
+            reg.write(0xAAAA).unwrap();
+            reg.commit().unwrap();
+            reg.write(0xBBBB).unwrap();
+            reg.commit().unwrap();
+            reg.set_write_protected(true);
+        }
+        bank.lock_register(RegisterId(7), LockMode::WriteProtect).unwrap();
+
+        // A lock needs an explicit override
+        assert_eq!(bank.reset_register(RegisterId(7), false), Err(ShadowError::Locked));
+        assert_eq!(bank.get_register(RegisterId(7)).unwrap().read(), 0xBBBB);
+        assert_eq!(bank.reset_register(RegisterId(8), true), Err(ShadowError::NotFound));
+
+        bank.reset_register(RegisterId(7), true).unwrap();
+        let reg = bank.get_register_mut(RegisterId(7)).unwrap();
+        assert_eq!(reg.get_state(), RegisterState::Uninitialized);
+        assert_eq!(reg.get_version(), 0);
+        assert_eq!(reg.read(), 0);
+        assert_eq!(reg.undo_depth(), 0);
+        assert_eq!(reg.get_write_count(), 0);
+        assert_eq!(reg.get_lock_mode(), LockMode::None);
+        assert_eq!(reg.get_lock_owner(), None);
+        assert!(!reg.is_write_protected());
+        assert_eq!(reg.get_fuse_address(), 0x1000);
+        assert!(reg.verify());
+        assert_eq!(reg.rollback(), Err(ShadowError::NoUndoHistory));
+
+        // The slot is reusable
+        reg.write(0xCCCC).unwrap();
+        reg.commit().unwrap();
+        assert_eq!(reg.read(), 0xCCCC);
+        assert_eq!(reg.get_version(), 1);
+
+        // An unlocked register resets without `force`
+        bank.reset_register(RegisterId(7), false).unwrap();
+        assert_eq!(bank.get_register(RegisterId(7)).unwrap().read(), 0);
+    }
+
+    #[test]
+    fn test_wide_register_write_commit_verify() {
+        let mut reg = WideShadowRegister::<2>::new(RegisterId(9), 0x2000);
+        assert_eq!(WideShadowRegister::<2>::BITS, 128);
+        assert_eq!(reg.word(1).unwrap().get_fuse_address(), 0x2008);
+
+        reg.write(&[0x1111_2222, 0x3333_4444]).unwrap();
+        // Staged only
+        assert_eq!(reg.read(), [0, 0]);
+
+        reg.commit().unwrap();
+        assert_eq!(reg.read(), [0x1111_2222, 0x3333_4444]);
+        assert_eq!(reg.get_version(), 1);
+        assert!(reg.verify());
+        assert_eq!(reg.commit(), Err(ShadowError::NoPendingChanges));
+
+        // Swapping words keeps each word's CRC valid but not the combined one
+        reg.words[0].write(0x3333_4444).unwrap();
+        reg.words[0].commit().unwrap();
+        reg.words[1].write(0x1111_2222).unwrap();
+        reg.words[1].commit().unwrap();
+        assert!(reg.words.iter().all(ShadowRegister::verify));
+        assert!(!reg.verify());
+
+        // A write refused by one word stages none
+        let mut reg = WideShadowRegister::<2>::new(RegisterId(9), 0x2000);
+        reg.words[1].lock(LockMode::WriteProtect);
+        assert_eq!(reg.write(&[1, 2]), Err(ShadowError::Locked));
+        assert_eq!(reg.word(0).unwrap().get_state(), RegisterState::Uninitialized);
+        assert_eq!(reg.commit(), Err(ShadowError::NoPendingChanges));
+    }
+
+    #[test]
+    fn test_wide_register_rollback_across_words() {
+        let mut reg = WideShadowRegister::<2>::new(RegisterId(9), 0x2000);
+        reg.write(&[0xA0, 0xA1]).unwrap();
+        reg.commit().unwrap();
+        reg.write(&[0xB0, 0xB1]).unwrap();
+        reg.commit().unwrap();
+        assert_eq!(reg.get_version(), 2);
+
+        reg.rollback().unwrap();
+        assert_eq!(reg.read(), [0xA0, 0xA1]);
+        assert_eq!(reg.get_version(), 1);
+        assert!(reg.verify());
+
+        // One word short of history: neither word moves
+        reg.words[1].rollback().unwrap();
+        assert_eq!(reg.rollback(), Err(ShadowError::NoUndoHistory));
+        assert_eq!(reg.read(), [0xA0, 0]);
     }
 
     #[test]
     fn test_shadow_register_version_increment() {
-        let mut reg = ShadowRegister::new(1, 0x1000);
+        let mut reg = ShadowRegister::new(RegisterId(1), 0x1000);
 
         assert_eq!(reg.get_version(), 0);
 
@@ -542,4 +2543,142 @@ mod tests {
         reg.rollback().unwrap();
         assert_eq!(reg.get_version(), 2);
     }
+
+    #[test]
+    fn test_shadow_register_multi_level_rollback() {
+        let mut reg = ShadowRegister::new(RegisterId(1), 0x1000);
+        assert_eq!(reg.rollback(), Err(ShadowError::NoUndoHistory));
+        assert_eq!(ShadowError::NoUndoHistory.as_str(), "No more undo history");
+
+        for value in [0x10, 0x20, 0x30] {
+            reg.write(value).unwrap();
+            reg.commit().unwrap();
+        }
+        assert!(reg.compare_and_commit(0x30, 0x40).is_ok());
+        assert_eq!(reg.undo_depth(), 4);
+
+        // Walk back through every committed value, newest first
+        for expected in [0x30, 0x20, 0x10, 0x0] {
+            reg.rollback().unwrap();
+            assert_eq!(reg.read(), expected);
+            assert!(reg.verify());
+        }
+        assert_eq!(reg.rollback(), Err(ShadowError::NoUndoHistory));
+        assert_eq!(reg.read(), 0x0);
+
+        // Committing after a rollback pushes onto what is left
+        reg.write(0x50).unwrap();
+        reg.commit().unwrap();
+        reg.rollback().unwrap();
+        assert_eq!(reg.read(), 0x0);
+    }
+
+    #[test]
+    fn test_shadow_register_undo_drops_oldest() {
+        let mut reg = ShadowRegister::new(RegisterId(1), 0x1000);
+
+        for value in 1..=6 {
+            reg.write(value).unwrap();
+            reg.commit().unwrap();
+        }
+        assert_eq!(reg.undo_depth(), UNDO_DEPTH);
+
+        // Only the last UNDO_DEPTH replaced values are kept
+        for expected in [5, 4, 3, 2] {
+            reg.rollback().unwrap();
+            assert_eq!(reg.read(), expected);
+        }
+        assert_eq!(reg.rollback(), Err(ShadowError::NoUndoHistory));
+        assert_eq!(reg.read(), 2);
+    }
+
+    #[test]
+    fn test_shadow_register_version_saturates() {
+        let mut reg = ShadowRegister::new(RegisterId(1), 0x1000);
+
+        // Rolling back at version 0 stays at 0
+        reg.write(0x1).unwrap();
+        reg.commit().unwrap();
+        reg.version.store(0, Ordering::Release);
+        reg.rollback().unwrap();
+        assert_eq!(reg.get_version(), 0);
+        assert!(!reg.version_saturated());
+
+        // Writes at u32::MAX clamp instead of wrapping
+        reg.version.store(u32::MAX - 1, Ordering::Release);
+        reg.write(0x1).unwrap();
+        assert_eq!(reg.get_version(), u32::MAX);
+        assert!(reg.version_saturated());
+        reg.write_masked(0x2, 0xF).unwrap();
+        reg.commit().unwrap();
+        assert!(reg.compare_and_commit(0x2, 0x3).is_ok());
+        assert_eq!(reg.get_version(), u32::MAX);
+
+        reg.rollback().unwrap();
+        assert_eq!(reg.get_version(), u32::MAX - 1);
+        assert!(!reg.version_saturated());
+    }
+
+    #[test]
+    fn test_bank_view_reflects_live_state() {
+        let mut bank = ShadowRegisterBank::new();
+        bank.add_register(RegisterId(1), 0x1000).unwrap();
+        bank.add_register(RegisterId(2), 0x2000).unwrap();
+        bank.get_register(RegisterId(1)).unwrap().write(0xAA).unwrap();
+
+        let view = bank.view();
+        assert_eq!(view.count(), 2);
+        assert!(view.get_register(RegisterId(3)).is_none());
+
+        let reg = view.get_register(RegisterId(1)).unwrap();
+        assert_eq!(reg.get_state(), RegisterState::Modified);
+        assert_eq!(reg.get_version(), 1);
+
+        // Writes through the bank show up in an existing view
+        bank.get_register(RegisterId(2)).unwrap().write(0xBB).unwrap();
+        let versions: alloc::vec::Vec<u32> = view.iter().map(|reg| reg.get_version()).collect();
+        assert_eq!(versions, [1, 1]);
+        let ids: alloc::vec::Vec<RegisterId> = view.iter().map(|reg| reg.get_id()).collect();
+        assert_eq!(ids, [RegisterId(1), RegisterId(2)]);
+
+        bank.commit_all().unwrap();
+        let view = bank.view();
+        assert!(view.verify_all());
+        let values: alloc::vec::Vec<u64> = view.iter().map(|reg| reg.read()).collect();
+        assert_eq!(values, [0xAA, 0xBB]);
+        assert!(view
+            .iter()
+            .all(|reg| reg.get_state() == RegisterState::Committed));
+    }
+
+    #[test]
+    fn test_register_view_honours_protection_without_poisoning() {
+        let mut bank = ShadowRegisterBank::new();
+        bank.add_register(RegisterId(1), 0x1000).unwrap();
+        let reg = bank.get_register_mut(RegisterId(1)).unwrap();
+        reg.write(0x1234).unwrap();
+        reg.commit().unwrap();
+        reg.lock(LockMode::ReadWriteProtect);
+
+        let view = bank.view();
+        let reg_view = view.get_register(RegisterId(1)).unwrap();
+        assert!(reg_view.is_read_protected());
+        assert_eq!(reg_view.read(), 0);
+        assert_eq!(reg_view.try_read(), Err(ShadowError::ReadProtected));
+
+        // A corrupted value is reported but the view leaves it unpoisoned
+        bank.get_register(RegisterId(1)).unwrap().inject_bit_flips(1 << 3);
+        let view = bank.view();
+        let reg_view = view.get_register(RegisterId(1)).unwrap();
+        assert!(!reg_view.verify());
+        assert!(!view.verify_all());
+        assert!(!reg_view.is_poisoned());
+        assert_eq!(reg_view.get_state(), RegisterState::Locked);
+
+        bank.get_register_mut(RegisterId(1)).unwrap().set_verify_on_read(true);
+        let view = bank.view();
+        let reg_view = view.get_register(RegisterId(1)).unwrap();
+        assert_eq!(reg_view.try_read(), Err(ShadowError::ChecksumMismatch));
+        assert!(!reg_view.is_poisoned());
+    }
 }
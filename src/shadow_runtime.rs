@@ -2,11 +2,37 @@
 /// Complete runtime system combining all shadow register components
 
 use crate::ecc_handler::{ECCManager, ECCStrategy};
-use crate::fuse_manager::{FuseManager, FuseMode};
-use crate::shadow_mmio::ShadowMMIOController;
-use crate::shadow_register::ShadowRegisterBank;
+use crate::fuse_manager::{FuseAddr, FuseManager, FuseMode};
+use crate::mmio::MmioLayout;
+use crate::shadow_mmio::{Capabilities, ShadowMMIOController};
+use crate::shadow_register::{
+    RegisterId, RegisterState, ShadowRegisterBank, SlotIndex,
+};
 use crate::sync_manager::{SyncDirection, SyncManager, SyncPolicy};
 use crate::version_control::{get_timestamp, VersionedShadowRegister};
+use alloc::vec::Vec;
+
+/// Outcome of `ShadowRegisterRuntime::scrub`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScrubReport {
+    /// Registers whose checksum was checked
+    pub checked: usize,
+    /// Registers that failed and could not be corrected
+    pub failed: Vec<RegisterId>,
+    /// Registers repaired from their ECC parity
+    pub corrected: usize,
+}
+
+/// ECC parity recorded for a committed value
+///
+/// Keyed by the value's checksum: the committed value can also change
+/// behind the runtime (through the bank, MMIO or sync), and a parity that
+/// belongs to an older value must not be used to correct a newer one.
+#[derive(Clone, Copy)]
+struct EccRecord {
+    checksum: u32,
+    parity: u8,
+}
 
 /// Shadow Register System Runtime
 pub struct ShadowRegisterRuntime {
@@ -20,17 +46,31 @@ pub struct ShadowRegisterRuntime {
     ecc_manager: ECCManager,
     /// MMIO controller
     mmio_controller: Option<ShadowMMIOController>,
+    /// ECC parity of each slot's committed value, once recorded
+    ecc_parity: [Option<EccRecord>; 256],
+    /// MMIO bases used by `init`
+    layout: MmioLayout,
+    /// Hardware limits reported by `probe_capabilities`
+    capabilities: Option<Capabilities>,
 }
 
 impl ShadowRegisterRuntime {
     /// Create a new shadow register runtime
     pub const fn new() -> Self {
+        Self::with_layout(MmioLayout::DEFAULT)
+    }
+
+    /// Create a runtime whose MMIO controller uses `layout`'s shadow base
+    pub const fn with_layout(layout: MmioLayout) -> Self {
         Self {
             shadow_bank: ShadowRegisterBank::new(),
             fuse_manager: FuseManager::new(),
             sync_manager: SyncManager::new(),
             ecc_manager: ECCManager::new(ECCStrategy::Hamming),
             mmio_controller: None,
+            ecc_parity: [None; 256],
+            layout,
+            capabilities: None,
         }
     }
 
@@ -39,18 +79,54 @@ impl ShadowRegisterRuntime {
         let shadow_ptr = &mut self.shadow_bank as *mut ShadowRegisterBank;
         let fuse_ptr = &mut self.fuse_manager as *mut FuseManager;
 
-        self.mmio_controller = Some(ShadowMMIOController::new(shadow_ptr, fuse_ptr));
+        self.mmio_controller = Some(ShadowMMIOController::with_base(
+            self.layout.shadow_base,
+            shadow_ptr,
+            fuse_ptr,
+        ));
+    }
+
+    /// Read the hardware block's capabilities and size the runtime to them
+    ///
+    /// Once probed, `register_fuse` refuses registers or fuses beyond what
+    /// the block implements. Blocks that don't report capabilities leave
+    /// the runtime unlimited.
+    ///
+    /// # Safety
+    /// The shadow MMIO block from `init` must be mapped.
+    pub unsafe fn probe_capabilities(&mut self) -> Result<Capabilities, &'static str> {
+        let controller = self.mmio_controller.as_ref().ok_or("MMIO not initialized")?;
+        let capabilities = controller.probe();
+        self.capabilities = Some(capabilities).filter(Capabilities::is_reported);
+        Ok(capabilities)
+    }
+
+    /// Hardware limits in effect, if the block reported any
+    pub fn capabilities(&self) -> Option<Capabilities> {
+        self.capabilities
     }
 
     /// Register a new fuse-backed shadow register
     pub fn register_fuse(
         &mut self,
-        register_id: u32,
+        register_id: RegisterId,
         fuse_addr: u64,
         mode: FuseMode,
     ) -> Result<(), &'static str> {
+        let address = FuseAddr::new_in(fuse_addr, self.layout.fuse_base as u64)
+            .ok_or("Invalid fuse address")?;
+
+        if let Some(capabilities) = self.capabilities {
+            if self.shadow_bank.count() >= capabilities.num_registers as usize {
+                return Err("Register capacity exceeded");
+            }
+            if self.fuse_manager.count() >= capabilities.num_fuses as usize {
+                return Err("Fuse capacity exceeded");
+            }
+        }
+
         // Add fuse to manager
-        self.fuse_manager.add_fuse(fuse_addr, mode)?;
+        self.fuse_manager.add_fuse_with_id(register_id, address, mode)?;
 
         // Add corresponding shadow register
         self.shadow_bank.add_register(register_id, fuse_addr)?;
@@ -60,30 +136,59 @@ impl ShadowRegisterRuntime {
 
     /// Load all fuses into shadow registers
     pub unsafe fn load_from_fuses(&mut self) -> Result<usize, &'static str> {
-        self.fuse_manager.load_all()
+        self.fuse_manager.load_all().map_err(Into::into)
     }
 
     /// Commit all shadow registers to fuses
     pub unsafe fn commit_to_fuses(&mut self) -> Result<usize, &'static str> {
-        self.fuse_manager.commit_all()
+        self.fuse_manager
+            .commit_all()
+            .map(|report| report.committed)
+            .map_err(Into::into)
     }
 
     /// Read a shadow register
-    pub fn read(&self, register_id: u32) -> Result<u64, &'static str> {
+    ///
+    /// Poisoned registers are refused; a failed integrity check poisons
+    /// the register.
+    pub fn read(&self, register_id: RegisterId) -> Result<u64, &'static str> {
         if let Some(reg) = self.shadow_bank.get_register(register_id) {
-            // Verify integrity
-            if !reg.verify() {
-                return Err("Register checksum verification failed");
-            }
-
-            Ok(reg.read())
+            // Verify integrity whatever the register's own setting
+            reg.try_read_verified().map_err(Into::into)
         } else {
             Err("Register not found")
         }
     }
 
+    /// Reload a poisoned register from its fuse
+    ///
+    /// The poison stays if the fuse read fails (e.g. uncorrectable ECC).
+    ///
+    /// # Safety
+    /// The register's fuse address must be readable
+    pub unsafe fn clear_poison(&mut self, register_id: RegisterId) -> Result<(), &'static str> {
+        let slot = self
+            .fuse_manager
+            .get_shadow_bank()
+            .index_of(register_id)
+            .ok_or("Register not found")?;
+        let fuse = self.fuse_manager.get_fuse_mut(slot).ok_or("Register not found")?;
+        let value = fuse.read_from_hardware()?;
+
+        let reg = self
+            .shadow_bank
+            .get_register_mut(register_id)
+            .ok_or("Register not found")?;
+        reg.clear_poison(value);
+        if let Some(slot) = self.shadow_bank.index_of(register_id) {
+            self.record_ecc(slot);
+        }
+
+        Ok(())
+    }
+
     /// Write to a shadow register
-    pub fn write(&mut self, register_id: u32, value: u64) -> Result<(), &'static str> {
+    pub fn write(&mut self, register_id: RegisterId, value: u64) -> Result<(), &'static str> {
         if let Some(reg) = self.shadow_bank.get_register_mut(register_id) {
             // Encode with ECC
             let (_encoded_value, _ecc) = self.ecc_manager.encode_u64(value);
@@ -98,14 +203,113 @@ impl ShadowRegisterRuntime {
     }
 
     /// Commit a shadow register
-    pub fn commit(&mut self, register_id: u32) -> Result<(), &'static str> {
-        if let Some(reg) = self.shadow_bank.get_register_mut(register_id) {
-            reg.commit()
-        } else {
-            Err("Register not found")
+    pub fn commit(&mut self, register_id: RegisterId) -> Result<(), &'static str> {
+        let slot = self.shadow_bank.index_of(register_id).ok_or("Register not found")?;
+        if let Some(reg) = self.shadow_bank.get_by_index_mut(slot) {
+            reg.commit()?;
+        }
+        self.record_ecc(slot);
+
+        Ok(())
+    }
+
+    /// Remember the ECC parity of the committed value in `slot` for `scrub`
+    fn record_ecc(&mut self, slot: SlotIndex) {
+        if let Some(reg) = self.shadow_bank.get_by_index(slot) {
+            let (value, checksum) = reg.committed_pair();
+            let (_, parity) = self.ecc_manager.encode_u64(value);
+            self.ecc_parity[slot.0] = Some(EccRecord { checksum, parity });
         }
     }
 
+    /// Check every register's integrity and correct single-bit errors
+    ///
+    /// Memory scrubbing pass: registers failing `verify` are decoded with
+    /// the ECC parity recorded for their committed value and repaired if
+    /// the result matches their checksum; the rest stay poisoned and are
+    /// listed in `failed`. Registers that pass have their parity refreshed,
+    /// which picks up values committed behind the runtime's back; a value
+    /// corrupted before any scrub saw it has no parity and can't be
+    /// corrected. Uninitialized registers have nothing to check and are
+    /// skipped.
+    pub fn scrub(&mut self) -> ScrubReport {
+        let mut report = ScrubReport::default();
+
+        for index in 0..self.shadow_bank.get_register_count() {
+            let slot = SlotIndex(index);
+            let record = self.ecc_parity[index];
+            let reg = match self.shadow_bank.get_by_index_mut(slot) {
+                Some(reg) => reg,
+                None => continue,
+            };
+
+            if reg.get_state() == RegisterState::Uninitialized {
+                continue;
+            }
+
+            report.checked += 1;
+            if reg.verify() {
+                self.record_ecc(slot);
+                continue;
+            }
+
+            // Only a parity recorded for this exact committed value can help
+            let (value, checksum) = reg.committed_pair();
+            let parity = match record {
+                Some(record) if record.checksum == checksum => Some(record.parity),
+                _ => None,
+            };
+            let repaired = match parity.map(|parity| self.ecc_manager.decode_u64(value, parity)) {
+                Some(Ok((candidate, _))) => reg.repair(candidate),
+                _ => false,
+            };
+            if repaired {
+                report.corrected += 1;
+            } else {
+                report.failed.push(reg.get_id());
+            }
+        }
+
+        report
+    }
+
+    /// Write and commit several registers as one all-or-nothing update
+    ///
+    /// Unknown ids abort before anything is written. If any staging write
+    /// fails, the writes already staged are discarded and every register
+    /// keeps its committed value. A repeated id keeps its last value.
+    pub fn transaction(&mut self, writes: &[(RegisterId, u64)]) -> Result<(), &'static str> {
+        if writes.iter().any(|&(id, _)| self.shadow_bank.get_register(id).is_none()) {
+            return Err("Register not found");
+        }
+
+        for (staged, &(id, value)) in writes.iter().enumerate() {
+            if let Err(err) = self.write(id, value) {
+                for &(id, _) in &writes[..staged] {
+                    if let Some(reg) = self.shadow_bank.get_register_mut(id) {
+                        // Repeated ids are already discarded
+                        let _ = reg.discard();
+                    }
+                }
+                return Err(err);
+            }
+        }
+
+        for &(id, _) in writes {
+            let slot = self.shadow_bank.index_of(id).ok_or("Register not found")?;
+            if let Some(reg) = self.shadow_bank.get_by_index_mut(slot) {
+                // Repeated ids were committed on their first occurrence
+                if reg.get_state() != RegisterState::Modified {
+                    continue;
+                }
+                reg.commit()?;
+            }
+            self.record_ecc(slot);
+        }
+
+        Ok(())
+    }
+
     /// Synchronize registers with fuses
     pub unsafe fn sync(
         &mut self,
@@ -129,6 +333,46 @@ impl ShadowRegisterRuntime {
         self.ecc_manager.get_total_errors()
     }
 
+    /// Write a human-readable state dump (ECC stats, registers, fuse count)
+    ///
+    /// Intended for post-mortem output, e.g. from a panic handler. Goes
+    /// through the bank's read-only view, so a checksum mismatch is reported
+    /// as `verify=FAIL` without poisoning the register.
+    pub fn dump_state(&self, out: &mut dyn core::fmt::Write) -> core::fmt::Result {
+        let view = self.shadow_bank.view();
+        let (detected, corrected) = self.get_ecc_stats();
+        writeln!(out, "Shadow Registers:")?;
+        writeln!(
+            out,
+            "  ECC errors: {} detected, {} corrected ({} words)",
+            detected,
+            corrected,
+            self.ecc_manager.get_words_processed()
+        )?;
+        writeln!(
+            out,
+            "  Registers: {} (checksums {})",
+            view.count(),
+            if view.verify_all() { "ok" } else { "CORRUPT" }
+        )?;
+
+        for reg in view.iter() {
+            write!(out, "  [{:#06x}] {:?} ", reg.get_id().0, reg.get_state())?;
+            match reg.try_read() {
+                Ok(value) => write!(out, "value={:#018x}", value)?,
+                Err(_) => write!(out, "value=<read-protected>")?,
+            }
+            writeln!(
+                out,
+                " v{} verify={}",
+                reg.get_version(),
+                if reg.verify() { "ok" } else { "FAIL" }
+            )?;
+        }
+
+        writeln!(out, "  Fuses: {}", self.fuse_manager.count())
+    }
+
     /// Get shadow bank
     #[inline(always)]
     pub fn get_shadow_bank(&self) -> &ShadowRegisterBank {
@@ -179,7 +423,7 @@ pub struct VersionedShadowRuntime {
 impl VersionedShadowRuntime {
     /// Create a new versioned shadow runtime
     pub const fn new() -> Self {
-        const INIT: VersionedShadowRegister = VersionedShadowRegister::new(0, 0);
+        const INIT: VersionedShadowRegister = VersionedShadowRegister::new(RegisterId(0), 0);
         Self {
             registers: [INIT; 64],
             count: 0,
@@ -188,7 +432,7 @@ impl VersionedShadowRuntime {
     }
 
     /// Add a new versioned register
-    pub fn add_register(&mut self, id: u32, fuse_addr: u64) -> Result<usize, &'static str> {
+    pub fn add_register(&mut self, id: RegisterId, fuse_addr: u64) -> Result<usize, &'static str> {
         if self.count >= 64 {
             return Err("Runtime is full");
         }
@@ -236,6 +480,15 @@ impl VersionedShadowRuntime {
         self.registers[index].rollback_by_offset(offset)
     }
 
+    /// Get the version counter of a register's history
+    pub fn current_version(&self, index: usize) -> Result<u32, &'static str> {
+        if index >= self.count {
+            return Err("Invalid register index");
+        }
+
+        Ok(self.registers[index].get_history().current_version())
+    }
+
     /// Get register
     pub fn get_register(&self, index: usize) -> Option<&VersionedShadowRegister> {
         if index < self.count {
@@ -256,6 +509,10 @@ impl VersionedShadowRuntime {
 }
 
 /// FFI interface for C integration
+///
+/// # Safety
+/// Must be called after the heap is up. Calls `init`, so the MMIO layout
+/// must be mapped. The runtime is leaked and is never freed
 #[no_mangle]
 pub unsafe extern "C" fn shadow_runtime_init() -> *mut ShadowRegisterRuntime {
     let runtime = Box::leak(Box::new(ShadowRegisterRuntime::new()));
@@ -263,6 +520,9 @@ pub unsafe extern "C" fn shadow_runtime_init() -> *mut ShadowRegisterRuntime {
     runtime as *mut ShadowRegisterRuntime
 }
 
+/// # Safety
+/// `runtime` must be null or a pointer returned by `shadow_runtime_init`,
+/// with no other reference to the runtime live for the duration of the call
 #[no_mangle]
 pub unsafe extern "C" fn shadow_runtime_register_fuse(
     runtime: *mut ShadowRegisterRuntime,
@@ -278,15 +538,20 @@ pub unsafe extern "C" fn shadow_runtime_register_fuse(
         0 => FuseMode::OTP,
         1 => FuseMode::MTP,
         2 => FuseMode::EEPROM,
+        3 => FuseMode::ReadOnly,
         _ => return -1,
     };
 
-    match (*runtime).register_fuse(register_id, fuse_addr, fuse_mode) {
+    match (*runtime).register_fuse(RegisterId(register_id), fuse_addr, fuse_mode) {
         Ok(_) => 0,
         Err(_) => -1,
     }
 }
 
+/// # Safety
+/// `runtime` must be null or a pointer returned by `shadow_runtime_init`,
+/// with no other reference to the runtime live for the duration of the call.
+/// `out_value` must be null or valid for a `u64` write
 #[no_mangle]
 pub unsafe extern "C" fn shadow_runtime_read(
     runtime: *mut ShadowRegisterRuntime,
@@ -297,7 +562,7 @@ pub unsafe extern "C" fn shadow_runtime_read(
         return -1;
     }
 
-    match (*runtime).read(register_id) {
+    match (*runtime).read(RegisterId(register_id)) {
         Ok(value) => {
             *out_value = value;
             0
@@ -306,6 +571,9 @@ pub unsafe extern "C" fn shadow_runtime_read(
     }
 }
 
+/// # Safety
+/// `runtime` must be null or a pointer returned by `shadow_runtime_init`,
+/// with no other reference to the runtime live for the duration of the call
 #[no_mangle]
 pub unsafe extern "C" fn shadow_runtime_write(
     runtime: *mut ShadowRegisterRuntime,
@@ -316,12 +584,15 @@ pub unsafe extern "C" fn shadow_runtime_write(
         return -1;
     }
 
-    match (*runtime).write(register_id, value) {
+    match (*runtime).write(RegisterId(register_id), value) {
         Ok(_) => 0,
         Err(_) => -1,
     }
 }
 
+/// # Safety
+/// `runtime` must be null or a pointer returned by `shadow_runtime_init`,
+/// with no other reference to the runtime live for the duration of the call
 #[no_mangle]
 pub unsafe extern "C" fn shadow_runtime_commit(
     runtime: *mut ShadowRegisterRuntime,
@@ -331,12 +602,15 @@ pub unsafe extern "C" fn shadow_runtime_commit(
         return -1;
     }
 
-    match (*runtime).commit(register_id) {
+    match (*runtime).commit(RegisterId(register_id)) {
         Ok(_) => 0,
         Err(_) => -1,
     }
 }
 
+/// # Safety
+/// `runtime` must be null or a pointer returned by `shadow_runtime_init`,
+/// with no other reference to the runtime live for the duration of the call
 #[no_mangle]
 pub unsafe extern "C" fn shadow_runtime_load_from_fuses(
     runtime: *mut ShadowRegisterRuntime,
@@ -351,6 +625,9 @@ pub unsafe extern "C" fn shadow_runtime_load_from_fuses(
     }
 }
 
+/// # Safety
+/// `runtime` must be null or a pointer returned by `shadow_runtime_init`,
+/// with no other reference to the runtime live for the duration of the call
 #[no_mangle]
 pub unsafe extern "C" fn shadow_runtime_commit_to_fuses(
     runtime: *mut ShadowRegisterRuntime,
@@ -365,6 +642,9 @@ pub unsafe extern "C" fn shadow_runtime_commit_to_fuses(
     }
 }
 
+/// # Safety
+/// `runtime` must be null or a pointer returned by `shadow_runtime_init`,
+/// with no other reference to the runtime live for the duration of the call
 #[no_mangle]
 pub unsafe extern "C" fn shadow_runtime_verify_all(runtime: *mut ShadowRegisterRuntime) -> i32 {
     if runtime.is_null() {
@@ -378,12 +658,124 @@ pub unsafe extern "C" fn shadow_runtime_verify_all(runtime: *mut ShadowRegisterR
     }
 }
 
+/// # Safety
+/// `runtime` must be null or a pointer returned by `shadow_runtime_init`,
+/// with no other reference to the runtime live for the duration of the call.
+/// `out_detected` and `out_corrected` must be null or valid for a `u32` write
+#[no_mangle]
+pub unsafe extern "C" fn shadow_runtime_get_ecc_stats(
+    runtime: *mut ShadowRegisterRuntime,
+    out_detected: *mut u32,
+    out_corrected: *mut u32,
+) -> i32 {
+    if runtime.is_null() || out_detected.is_null() || out_corrected.is_null() {
+        return -1;
+    }
+
+    let (detected, corrected) = (*runtime).get_ecc_stats();
+    *out_detected = detected;
+    *out_corrected = corrected;
+    0
+}
+
+/// # Safety
+/// Must be called after the heap is up. The runtime is leaked and is never
+/// freed
+#[no_mangle]
+pub unsafe extern "C" fn versioned_runtime_init() -> *mut VersionedShadowRuntime {
+    let runtime = Box::leak(Box::new(VersionedShadowRuntime::new()));
+    runtime as *mut VersionedShadowRuntime
+}
+
+/// Returns the register index on success
+///
+/// # Safety
+/// `runtime` must be null or a pointer returned by `versioned_runtime_init`,
+/// with no other reference to the runtime live for the duration of the call
+#[no_mangle]
+pub unsafe extern "C" fn versioned_runtime_add_register(
+    runtime: *mut VersionedShadowRuntime,
+    register_id: u32,
+    fuse_addr: u64,
+) -> i32 {
+    if runtime.is_null() {
+        return -1;
+    }
+
+    match (*runtime).add_register(RegisterId(register_id), fuse_addr) {
+        Ok(index) => index as i32,
+        Err(_) => -1,
+    }
+}
+
+/// # Safety
+/// `runtime` must be null or a pointer returned by `versioned_runtime_init`,
+/// with no other reference to the runtime live for the duration of the call
+#[no_mangle]
+pub unsafe extern "C" fn versioned_runtime_write(
+    runtime: *mut VersionedShadowRuntime,
+    index: u32,
+    value: u64,
+) -> i32 {
+    if runtime.is_null() {
+        return -1;
+    }
+
+    match (*runtime).write_versioned(index as usize, value) {
+        Ok(_) => 0,
+        Err(_) => -1,
+    }
+}
+
+/// # Safety
+/// `runtime` must be null or a pointer returned by `versioned_runtime_init`,
+/// with no other reference to the runtime live for the duration of the call
+#[no_mangle]
+pub unsafe extern "C" fn versioned_runtime_rollback_offset(
+    runtime: *mut VersionedShadowRuntime,
+    index: u32,
+    offset: u32,
+) -> i32 {
+    if runtime.is_null() {
+        return -1;
+    }
+
+    match (*runtime).rollback_by_offset(index as usize, offset as usize) {
+        Ok(_) => 0,
+        Err(_) => -1,
+    }
+}
+
+/// # Safety
+/// `runtime` must be null or a pointer returned by `versioned_runtime_init`,
+/// with no other reference to the runtime live for the duration of the call.
+/// `out_version` must be null or valid for a `u32` write
+#[no_mangle]
+pub unsafe extern "C" fn versioned_runtime_current_version(
+    runtime: *mut VersionedShadowRuntime,
+    index: u32,
+    out_version: *mut u32,
+) -> i32 {
+    if runtime.is_null() || out_version.is_null() {
+        return -1;
+    }
+
+    match (*runtime).current_version(index as usize) {
+        Ok(version) => {
+            *out_version = version;
+            0
+        }
+        Err(_) => -1,
+    }
+}
+
 extern crate alloc;
 use alloc::boxed::Box;
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::fuse_manager::FUSE_BASE;
     use alloc::boxed::Box;
 
     /// Test: ShadowRegisterRuntime initialization
@@ -392,7 +784,7 @@ mod tests {
         let runtime = ShadowRegisterRuntime::new();
 
         // Verify empty bank has no registers
-        assert!(runtime.shadow_bank.get_register(0).is_none());
+        assert!(runtime.shadow_bank.get_register(RegisterId(0)).is_none());
         assert!(runtime.mmio_controller.is_none());
         assert!(runtime.verify_all());
     }
@@ -409,16 +801,109 @@ mod tests {
         assert!(runtime.mmio_controller.is_some());
     }
 
+    /// Test: A custom layout moves the MMIO controller off SHADOW_REG_BASE
+    #[test]
+    fn test_shadow_register_runtime_custom_layout() {
+        let layout = MmioLayout::DEFAULT.with_shadow_base(0x7000_0000);
+        let mut runtime = ShadowRegisterRuntime::with_layout(layout);
+
+        unsafe {
+            runtime.init();
+        }
+
+        assert_eq!(runtime.get_mmio_controller().unwrap().base(), 0x7000_0000);
+
+        let mut default = ShadowRegisterRuntime::new();
+        unsafe {
+            default.init();
+        }
+        assert_eq!(
+            default.get_mmio_controller().unwrap().base(),
+            crate::shadow_mmio::SHADOW_REG_BASE
+        );
+    }
+
+    /// Test: Fuse addresses are validated against the layout's fuse window
+    #[test]
+    fn test_shadow_register_runtime_layout_fuse_base() {
+        let layout = MmioLayout::DEFAULT.with_fuse_base(0x6000_0000);
+        let mut runtime = ShadowRegisterRuntime::with_layout(layout);
+
+        assert!(runtime.register_fuse(RegisterId(1), 0x6000_0008, FuseMode::MTP).is_ok());
+        assert_eq!(
+            runtime.register_fuse(RegisterId(2), FUSE_BASE, FuseMode::MTP),
+            Err("Invalid fuse address")
+        );
+        assert_eq!(runtime.get_fuse_manager().count(), 1);
+    }
+
+    /// Test: Probed capabilities cap how many fuses can be registered
+    #[test]
+    fn test_shadow_register_runtime_probe_capabilities() {
+        use crate::fuse_manager::FUSE_BASE;
+        use crate::shadow_mmio::ShadowRegisterMMIO;
+
+        let mut block = Box::new(ShadowRegisterMMIO {
+            control: 0,
+            data: 0,
+            address: 0,
+            status: 0,
+            ecc: 0,
+            capabilities: Capabilities {
+                num_registers: 2,
+                num_fuses: 8,
+                ecc_supported: true,
+                version: 1,
+            }
+            .to_word(),
+        });
+        let base = &mut *block as *mut ShadowRegisterMMIO as usize;
+        let layout = MmioLayout::DEFAULT.with_shadow_base(base);
+        let mut runtime = Box::new(ShadowRegisterRuntime::with_layout(layout));
+
+        unsafe {
+            assert_eq!(runtime.probe_capabilities(), Err("MMIO not initialized"));
+            runtime.init();
+            assert_eq!(runtime.probe_capabilities().unwrap().num_registers, 2);
+        }
+
+        runtime.register_fuse(RegisterId(0), FUSE_BASE, FuseMode::MTP).unwrap();
+        runtime.register_fuse(RegisterId(1), FUSE_BASE + 8, FuseMode::MTP).unwrap();
+        assert_eq!(
+            runtime.register_fuse(RegisterId(2), FUSE_BASE + 16, FuseMode::MTP),
+            Err("Register capacity exceeded")
+        );
+
+        // A block without a capability word leaves the runtime unlimited
+        block.capabilities = 0;
+        unsafe {
+            assert!(!runtime.probe_capabilities().unwrap().is_reported());
+        }
+        assert_eq!(runtime.capabilities(), None);
+        runtime.register_fuse(RegisterId(2), FUSE_BASE + 16, FuseMode::MTP).unwrap();
+    }
+
     /// Test: Register a fuse successfully
     #[test]
     fn test_shadow_register_runtime_register_fuse_success() {
         let mut runtime = ShadowRegisterRuntime::new();
 
-        let result = runtime.register_fuse(1, 0x1000, FuseMode::OTP);
+        let result = runtime.register_fuse(RegisterId(1), FUSE_BASE + 0x1000, FuseMode::OTP);
         assert!(result.is_ok());
 
         // Verify the register was added
-        assert!(runtime.shadow_bank.get_register(1).is_some());
+        assert!(runtime.shadow_bank.get_register(RegisterId(1)).is_some());
+    }
+
+    /// Test: Addresses outside the fuse window are rejected
+    #[test]
+    fn test_shadow_register_runtime_register_fuse_invalid_address() {
+        let mut runtime = ShadowRegisterRuntime::new();
+
+        let result = runtime.register_fuse(RegisterId(1), 0x1000, FuseMode::OTP);
+        assert_eq!(result, Err("Invalid fuse address"));
+        assert!(runtime.shadow_bank.get_register(RegisterId(1)).is_none());
+        assert_eq!(runtime.fuse_manager.count(), 0);
     }
 
     /// Test: Register duplicate returns error on full bank
@@ -427,16 +912,16 @@ mod tests {
         let mut runtime = ShadowRegisterRuntime::new();
 
         // Register first fuse
-        let result1 = runtime.register_fuse(1, 0x1000, FuseMode::OTP);
+        let result1 = runtime.register_fuse(RegisterId(1), FUSE_BASE + 0x1000, FuseMode::OTP);
         assert!(result1.is_ok());
 
         // Registering different register_id with different fuse address should succeed
-        let result2 = runtime.register_fuse(2, 0x2000, FuseMode::MTP);
+        let result2 = runtime.register_fuse(RegisterId(2), FUSE_BASE + 0x2000, FuseMode::MTP);
         assert!(result2.is_ok());
 
         // Verify both registers exist
-        assert!(runtime.shadow_bank.get_register(1).is_some());
-        assert!(runtime.shadow_bank.get_register(2).is_some());
+        assert!(runtime.shadow_bank.get_register(RegisterId(1)).is_some());
+        assert!(runtime.shadow_bank.get_register(RegisterId(2)).is_some());
     }
 
     /// Test: Read from existing register
@@ -445,41 +930,159 @@ mod tests {
         let mut runtime = ShadowRegisterRuntime::new();
 
         // Register, write, and commit
-        runtime.register_fuse(1, 0x1000, FuseMode::OTP).unwrap();
-        runtime.write(1, 0x12345678).unwrap();
-        runtime.commit(1).unwrap();
+        runtime.register_fuse(RegisterId(1), FUSE_BASE + 0x1000, FuseMode::OTP).unwrap();
+        runtime.write(RegisterId(1), 0x12345678).unwrap();
+        runtime.commit(RegisterId(1)).unwrap();
 
         // Read back
-        let result = runtime.read(1);
+        let result = runtime.read(RegisterId(1));
         assert!(result.is_ok());
         assert_eq!(result.unwrap(), 0x12345678);
     }
 
+    /// Test: Read-protected registers refuse reads
+    #[test]
+    fn test_shadow_register_runtime_read_protected() {
+        use crate::shadow_register::LockMode;
+
+        let mut runtime = ShadowRegisterRuntime::new();
+        runtime.register_fuse(RegisterId(1), FUSE_BASE + 0x1000, FuseMode::OTP).unwrap();
+        runtime.write(RegisterId(1), 0x5EC2E7).unwrap();
+        runtime.commit(RegisterId(1)).unwrap();
+
+        runtime.shadow_bank.lock_register(RegisterId(1), LockMode::WriteProtect).unwrap();
+        assert_eq!(runtime.read(RegisterId(1)), Ok(0x5EC2E7));
+
+        runtime.shadow_bank.lock_register(RegisterId(1), LockMode::ReadWriteProtect).unwrap();
+        assert_eq!(runtime.read(RegisterId(1)), Err("Register is read-protected"));
+        assert_eq!(runtime.write(RegisterId(1), 0), Err("Register is locked"));
+
+        let mut out = alloc::string::String::new();
+        runtime.dump_state(&mut out).unwrap();
+        assert!(out.contains("[0x0001] Locked value=<read-protected> v1"));
+    }
+
+    /// Test: Poisoned registers refuse reads until reloaded from fuse
+    #[test]
+    fn test_shadow_register_runtime_poison_and_clear() {
+        let mut memory = Box::new([0u64; 4]);
+        let addr = memory.as_mut_ptr() as u64;
+
+        // Fuse window addresses can't be dereferenced here, so wire the
+        // fuse to test memory directly
+        let mut runtime = ShadowRegisterRuntime::new();
+        runtime.fuse_manager.add_fuse_with_id(RegisterId(7), addr.into(), FuseMode::MTP).unwrap();
+        runtime.shadow_bank.add_register(RegisterId(7), addr).unwrap();
+        memory[0] = 0xF00D;
+
+        runtime.write(RegisterId(7), 0xBEEF).unwrap();
+        runtime.commit(RegisterId(7)).unwrap();
+
+        // Clearing a healthy register is a no-op
+        unsafe { runtime.clear_poison(RegisterId(7)).unwrap() };
+        assert_eq!(runtime.read(RegisterId(7)), Ok(0xBEEF));
+
+        runtime.shadow_bank.get_register(RegisterId(7)).unwrap().poison();
+        assert_eq!(runtime.read(RegisterId(7)), Err("Register is poisoned"));
+        assert_eq!(runtime.write(RegisterId(7), 1), Err("Register is poisoned"));
+
+        unsafe { runtime.clear_poison(RegisterId(7)).unwrap() };
+        let reg = runtime.shadow_bank.get_register(RegisterId(7)).unwrap();
+        assert_eq!(reg.get_state(), RegisterState::Loaded);
+        assert_eq!(runtime.read(RegisterId(7)), Ok(0xF00D));
+    }
+
     /// Test: Read from non-existent register
     #[test]
     fn test_shadow_register_runtime_read_not_found() {
         let runtime = ShadowRegisterRuntime::new();
 
-        let result = runtime.read(999);
+        let result = runtime.read(RegisterId(999));
         assert!(result.is_err());
         assert_eq!(result.unwrap_err(), "Register not found");
     }
 
+    /// Test: Transaction commits every write together
+    #[test]
+    fn test_shadow_register_runtime_transaction_success() {
+        let mut runtime = ShadowRegisterRuntime::new();
+        for id in 1..=3 {
+            runtime.register_fuse(RegisterId(id), FUSE_BASE + id as u64 * 8, FuseMode::MTP).unwrap();
+        }
+
+        let writes = [(RegisterId(1), 0x11), (RegisterId(3), 0x33), (RegisterId(2), 0x22)];
+        assert_eq!(runtime.transaction(&writes), Ok(()));
+
+        for (id, value) in writes {
+            assert_eq!(runtime.read(id), Ok(value));
+            let reg = runtime.shadow_bank.get_register(id).unwrap();
+            assert_eq!(reg.get_state(), RegisterState::Committed);
+        }
+    }
+
+    /// Test: Unknown id aborts the transaction before any write
+    #[test]
+    fn test_shadow_register_runtime_transaction_missing_id() {
+        let mut runtime = ShadowRegisterRuntime::new();
+        for id in 1..=3 {
+            runtime.register_fuse(RegisterId(id), FUSE_BASE + id as u64 * 8, FuseMode::MTP).unwrap();
+            runtime.write(RegisterId(id), id as u64).unwrap();
+            runtime.commit(RegisterId(id)).unwrap();
+        }
+
+        let writes = [(RegisterId(1), 0xA1), (RegisterId(99), 0xA9), (RegisterId(3), 0xA3)];
+        assert_eq!(runtime.transaction(&writes), Err("Register not found"));
+
+        for id in 1..=3 {
+            assert_eq!(runtime.read(RegisterId(id)), Ok(id as u64));
+            let reg = runtime.shadow_bank.get_register(RegisterId(id)).unwrap();
+            assert_eq!(reg.get_state(), RegisterState::Committed);
+            assert_eq!(reg.get_version(), 1);
+        }
+    }
+
+    /// Test: Failed staging write discards the writes staged before it
+    #[test]
+    fn test_shadow_register_runtime_transaction_rolls_back() {
+        use crate::shadow_register::LockMode;
+
+        let mut runtime = ShadowRegisterRuntime::new();
+        for id in 1..=3 {
+            runtime.register_fuse(RegisterId(id), FUSE_BASE + id as u64 * 8, FuseMode::MTP).unwrap();
+            runtime.write(RegisterId(id), id as u64).unwrap();
+            runtime.commit(RegisterId(id)).unwrap();
+        }
+        runtime.shadow_bank.lock_register(RegisterId(3), LockMode::WriteProtect).unwrap();
+
+        let writes = [(RegisterId(1), 0xA1), (RegisterId(2), 0xA2), (RegisterId(3), 0xA3)];
+        assert_eq!(runtime.transaction(&writes), Err("Register is locked"));
+
+        for id in 1..=2 {
+            let reg = runtime.shadow_bank.get_register(RegisterId(id)).unwrap();
+            assert_eq!(reg.read(), id as u64);
+            assert_eq!(reg.get_state(), RegisterState::Committed);
+            assert_eq!(reg.get_version(), 1);
+        }
+
+        // Nothing was left staged behind
+        assert_eq!(runtime.commit(RegisterId(1)), Err("No pending changes to commit"));
+    }
+
     /// Test: Write to existing register
     #[test]
     fn test_shadow_register_runtime_write_success() {
         let mut runtime = ShadowRegisterRuntime::new();
 
-        runtime.register_fuse(1, 0x1000, FuseMode::OTP).unwrap();
+        runtime.register_fuse(RegisterId(1), FUSE_BASE + 0x1000, FuseMode::OTP).unwrap();
 
-        let result = runtime.write(1, 0xDEADBEEF);
+        let result = runtime.write(RegisterId(1), 0xDEADBEEF);
         assert!(result.is_ok());
 
         // Commit to update checksum
-        runtime.commit(1).unwrap();
+        runtime.commit(RegisterId(1)).unwrap();
 
         // Verify the write
-        let read_result = runtime.read(1);
+        let read_result = runtime.read(RegisterId(1));
         assert_eq!(read_result.unwrap(), 0xDEADBEEF);
     }
 
@@ -488,7 +1091,7 @@ mod tests {
     fn test_shadow_register_runtime_write_not_found() {
         let mut runtime = ShadowRegisterRuntime::new();
 
-        let result = runtime.write(999, 0x12345678);
+        let result = runtime.write(RegisterId(999), 0x12345678);
         assert!(result.is_err());
         assert_eq!(result.unwrap_err(), "Register not found");
     }
@@ -498,10 +1101,10 @@ mod tests {
     fn test_shadow_register_runtime_commit_success() {
         let mut runtime = ShadowRegisterRuntime::new();
 
-        runtime.register_fuse(1, 0x1000, FuseMode::OTP).unwrap();
-        runtime.write(1, 0x12345678).unwrap();
+        runtime.register_fuse(RegisterId(1), FUSE_BASE + 0x1000, FuseMode::OTP).unwrap();
+        runtime.write(RegisterId(1), 0x12345678).unwrap();
 
-        let result = runtime.commit(1);
+        let result = runtime.commit(RegisterId(1));
         assert!(result.is_ok());
     }
 
@@ -510,7 +1113,7 @@ mod tests {
     fn test_shadow_register_runtime_commit_not_found() {
         let mut runtime = ShadowRegisterRuntime::new();
 
-        let result = runtime.commit(999);
+        let result = runtime.commit(RegisterId(999));
         assert!(result.is_err());
         assert_eq!(result.unwrap_err(), "Register not found");
     }
@@ -524,9 +1127,9 @@ mod tests {
         assert!(runtime.verify_all());
 
         // Add, write, commit and verify with registers
-        runtime.register_fuse(1, 0x1000, FuseMode::OTP).unwrap();
-        runtime.write(1, 0x12345678).unwrap();
-        runtime.commit(1).unwrap();
+        runtime.register_fuse(RegisterId(1), FUSE_BASE + 0x1000, FuseMode::OTP).unwrap();
+        runtime.write(RegisterId(1), 0x12345678).unwrap();
+        runtime.commit(RegisterId(1)).unwrap();
 
         assert!(runtime.verify_all());
     }
@@ -543,6 +1146,124 @@ mod tests {
         assert_eq!(multi_bit, 0);
     }
 
+    /// Test: State dump lists ECC stats and every register
+    #[test]
+    fn test_shadow_register_runtime_dump_state() {
+        use alloc::string::String;
+
+        let mut runtime = ShadowRegisterRuntime::new();
+        runtime.register_fuse(RegisterId(0x10), FUSE_BASE, FuseMode::MTP).unwrap();
+        runtime.register_fuse(RegisterId(0x20), FUSE_BASE + 8, FuseMode::MTP).unwrap();
+        runtime.write(RegisterId(0x20), 0xABCD).unwrap();
+        runtime.commit(RegisterId(0x20)).unwrap();
+
+        let mut out = String::new();
+        runtime.dump_state(&mut out).unwrap();
+
+        assert!(out.starts_with("Shadow Registers:\n"));
+        assert!(out.contains("ECC errors: 0 detected, 0 corrected"));
+        assert!(out.contains("Registers: 2 (checksums ok)"));
+        assert!(out.contains("[0x0010] Uninitialized value=0x0000000000000000 v0 verify=ok"));
+        assert!(out.contains("[0x0020] Committed value=0x000000000000abcd v1 verify=ok"));
+        assert!(out.ends_with("Fuses: 2\n"));
+    }
+
+    /// Test: Dumping a corrupted register reports it without poisoning it
+    #[test]
+    fn test_shadow_register_runtime_dump_state_leaves_state() {
+        use alloc::string::String;
+
+        let mut runtime = ShadowRegisterRuntime::new();
+        runtime.register_fuse(RegisterId(0x20), FUSE_BASE, FuseMode::MTP).unwrap();
+        runtime.write(RegisterId(0x20), 0xABCD).unwrap();
+        runtime.commit(RegisterId(0x20)).unwrap();
+        let reg = runtime.get_shadow_bank().get_register(RegisterId(0x20)).unwrap();
+        reg.inject_bit_flips(1 << 4);
+
+        let mut out = String::new();
+        runtime.dump_state(&mut out).unwrap();
+
+        assert!(out.contains("Registers: 1 (checksums CORRUPT)"));
+        assert!(out.contains("[0x0020] Committed value=0x000000000000abdd v1 verify=FAIL"));
+        let reg = runtime.get_shadow_bank().get_register(RegisterId(0x20)).unwrap();
+        assert_eq!(reg.get_state(), RegisterState::Committed);
+    }
+
+    /// Test: Scrub passes clean registers, corrects single-bit rot, reports the rest
+    #[test]
+    fn test_shadow_register_runtime_scrub() {
+        let mut runtime = ShadowRegisterRuntime::new();
+        for id in 1..=4 {
+            runtime.register_fuse(RegisterId(id), FUSE_BASE + id as u64 * 8, FuseMode::MTP).unwrap();
+        }
+        runtime
+            .transaction(&[(RegisterId(1), 0x1111), (RegisterId(2), 0x2222)])
+            .unwrap();
+        runtime.write(RegisterId(3), 0x0123_4567_89AB_CDEF).unwrap();
+        runtime.commit(RegisterId(3)).unwrap();
+        // Register 4 stays uninitialized and is not checked
+
+        let bank = &runtime.shadow_bank;
+        bank.get_register(RegisterId(2)).unwrap().inject_bit_flips(1 << 20);
+        bank.get_register(RegisterId(3)).unwrap().inject_bit_flips((1 << 3) | (1 << 40));
+
+        let report = runtime.scrub();
+        assert_eq!(report.checked, 3);
+        assert_eq!(report.corrected, 1);
+        assert_eq!(report.failed, [RegisterId(3)]);
+
+        assert_eq!(runtime.read(RegisterId(1)), Ok(0x1111));
+        assert_eq!(runtime.read(RegisterId(2)), Ok(0x2222));
+        assert_eq!(runtime.read(RegisterId(3)), Err("Register is poisoned"));
+
+        // A second pass finds only the register that is still bad
+        let again = runtime.scrub();
+        assert_eq!(again.corrected, 0);
+        assert_eq!(again.failed, [RegisterId(3)]);
+    }
+
+    /// Test: Scrub corrects values committed outside `commit`/`transaction`
+    #[test]
+    fn test_shadow_register_runtime_scrub_tracks_value_changes() {
+        let mut runtime = ShadowRegisterRuntime::new();
+        runtime.register_fuse(RegisterId(1), FUSE_BASE, FuseMode::MTP).unwrap();
+        runtime.register_fuse(RegisterId(2), FUSE_BASE + 8, FuseMode::MTP).unwrap();
+        for value in [0x1111, 0x2222] {
+            runtime.write(RegisterId(1), value).unwrap();
+            runtime.commit(RegisterId(1)).unwrap();
+        }
+        runtime.write(RegisterId(2), 0xAAAA).unwrap();
+        runtime.commit(RegisterId(2)).unwrap();
+
+        // Roll back and compare-and-commit straight through the bank
+        let bank = runtime.get_shadow_bank_mut();
+        bank.get_register_mut(RegisterId(1)).unwrap().rollback().unwrap();
+        bank.get_register(RegisterId(2)).unwrap().compare_and_commit(0xAAAA, 0xBBBB).unwrap();
+
+        // A clean pass records parity for the new values
+        let report = runtime.scrub();
+        assert_eq!((report.checked, report.corrected), (2, 0));
+        assert!(report.failed.is_empty());
+
+        let bank = runtime.get_shadow_bank();
+        bank.get_register(RegisterId(1)).unwrap().inject_bit_flips(1 << 33);
+        bank.get_register(RegisterId(2)).unwrap().inject_bit_flips(1 << 1);
+        let report = runtime.scrub();
+        assert_eq!(report.corrected, 2);
+        assert_eq!(runtime.read(RegisterId(1)), Ok(0x1111));
+        assert_eq!(runtime.read(RegisterId(2)), Ok(0xBBBB));
+
+        // Changed and corrupted before any scrub: no parity to trust
+        let bank = runtime.get_shadow_bank();
+        let reg = bank.get_register(RegisterId(2)).unwrap();
+        reg.compare_and_commit(0xBBBB, 0xCCCC).unwrap();
+        reg.inject_bit_flips(1 << 4);
+        let report = runtime.scrub();
+        assert_eq!(report.corrected, 0);
+        assert_eq!(report.failed, [RegisterId(2)]);
+    }
+
+
     /// Test: VersionedShadowRuntime initialization
     #[test]
     fn test_versioned_shadow_runtime_new() {
@@ -559,7 +1280,7 @@ mod tests {
     fn test_versioned_shadow_runtime_add_register() {
         let mut runtime = VersionedShadowRuntime::new();
 
-        let result = runtime.add_register(1, 0x1000);
+        let result = runtime.add_register(RegisterId(1), 0x1000);
         assert!(result.is_ok());
         assert_eq!(result.unwrap(), 0);
         assert_eq!(runtime.count, 1);
@@ -575,12 +1296,12 @@ mod tests {
 
         // Fill to capacity (64 registers)
         for i in 0..64 {
-            let result = runtime.add_register(i, 0x1000 + (i as u64 * 0x100));
+            let result = runtime.add_register(RegisterId(i), 0x1000 + (i as u64 * 0x100));
             assert!(result.is_ok());
         }
 
         // Try to add one more - should fail
-        let result = runtime.add_register(64, 0x10000);
+        let result = runtime.add_register(RegisterId(64), 0x10000);
         assert!(result.is_err());
         assert_eq!(result.unwrap_err(), "Runtime is full");
     }
@@ -590,7 +1311,7 @@ mod tests {
     fn test_versioned_shadow_runtime_write_versioned() {
         let mut runtime = VersionedShadowRuntime::new();
 
-        runtime.add_register(1, 0x1000).unwrap();
+        runtime.add_register(RegisterId(1), 0x1000).unwrap();
 
         let result = runtime.write_versioned(0, 0x12345678);
         assert!(result.is_ok());
@@ -615,7 +1336,7 @@ mod tests {
     fn test_versioned_shadow_runtime_rollback_to_version() {
         let mut runtime = VersionedShadowRuntime::new();
 
-        runtime.add_register(1, 0x1000).unwrap();
+        runtime.add_register(RegisterId(1), 0x1000).unwrap();
 
         // Write multiple versions
         let v1 = runtime.write_versioned(0, 0x1111).unwrap();
@@ -632,7 +1353,7 @@ mod tests {
     fn test_versioned_shadow_runtime_rollback_by_offset() {
         let mut runtime = VersionedShadowRuntime::new();
 
-        runtime.add_register(1, 0x1000).unwrap();
+        runtime.add_register(RegisterId(1), 0x1000).unwrap();
 
         // Write multiple versions
         runtime.write_versioned(0, 0x1111).unwrap();
@@ -682,15 +1403,19 @@ mod tests {
             let ptr = shadow_runtime_init();
 
             // Register with OTP mode (0)
-            let result = shadow_runtime_register_fuse(ptr, 1, 0x1000, 0);
+            let result = shadow_runtime_register_fuse(ptr, 1, FUSE_BASE + 0x1000, 0);
             assert_eq!(result, 0);
 
             // Register with MTP mode (1)
-            let result = shadow_runtime_register_fuse(ptr, 2, 0x2000, 1);
+            let result = shadow_runtime_register_fuse(ptr, 2, FUSE_BASE + 0x2000, 1);
             assert_eq!(result, 0);
 
             // Register with EEPROM mode (2)
-            let result = shadow_runtime_register_fuse(ptr, 3, 0x3000, 2);
+            let result = shadow_runtime_register_fuse(ptr, 3, FUSE_BASE + 0x3000, 2);
+            assert_eq!(result, 0);
+
+            // Register with ReadOnly mode (3)
+            let result = shadow_runtime_register_fuse(ptr, 4, FUSE_BASE + 0x4000, 3);
             assert_eq!(result, 0);
 
             // Cleanup
@@ -704,8 +1429,8 @@ mod tests {
         unsafe {
             let ptr = shadow_runtime_init();
 
-            // Invalid mode (3)
-            let result = shadow_runtime_register_fuse(ptr, 1, 0x1000, 3);
+            // Invalid mode (4)
+            let result = shadow_runtime_register_fuse(ptr, 1, FUSE_BASE + 0x1000, 4);
             assert_eq!(result, -1);
 
             // Cleanup
@@ -740,7 +1465,7 @@ mod tests {
             let ptr = shadow_runtime_init();
 
             // Register, write, and commit
-            shadow_runtime_register_fuse(ptr, 1, 0x1000, 0);
+            shadow_runtime_register_fuse(ptr, 1, FUSE_BASE + 0x1000, 0);
             shadow_runtime_write(ptr, 1, 0xDEADBEEF);
             shadow_runtime_commit(ptr, 1);
 
@@ -771,7 +1496,7 @@ mod tests {
         unsafe {
             let ptr = shadow_runtime_init();
 
-            shadow_runtime_register_fuse(ptr, 1, 0x1000, 0);
+            shadow_runtime_register_fuse(ptr, 1, FUSE_BASE + 0x1000, 0);
 
             let result = shadow_runtime_write(ptr, 1, 0xCAFEBABE);
             assert_eq!(result, 0);
@@ -796,7 +1521,7 @@ mod tests {
         unsafe {
             let ptr = shadow_runtime_init();
 
-            shadow_runtime_register_fuse(ptr, 1, 0x1000, 0);
+            shadow_runtime_register_fuse(ptr, 1, FUSE_BASE + 0x1000, 0);
             shadow_runtime_write(ptr, 1, 0x12345678);
 
             let result = shadow_runtime_commit(ptr, 1);
@@ -840,7 +1565,7 @@ mod tests {
         unsafe {
             let ptr = shadow_runtime_init();
 
-            shadow_runtime_register_fuse(ptr, 1, 0x1000, 0);
+            shadow_runtime_register_fuse(ptr, 1, FUSE_BASE + 0x1000, 0);
             shadow_runtime_write(ptr, 1, 0x12345678);
             shadow_runtime_commit(ptr, 1);
 
@@ -852,6 +1577,58 @@ mod tests {
         }
     }
 
+    /// Test: FFI shadow_runtime_get_ecc_stats with null pointers
+    #[test]
+    fn test_ffi_shadow_runtime_get_ecc_stats_null() {
+        unsafe {
+            let mut detected: u32 = 0;
+            let mut corrected: u32 = 0;
+
+            // Null runtime pointer
+            let result = shadow_runtime_get_ecc_stats(
+                core::ptr::null_mut(),
+                &mut detected,
+                &mut corrected,
+            );
+            assert_eq!(result, -1);
+
+            // Null output pointers
+            let ptr = shadow_runtime_init();
+            let result = shadow_runtime_get_ecc_stats(ptr, core::ptr::null_mut(), &mut corrected);
+            assert_eq!(result, -1);
+            let result = shadow_runtime_get_ecc_stats(ptr, &mut detected, core::ptr::null_mut());
+            assert_eq!(result, -1);
+
+            // Cleanup
+            let _ = Box::from_raw(ptr);
+        }
+    }
+
+    /// Test: FFI shadow_runtime_get_ecc_stats success
+    #[test]
+    fn test_ffi_shadow_runtime_get_ecc_stats_success() {
+        unsafe {
+            let ptr = shadow_runtime_init();
+
+            let mut detected: u32 = 0xFF;
+            let mut corrected: u32 = 0xFF;
+            assert_eq!(shadow_runtime_get_ecc_stats(ptr, &mut detected, &mut corrected), 0);
+            assert_eq!(detected, 0);
+            assert_eq!(corrected, 0);
+
+            // Decode a word with a single-bit error
+            let (encoded, parity) = (*ptr).ecc_manager.encode_u64(0x12345678);
+            let _ = (*ptr).ecc_manager.decode_u64(encoded ^ (1u64 << 9), parity);
+
+            assert_eq!(shadow_runtime_get_ecc_stats(ptr, &mut detected, &mut corrected), 0);
+            assert_eq!(detected, 1);
+            assert_eq!(corrected, 1);
+
+            // Cleanup
+            let _ = Box::from_raw(ptr);
+        }
+    }
+
     /// Test: FFI integration - complete workflow
     #[test]
     fn test_ffi_complete_workflow() {
@@ -861,9 +1638,9 @@ mod tests {
             assert!(!ptr.is_null());
 
             // Register multiple fuses
-            assert_eq!(shadow_runtime_register_fuse(ptr, 1, 0x1000, 0), 0);
-            assert_eq!(shadow_runtime_register_fuse(ptr, 2, 0x2000, 1), 0);
-            assert_eq!(shadow_runtime_register_fuse(ptr, 3, 0x3000, 2), 0);
+            assert_eq!(shadow_runtime_register_fuse(ptr, 1, FUSE_BASE + 0x1000, 0), 0);
+            assert_eq!(shadow_runtime_register_fuse(ptr, 2, FUSE_BASE + 0x2000, 1), 0);
+            assert_eq!(shadow_runtime_register_fuse(ptr, 3, FUSE_BASE + 0x3000, 2), 0);
 
             // Write to registers
             assert_eq!(shadow_runtime_write(ptr, 1, 0x1111), 0);
@@ -893,4 +1670,85 @@ mod tests {
             let _ = Box::from_raw(ptr);
         }
     }
+
+    /// Test: FFI versioned runtime functions with null pointers
+    #[test]
+    fn test_ffi_versioned_runtime_null() {
+        unsafe {
+            let null = core::ptr::null_mut();
+            let mut version: u32 = 0;
+
+            assert_eq!(versioned_runtime_add_register(null, 1, 0x1000), -1);
+            assert_eq!(versioned_runtime_write(null, 0, 0x1111), -1);
+            assert_eq!(versioned_runtime_rollback_offset(null, 0, 1), -1);
+            assert_eq!(versioned_runtime_current_version(null, 0, &mut version), -1);
+
+            // Null output pointer
+            let ptr = versioned_runtime_init();
+            versioned_runtime_add_register(ptr, 1, 0x1000);
+            assert_eq!(versioned_runtime_current_version(ptr, 0, core::ptr::null_mut()), -1);
+
+            // Cleanup
+            let _ = Box::from_raw(ptr);
+        }
+    }
+
+    /// Test: FFI versioned runtime invalid index
+    #[test]
+    fn test_ffi_versioned_runtime_invalid_index() {
+        unsafe {
+            let ptr = versioned_runtime_init();
+            let mut version: u32 = 0;
+
+            assert_eq!(versioned_runtime_write(ptr, 0, 0x1111), -1);
+            assert_eq!(versioned_runtime_rollback_offset(ptr, 0, 0), -1);
+            assert_eq!(versioned_runtime_current_version(ptr, 0, &mut version), -1);
+
+            // Cleanup
+            let _ = Box::from_raw(ptr);
+        }
+    }
+
+    /// Test: FFI versioned runtime - complete workflow
+    #[test]
+    fn test_ffi_versioned_complete_workflow() {
+        unsafe {
+            // Initialize runtime
+            let ptr = versioned_runtime_init();
+            assert!(!ptr.is_null());
+
+            // Register returns its index
+            assert_eq!(versioned_runtime_add_register(ptr, 1, 0x1000), 0);
+            assert_eq!(versioned_runtime_add_register(ptr, 2, 0x2000), 1);
+
+            // Write three versions
+            assert_eq!(versioned_runtime_write(ptr, 0, 0x1111), 0);
+            assert_eq!(versioned_runtime_write(ptr, 0, 0x2222), 0);
+            assert_eq!(versioned_runtime_write(ptr, 0, 0x3333), 0);
+
+            let mut version: u32 = 0;
+            assert_eq!(versioned_runtime_current_version(ptr, 0, &mut version), 0);
+            assert_eq!(version, 3);
+
+            // Roll back to the previous version
+            assert_eq!(versioned_runtime_rollback_offset(ptr, 0, 1), 0);
+            let reg = (*ptr).get_register(0).unwrap();
+            assert_eq!(reg.get_register().read(), 0x2222);
+
+            // Roll back to the oldest version
+            assert_eq!(versioned_runtime_rollback_offset(ptr, 0, 2), 0);
+            let reg = (*ptr).get_register(0).unwrap();
+            assert_eq!(reg.get_register().read(), 0x1111);
+
+            // Offset beyond history fails
+            assert_eq!(versioned_runtime_rollback_offset(ptr, 0, 3), -1);
+
+            // Other registers are untouched
+            assert_eq!(versioned_runtime_current_version(ptr, 1, &mut version), 0);
+            assert_eq!(version, 0);
+
+            // Cleanup
+            let _ = Box::from_raw(ptr);
+        }
+    }
 }
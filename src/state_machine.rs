@@ -6,50 +6,81 @@ use crate::cache_coherency::CacheState;
 /// State Transition Matrix for 4-State Logic
 pub struct StateTransitionTable {
     /// Transition table: [current_state][event] -> next_state
-    table: [[CacheState; 4]; 4],
+    table: [[CacheState; CacheEvent::COUNT]; 4],
 }
 
 /// Cache Events that trigger state transitions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u8)]
 pub enum CacheEvent {
     LocalRead = 0,
     LocalWrite = 1,
     RemoteRead = 2,
     RemoteWrite = 3,
+    /// Write dirty data back but keep the line (Modified → Exclusive)
+    Flush = 4,
+    /// Drop the line, writing it back first if dirty
+    Evict = 5,
+}
+
+impl CacheEvent {
+    /// Number of events (columns of the transition table)
+    pub const COUNT: usize = 6;
+
+    /// Every event, in column order
+    pub const ALL: [CacheEvent; CacheEvent::COUNT] = [
+        CacheEvent::LocalRead,
+        CacheEvent::LocalWrite,
+        CacheEvent::RemoteRead,
+        CacheEvent::RemoteWrite,
+        CacheEvent::Flush,
+        CacheEvent::Evict,
+    ];
 }
 
 impl StateTransitionTable {
+    /// Canonical MESI table, evaluated at compile time
+    pub const MESI: StateTransitionTable = StateTransitionTable::new();
+
     /// Initialize the 4-state MESI transition table
     pub const fn new() -> Self {
         use CacheState::*;
         use CacheEvent::*;
 
         // Pre-computed transition table for real-time performance
-        let mut table = [[Invalid; 4]; 4];
+        let mut table = [[Invalid; CacheEvent::COUNT]; 4];
 
         // Modified state transitions
         table[Modified as usize][LocalRead as usize] = Modified;
         table[Modified as usize][LocalWrite as usize] = Modified;
         table[Modified as usize][RemoteRead as usize] = Shared;
         table[Modified as usize][RemoteWrite as usize] = Invalid;
+        table[Modified as usize][Flush as usize] = Exclusive;
+        table[Modified as usize][Evict as usize] = Invalid;
 
         // Exclusive state transitions
         table[Exclusive as usize][LocalRead as usize] = Exclusive;
         table[Exclusive as usize][LocalWrite as usize] = Modified;
         table[Exclusive as usize][RemoteRead as usize] = Shared;
         table[Exclusive as usize][RemoteWrite as usize] = Invalid;
+        table[Exclusive as usize][Flush as usize] = Exclusive;
+        table[Exclusive as usize][Evict as usize] = Invalid;
 
         // Shared state transitions
         table[Shared as usize][LocalRead as usize] = Shared;
         table[Shared as usize][LocalWrite as usize] = Modified;
         table[Shared as usize][RemoteRead as usize] = Shared;
         table[Shared as usize][RemoteWrite as usize] = Invalid;
+        table[Shared as usize][Flush as usize] = Shared;
+        table[Shared as usize][Evict as usize] = Invalid;
 
         // Invalid state transitions
         table[Invalid as usize][LocalRead as usize] = Shared;
         table[Invalid as usize][LocalWrite as usize] = Modified;
         table[Invalid as usize][RemoteRead as usize] = Invalid;
         table[Invalid as usize][RemoteWrite as usize] = Invalid;
+        table[Invalid as usize][Flush as usize] = Invalid;
+        table[Invalid as usize][Evict as usize] = Invalid;
 
         Self { table }
     }
@@ -59,6 +90,21 @@ impl StateTransitionTable {
     pub const fn transition(&self, current: CacheState, event: CacheEvent) -> CacheState {
         self.table[current as usize][event as usize]
     }
+
+    /// Whether taking `event` in `current` must write the line back first
+    ///
+    /// Only dirty (Modified) lines leaving through Flush or Evict do.
+    #[inline(always)]
+    pub const fn needs_writeback(current: CacheState, event: CacheEvent) -> bool {
+        matches!(current, CacheState::Modified)
+            && matches!(event, CacheEvent::Flush | CacheEvent::Evict)
+    }
+
+    /// Full matrix, indexed `[current_state as usize][event as usize]`
+    #[inline(always)]
+    pub const fn as_matrix(&self) -> &[[CacheState; CacheEvent::COUNT]; 4] {
+        &self.table
+    }
 }
 
 /// Real-Time Cache Coherency State Machine
@@ -69,10 +115,16 @@ pub struct CoherencyStateMachine {
 impl CoherencyStateMachine {
     pub const fn new() -> Self {
         Self {
-            transition_table: StateTransitionTable::new(),
+            transition_table: StateTransitionTable::MESI,
         }
     }
 
+    /// Transition table driving this state machine
+    #[inline(always)]
+    pub fn transition_table(&self) -> &StateTransitionTable {
+        &self.transition_table
+    }
+
     /// Execute state transition based on your 5-step flow
     #[inline]
     pub fn execute_flow(&self, step: u8, current_state: CacheState, is_local: bool) -> CacheState {
@@ -139,4 +191,55 @@ mod tests {
         let state5 = sm.execute_flow(5, state4_core2, true);
         assert_eq!(state5, CacheState::Shared);
     }
+
+    #[test]
+    fn test_mesi_matrix() {
+        use CacheState::*;
+
+        // Columns: LocalRead, LocalWrite, RemoteRead, RemoteWrite, Flush, Evict
+        let expected = [
+            [Modified, Modified, Shared, Invalid, Exclusive, Invalid],   // Modified
+            [Exclusive, Modified, Shared, Invalid, Exclusive, Invalid],  // Exclusive
+            [Shared, Modified, Shared, Invalid, Shared, Invalid],        // Shared
+            [Shared, Modified, Invalid, Invalid, Invalid, Invalid],      // Invalid
+        ];
+        assert_eq!(StateTransitionTable::MESI.as_matrix(), &expected);
+
+        let sm = CoherencyStateMachine::new();
+        assert_eq!(sm.transition_table().as_matrix(), &expected);
+        assert_eq!(
+            StateTransitionTable::MESI.transition(Exclusive, CacheEvent::LocalWrite),
+            expected[Exclusive as usize][CacheEvent::LocalWrite as usize]
+        );
+    }
+
+    #[test]
+    fn test_flush_and_evict_transitions() {
+        use CacheState::*;
+        let table = &StateTransitionTable::MESI;
+
+        // Flush only cleans a dirty line
+        assert_eq!(table.transition(Modified, CacheEvent::Flush), Exclusive);
+        assert_eq!(table.transition(Exclusive, CacheEvent::Flush), Exclusive);
+        assert_eq!(table.transition(Shared, CacheEvent::Flush), Shared);
+        assert_eq!(table.transition(Invalid, CacheEvent::Flush), Invalid);
+
+        // Evict drops every line
+        for state in [Modified, Exclusive, Shared, Invalid] {
+            assert_eq!(table.transition(state, CacheEvent::Evict), Invalid);
+        }
+    }
+
+    #[test]
+    fn test_needs_writeback() {
+        use CacheState::*;
+
+        for state in [Modified, Exclusive, Shared, Invalid] {
+            for event in CacheEvent::ALL {
+                let expected =
+                    state == Modified && matches!(event, CacheEvent::Flush | CacheEvent::Evict);
+                assert_eq!(StateTransitionTable::needs_writeback(state, event), expected);
+            }
+        }
+    }
 }
@@ -1,9 +1,10 @@
 /// Register Synchronization Manager
 /// Handles synchronization between shadow registers, fuses, and active hardware
 
-use crate::fuse_manager::FuseManager;
-use crate::shadow_register::RegisterState;
-use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use crate::fuse_manager::{FuseManager, HardwareFuse};
+use crate::shadow_register::{RegisterId, RegisterState, ShadowError, ShadowRegister, SlotIndex};
+use core::ptr;
+use core::sync::atomic::{AtomicBool, AtomicPtr, AtomicU32, AtomicUsize, Ordering};
 
 /// Synchronization Direction
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -31,8 +32,14 @@ pub enum SyncPolicy {
     VersionChecked,
     /// Use conflict resolution (newest wins)
     ConflictResolve,
+    /// Ask the installed `ConflictResolver`; its value is written to both sides
+    Custom,
 }
 
+/// Conflict resolver for `SyncPolicy::Custom` - receives the fuse and shadow
+/// values and returns the value both sides should hold
+pub type ConflictResolver = fn(fuse_value: u64, shadow_value: u64) -> u64;
+
 /// Synchronization Status
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u8)]
@@ -44,12 +51,75 @@ pub enum SyncStatus {
     Conflict = 0x04,
 }
 
+impl SyncStatus {
+    /// Decode a raw status value (unknown values read as Failed)
+    const fn from_u32(value: u32) -> Self {
+        match value {
+            0 => SyncStatus::Idle,
+            1 => SyncStatus::InProgress,
+            2 => SyncStatus::Success,
+            3 => SyncStatus::Failed,
+            4 => SyncStatus::Conflict,
+            _ => SyncStatus::Failed,
+        }
+    }
+
+    /// Rank used when merging results (Failed > Conflict > Success)
+    const fn severity(self) -> u8 {
+        match self {
+            SyncStatus::Idle | SyncStatus::InProgress => 0,
+            SyncStatus::Success => 1,
+            SyncStatus::Conflict => 2,
+            SyncStatus::Failed => 3,
+        }
+    }
+
+    /// The more severe of two statuses
+    fn worst(self, other: SyncStatus) -> SyncStatus {
+        if other.severity() > self.severity() {
+            other
+        } else {
+            self
+        }
+    }
+}
+
 /// Synchronization Result
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct SyncResult {
     pub status: SyncStatus,
     pub synced_count: usize,
     pub failed_count: usize,
     pub conflict_count: usize,
+    /// Registers left untouched: read-protected registers, plus (for dry
+    /// runs only) those the policy skips, which `sync_all` counts as synced
+    pub skipped_count: usize,
+}
+
+impl SyncResult {
+    /// Fold another result into this one
+    ///
+    /// Counts are summed and the worse status is kept.
+    pub fn merge(&mut self, other: &SyncResult) {
+        self.status = self.status.worst(other.status);
+        self.synced_count += other.synced_count;
+        self.failed_count += other.failed_count;
+        self.conflict_count += other.conflict_count;
+        self.skipped_count += other.skipped_count;
+    }
+}
+
+/// Predicted outcome of syncing one register
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SyncPrediction {
+    /// Already in sync (or nothing to do)
+    Synced,
+    /// Policy leaves the destination alone
+    Skipped,
+    /// Values diverge
+    Conflict,
+    /// The real sync would return an error
+    Failed,
 }
 
 /// Register Synchronization Manager
@@ -60,6 +130,16 @@ pub struct SyncManager {
     syncing: AtomicBool,
     /// Total syncs performed
     sync_count: AtomicU32,
+    /// Worst `sync_all` status since the last reset
+    acc_status: AtomicU32,
+    /// Registers synced by `sync_all` since the last reset
+    acc_synced: AtomicUsize,
+    /// Registers failed in `sync_all` since the last reset
+    acc_failed: AtomicUsize,
+    /// Conflicts seen by `sync_all` since the last reset
+    acc_conflicts: AtomicUsize,
+    /// Installed `ConflictResolver` (null if none)
+    conflict_resolver: AtomicPtr<()>,
 }
 
 impl SyncManager {
@@ -69,20 +149,66 @@ impl SyncManager {
             status: AtomicU32::new(SyncStatus::Idle as u32),
             syncing: AtomicBool::new(false),
             sync_count: AtomicU32::new(0),
+            acc_status: AtomicU32::new(SyncStatus::Idle as u32),
+            acc_synced: AtomicUsize::new(0),
+            acc_failed: AtomicUsize::new(0),
+            acc_conflicts: AtomicUsize::new(0),
+            conflict_resolver: AtomicPtr::new(ptr::null_mut()),
         }
     }
 
+    /// Install the resolver used by `SyncPolicy::Custom` (`None` removes it)
+    ///
+    /// Without a resolver, `Custom` reports conflicts like `VersionChecked`.
+    pub fn set_conflict_resolver(&self, resolver: Option<ConflictResolver>) {
+        let raw = resolver.map_or(ptr::null_mut(), |f| f as *mut ());
+        self.conflict_resolver.store(raw, Ordering::Release);
+    }
+
+    /// Currently installed conflict resolver
+    fn conflict_resolver(&self) -> Option<ConflictResolver> {
+        let raw = self.conflict_resolver.load(Ordering::Acquire);
+        if raw.is_null() {
+            return None;
+        }
+        // SAFETY: only `set_conflict_resolver` stores non-null values, and it
+        // stores them from a `ConflictResolver`
+        Some(unsafe { core::mem::transmute::<*mut (), ConflictResolver>(raw) })
+    }
+
+    /// Fuse slot backing `register_id`
+    ///
+    /// Ids are caller-chosen, so the slot comes from the fuse manager's
+    /// shadow bank rather than from the id itself.
+    fn fuse_slot(
+        fuse_manager: &FuseManager,
+        register_id: RegisterId,
+    ) -> Result<SlotIndex, ShadowError> {
+        fuse_manager
+            .get_shadow_bank()
+            .index_of(register_id)
+            .ok_or(ShadowError::NotFound)
+    }
+
+    /// Id of the register in fuse slot `slot`
+    fn slot_register_id(fuse_manager: &FuseManager, slot: SlotIndex) -> Option<RegisterId> {
+        fuse_manager
+            .get_shadow_bank()
+            .get_by_index(slot)
+            .map(ShadowRegister::get_id)
+    }
+
     /// Synchronize a single register
     pub unsafe fn sync_register(
         &self,
         fuse_manager: &mut FuseManager,
-        register_id: u32,
+        register_id: RegisterId,
         direction: SyncDirection,
         policy: SyncPolicy,
-    ) -> Result<(), &'static str> {
+    ) -> Result<(), ShadowError> {
         // Check if already syncing
         if self.syncing.swap(true, Ordering::AcqRel) {
-            return Err("Sync already in progress");
+            return Err(ShadowError::SyncInProgress);
         }
 
         self.status
@@ -105,6 +231,10 @@ impl SyncManager {
                     .store(SyncStatus::Success as u32, Ordering::Release);
                 self.sync_count.fetch_add(1, Ordering::AcqRel);
             }
+            Err(ShadowError::Conflict) => {
+                self.status
+                    .store(SyncStatus::Conflict as u32, Ordering::Release);
+            }
             Err(_) => {
                 self.status
                     .store(SyncStatus::Failed as u32, Ordering::Release);
@@ -120,9 +250,9 @@ impl SyncManager {
     unsafe fn sync_fuse_to_shadow(
         &self,
         fuse_manager: &mut FuseManager,
-        register_id: u32,
+        register_id: RegisterId,
         policy: SyncPolicy,
-    ) -> Result<(), &'static str> {
+    ) -> Result<(), ShadowError> {
         let shadow_bank = fuse_manager.get_shadow_bank_mut();
 
         if let Some(shadow_reg) = shadow_bank.get_register_mut(register_id) {
@@ -141,7 +271,8 @@ impl SyncManager {
             }
 
             // Load from fuse
-            fuse_manager.load_to_shadow(register_id as usize)?;
+            let slot = Self::fuse_slot(fuse_manager, register_id)?;
+            fuse_manager.load_to_shadow(slot)?;
         }
 
         Ok(())
@@ -151,16 +282,18 @@ impl SyncManager {
     unsafe fn sync_shadow_to_fuse(
         &self,
         fuse_manager: &mut FuseManager,
-        register_id: u32,
+        register_id: RegisterId,
         policy: SyncPolicy,
-    ) -> Result<(), &'static str> {
+    ) -> Result<(), ShadowError> {
         let shadow_bank = fuse_manager.get_shadow_bank();
 
         if let Some(shadow_reg) = shadow_bank.get_register(register_id) {
+            let slot = Self::fuse_slot(fuse_manager, register_id)?;
+
             // Check policy
             match policy {
                 SyncPolicy::InitializeOnly => {
-                    if let Some(fuse) = fuse_manager.get_fuse(register_id as usize) {
+                    if let Some(fuse) = fuse_manager.get_fuse(slot) {
                         if !fuse.is_virgin() {
                             return Ok(()); // Skip if fuse already programmed
                         }
@@ -170,7 +303,7 @@ impl SyncManager {
             }
 
             // Commit to fuse
-            fuse_manager.commit_to_fuse(register_id as usize)?;
+            fuse_manager.commit_to_fuse(slot)?;
         }
 
         Ok(())
@@ -180,22 +313,24 @@ impl SyncManager {
     unsafe fn sync_bidirectional(
         &self,
         fuse_manager: &mut FuseManager,
-        register_id: u32,
+        register_id: RegisterId,
         policy: SyncPolicy,
-    ) -> Result<(), &'static str> {
+    ) -> Result<(), ShadowError> {
+        let slot = Self::fuse_slot(fuse_manager, register_id)?;
+
         // Read both values
-        let fuse_value = if let Some(fuse) = fuse_manager.get_fuse_mut(register_id as usize) {
+        let fuse_value = if let Some(fuse) = fuse_manager.get_fuse_mut(slot) {
             fuse.read_from_hardware()?
         } else {
-            return Err("Fuse not found");
+            return Err(ShadowError::NotFound);
         };
 
         let shadow_value = if let Some(shadow_reg) =
             fuse_manager.get_shadow_bank().get_register(register_id)
         {
-            shadow_reg.read()
+            shadow_reg.try_read()?
         } else {
-            return Err("Shadow register not found");
+            return Err(ShadowError::NotFound);
         };
 
         // Check for conflicts
@@ -203,7 +338,7 @@ impl SyncManager {
             match policy {
                 SyncPolicy::ForceOverwrite => {
                     // Shadow wins
-                    fuse_manager.commit_to_fuse(register_id as usize)?;
+                    fuse_manager.commit_to_fuse(slot)?;
                 }
                 SyncPolicy::ConflictResolve => {
                     // Use version to determine winner
@@ -212,17 +347,57 @@ impl SyncManager {
                     {
                         if shadow_reg.get_version() > 0 {
                             // Shadow is newer, commit to fuse
-                            fuse_manager.commit_to_fuse(register_id as usize)?;
+                            fuse_manager.commit_to_fuse(slot)?;
                         } else {
                             // Fuse is newer, load to shadow
-                            fuse_manager.load_to_shadow(register_id as usize)?;
+                            fuse_manager.load_to_shadow(slot)?;
+                        }
+                    }
+                }
+                SyncPolicy::Custom => {
+                    let resolve = match self.conflict_resolver() {
+                        Some(resolve) => resolve,
+                        None => {
+                            self.status
+                                .store(SyncStatus::Conflict as u32, Ordering::Release);
+                            return Err(ShadowError::Conflict);
+                        }
+                    };
+                    let merged = resolve(fuse_value, shadow_value);
+
+                    // Refuse up front if the fuse can't take the merged value
+                    if merged != fuse_value {
+                        fuse_manager
+                            .get_fuse(slot)
+                            .ok_or(ShadowError::NotFound)?
+                            .check_programmable()?;
+                    }
+
+                    // Shadow first, then the fuse takes the committed value
+                    let shadow_updated = merged != shadow_value;
+                    if shadow_updated {
+                        let shadow_reg = fuse_manager
+                            .get_shadow_bank_mut()
+                            .get_register_mut(register_id)
+                            .ok_or(ShadowError::NotFound)?;
+                        shadow_reg.write(merged)?;
+                        shadow_reg.commit()?;
+                    }
+                    if merged != fuse_value {
+                        if let Err(err) = fuse_manager.commit_to_fuse(slot) {
+                            // Don't leave the shadow holding a value the fuse refused
+                            if shadow_updated {
+                                let bank = fuse_manager.get_shadow_bank_mut();
+                                let _ = bank.rollback_register(register_id);
+                            }
+                            return Err(err);
                         }
                     }
                 }
                 _ => {
                     self.status
                         .store(SyncStatus::Conflict as u32, Ordering::Release);
-                    return Err("Sync conflict detected");
+                    return Err(ShadowError::Conflict);
                 }
             }
         }
@@ -230,7 +405,25 @@ impl SyncManager {
         Ok(())
     }
 
+    /// Whether the register in `slot` is locked against reads
+    fn slot_read_protected(fuse_manager: &FuseManager, slot: SlotIndex) -> bool {
+        fuse_manager
+            .get_shadow_bank()
+            .get_by_index(slot)
+            .is_some_and(ShadowRegister::is_read_protected)
+    }
+
+    /// Whether the fuse in `slot` is factory-provisioned read-only
+    fn slot_fuse_read_only(fuse_manager: &FuseManager, slot: SlotIndex) -> bool {
+        fuse_manager
+            .get_fuse(slot)
+            .is_some_and(HardwareFuse::is_read_only)
+    }
+
     /// Synchronize all registers
+    ///
+    /// Read-protected registers are skipped rather than failed, as are
+    /// read-only fuses when syncing shadow to fuse.
     pub unsafe fn sync_all(
         &self,
         fuse_manager: &mut FuseManager,
@@ -240,9 +433,26 @@ impl SyncManager {
         let mut synced = 0;
         let mut failed = 0;
         let mut conflicts = 0;
+        let mut skipped = 0;
 
         for i in 0..fuse_manager.count() {
-            match self.sync_register(fuse_manager, i as u32, direction, policy) {
+            let register_id = match Self::slot_register_id(fuse_manager, SlotIndex(i)) {
+                Some(id) => id,
+                None => {
+                    failed += 1;
+                    continue;
+                }
+            };
+
+            if Self::slot_read_protected(fuse_manager, SlotIndex(i))
+                || (direction == SyncDirection::ShadowToFuse
+                    && Self::slot_fuse_read_only(fuse_manager, SlotIndex(i)))
+            {
+                skipped += 1;
+                continue;
+            }
+
+            match self.sync_register(fuse_manager, register_id, direction, policy) {
                 Ok(_) => synced += 1,
                 Err(_) => {
                     if self.status.load(Ordering::Acquire) == SyncStatus::Conflict as u32 {
@@ -262,11 +472,168 @@ impl SyncManager {
             SyncStatus::Conflict
         };
 
-        SyncResult {
+        let result = SyncResult {
             status,
             synced_count: synced,
             failed_count: failed,
             conflict_count: conflicts,
+            skipped_count: skipped,
+        };
+        self.accumulate(&result);
+
+        result
+    }
+
+    /// Fold a `sync_all` result into the running totals
+    fn accumulate(&self, result: &SyncResult) {
+        let _ = self
+            .acc_status
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |raw| {
+                Some(SyncStatus::from_u32(raw).worst(result.status) as u32)
+            });
+        self.acc_synced.fetch_add(result.synced_count, Ordering::AcqRel);
+        self.acc_failed.fetch_add(result.failed_count, Ordering::AcqRel);
+        self.acc_conflicts.fetch_add(result.conflict_count, Ordering::AcqRel);
+    }
+
+    /// Combined result of every `sync_all` since creation or `reset`
+    pub fn accumulated_result(&self) -> SyncResult {
+        SyncResult {
+            status: SyncStatus::from_u32(self.acc_status.load(Ordering::Acquire)),
+            synced_count: self.acc_synced.load(Ordering::Acquire),
+            failed_count: self.acc_failed.load(Ordering::Acquire),
+            conflict_count: self.acc_conflicts.load(Ordering::Acquire),
+            skipped_count: 0,
+        }
+    }
+
+    /// Predict what `sync_all` would do without touching any fuse or register
+    ///
+    /// Diverging register/fuse pairs are counted as conflicts, including
+    /// those a resolving policy would overwrite. Fuse values come from the
+    /// last hardware read cached in each `HardwareFuse`. Neither the status
+    /// nor the `syncing` flag is changed.
+    pub fn dry_run_all(
+        &self,
+        fuse_manager: &FuseManager,
+        direction: SyncDirection,
+        policy: SyncPolicy,
+    ) -> SyncResult {
+        let mut result = SyncResult {
+            status: SyncStatus::Success,
+            synced_count: 0,
+            failed_count: 0,
+            conflict_count: 0,
+            skipped_count: 0,
+        };
+
+        for i in 0..fuse_manager.count() {
+            let register_id = match Self::slot_register_id(fuse_manager, SlotIndex(i)) {
+                Some(id) => id,
+                None => {
+                    result.failed_count += 1;
+                    continue;
+                }
+            };
+
+            match Self::predict_register(fuse_manager, register_id, direction, policy) {
+                SyncPrediction::Synced => result.synced_count += 1,
+                SyncPrediction::Skipped => result.skipped_count += 1,
+                SyncPrediction::Conflict => result.conflict_count += 1,
+                SyncPrediction::Failed => result.failed_count += 1,
+            }
+        }
+
+        result.status = if result.failed_count == 0 && result.conflict_count == 0 {
+            SyncStatus::Success
+        } else if result.synced_count + result.conflict_count == 0 {
+            SyncStatus::Failed
+        } else {
+            SyncStatus::Conflict
+        };
+
+        result
+    }
+
+    /// Predict the outcome of `sync_register` for one register
+    fn predict_register(
+        fuse_manager: &FuseManager,
+        register_id: RegisterId,
+        direction: SyncDirection,
+        policy: SyncPolicy,
+    ) -> SyncPrediction {
+        let fuse = Self::fuse_slot(fuse_manager, register_id)
+            .ok()
+            .and_then(|slot| fuse_manager.get_fuse(slot));
+        let shadow_reg = fuse_manager.get_shadow_bank().get_register(register_id);
+
+        let (fuse, shadow_reg) = match (fuse, shadow_reg) {
+            (_, Some(shadow_reg)) if shadow_reg.is_read_protected() => {
+                return SyncPrediction::Skipped;
+            }
+            (Some(fuse), Some(shadow_reg)) => (fuse, shadow_reg),
+            // One-way syncs quietly ignore a missing register
+            (_, None) if direction != SyncDirection::Bidirectional => {
+                return SyncPrediction::Synced;
+            }
+            _ => return SyncPrediction::Failed,
+        };
+
+        let diverged = fuse.get_value() != shadow_reg.read();
+        let shadow_writable = |reg: &ShadowRegister| {
+            reg.get_state() != RegisterState::Locked && !reg.is_write_protected()
+        };
+
+        match direction {
+            SyncDirection::ShadowToActive | SyncDirection::ActiveToShadow => SyncPrediction::Synced,
+            SyncDirection::FuseToShadow => {
+                if matches!(policy, SyncPolicy::InitializeOnly)
+                    && shadow_reg.get_state() != RegisterState::Uninitialized
+                {
+                    SyncPrediction::Skipped
+                } else if !shadow_writable(shadow_reg) {
+                    SyncPrediction::Failed
+                } else if diverged {
+                    SyncPrediction::Conflict
+                } else {
+                    SyncPrediction::Synced
+                }
+            }
+            SyncDirection::ShadowToFuse => {
+                if fuse.is_read_only()
+                    || (matches!(policy, SyncPolicy::InitializeOnly) && !fuse.is_virgin())
+                {
+                    SyncPrediction::Skipped
+                } else if !fuse.can_program() {
+                    SyncPrediction::Failed
+                } else if diverged {
+                    SyncPrediction::Conflict
+                } else {
+                    SyncPrediction::Synced
+                }
+            }
+            SyncDirection::Bidirectional => {
+                if !diverged {
+                    return SyncPrediction::Synced;
+                }
+
+                let resolvable = match policy {
+                    SyncPolicy::ForceOverwrite => fuse.can_program(),
+                    SyncPolicy::ConflictResolve if shadow_reg.get_version() > 0 => {
+                        fuse.can_program()
+                    }
+                    SyncPolicy::ConflictResolve => shadow_writable(shadow_reg),
+                    SyncPolicy::Custom => fuse.can_program() && shadow_writable(shadow_reg),
+                    // Reported as a conflict by the real sync as well
+                    SyncPolicy::InitializeOnly | SyncPolicy::VersionChecked => true,
+                };
+
+                if resolvable {
+                    SyncPrediction::Conflict
+                } else {
+                    SyncPrediction::Failed
+                }
+            }
         }
     }
 
@@ -279,14 +646,7 @@ impl SyncManager {
     /// Get current sync status
     #[inline(always)]
     pub fn get_status(&self) -> SyncStatus {
-        match self.status.load(Ordering::Acquire) {
-            0 => SyncStatus::Idle,
-            1 => SyncStatus::InProgress,
-            2 => SyncStatus::Success,
-            3 => SyncStatus::Failed,
-            4 => SyncStatus::Conflict,
-            _ => SyncStatus::Failed,
-        }
+        SyncStatus::from_u32(self.status.load(Ordering::Acquire))
     }
 
     /// Get total sync count
@@ -299,6 +659,10 @@ impl SyncManager {
     pub fn reset(&self) {
         self.status.store(SyncStatus::Idle as u32, Ordering::Release);
         self.syncing.store(false, Ordering::Release);
+        self.acc_status.store(SyncStatus::Idle as u32, Ordering::Release);
+        self.acc_synced.store(0, Ordering::Release);
+        self.acc_failed.store(0, Ordering::Release);
+        self.acc_conflicts.store(0, Ordering::Release);
     }
 }
 
@@ -306,6 +670,7 @@ impl SyncManager {
 mod tests {
     use super::*;
     use crate::fuse_manager::{FuseManager, FuseMode};
+    use crate::shadow_register::LockMode;
     use alloc::boxed::Box;
 
     /// Helper to create test memory backing for fuses
@@ -320,9 +685,9 @@ mod tests {
         let addr0 = &memory[0] as *const u64 as u64;
         let addr1 = &memory[4] as *const u64 as u64;
         let addr2 = &memory[8] as *const u64 as u64;
-        manager.add_fuse(addr0, FuseMode::OTP).unwrap();
-        manager.add_fuse(addr1, FuseMode::MTP).unwrap();
-        manager.add_fuse(addr2, FuseMode::OTP).unwrap();
+        manager.add_fuse(addr0.into(), FuseMode::OTP).unwrap();
+        manager.add_fuse(addr1.into(), FuseMode::MTP).unwrap();
+        manager.add_fuse(addr2.into(), FuseMode::OTP).unwrap();
         manager
     }
 
@@ -356,12 +721,12 @@ mod tests {
 
         unsafe {
             // Set up a fuse with a value
-            if let Some(fuse) = fuse_mgr.get_fuse_mut(0) {
+            if let Some(fuse) = fuse_mgr.get_fuse_mut(SlotIndex(0)) {
                 fuse.program_to_hardware(0xDEADBEEF).unwrap();
             }
 
             // Write different value to shadow
-            if let Some(shadow_reg) = fuse_mgr.get_shadow_bank_mut().get_register_mut(0) {
+            if let Some(shadow_reg) = fuse_mgr.get_shadow_bank_mut().get_register_mut(RegisterId(0)) {
                 shadow_reg.write(0x11111111).unwrap();
                 shadow_reg.commit().unwrap();
             }
@@ -369,7 +734,7 @@ mod tests {
             // Sync fuse to shadow with ForceOverwrite
             let result = sync_mgr.sync_register(
                 &mut fuse_mgr,
-                0,
+                RegisterId(0),
                 SyncDirection::FuseToShadow,
                 SyncPolicy::ForceOverwrite
             );
@@ -379,7 +744,7 @@ mod tests {
             assert_eq!(sync_mgr.get_sync_count(), 1);
 
             // Verify shadow was updated with fuse value
-            let shadow_value = fuse_mgr.get_shadow_bank().get_register(0).unwrap().read();
+            let shadow_value = fuse_mgr.get_shadow_bank().get_register(RegisterId(0)).unwrap().read();
             assert_eq!(shadow_value, 0xDEADBEEF);
         }
     }
@@ -392,7 +757,7 @@ mod tests {
 
         unsafe {
             // Write to shadow register
-            if let Some(shadow_reg) = fuse_mgr.get_shadow_bank_mut().get_register_mut(0) {
+            if let Some(shadow_reg) = fuse_mgr.get_shadow_bank_mut().get_register_mut(RegisterId(0)) {
                 shadow_reg.write(0xCAFEBABE).unwrap();
                 shadow_reg.commit().unwrap();
             }
@@ -400,7 +765,7 @@ mod tests {
             // Sync shadow to fuse
             let result = sync_mgr.sync_register(
                 &mut fuse_mgr,
-                0,
+                RegisterId(0),
                 SyncDirection::ShadowToFuse,
                 SyncPolicy::ForceOverwrite
             );
@@ -409,11 +774,103 @@ mod tests {
             assert_eq!(sync_mgr.get_status(), SyncStatus::Success);
 
             // Verify fuse was updated
-            let fuse_value = fuse_mgr.get_fuse(0).unwrap().get_value();
+            let fuse_value = fuse_mgr.get_fuse(SlotIndex(0)).unwrap().get_value();
             assert_eq!(fuse_value, 0xCAFEBABE);
         }
     }
 
+    /// Fuse manager whose register ids (10, 20) differ from their slots (0, 1)
+    fn create_sparse_id_fuse_manager(memory: &mut [u64; 16]) -> FuseManager {
+        let mut manager = FuseManager::new();
+        let addr0 = &memory[0] as *const u64 as u64;
+        let addr1 = &memory[4] as *const u64 as u64;
+        manager.add_fuse_with_id(RegisterId(10), addr0.into(), FuseMode::MTP).unwrap();
+        manager.add_fuse_with_id(RegisterId(20), addr1.into(), FuseMode::MTP).unwrap();
+        manager
+    }
+
+    #[test]
+    fn test_sync_shadow_to_fuse_resolves_id_to_slot() {
+        let mut memory = create_test_memory();
+        let mut fuse_mgr = create_sparse_id_fuse_manager(&mut memory);
+        let sync_mgr = SyncManager::new();
+
+        unsafe {
+            let shadow_reg = fuse_mgr.get_shadow_bank_mut().get_register_mut(RegisterId(20)).unwrap();
+            shadow_reg.write(0x2020).unwrap();
+            shadow_reg.commit().unwrap();
+
+            // Id 20 lives in slot 1; there is no slot 20
+            let result = sync_mgr.sync_register(
+                &mut fuse_mgr,
+                RegisterId(20),
+                SyncDirection::ShadowToFuse,
+                SyncPolicy::ForceOverwrite,
+            );
+
+            assert_eq!(result, Ok(()));
+            assert_eq!(fuse_mgr.get_fuse(SlotIndex(1)).unwrap().get_value(), 0x2020);
+            assert_eq!(fuse_mgr.get_fuse(SlotIndex(0)).unwrap().get_value(), 0);
+        }
+    }
+
+    #[test]
+    fn test_sync_fuse_to_shadow_resolves_id_to_slot() {
+        let mut memory = create_test_memory();
+        memory[0] = 0x1010;
+        memory[4] = 0x2020;
+        let mut fuse_mgr = create_sparse_id_fuse_manager(&mut memory);
+        let sync_mgr = SyncManager::new();
+
+        unsafe {
+            let result = sync_mgr.sync_register(
+                &mut fuse_mgr,
+                RegisterId(20),
+                SyncDirection::FuseToShadow,
+                SyncPolicy::ForceOverwrite,
+            );
+
+            assert_eq!(result, Ok(()));
+            let bank = fuse_mgr.get_shadow_bank();
+            assert_eq!(bank.get_register(RegisterId(20)).unwrap().read(), 0x2020);
+            assert_eq!(bank.get_register(RegisterId(10)).unwrap().read(), 0);
+        }
+    }
+
+    #[test]
+    fn test_sync_all_with_sparse_ids() {
+        let mut memory = create_test_memory();
+        let mut fuse_mgr = create_sparse_id_fuse_manager(&mut memory);
+        let sync_mgr = SyncManager::new();
+
+        unsafe {
+            for (id, value) in [(RegisterId(10), 0xA), (RegisterId(20), 0xB)] {
+                let shadow_reg = fuse_mgr.get_shadow_bank_mut().get_register_mut(id).unwrap();
+                shadow_reg.write(value).unwrap();
+                shadow_reg.commit().unwrap();
+            }
+
+            let predicted = sync_mgr.dry_run_all(
+                &fuse_mgr,
+                SyncDirection::ShadowToFuse,
+                SyncPolicy::ForceOverwrite,
+            );
+            // Both pairs diverge, so both are predicted (and none fail lookup)
+            assert_eq!(predicted.conflict_count, 2);
+            assert_eq!(predicted.failed_count, 0);
+
+            let result = sync_mgr.sync_all(
+                &mut fuse_mgr,
+                SyncDirection::ShadowToFuse,
+                SyncPolicy::ForceOverwrite,
+            );
+            assert_eq!(result.status, SyncStatus::Success);
+            assert_eq!(result.synced_count, 2);
+            assert_eq!(fuse_mgr.get_fuse(SlotIndex(0)).unwrap().get_value(), 0xA);
+            assert_eq!(fuse_mgr.get_fuse(SlotIndex(1)).unwrap().get_value(), 0xB);
+        }
+    }
+
     #[test]
     fn test_sync_policy_initialize_only_skips_initialized() {
         let mut memory = create_test_memory();
@@ -422,20 +879,20 @@ mod tests {
 
         unsafe {
             // Initialize shadow register first
-            if let Some(shadow_reg) = fuse_mgr.get_shadow_bank_mut().get_register_mut(0) {
+            if let Some(shadow_reg) = fuse_mgr.get_shadow_bank_mut().get_register_mut(RegisterId(0)) {
                 shadow_reg.write(0x12345678).unwrap();
                 shadow_reg.commit().unwrap();
             }
 
             // Set fuse to different value
-            if let Some(fuse) = fuse_mgr.get_fuse_mut(0) {
+            if let Some(fuse) = fuse_mgr.get_fuse_mut(SlotIndex(0)) {
                 fuse.program_to_hardware(0x87654321).unwrap();
             }
 
             // Try to sync with InitializeOnly - should skip since shadow is already initialized
             let result = sync_mgr.sync_register(
                 &mut fuse_mgr,
-                0,
+                RegisterId(0),
                 SyncDirection::FuseToShadow,
                 SyncPolicy::InitializeOnly
             );
@@ -443,7 +900,7 @@ mod tests {
             assert!(result.is_ok());
 
             // Shadow should still have its original value (not overwritten)
-            let shadow_value = fuse_mgr.get_shadow_bank().get_register(0).unwrap().read();
+            let shadow_value = fuse_mgr.get_shadow_bank().get_register(RegisterId(0)).unwrap().read();
             assert_eq!(shadow_value, 0x12345678);
         }
     }
@@ -456,14 +913,14 @@ mod tests {
 
         unsafe {
             // Set fuse value
-            if let Some(fuse) = fuse_mgr.get_fuse_mut(0) {
+            if let Some(fuse) = fuse_mgr.get_fuse_mut(SlotIndex(0)) {
                 fuse.program_to_hardware(0xAABBCCDD).unwrap();
             }
 
             // Shadow is uninitialized - sync should happen
             let result = sync_mgr.sync_register(
                 &mut fuse_mgr,
-                0,
+                RegisterId(0),
                 SyncDirection::FuseToShadow,
                 SyncPolicy::InitializeOnly
             );
@@ -471,7 +928,7 @@ mod tests {
             assert!(result.is_ok());
 
             // Shadow should now have fuse value
-            let shadow_value = fuse_mgr.get_shadow_bank().get_register(0).unwrap().read();
+            let shadow_value = fuse_mgr.get_shadow_bank().get_register(RegisterId(0)).unwrap().read();
             assert_eq!(shadow_value, 0xAABBCCDD);
         }
     }
@@ -484,12 +941,12 @@ mod tests {
 
         unsafe {
             // Program fuse first
-            if let Some(fuse) = fuse_mgr.get_fuse_mut(0) {
+            if let Some(fuse) = fuse_mgr.get_fuse_mut(SlotIndex(0)) {
                 fuse.program_to_hardware(0x99999999).unwrap();
             }
 
             // Write different value to shadow
-            if let Some(shadow_reg) = fuse_mgr.get_shadow_bank_mut().get_register_mut(0) {
+            if let Some(shadow_reg) = fuse_mgr.get_shadow_bank_mut().get_register_mut(RegisterId(0)) {
                 shadow_reg.write(0x88888888).unwrap();
                 shadow_reg.commit().unwrap();
             }
@@ -497,7 +954,7 @@ mod tests {
             // Sync with InitializeOnly - should skip since fuse is already programmed
             let result = sync_mgr.sync_register(
                 &mut fuse_mgr,
-                0,
+                RegisterId(0),
                 SyncDirection::ShadowToFuse,
                 SyncPolicy::InitializeOnly
             );
@@ -505,7 +962,7 @@ mod tests {
             assert!(result.is_ok());
 
             // Fuse should still have its original value
-            let fuse_value = fuse_mgr.get_fuse(0).unwrap().get_value();
+            let fuse_value = fuse_mgr.get_fuse(SlotIndex(0)).unwrap().get_value();
             assert_eq!(fuse_value, 0x99999999);
         }
     }
@@ -520,11 +977,11 @@ mod tests {
             let value = 0x11223344u64;
 
             // Set both fuse and shadow to same value
-            if let Some(fuse) = fuse_mgr.get_fuse_mut(0) {
+            if let Some(fuse) = fuse_mgr.get_fuse_mut(SlotIndex(0)) {
                 fuse.program_to_hardware(value).unwrap();
             }
 
-            if let Some(shadow_reg) = fuse_mgr.get_shadow_bank_mut().get_register_mut(0) {
+            if let Some(shadow_reg) = fuse_mgr.get_shadow_bank_mut().get_register_mut(RegisterId(0)) {
                 shadow_reg.write(value).unwrap();
                 shadow_reg.commit().unwrap();
             }
@@ -532,7 +989,7 @@ mod tests {
             // Bidirectional sync with no conflict
             let result = sync_mgr.sync_register(
                 &mut fuse_mgr,
-                0,
+                RegisterId(0),
                 SyncDirection::Bidirectional,
                 SyncPolicy::ForceOverwrite
             );
@@ -551,11 +1008,11 @@ mod tests {
         unsafe {
             // Use index 1 which is MTP (can be reprogrammed)
             // Set different values in fuse and shadow
-            if let Some(fuse) = fuse_mgr.get_fuse_mut(1) {
+            if let Some(fuse) = fuse_mgr.get_fuse_mut(SlotIndex(1)) {
                 fuse.program_to_hardware(0xAAAAAAAA).unwrap();
             }
 
-            if let Some(shadow_reg) = fuse_mgr.get_shadow_bank_mut().get_register_mut(1) {
+            if let Some(shadow_reg) = fuse_mgr.get_shadow_bank_mut().get_register_mut(RegisterId(1)) {
                 shadow_reg.write(0xBBBBBBBB).unwrap();
                 shadow_reg.commit().unwrap();
             }
@@ -563,7 +1020,7 @@ mod tests {
             // Bidirectional sync with ForceOverwrite - shadow wins
             let result = sync_mgr.sync_register(
                 &mut fuse_mgr,
-                1,
+                RegisterId(1),
                 SyncDirection::Bidirectional,
                 SyncPolicy::ForceOverwrite
             );
@@ -571,7 +1028,7 @@ mod tests {
             assert!(result.is_ok());
 
             // Fuse should now have shadow value
-            let fuse_value = fuse_mgr.get_fuse(1).unwrap().get_value();
+            let fuse_value = fuse_mgr.get_fuse(SlotIndex(1)).unwrap().get_value();
             assert_eq!(fuse_value, 0xBBBBBBBB);
         }
     }
@@ -585,12 +1042,12 @@ mod tests {
         unsafe {
             // Use index 1 which is MTP (can be reprogrammed)
             // Set fuse value
-            if let Some(fuse) = fuse_mgr.get_fuse_mut(1) {
+            if let Some(fuse) = fuse_mgr.get_fuse_mut(SlotIndex(1)) {
                 fuse.program_to_hardware(0xFFFFFFFF).unwrap();
             }
 
             // Set shadow value with higher version (write increments version)
-            if let Some(shadow_reg) = fuse_mgr.get_shadow_bank_mut().get_register_mut(1) {
+            if let Some(shadow_reg) = fuse_mgr.get_shadow_bank_mut().get_register_mut(RegisterId(1)) {
                 shadow_reg.write(0xEEEEEEEE).unwrap();
                 shadow_reg.commit().unwrap();
             }
@@ -598,7 +1055,7 @@ mod tests {
             // Shadow has version > 0, so it should win with ConflictResolve
             let result = sync_mgr.sync_register(
                 &mut fuse_mgr,
-                1,
+                RegisterId(1),
                 SyncDirection::Bidirectional,
                 SyncPolicy::ConflictResolve
             );
@@ -606,11 +1063,89 @@ mod tests {
             assert!(result.is_ok());
 
             // Fuse should be updated with shadow value
-            let fuse_value = fuse_mgr.get_fuse(1).unwrap().get_value();
+            let fuse_value = fuse_mgr.get_fuse(SlotIndex(1)).unwrap().get_value();
             assert_eq!(fuse_value, 0xEEEEEEEE);
         }
     }
 
+    fn or_resolver(fuse_value: u64, shadow_value: u64) -> u64 {
+        fuse_value | shadow_value
+    }
+
+    #[test]
+    fn test_sync_bidirectional_custom_resolver() {
+        let mut memory = create_test_memory();
+        let mut fuse_mgr = create_test_fuse_manager(&mut memory);
+        let sync_mgr = SyncManager::new();
+
+        unsafe {
+            // Index 1 is MTP; each side holds different flag bits
+            fuse_mgr.get_fuse_mut(SlotIndex(1)).unwrap().program_to_hardware(0x0F0).unwrap();
+            let bank = fuse_mgr.get_shadow_bank_mut();
+            let shadow_reg = bank.get_register_mut(RegisterId(1)).unwrap();
+            shadow_reg.write(0x00F).unwrap();
+            shadow_reg.commit().unwrap();
+
+            // No resolver installed: Custom reports the conflict
+            let result = sync_mgr.sync_register(
+                &mut fuse_mgr,
+                RegisterId(1),
+                SyncDirection::Bidirectional,
+                SyncPolicy::Custom,
+            );
+            assert_eq!(result, Err(ShadowError::Conflict));
+
+            sync_mgr.set_conflict_resolver(Some(or_resolver));
+            let result = sync_mgr.sync_register(
+                &mut fuse_mgr,
+                RegisterId(1),
+                SyncDirection::Bidirectional,
+                SyncPolicy::Custom,
+            );
+            assert_eq!(result, Ok(()));
+
+            // Both sides converge on the OR of the two values
+            assert_eq!(fuse_mgr.get_fuse(SlotIndex(1)).unwrap().get_value(), 0x0FF);
+            let shadow_reg = fuse_mgr.get_shadow_bank().get_register(RegisterId(1)).unwrap();
+            assert_eq!(shadow_reg.read(), 0x0FF);
+            assert_eq!(shadow_reg.get_state(), RegisterState::Committed);
+
+            sync_mgr.set_conflict_resolver(None);
+            assert!(sync_mgr.conflict_resolver().is_none());
+        }
+    }
+
+    #[test]
+    fn test_sync_bidirectional_custom_leaves_shadow_when_fuse_refuses() {
+        let mut memory = create_test_memory();
+        let mut fuse_mgr = create_test_fuse_manager(&mut memory);
+        let sync_mgr = SyncManager::new();
+        sync_mgr.set_conflict_resolver(Some(or_resolver));
+
+        unsafe {
+            // Index 0 is OTP and already programmed, so it can't take the merge
+            fuse_mgr.get_fuse_mut(SlotIndex(0)).unwrap().program_to_hardware(0x0F0).unwrap();
+            let shadow_reg = fuse_mgr.get_shadow_bank_mut().get_register_mut(RegisterId(0)).unwrap();
+            shadow_reg.write(0x00F).unwrap();
+            shadow_reg.commit().unwrap();
+
+            let result = sync_mgr.sync_register(
+                &mut fuse_mgr,
+                RegisterId(0),
+                SyncDirection::Bidirectional,
+                SyncPolicy::Custom,
+            );
+            assert_eq!(result, Err(ShadowError::AlreadyProgrammed));
+
+            // Neither side moved
+            assert_eq!(fuse_mgr.get_fuse(SlotIndex(0)).unwrap().get_value(), 0x0F0);
+            let shadow_reg = fuse_mgr.get_shadow_bank().get_register(RegisterId(0)).unwrap();
+            assert_eq!(shadow_reg.read(), 0x00F);
+            assert_eq!(shadow_reg.get_version(), 1);
+            assert_eq!(shadow_reg.get_state(), RegisterState::Committed);
+        }
+    }
+
     #[test]
     fn test_sync_bidirectional_conflict_detection() {
         let mut memory = create_test_memory();
@@ -620,11 +1155,11 @@ mod tests {
         unsafe {
             // Use index 1 which is MTP (can be reprogrammed)
             // Set different values
-            if let Some(fuse) = fuse_mgr.get_fuse_mut(1) {
+            if let Some(fuse) = fuse_mgr.get_fuse_mut(SlotIndex(1)) {
                 fuse.program_to_hardware(0x12345678).unwrap();
             }
 
-            if let Some(shadow_reg) = fuse_mgr.get_shadow_bank_mut().get_register_mut(1) {
+            if let Some(shadow_reg) = fuse_mgr.get_shadow_bank_mut().get_register_mut(RegisterId(1)) {
                 shadow_reg.write(0x87654321).unwrap();
                 shadow_reg.commit().unwrap();
             }
@@ -632,16 +1167,14 @@ mod tests {
             // Use InitializeOnly policy which doesn't resolve conflicts
             let result = sync_mgr.sync_register(
                 &mut fuse_mgr,
-                1,
+                RegisterId(1),
                 SyncDirection::Bidirectional,
                 SyncPolicy::InitializeOnly
             );
 
             assert!(result.is_err());
-            assert_eq!(result.unwrap_err(), "Sync conflict detected");
-            // Note: sync_register overwrites Conflict status to Failed when error is returned
-            // The sync_all method checks for this by inspecting the status before categorizing
-            assert_eq!(sync_mgr.get_status(), SyncStatus::Failed);
+            assert_eq!(result.unwrap_err(), ShadowError::Conflict);
+            assert_eq!(sync_mgr.get_status(), SyncStatus::Conflict);
         }
     }
 
@@ -654,7 +1187,7 @@ mod tests {
         unsafe {
             // Write values to all shadow registers
             for i in 0..fuse_mgr.count() {
-                if let Some(shadow_reg) = fuse_mgr.get_shadow_bank_mut().get_register_mut(i as u32) {
+                if let Some(shadow_reg) = fuse_mgr.get_shadow_bank_mut().get_register_mut(RegisterId(i as u32)) {
                     shadow_reg.write(0x1000 + i as u64).unwrap();
                     shadow_reg.commit().unwrap();
                 }
@@ -674,12 +1207,339 @@ mod tests {
 
             // Verify all fuses were updated
             for i in 0..fuse_mgr.count() {
-                let fuse_value = fuse_mgr.get_fuse(i).unwrap().get_value();
+                let fuse_value = fuse_mgr.get_fuse(SlotIndex(i)).unwrap().get_value();
                 assert_eq!(fuse_value, 0x1000 + i as u64);
             }
         }
     }
 
+    #[test]
+    fn test_read_only_fuse_loads_but_never_programs() {
+        let mut memory = create_test_memory();
+        memory[12] = 0xFAC7_0001;
+        let mut fuse_mgr = create_test_fuse_manager(&mut memory);
+        let addr3 = &memory[12] as *const u64 as u64;
+        fuse_mgr.add_fuse(addr3.into(), FuseMode::ReadOnly).unwrap();
+        let sync_mgr = SyncManager::new();
+
+        unsafe {
+            sync_mgr
+                .sync_register(
+                    &mut fuse_mgr,
+                    RegisterId(3),
+                    SyncDirection::FuseToShadow,
+                    SyncPolicy::ForceOverwrite,
+                )
+                .unwrap();
+        }
+        let shadow_reg = fuse_mgr.get_shadow_bank().get_register(RegisterId(3)).unwrap();
+        assert_eq!(shadow_reg.read(), 0xFAC7_0001);
+
+        let shadow_reg = fuse_mgr.get_shadow_bank_mut().get_register_mut(RegisterId(3)).unwrap();
+        shadow_reg.write(0xBAD).unwrap();
+        shadow_reg.commit().unwrap();
+
+        let predicted = sync_mgr.dry_run_all(
+            &fuse_mgr,
+            SyncDirection::ShadowToFuse,
+            SyncPolicy::ForceOverwrite,
+        );
+        assert_eq!(predicted.skipped_count, 1);
+
+        unsafe {
+            let result = sync_mgr.sync_all(
+                &mut fuse_mgr,
+                SyncDirection::ShadowToFuse,
+                SyncPolicy::ForceOverwrite,
+            );
+            assert_eq!(result.status, SyncStatus::Success);
+            assert_eq!(result.synced_count, 3);
+            assert_eq!(result.skipped_count, 1);
+
+            let direct = sync_mgr.sync_register(
+                &mut fuse_mgr,
+                RegisterId(3),
+                SyncDirection::ShadowToFuse,
+                SyncPolicy::ForceOverwrite,
+            );
+            assert_eq!(direct, Err(ShadowError::ReadOnly));
+        }
+        assert_eq!(memory[12], 0xFAC7_0001);
+    }
+
+    #[test]
+    fn test_sync_all_skips_read_protected() {
+        let mut memory = create_test_memory();
+        let mut fuse_mgr = create_test_fuse_manager(&mut memory);
+        let sync_mgr = SyncManager::new();
+
+        for i in 0..3u32 {
+            let shadow_reg = fuse_mgr.get_shadow_bank_mut().get_register_mut(RegisterId(i)).unwrap();
+            shadow_reg.write(0x1000 + i as u64).unwrap();
+            shadow_reg.commit().unwrap();
+        }
+        let bank = fuse_mgr.get_shadow_bank_mut();
+        bank.lock_register(RegisterId(1), LockMode::ReadWriteProtect).unwrap();
+
+        // Dry run predicts the skip
+        let predicted = sync_mgr.dry_run_all(
+            &fuse_mgr,
+            SyncDirection::ShadowToFuse,
+            SyncPolicy::ForceOverwrite,
+        );
+        assert_eq!(predicted.skipped_count, 1);
+        assert_eq!(predicted.failed_count, 0);
+
+        unsafe {
+            let result = sync_mgr.sync_all(
+                &mut fuse_mgr,
+                SyncDirection::ShadowToFuse,
+                SyncPolicy::ForceOverwrite,
+            );
+
+            assert_eq!(result.status, SyncStatus::Success);
+            assert_eq!(result.synced_count, 2);
+            assert_eq!(result.skipped_count, 1);
+            assert_eq!(result.failed_count, 0);
+
+            // The protected value never reaches its fuse
+            assert_eq!(fuse_mgr.get_fuse(SlotIndex(0)).unwrap().get_value(), 0x1000);
+            assert_eq!(fuse_mgr.get_fuse(SlotIndex(1)).unwrap().get_value(), 0);
+            assert_eq!(fuse_mgr.get_fuse(SlotIndex(2)).unwrap().get_value(), 0x1002);
+
+            // Syncing it directly reports the protection
+            let direct = sync_mgr.sync_register(
+                &mut fuse_mgr,
+                RegisterId(1),
+                SyncDirection::ShadowToFuse,
+                SyncPolicy::ForceOverwrite,
+            );
+            assert_eq!(direct, Err(ShadowError::ReadProtected));
+        }
+    }
+
+    #[test]
+    fn test_sync_result_merge() {
+        let mut merged = SyncResult {
+            status: SyncStatus::Success,
+            synced_count: 3,
+            failed_count: 0,
+            conflict_count: 0,
+            skipped_count: 0,
+        };
+        let conflict = SyncResult {
+            status: SyncStatus::Conflict,
+            synced_count: 1,
+            failed_count: 1,
+            conflict_count: 2,
+            skipped_count: 1,
+        };
+
+        merged.merge(&conflict);
+        assert_eq!(merged.status, SyncStatus::Conflict);
+        assert_eq!(merged.synced_count, 4);
+        assert_eq!(merged.failed_count, 1);
+        assert_eq!(merged.conflict_count, 2);
+        assert_eq!(merged.skipped_count, 1);
+
+        // Failed outranks Conflict, and a later Success does not downgrade it
+        merged.merge(&SyncResult { status: SyncStatus::Failed, ..conflict });
+        merged.merge(&SyncResult { status: SyncStatus::Success, ..conflict });
+        assert_eq!(merged.status, SyncStatus::Failed);
+        assert_eq!(merged.conflict_count, 6);
+    }
+
+    #[test]
+    fn test_sync_manager_accumulated_result() {
+        let mut memory = create_test_memory();
+        let mut fuse_mgr = create_test_fuse_manager(&mut memory);
+        let sync_mgr = SyncManager::new();
+
+        assert_eq!(sync_mgr.accumulated_result().status, SyncStatus::Idle);
+
+        unsafe {
+            for i in 0..fuse_mgr.count() {
+                if let Some(shadow_reg) = fuse_mgr.get_shadow_bank_mut().get_register_mut(RegisterId(i as u32)) {
+                    shadow_reg.write(0x2000 + i as u64).unwrap();
+                    shadow_reg.commit().unwrap();
+                }
+            }
+
+            let first = sync_mgr.sync_all(
+                &mut fuse_mgr,
+                SyncDirection::ShadowToFuse,
+                SyncPolicy::ForceOverwrite
+            );
+            assert_eq!(first.status, SyncStatus::Success);
+
+            // OTP fuses 0 and 2 are now programmed and reject a second write
+            let second = sync_mgr.sync_all(
+                &mut fuse_mgr,
+                SyncDirection::ShadowToFuse,
+                SyncPolicy::ForceOverwrite
+            );
+            assert_eq!(second.failed_count, 2);
+
+            // Register 1 now diverges from its fuse
+            let shadow_reg = fuse_mgr.get_shadow_bank_mut().get_register_mut(RegisterId(1)).unwrap();
+            shadow_reg.write(0xBAD1).unwrap();
+            shadow_reg.commit().unwrap();
+            let third = sync_mgr.sync_all(
+                &mut fuse_mgr,
+                SyncDirection::Bidirectional,
+                SyncPolicy::InitializeOnly
+            );
+            assert_eq!(third.conflict_count, 1);
+
+            let mut expected = first;
+            expected.merge(&second);
+            expected.merge(&third);
+            let accumulated = sync_mgr.accumulated_result();
+            assert_eq!(accumulated, expected);
+            assert!(accumulated.conflict_count > 0);
+            assert_eq!(accumulated.status, SyncStatus::Conflict);
+        }
+
+        sync_mgr.reset();
+        let cleared = sync_mgr.accumulated_result();
+        assert_eq!(cleared.status, SyncStatus::Idle);
+        assert_eq!(cleared.synced_count + cleared.failed_count + cleared.conflict_count, 0);
+    }
+
+    #[test]
+    fn test_dry_run_matches_real_run_conflicts() {
+        let mut memory = create_test_memory();
+        let mut fuse_mgr = FuseManager::new();
+        for i in 0..4 {
+            fuse_mgr.add_fuse((&mut memory[i * 4] as *mut u64 as u64).into(), FuseMode::MTP).unwrap();
+        }
+        let sync_mgr = SyncManager::new();
+
+        unsafe {
+            // Registers 1 and 3 diverge from their fuses
+            let shadow_values = [0x10, 0xBAD1, 0x30, 0xBAD3];
+            for (i, &value) in shadow_values.iter().enumerate() {
+                fuse_mgr.get_fuse_mut(SlotIndex(i)).unwrap().program_to_hardware(0x10 * (i as u64 + 1)).unwrap();
+                let shadow_reg = fuse_mgr.get_shadow_bank_mut().get_register_mut(RegisterId(i as u32)).unwrap();
+                shadow_reg.write(value).unwrap();
+                shadow_reg.commit().unwrap();
+            }
+
+            let predicted = sync_mgr.dry_run_all(
+                &fuse_mgr,
+                SyncDirection::Bidirectional,
+                SyncPolicy::InitializeOnly,
+            );
+            let result = sync_mgr.sync_all(
+                &mut fuse_mgr,
+                SyncDirection::Bidirectional,
+                SyncPolicy::InitializeOnly,
+            );
+
+            assert_eq!(predicted.conflict_count, 2);
+            assert_eq!(result, predicted);
+            assert_eq!(result.status, SyncStatus::Conflict);
+            assert_eq!(result.failed_count, 0);
+        }
+    }
+
+    #[test]
+    fn test_dry_run_predicts_force_overwrite() {
+        let mut memory = create_test_memory();
+        let mut fuse_mgr = FuseManager::new();
+        for i in 0..4 {
+            fuse_mgr.add_fuse((&mut memory[i * 4] as *mut u64 as u64).into(), FuseMode::MTP).unwrap();
+        }
+        let sync_mgr = SyncManager::new();
+
+        unsafe {
+            // Registers 1 and 3 diverge from their fuses
+            let shadow_values = [0x10, 0xBAD1, 0x30, 0xBAD3];
+            for (i, &value) in shadow_values.iter().enumerate() {
+                fuse_mgr.get_fuse_mut(SlotIndex(i)).unwrap().program_to_hardware(0x10 * (i as u64 + 1)).unwrap();
+                let shadow_reg = fuse_mgr.get_shadow_bank_mut().get_register_mut(RegisterId(i as u32)).unwrap();
+                shadow_reg.write(value).unwrap();
+                shadow_reg.commit().unwrap();
+            }
+
+            let predicted = sync_mgr.dry_run_all(
+                &fuse_mgr,
+                SyncDirection::Bidirectional,
+                SyncPolicy::ForceOverwrite,
+            );
+            assert_eq!(predicted.status, SyncStatus::Conflict);
+            assert_eq!(predicted.synced_count, 2);
+            assert_eq!(predicted.conflict_count, 2);
+            assert_eq!(predicted.failed_count, 0);
+
+            // Nothing changed
+            assert_eq!(sync_mgr.get_status(), SyncStatus::Idle);
+            assert!(!sync_mgr.is_syncing());
+            assert_eq!(fuse_mgr.get_fuse(SlotIndex(1)).unwrap().get_value(), 0x20);
+
+            // The real run overwrites exactly the predicted conflicts
+            let before: [u64; 4] = core::array::from_fn(|i| fuse_mgr.get_fuse(SlotIndex(i)).unwrap().get_value());
+            let result = sync_mgr.sync_all(
+                &mut fuse_mgr,
+                SyncDirection::Bidirectional,
+                SyncPolicy::ForceOverwrite,
+            );
+            assert_eq!(result.status, SyncStatus::Success);
+
+            let resolved = (0..4)
+                .filter(|&i| fuse_mgr.get_fuse(SlotIndex(i)).unwrap().get_value() != before[i])
+                .count();
+            assert_eq!(resolved, predicted.conflict_count);
+            assert_eq!(fuse_mgr.get_fuse(SlotIndex(1)).unwrap().get_value(), 0xBAD1);
+        }
+    }
+
+    #[test]
+    fn test_dry_run_policy_semantics() {
+        let mut memory = create_test_memory();
+        let mut fuse_mgr = create_test_fuse_manager(&mut memory);
+        let sync_mgr = SyncManager::new();
+
+        unsafe {
+            // OTP fuse 0 is programmed; fuses 1 and 2 stay virgin
+            fuse_mgr.get_fuse_mut(SlotIndex(0)).unwrap().program_to_hardware(0xAAAA).unwrap();
+            for i in 0..3u32 {
+                let shadow_reg = fuse_mgr.get_shadow_bank_mut().get_register_mut(RegisterId(i)).unwrap();
+                shadow_reg.write(0xBBBB).unwrap();
+                shadow_reg.commit().unwrap();
+            }
+        }
+
+        // InitializeOnly skips the programmed fuse, the rest would change
+        let init_only = sync_mgr.dry_run_all(
+            &fuse_mgr,
+            SyncDirection::ShadowToFuse,
+            SyncPolicy::InitializeOnly,
+        );
+        assert_eq!(init_only.skipped_count, 1);
+        assert_eq!(init_only.conflict_count, 2);
+
+        // ForceOverwrite cannot reprogram the OTP fuse
+        let forced = sync_mgr.dry_run_all(
+            &fuse_mgr,
+            SyncDirection::ShadowToFuse,
+            SyncPolicy::ForceOverwrite,
+        );
+        assert_eq!(forced.failed_count, 1);
+        assert_eq!(forced.conflict_count, 2);
+
+        // Fuse-to-shadow is blocked by a locked register
+        fuse_mgr.get_shadow_bank_mut().get_register_mut(RegisterId(1)).unwrap().lock(LockMode::WriteProtect);
+        let load = sync_mgr.dry_run_all(
+            &fuse_mgr,
+            SyncDirection::FuseToShadow,
+            SyncPolicy::ForceOverwrite,
+        );
+        assert_eq!(load.failed_count, 1);
+        assert_eq!(load.conflict_count, 2);
+        assert_eq!(sync_mgr.accumulated_result().status, SyncStatus::Idle);
+    }
+
     #[test]
     fn test_sync_concurrent_protection() {
         let sync_mgr = SyncManager::new();
@@ -693,13 +1553,13 @@ mod tests {
             // Try to sync - should fail
             let result = sync_mgr.sync_register(
                 &mut fuse_mgr,
-                0,
+                RegisterId(0),
                 SyncDirection::FuseToShadow,
                 SyncPolicy::ForceOverwrite
             );
 
             assert!(result.is_err());
-            assert_eq!(result.unwrap_err(), "Sync already in progress");
+            assert_eq!(result.unwrap_err(), ShadowError::SyncInProgress);
 
             // Reset for cleanup
             sync_mgr.syncing.store(false, Ordering::Release);
@@ -716,7 +1576,7 @@ mod tests {
 
         unsafe {
             // Write to shadow
-            if let Some(shadow_reg) = fuse_mgr.get_shadow_bank_mut().get_register_mut(0) {
+            if let Some(shadow_reg) = fuse_mgr.get_shadow_bank_mut().get_register_mut(RegisterId(0)) {
                 shadow_reg.write(0x55555555).unwrap();
                 shadow_reg.commit().unwrap();
             }
@@ -724,7 +1584,7 @@ mod tests {
             // Perform sync
             sync_mgr.sync_register(
                 &mut fuse_mgr,
-                0,
+                RegisterId(0),
                 SyncDirection::ShadowToFuse,
                 SyncPolicy::ForceOverwrite
             ).unwrap();
@@ -746,14 +1606,14 @@ mod tests {
         unsafe {
             // Perform multiple syncs
             for i in 0..3 {
-                if let Some(shadow_reg) = fuse_mgr.get_shadow_bank_mut().get_register_mut(i) {
+                if let Some(shadow_reg) = fuse_mgr.get_shadow_bank_mut().get_register_mut(RegisterId(i)) {
                     shadow_reg.write(0x1000 * (i as u64 + 1)).unwrap();
                     shadow_reg.commit().unwrap();
                 }
 
                 sync_mgr.sync_register(
                     &mut fuse_mgr,
-                    i,
+                    RegisterId(i),
                     SyncDirection::ShadowToFuse,
                     SyncPolicy::ForceOverwrite
                 ).unwrap();
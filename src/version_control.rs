@@ -1,7 +1,7 @@
 /// Register Version Control and Rollback System
 /// Provides temporal management of shadow register values
 
-use crate::shadow_register::ShadowRegister;
+use crate::shadow_register::{RegisterId, ShadowRegister};
 use core::sync::atomic::{AtomicU32, Ordering};
 
 /// Maximum number of versions to keep in history
@@ -136,6 +136,17 @@ impl VersionHistory {
         version
     }
 
+    /// Add a new version only if `value` differs from the latest entry
+    ///
+    /// Returns `None` without touching the history when the value repeats.
+    pub fn push_if_changed(&mut self, value: u64, timestamp: u64) -> Option<u32> {
+        if self.get_latest().is_some_and(|latest| latest.get_value() == value) {
+            return None;
+        }
+
+        Some(self.push(value, timestamp))
+    }
+
     /// Get version entry by version number
     pub fn get(&self, version: u32) -> Option<&VersionEntry> {
         let count = self.count.load(Ordering::Acquire) as usize;
@@ -199,6 +210,62 @@ impl VersionHistory {
         // Note: version_counter is NOT reset to maintain uniqueness
     }
 
+    /// Valid entries, oldest first
+    fn valid_entries(&self) -> Vec<VersionEntry> {
+        (0..self.count())
+            .rev()
+            .filter_map(|offset| self.get_by_offset(offset))
+            .filter(|entry| entry.is_valid())
+            .copied()
+            .collect()
+    }
+
+    /// Entry `merge(other)` would leave as the latest, without merging
+    fn merged_latest(&self, other: &VersionHistory) -> Option<VersionEntry> {
+        let local = self.valid_entries();
+        let incoming = other
+            .valid_entries()
+            .into_iter()
+            .filter(|entry| !local.iter().any(|known| known.version == entry.version));
+
+        local.iter().copied().chain(incoming).max_by_key(|entry| entry.version)
+    }
+
+    /// Merge another history's entries into this one by version number
+    ///
+    /// Versions already present here are kept as-is. If the union exceeds
+    /// `MAX_VERSION_HISTORY`, the oldest versions are dropped. Fails without
+    /// changing anything if an incoming entry does not verify.
+    pub fn merge(&mut self, other: &VersionHistory) -> Result<(), &'static str> {
+        let incoming = other.valid_entries();
+        if incoming.iter().any(|entry| !entry.verify()) {
+            return Err("Version entry corrupted");
+        }
+
+        let mut merged = self.valid_entries();
+        for entry in incoming {
+            if !merged.iter().any(|known| known.version == entry.version) {
+                merged.push(entry);
+            }
+        }
+        merged.sort_unstable_by_key(|entry| entry.version);
+
+        // Newest versions win the 16 slots
+        let kept = &merged[merged.len().saturating_sub(MAX_VERSION_HISTORY)..];
+        for (slot, entry) in self.entries.iter_mut().zip(kept) {
+            *slot = *entry;
+        }
+        self.head.store((kept.len() % MAX_VERSION_HISTORY) as u32, Ordering::Release);
+        self.count.store(kept.len() as u32, Ordering::Release);
+
+        // Keep new versions unique on both sides
+        let next = kept.last().map_or(0, |entry| entry.version.saturating_add(1));
+        let next = next.max(other.current_version());
+        self.version_counter.fetch_max(next, Ordering::AcqRel);
+
+        Ok(())
+    }
+
     /// Verify all entries in history
     pub fn verify_all(&self) -> bool {
         let count = self.count.load(Ordering::Acquire) as usize;
@@ -223,7 +290,7 @@ pub struct VersionedShadowRegister {
 
 impl VersionedShadowRegister {
     /// Create a new versioned shadow register
-    pub const fn new(id: u32, fuse_addr: u64) -> Self {
+    pub const fn new(id: RegisterId, fuse_addr: u64) -> Self {
         Self {
             register: ShadowRegister::new(id, fuse_addr),
             history: VersionHistory::new(),
@@ -241,6 +308,22 @@ impl VersionedShadowRegister {
         Ok(version)
     }
 
+    /// Write to register, recording a version only if the value changed
+    ///
+    /// The register write is always staged; `Ok(None)` means the history
+    /// already ends with `value`.
+    pub fn write_versioned_dedup(
+        &mut self,
+        value: u64,
+        timestamp: u64,
+    ) -> Result<Option<u32>, &'static str> {
+        // Write to shadow register
+        self.register.write(value)?;
+
+        // Add to version history unless it repeats the latest entry
+        Ok(self.history.push_if_changed(value, timestamp))
+    }
+
     /// Rollback to specific version
     pub fn rollback_to_version(&mut self, version: u32) -> Result<(), &'static str> {
         // Find version in history
@@ -275,6 +358,38 @@ impl VersionedShadowRegister {
         }
     }
 
+    /// Reconcile with a diverged mirror's history
+    ///
+    /// Merges `other` into this history (see `VersionHistory::merge`) and
+    /// commits the highest-version entry to the register. Returns the
+    /// winning version.
+    ///
+    /// The winner is staged in the register before the history changes, so
+    /// if the register refuses it (locked, write-protected or poisoned) the
+    /// history is left unmerged.
+    pub fn reconcile(&mut self, other: &VersionHistory) -> Result<u32, &'static str> {
+        if other.valid_entries().iter().any(|entry| !entry.verify()) {
+            return Err("Version entry corrupted");
+        }
+
+        let winner = match self.history.merged_latest(other) {
+            Some(entry) => entry,
+            None => return Err("Version history empty"),
+        };
+        if !winner.verify() {
+            return Err("Version entry corrupted");
+        }
+
+        self.register.write(winner.get_value())?;
+        if let Err(err) = self.history.merge(other) {
+            let _ = self.register.discard();
+            return Err(err);
+        }
+        self.register.commit()?;
+
+        Ok(winner.get_version())
+    }
+
     /// Get register
     #[inline(always)]
     pub fn get_register(&self) -> &ShadowRegister {
@@ -341,6 +456,7 @@ use alloc::vec::Vec;
 mod tests {
     use super::*;
     use alloc::vec;
+    use crate::shadow_register::LockMode;
 
     #[test]
     fn test_version_entry_initialization() {
@@ -486,9 +602,44 @@ mod tests {
         assert!(history.get_by_offset(5).is_none());
     }
 
+    #[test]
+    fn test_version_history_push_if_changed() {
+        let mut history = VersionHistory::new();
+
+        assert_eq!(history.push_if_changed(0xAAAA, 100), Some(0));
+        assert_eq!(history.push_if_changed(0xAAAA, 200), None);
+        assert_eq!(history.push_if_changed(0xBBBB, 300), Some(1));
+        assert_eq!(history.push_if_changed(0xBBBB, 400), None);
+
+        // Only consecutive repeats are skipped
+        assert_eq!(history.push_if_changed(0xAAAA, 500), Some(2));
+
+        assert_eq!(history.count(), 3);
+        assert_eq!(history.current_version(), 3);
+        assert_eq!(history.get_latest().unwrap().get_timestamp(), 500);
+    }
+
+    #[test]
+    fn test_versioned_shadow_register_write_dedup() {
+        let mut vreg = VersionedShadowRegister::new(RegisterId(7), 0x7000);
+
+        let versions: Vec<Option<u32>> = [0xA, 0xA, 0xB, 0xB, 0xA]
+            .iter()
+            .enumerate()
+            .map(|(i, &value)| vreg.write_versioned_dedup(value, i as u64 * 100).unwrap())
+            .collect();
+
+        assert_eq!(versions, vec![Some(0), None, Some(1), None, Some(2)]);
+        assert_eq!(vreg.get_history().count(), 3);
+        assert_eq!(vreg.get_all_versions(), vec![2, 1, 0]);
+
+        // Every write is still staged on the register
+        assert_eq!(vreg.get_register().get_version(), 5);
+    }
+
     #[test]
     fn test_versioned_shadow_register_write_and_rollback() {
-        let mut vreg = VersionedShadowRegister::new(1, 0x1000);
+        let mut vreg = VersionedShadowRegister::new(RegisterId(1), 0x1000);
 
         // Write first version
         let v1 = vreg.write_versioned(0xAAAA, 100).unwrap();
@@ -519,7 +670,7 @@ mod tests {
 
     #[test]
     fn test_versioned_shadow_register_rollback_by_offset() {
-        let mut vreg = VersionedShadowRegister::new(2, 0x2000);
+        let mut vreg = VersionedShadowRegister::new(RegisterId(2), 0x2000);
 
         // Write multiple versions
         vreg.write_versioned(0x1111, 100).unwrap();
@@ -548,7 +699,7 @@ mod tests {
 
     #[test]
     fn test_versioned_shadow_register_rollback_errors() {
-        let mut vreg = VersionedShadowRegister::new(3, 0x3000);
+        let mut vreg = VersionedShadowRegister::new(RegisterId(3), 0x3000);
 
         // Rollback to non-existent version
         assert!(vreg.rollback_to_version(999).is_err());
@@ -559,7 +710,7 @@ mod tests {
 
     #[test]
     fn test_version_overflow_after_16_versions() {
-        let mut vreg = VersionedShadowRegister::new(4, 0x4000);
+        let mut vreg = VersionedShadowRegister::new(RegisterId(4), 0x4000);
 
         // Add 20 versions to test overflow behavior
         for i in 0..20 {
@@ -602,7 +753,7 @@ mod tests {
 
     #[test]
     fn test_versioned_shadow_register_diff_versions() {
-        let mut vreg = VersionedShadowRegister::new(5, 0x5000);
+        let mut vreg = VersionedShadowRegister::new(RegisterId(5), 0x5000);
 
         // Write versions
         vreg.write_versioned(0xAAAA, 100).unwrap();
@@ -622,7 +773,7 @@ mod tests {
 
     #[test]
     fn test_versioned_shadow_register_get_all_versions() {
-        let mut vreg = VersionedShadowRegister::new(6, 0x6000);
+        let mut vreg = VersionedShadowRegister::new(RegisterId(6), 0x6000);
 
         // Write 5 versions
         for i in 0..5 {
@@ -636,6 +787,101 @@ mod tests {
         assert_eq!(versions, vec![4, 3, 2, 1, 0]); // Latest to oldest
     }
 
+    #[test]
+    fn test_versioned_shadow_register_reconcile() {
+        let mut local = VersionedShadowRegister::new(RegisterId(7), 0x7000);
+        let mut remote = VersionedShadowRegister::new(RegisterId(7), 0x7000);
+
+        // Shared prefix (versions 0-2), then the remote moves ahead
+        for i in 0..3 {
+            local.write_versioned(0x100 + i, i * 10).unwrap();
+        }
+        for i in 0..5 {
+            remote.write_versioned(0x100 + i, i * 10).unwrap();
+        }
+        // A local-only value under an already-known version number is kept
+        local.get_history_mut().entries[2] = VersionEntry::from_state(2, 0xDEAD, 25);
+
+        let winner = local.reconcile(remote.get_history()).unwrap();
+        assert_eq!(winner, 4);
+        assert_eq!(local.get_register().read(), 0x104);
+        assert_eq!(local.get_all_versions(), vec![4, 3, 2, 1, 0]);
+        assert_eq!(local.get_history().get(2).unwrap().get_value(), 0xDEAD);
+        assert!(local.get_history().verify_all());
+
+        // New local writes continue past every merged version
+        assert_eq!(local.write_versioned(0x200, 100).unwrap(), 5);
+
+        // Reconciling again is a no-op
+        assert_eq!(local.reconcile(remote.get_history()).unwrap(), 5);
+        assert_eq!(local.get_register().read(), 0x200);
+    }
+
+    #[test]
+    fn test_versioned_shadow_register_reconcile_caps_history() {
+        let mut local = VersionedShadowRegister::new(RegisterId(8), 0x8000);
+        let mut remote = VersionedShadowRegister::new(RegisterId(8), 0x8000);
+
+        for i in 0..4 {
+            local.write_versioned(0xA00 + i, i).unwrap();
+        }
+        for i in 0..20 {
+            remote.write_versioned(0xB00 + i, i).unwrap();
+        }
+
+        assert_eq!(local.reconcile(remote.get_history()).unwrap(), 19);
+        assert_eq!(local.get_history().count(), MAX_VERSION_HISTORY);
+        assert_eq!(local.get_history().get_latest().unwrap().get_version(), 19);
+        assert!(local.get_history().get(3).is_none());
+        assert_eq!(local.get_history().get(4).unwrap().get_value(), 0xB04);
+    }
+
+    #[test]
+    fn test_versioned_shadow_register_reconcile_rejects_corruption() {
+        let mut local = VersionedShadowRegister::new(RegisterId(9), 0x9000);
+        let mut remote = VersionedShadowRegister::new(RegisterId(9), 0x9000);
+
+        local.write_versioned(0x1111, 1).unwrap();
+        local.get_register_mut().commit().unwrap();
+        for i in 0..3 {
+            remote.write_versioned(0x2222 + i, i).unwrap();
+        }
+        remote.get_history_mut().entries[1].checksum ^= 1;
+
+        assert_eq!(
+            local.reconcile(remote.get_history()),
+            Err("Version entry corrupted")
+        );
+
+        // Nothing was merged
+        assert_eq!(local.get_history().count(), 1);
+        assert_eq!(local.get_register().read(), 0x1111);
+    }
+
+    #[test]
+    fn test_versioned_shadow_register_reconcile_locked_register() {
+        let mut local = VersionedShadowRegister::new(RegisterId(10), 0xA000);
+        let mut remote = VersionedShadowRegister::new(RegisterId(10), 0xA000);
+
+        local.write_versioned(0x1111, 1).unwrap();
+        local.get_register_mut().commit().unwrap();
+        for i in 0..3 {
+            remote.write_versioned(0x2222 + i, i).unwrap();
+        }
+        local.get_register_mut().lock(LockMode::WriteProtect);
+
+        assert_eq!(local.reconcile(remote.get_history()), Err("Register is locked"));
+
+        // The history still matches what the register holds
+        assert_eq!(local.get_history().count(), 1);
+        assert_eq!(local.get_history().get_latest().unwrap().get_value(), 0x1111);
+        assert_eq!(local.get_register().read(), 0x1111);
+
+        local.get_register_mut().unlock();
+        assert_eq!(local.reconcile(remote.get_history()), Ok(2));
+        assert_eq!(local.get_register().read(), 0x2224);
+    }
+
     #[test]
     fn test_version_history_clear() {
         let mut history = VersionHistory::new();